@@ -0,0 +1,542 @@
+//! A high-level facade over the client, cache, and configuration for embedding the todo core
+//! in other frontends.
+//!
+//! The binary's command handlers mix orchestration with terminal interaction; [`App`] exposes
+//! just the orchestration — credential-agnostic client setup, cache-first fetching, grouping,
+//! and the focus lookup — with no prompting or styled output, so a GUI or bot can drive the
+//! same core without reimplementing it.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # async fn run() -> anyhow::Result<()> {
+//! use std::path::PathBuf;
+//!
+//! use todo::app::{client_from_config, App};
+//! use todo::asana::Credentials;
+//!
+//! let config = todo::config::load(&PathBuf::from("config.toml"), false)?;
+//! let cache_path = PathBuf::from("cache.json");
+//! let cache = todo::cache::load(&cache_path)?;
+//! let credentials = cache
+//!     .creds
+//!     .clone()
+//!     .unwrap_or(Credentials::PersonalAccessToken("a-pat".to_string()));
+//! let client = client_from_config(&config, credentials)?;
+//!
+//! let mut app = App::new(config, cache, cache_path, client);
+//! let grouped = app.tasks(true).await?;
+//! println!("{} overdue", grouped.overdue.len());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::path::PathBuf;
+
+use anyhow::Context;
+use chrono::{DateTime, Local, NaiveDate, Timelike};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+use crate::asana::{Client, Credentials, MutationRequest};
+use crate::cache::Cache;
+use crate::commands::status::Status;
+use crate::commands::{focus, pause, update};
+use crate::config::Config;
+use crate::context::GroupedTasks;
+use crate::focus::{FocusDay, ASANA_FOCUS_PROJECT_GID, START_HOUR_FOR_EOD};
+use crate::task::{UserTask, UserTaskList, ASANA_WORKSPACE_GID};
+
+/// Request body for creating a task.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CreateTaskRequest {
+    /// Human-readable name of the task.
+    pub name: String,
+    /// Gid of the workspace the task is created in.
+    pub workspace: String,
+    /// Gid of the user the task is assigned to, or `"me"`.
+    pub assignee: String,
+    /// Date the task is due, if it has one without a specific time.
+    // Asana rejects a task carrying both `due_on` and `due_at`, so whichever is unset must be
+    // skipped entirely rather than serialized as null.
+    #[serde(
+        with = "crate::asana::serde_formats::optional_date",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub due_on: Option<NaiveDate>,
+    /// Instant the task is due, if it is due at a specific time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Date work on the task is meant to start, if one was given.
+    #[serde(
+        with = "crate::asana::serde_formats::optional_date",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub start_on: Option<NaiveDate>,
+    /// Gids of users added as followers, possibly empty.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub followers: Vec<String>,
+}
+
+impl<'a> MutationRequest<'a> for CreateTaskRequest {
+    type RequestData = ();
+    type ResponseData = UserTask;
+
+    fn segments((): &'a Self::RequestData) -> Vec<String> {
+        vec!["tasks".to_string()]
+    }
+
+    fn method() -> Method {
+        Method::POST
+    }
+}
+
+/// Request body for updating a task's completion state or due date.
+///
+/// Unset fields are skipped rather than serialized as null, so a request touches only the
+/// fields it carries.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct UpdateTaskRequest {
+    /// New completion state, if the request changes it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed: Option<bool>,
+    /// New due date, if the request changes it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_on: Option<NaiveDate>,
+}
+
+impl<'a> MutationRequest<'a> for UpdateTaskRequest {
+    type RequestData = String;
+    type ResponseData = serde_json::Value;
+
+    fn segments(task_gid: &'a Self::RequestData) -> Vec<String> {
+        vec!["tasks".to_string(), task_gid.clone()]
+    }
+
+    fn method() -> Method {
+        Method::PUT
+    }
+}
+
+/// Build an API client configured from the `[asana]` configuration table: timeouts, user
+/// agent, and proxy.
+///
+/// # Errors
+///
+/// This function will return an error if `asana.proxy_url` is invalid or the client could not
+/// be constructed.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use todo::app::client_from_config;
+/// # use todo::asana::Credentials;
+/// # fn run() -> anyhow::Result<()> {
+/// let config = todo::config::Config::default();
+/// let client = client_from_config(
+///     &config,
+///     Credentials::PersonalAccessToken("a-pat".to_string()),
+/// )?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn client_from_config(config: &Config, credentials: Credentials) -> anyhow::Result<Client> {
+    let mut builder = Client::builder(credentials);
+    if let Some(seconds) = config.asana.connect_timeout_seconds {
+        builder = builder.connect_timeout(std::time::Duration::from_secs(seconds));
+    }
+    if let Some(seconds) = config.asana.request_timeout_seconds {
+        builder = builder.timeout(std::time::Duration::from_secs(seconds));
+    }
+    if let Some(user_agent) = &config.asana.user_agent {
+        builder = builder.user_agent(user_agent.clone());
+    }
+    if let Some(proxy_url) = &config.asana.proxy_url {
+        builder = builder.proxy(
+            proxy_url
+                .parse()
+                .context("invalid asana.proxy_url in configuration")?,
+        );
+    }
+    builder.build()
+}
+
+/// The todo core behind a terminal-free API: cache-first reads, explicit refreshes, and the
+/// same cache writes the binary performs, so the two stay interchangeable on one cache file.
+pub struct App {
+    config: Config,
+    cache: Cache,
+    cache_path: PathBuf,
+    client: Client,
+    workspace_gid: String,
+    focus_project_gid: String,
+}
+
+impl App {
+    /// Wrap loaded configuration, cache, and a built client into a facade.
+    ///
+    /// The workspace and focus project come from the configuration, falling back to the same
+    /// built-in defaults the binary uses. Changes the methods make to the cache are saved back
+    /// to `cache_path`.
+    #[must_use]
+    pub fn new(config: Config, cache: Cache, cache_path: PathBuf, client: Client) -> Self {
+        let workspace_gid = config
+            .asana
+            .workspace_gid
+            .clone()
+            .unwrap_or_else(|| ASANA_WORKSPACE_GID.to_string());
+        let focus_project_gid = config
+            .asana
+            .focus_project_gid
+            .clone()
+            .unwrap_or_else(|| ASANA_FOCUS_PROJECT_GID.to_string());
+        Self {
+            config,
+            cache,
+            cache_path,
+            client,
+            workspace_gid,
+            focus_project_gid,
+        }
+    }
+
+    /// The loaded configuration.
+    #[must_use]
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// The cache in its current state, including any changes made through this facade.
+    #[must_use]
+    pub fn cache(&self) -> &Cache {
+        &self.cache
+    }
+
+    /// The user's tasks grouped into due-date buckets, sorted by the configured priority field.
+    ///
+    /// With `refresh` unset the cached tasks are used as-is; with it set (or when nothing is
+    /// cached yet) the tasks are fetched, re-resolving a stale user task list gid along the
+    /// way, and written back to the cache.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the tasks could not be fetched or the cache could
+    /// not be saved.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn run(mut app: todo::app::App) -> anyhow::Result<()> {
+    /// let grouped = app.tasks(false).await?;
+    /// for task in &grouped.due_today {
+    ///     println!("{}", task.name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn tasks(&mut self, refresh: bool) -> anyhow::Result<GroupedTasks<'_>> {
+        if refresh || self.cache.tasks.is_none() {
+            let user_task_list = if let Some(user_task_list) = self.cache.user_task_list.clone()
+            {
+                user_task_list
+            } else {
+                let user_gid = self
+                    .cache
+                    .user
+                    .as_ref()
+                    .map_or_else(|| "me".to_string(), |user| user.gid.clone());
+                let request = (user_gid, self.workspace_gid.clone());
+                self.client.get::<UserTaskList>(&request).await?
+            };
+            let (tasks, resolved) =
+                update::fetch_tasks(&mut self.client, user_task_list, &self.workspace_gid)
+                    .await?;
+            self.cache.user_task_list = Some(resolved);
+            self.cache.tasks = Some(tasks);
+            self.save_cache()?;
+        }
+        Ok(self.grouped(Local::now().date_naive()))
+    }
+
+    /// A snapshot of the current state: due counts, focus routine flags, and the pause state.
+    ///
+    /// Computed entirely from the cache, like the binary's `status` subcommand, so it never
+    /// touches the network.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if no tasks have been cached yet.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn run(app: todo::app::App) -> anyhow::Result<()> {
+    /// let status = app.status()?;
+    /// println!("{} overdue, {} due today", status.overdue, status.due_today);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn status(&self) -> anyhow::Result<Status> {
+        self.status_at(Local::now())
+    }
+
+    /// Compute the status as of `now`, the seam [`status`](App::status) fixes to the present.
+    fn status_at(&self, now: DateTime<Local>) -> anyhow::Result<Status> {
+        anyhow::ensure!(
+            self.cache.tasks.is_some(),
+            "no tasks are cached yet; load them with `tasks` first"
+        );
+        let today = now.date_naive();
+        let grouped = self.grouped(today);
+        let eod = now.hour() >= START_HOUR_FOR_EOD;
+        let paused = pause::is_paused(self.cache.paused.as_ref(), today);
+        // Entries carry their completion timestamps, so filtering on today's local date keeps a
+        // cache fetched yesterday evening from counting towards this morning.
+        let done_today = self.cache.completed_today.as_ref().map_or(0, |tasks| {
+            tasks
+                .iter()
+                .filter(|t| t.completed_at.is_some_and(|at| at.date_naive() == today))
+                .count()
+        });
+        Ok(Status::new(
+            &grouped,
+            self.cache.focus_day.as_ref().filter(|d| d.date == today),
+            eod,
+            today,
+            self.config.focus.is_scheduled(today),
+            paused,
+            done_today,
+        ))
+    }
+
+    /// Group the cached tasks relative to `today` and apply the configured priority sort.
+    fn grouped(&self, today: NaiveDate) -> GroupedTasks<'_> {
+        let tasks = self.cache.tasks.as_deref().unwrap_or_default();
+        let mut grouped = GroupedTasks::group(tasks, today);
+        if let Some(priority_field_gid) = &self.config.list.priority_field_gid {
+            grouped.sort_by_priority(priority_field_gid);
+        }
+        grouped
+    }
+
+    /// The focus day for `date`, from the cache when it holds that date and from the focus
+    /// project otherwise.
+    ///
+    /// This is a read: a missing day errors instead of being created. A freshly fetched day
+    /// for today is written back to the cache, matching what the binary caches.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the fetch fails, no focus day exists for `date`,
+    /// or the cache could not be saved.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn run(mut app: todo::app::App) -> anyhow::Result<()> {
+    /// let day = app.focus_day("2024-01-15".parse()?).await?;
+    /// println!("{}", day.diary);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn focus_day(&mut self, date: NaiveDate) -> anyhow::Result<FocusDay> {
+        if let Some(day) = self.cache.focus_day.as_ref().filter(|d| d.date == date) {
+            return Ok(day.clone());
+        }
+        let day = focus::find_focus_day(date, &mut self.client, &self.focus_project_gid)
+            .await?
+            .with_context(|| format!("no focus day exists for {date}"))?;
+        if date == Local::now().date_naive() {
+            self.cache.focus_day = Some(day.clone());
+            self.save_cache()?;
+        }
+        Ok(day)
+    }
+
+    /// Mark the task with the given gid as completed and drop it from the cached tasks.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the update fails or the cache could not be saved.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn run(mut app: todo::app::App) -> anyhow::Result<()> {
+    /// app.complete_task("1205000000000001").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn complete_task(&mut self, gid: &str) -> anyhow::Result<()> {
+        self.client
+            .mutate(
+                &gid.to_string(),
+                UpdateTaskRequest {
+                    completed: Some(true),
+                    due_on: None,
+                },
+            )
+            .await
+            .context("issue completing task")?;
+        if let Some(tasks) = &mut self.cache.tasks {
+            tasks.retain(|task| task.gid != gid);
+            self.save_cache()?;
+        }
+        Ok(())
+    }
+
+    /// Create a task and return it as Asana stored it.
+    ///
+    /// When the task is assigned to the user, it is mirrored into the cached task list so
+    /// cached views stay accurate until the next refresh.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the creation fails, the created task could not be
+    /// parsed, or the cache could not be saved.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn run(mut app: todo::app::App) -> anyhow::Result<()> {
+    /// use todo::app::CreateTaskRequest;
+    /// use todo::task::ASANA_WORKSPACE_GID;
+    ///
+    /// let created = app
+    ///     .add_task(CreateTaskRequest {
+    ///         name: "water the plants".to_string(),
+    ///         workspace: ASANA_WORKSPACE_GID.to_string(),
+    ///         assignee: "me".to_string(),
+    ///         due_on: None,
+    ///         due_at: None,
+    ///         start_on: None,
+    ///         followers: Vec::new(),
+    ///     })
+    ///     .await?;
+    /// println!("created {}", created.gid);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn add_task(&mut self, request: CreateTaskRequest) -> anyhow::Result<UserTask> {
+        let assigned_to_me = self
+            .cache
+            .user
+            .as_ref()
+            .map_or(request.assignee == "me", |user| user.gid == request.assignee);
+        let created = self
+            .client
+            .mutate(&(), request)
+            .await
+            .context("issue creating task")?;
+        if assigned_to_me {
+            if let Some(tasks) = &mut self.cache.tasks {
+                tasks.push(created.clone());
+                self.save_cache()?;
+            }
+        }
+        Ok(created)
+    }
+
+    /// Save the cache back to the path it was loaded from.
+    fn save_cache(&self) -> anyhow::Result<()> {
+        crate::cache::save(&self.cache_path, &self.cache)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use crate::task::CompletedTask;
+
+    use super::*;
+
+    fn task(gid: &str, due_on: Option<&str>) -> UserTask {
+        UserTask {
+            gid: gid.to_string(),
+            created_at: Local.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap(),
+            due_on: due_on.map(|d| d.parse().unwrap()),
+            name: format!("task {gid}"),
+            projects: Vec::new(),
+            custom_fields: Vec::new(),
+        }
+    }
+
+    fn app_with_cache(cache: Cache) -> App {
+        let client = Client::builder(Credentials::PersonalAccessToken("test".to_string()))
+            .build()
+            .unwrap();
+        App::new(
+            Config::default(),
+            cache,
+            PathBuf::from("unused-cache.json"),
+            client,
+        )
+    }
+
+    #[test]
+    fn grouping_reads_the_fixture_cache_without_the_network() {
+        let cache = Cache {
+            tasks: Some(vec![
+                task("overdue", Some("2024-01-10")),
+                task("today", Some("2024-01-15")),
+                task("week", Some("2024-01-18")),
+                task("undated", None),
+            ]),
+            ..Cache::default()
+        };
+        let app = app_with_cache(cache);
+
+        let grouped = app.grouped("2024-01-15".parse().unwrap());
+        assert_eq!(grouped.overdue.len(), 1);
+        assert_eq!(grouped.due_today.len(), 1);
+        assert_eq!(grouped.due_week.len(), 1);
+        assert_eq!(grouped.no_due_date.len(), 1);
+    }
+
+    #[test]
+    fn status_counts_come_from_the_fixture_cache() {
+        let cache = Cache {
+            tasks: Some(vec![
+                task("overdue", Some("2024-01-10")),
+                task("today", Some("2024-01-15")),
+            ]),
+            completed_today: Some(vec![
+                CompletedTask {
+                    gid: "done".to_string(),
+                    name: "done today".to_string(),
+                    completed_at: Some(Local.with_ymd_and_hms(2024, 1, 15, 9, 0, 0).unwrap()),
+                    projects: Vec::new(),
+                },
+                CompletedTask {
+                    gid: "stale".to_string(),
+                    name: "done yesterday".to_string(),
+                    completed_at: Some(Local.with_ymd_and_hms(2024, 1, 14, 21, 0, 0).unwrap()),
+                    projects: Vec::new(),
+                },
+            ]),
+            ..Cache::default()
+        };
+        let app = app_with_cache(cache);
+
+        let status = app
+            .status_at(Local.with_ymd_and_hms(2024, 1, 15, 10, 0, 0).unwrap())
+            .unwrap();
+        assert_eq!(status.overdue, 1);
+        assert_eq!(status.due_today, 1);
+        assert_eq!(status.done_today, 1);
+        // No focus day is cached and the morning is still running, so the routine is pending
+        // but the evening one is not.
+        assert!(status.morning_pending);
+        assert!(!status.evening_pending);
+    }
+
+    #[test]
+    fn status_errors_before_any_tasks_are_cached() {
+        let app = app_with_cache(Cache::default());
+        let error = app
+            .status_at(Local.with_ymd_and_hms(2024, 1, 15, 10, 0, 0).unwrap())
+            .unwrap_err();
+        assert!(error.to_string().contains("no tasks are cached yet"));
+    }
+}
@@ -9,9 +9,9 @@
 //! along with some information about when they were created and when they are due.
 //!
 //! ```no_run
+//! # use chrono::{DateTime, Local, NaiveDate};
 //! # use serde::{Deserialize, Serialize};
-//! # use todo::asana::{Client, DataRequest};
-//! # use todo::asana::execute_authorization_flow;
+//! # use todo::asana::{Client, Credentials, DataRequest};
 //! #[derive(Debug, Deserialize, Serialize)]
 //! struct Task {
 //!     gid: String,
@@ -35,18 +35,17 @@
 //!     }
 //!
 //!     fn fields() -> &'a [&'a str] {
-//!         &["this.gid", "this.created_at", "this.due_on", "this.name"]
+//!         &["gid", "created_at", "due_on", "name"]
 //!     }
 //!
-//!     fn params() -> Vec<(&'a str, String)> {
+//!     fn params(_: &'a Self::RequestData) -> Vec<(&'a str, String)> {
 //!         vec![("completed_since", "now".to_string())]
 //!     }
 //! }
 //!
 //! # async fn run() -> anyhow::Result<()> {
-//! let credentials = execute_authorization_flow().await?;
-//! let mut client = Client::new(credentials)?;
-//! let tasks: Vec<Task> = client.get::<Task>("user_task_list_gid".to_string()).await?;
+//! let mut client = Client::new(Credentials::PersonalAccessToken("pat".to_string()))?;
+//! let tasks: Vec<Task> = client.get::<Task>(&"user_task_list_gid".to_string()).await?;
 //! # Ok(())
 //! # }
 //! ```
@@ -57,7 +56,9 @@
 
 use anyhow::Context;
 use chrono::{DateTime, Duration, Local};
+#[cfg(feature = "cli")]
 use console::{style, Term};
+#[cfg(feature = "cli")]
 use dialoguer::{theme::ColorfulTheme, Input};
 use oauth2::{reqwest::async_http_client, TokenResponse};
 use reqwest::{Method, StatusCode, Url};
@@ -69,25 +70,67 @@ const OAUTH_AUTHORIZATION_URL: &str = "https://app.asana.com/-/oauth_authorize";
 const OAUTH_TOKEN_URL: &str = "https://app.asana.com/-/oauth_token";
 const OAUTH_LOCAL_REDIRECT_URI: &str = "urn:ietf:wg:oauth:2.0:oob";
 
+// Asana rejects uploads over 100 MB, so oversized files fail here with a clear message
+// instead of streaming the whole file up only to get a server error back.
+const ATTACHMENT_SIZE_LIMIT_BYTES: u64 = 100 * 1024 * 1024;
+
 const APP_CLIENT_ID: &str = "1206215514588292";
 const APP_CLIENT_SECRET: &str = "8c7ea1c603de8462a3ba24f827ff1658";
 
 /// Comprehensive set of authorization credentials for the client.
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Deserialize, Eq, PartialEq, Serialize)]
 pub enum Credentials {
-    /// OAuth2 authorization credentials for the client.
+    /// `OAuth2` authorization credentials for the client.
     OAuth2 {
-        /// OAuth2 access token, read more at <https://oauth.net/2/access-tokens/>
+        /// `OAuth2` access token, read more at <https://oauth.net/2/access-tokens/>
         access_token: String,
-        /// OAuth2 refresh token, read more at <https://oauth.net/2/refresh-tokens/>
+        /// `OAuth2` refresh token, read more at <https://oauth.net/2/refresh-tokens/>
         refresh_token: Option<String>,
     },
     /// Personal access token, read more at <https://developers.asana.com/docs/personal-access-token>
     PersonalAccessToken(String),
 }
 
+/// Tokens are secrets, so the derived `Debug` is replaced with one that prints only the
+/// credential kind and the last four characters of each token. Trace-level cache dumps and
+/// panic reports routinely format structures that carry credentials, and none of them may ever
+/// leak a full bearer token.
+impl std::fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OAuth2 {
+                access_token,
+                refresh_token,
+            } => f
+                .debug_struct("OAuth2")
+                .field("access_token", &redact_token(access_token))
+                .field(
+                    "refresh_token",
+                    &refresh_token.as_deref().map(redact_token),
+                )
+                .finish(),
+            Self::PersonalAccessToken(token) => f
+                .debug_tuple("PersonalAccessToken")
+                .field(&redact_token(token))
+                .finish(),
+        }
+    }
+}
+
+/// The last four characters of a token behind an ellipsis, enough to tell two tokens apart
+/// without revealing either. Tokens too short for the tail to be safe collapse to the ellipsis.
+fn redact_token(token: &str) -> String {
+    let length = token.chars().count();
+    if length > 8 {
+        let tail: String = token.chars().skip(length - 4).collect();
+        format!("…{tail}")
+    } else {
+        "…".to_string()
+    }
+}
+
 fn setup_oauth_client() -> anyhow::Result<oauth2::basic::BasicClient> {
-    log::debug!("Setting up OAuth client...");
+    tracing::debug!("Setting up OAuth client...");
     Ok(oauth2::basic::BasicClient::new(
         oauth2::ClientId::new(APP_CLIENT_ID.to_string()),
         Some(oauth2::ClientSecret::new(APP_CLIENT_SECRET.to_string())),
@@ -121,10 +164,11 @@ fn setup_oauth_client() -> anyhow::Result<oauth2::basic::BasicClient> {
 /// # See Also
 ///
 /// - [Asana API PAT documentation](https://developers.asana.com/docs/personal-access-token)
+#[cfg(feature = "cli")]
 pub fn ask_for_pat() -> anyhow::Result<Credentials> {
     let pat_page_url = Url::parse("https://app.asana.com/0/my-apps")?;
 
-    log::info!("Opening browser to PAT page...");
+    tracing::info!("Opening browser to PAT page...");
     Term::stdout().write_line(
       &style(format!(
           "Opening your browser and sending you to {pat_page_url}...\nGenerate a new personal access token, and once you're done, come back here and post the token you got."
@@ -171,17 +215,18 @@ pub fn ask_for_pat() -> anyhow::Result<Credentials> {
 /// - [OAuth2 documentation](https://oauth.net/2/)
 /// - [OAuth2 RFC](https://tools.ietf.org/html/rfc6749)
 /// - [OAuth2 for Native Apps RFC](https://tools.ietf.org/html/rfc8252)
+#[cfg(feature = "cli")]
 pub async fn execute_authorization_flow() -> anyhow::Result<Credentials> {
     let oauth_client = setup_oauth_client()?;
 
-    log::debug!("Setting up authorization request...");
+    tracing::debug!("Setting up authorization request...");
     let (pkce_challenge, pkce_verifier) = oauth2::PkceCodeChallenge::new_random_sha256();
     let (auth_url, _) = oauth_client
         .authorize_url(oauth2::CsrfToken::new_random)
         .set_pkce_challenge(pkce_challenge)
         .url();
 
-    log::info!("Opening browser to authorization URL...");
+    tracing::info!("Opening browser to authorization URL...");
     Term::stdout().write_line(
         &style(format!(
             "Opening your browser and sending you to {auth_url}...\nOnce you're done, come back here and post the code you got."
@@ -192,12 +237,12 @@ pub async fn execute_authorization_flow() -> anyhow::Result<Credentials> {
     open::that_detached(auth_url.to_string())
         .context("could not open authorization URL in the browser")?;
 
-    log::info!("Waiting for user to provide the authorization code...");
+    tracing::info!("Waiting for user to provide the authorization code...");
     let auth_code = Input::<String>::with_theme(&ColorfulTheme::default())
         .with_prompt("auth code")
         .interact_text()?;
 
-    log::info!("Exchanging authorization code for an access token...");
+    tracing::info!("Exchanging authorization code for an access token...");
     let token = oauth_client
         .exchange_code(oauth2::AuthorizationCode::new(auth_code.trim().to_string()))
         .set_pkce_verifier(pkce_verifier)
@@ -205,10 +250,8 @@ pub async fn execute_authorization_flow() -> anyhow::Result<Credentials> {
         .await
         .context("could not exchange authorization code for an access token")?;
     let credentials = Credentials::OAuth2 {
-        access_token: token.access_token().secret().to_string(),
-        refresh_token: token
-            .refresh_token()
-            .map(|token| token.secret().to_string()),
+        access_token: token.access_token().secret().clone(),
+        refresh_token: token.refresh_token().map(|token| token.secret().clone()),
     };
 
     Ok(credentials)
@@ -225,7 +268,8 @@ pub async fn execute_authorization_flow() -> anyhow::Result<Credentials> {
 /// ```no_run
 /// # use todo::asana::refresh_authorization;
 /// # async fn run() -> anyhow::Result<()> {
-/// let credentials = refresh_authorization(&"refresh_token".to_string()).await?;
+/// let credentials =
+///     refresh_authorization(&oauth2::RefreshToken::new("refresh_token".to_string())).await?;
 /// # Ok(())
 /// # }
 /// ```
@@ -247,19 +291,45 @@ pub async fn refresh_authorization(
         .await
         .context("could not exchange refresh token for an access token")?;
     let credentials = Credentials::OAuth2 {
-        access_token: token.access_token().secret().to_string(),
-        refresh_token: Some(
-            token
-                .refresh_token()
-                .unwrap_or(refresh_token)
-                .secret()
-                .to_string(),
-        ),
+        access_token: token.access_token().secret().clone(),
+        refresh_token: Some(token.refresh_token().unwrap_or(refresh_token).secret().clone()),
     };
 
     Ok(credentials)
 }
 
+/// How long to keep retrying the auth lock while a sibling process refreshes, and how long each
+/// retry waits. The budget comfortably covers a token exchange; anything holding the lock longer
+/// is the interactive flow, which is worth giving up on rather than queueing behind.
+const AUTH_LOCK_ATTEMPTS: u32 = 25;
+const AUTH_LOCK_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// How recently a sibling process must have refreshed for its stored token to be adopted in
+/// place of our own exchange, in minutes. Older tokens may be just as expired as the one being
+/// replaced.
+const STORED_REFRESH_MAX_AGE_MINUTES: i64 = 5;
+
+/// Credentials another process stored since ours were loaded, if they were refreshed recently
+/// enough to adopt: the stored access token differs from the one we hold and the recorded
+/// refresh time is within [`STORED_REFRESH_MAX_AGE_MINUTES`].
+fn freshly_stored_credentials(
+    cache_path: &std::path::Path,
+    current_access_token: &str,
+) -> anyhow::Result<Option<Credentials>> {
+    let cache = crate::cache::load(cache_path)?;
+    let refreshed_recently = cache
+        .creds_refreshed_at
+        .is_some_and(|at| at + Duration::minutes(STORED_REFRESH_MAX_AGE_MINUTES) > Local::now());
+    match &cache.creds {
+        Some(Credentials::OAuth2 { access_token, .. })
+            if access_token != current_access_token && refreshed_recently =>
+        {
+            Ok(cache.creds)
+        }
+        _ => Ok(None),
+    }
+}
+
 /// Trait for types that can be used to make requests to the Asana API.
 ///
 /// # Examples
@@ -289,10 +359,10 @@ pub async fn refresh_authorization(
 ///     }
 ///
 ///     fn fields() -> &'a [&'a str] {
-///         &["this.name"]
+///         &["name"]
 ///     }
 ///
-///     fn params() -> Vec<(&'a str, String)> {
+///     fn params(_: &'a Self::RequestData) -> Vec<(&'a str, String)> {
 ///         vec![("completed_since", "now".to_string())]
 ///     }
 /// }
@@ -312,20 +382,118 @@ pub trait DataRequest<'a> {
     #[must_use]
     fn segments(request_data: &'a Self::RequestData) -> Vec<String>;
 
-    /// Get the fields to query the Asana API for.
+    /// Get the fields to query the Asana API for, as bare field paths like `gid` or
+    /// `projects.name`.
     ///
     /// This should line up with the fields in `ResponseData` and must follow the `opt_fields` described in the [Asana
-    /// API input/output options documentation](https://developers.asana.com/docs/inputoutput-options).
+    /// API input/output options documentation](https://developers.asana.com/docs/inputoutput-options). The client
+    /// prefixes every path with `this.` when building the query, so implementations never hand-type the prefix (a
+    /// typo there yields silent nulls rather than an error).
     #[must_use]
     fn fields() -> &'a [&'a str];
 
     /// Get any additional query parameters to use when making the request.
     #[must_use]
-    fn params() -> Vec<(&'a str, String)> {
+    fn params(request_data: &'a Self::RequestData) -> Vec<(&'a str, String)> {
+        let _ = request_data;
         vec![]
     }
 }
 
+/// A mutation of the Asana API, described the same way [`DataRequest`] describes a read: URL
+/// segments typed by the data that addresses them, plus the HTTP method to send. The
+/// implementing type is the request body, sent under the standard `data` envelope.
+///
+/// Implementations live next to the body types they describe; [`Client::mutate`] joins the
+/// segments onto the client's base URL, so call sites never hand-format an absolute URL and the
+/// same code reaches a mock server in tests.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use reqwest::Method;
+/// # use serde::Serialize;
+/// # use todo::asana::MutationRequest;
+/// #[derive(Serialize)]
+/// struct CompleteTask {
+///     completed: bool,
+/// }
+///
+/// impl<'a> MutationRequest<'a> for CompleteTask {
+///     type RequestData = String;
+///     type ResponseData = serde_json::Value;
+///
+///     fn segments(task_gid: &'a Self::RequestData) -> Vec<String> {
+///         vec!["tasks".to_string(), task_gid.clone()]
+///     }
+///
+///     fn method() -> Method {
+///         Method::PUT
+///     }
+/// }
+/// ```
+pub trait MutationRequest<'a>: Serialize {
+    /// Type of additional data that is required to address the request, e.g. a task gid.
+    type RequestData: 'a;
+    /// Type of data that is returned by the request. Mutations whose callers never inspect the
+    /// response use [`serde_json::Value`].
+    type ResponseData: DeserializeOwned;
+
+    /// Get the segments of the URL that are required to make the request.
+    #[must_use]
+    fn segments(request_data: &'a Self::RequestData) -> Vec<String>;
+
+    /// Get the HTTP method to send the request with.
+    #[must_use]
+    fn method() -> Method;
+}
+
+/// Render a list of bare field paths as the `opt_fields` query value, prefixing each with
+/// `this.`.
+#[must_use]
+pub fn opt_fields(fields: &[&str]) -> String {
+    fields
+        .iter()
+        .map(|field| format!("this.{field}"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Test-time check that every path in a [`DataRequest`]'s `fields()` resolves to a key on a
+/// serialized sample of the type, so a typo in one of the lists fails a unit test instead of
+/// coming back from Asana as silent nulls (or a 400) at runtime.
+///
+/// The walk descends into arrays, so `projects.name` is checked against every element of a
+/// serialized `projects` list. Optional fields in the sample must be populated: a `None`
+/// serializes to `null`, which has no keys for nested segments to resolve against.
+#[cfg(test)]
+pub(crate) fn assert_opt_fields_match<D>(sample: &D)
+where
+    D: for<'a> DataRequest<'a> + Serialize,
+{
+    let sample = serde_json::to_value(sample).expect("sample serializes");
+    for field in <D as DataRequest>::fields() {
+        let mut values = vec![&sample];
+        for segment in field.split('.') {
+            let mut next = Vec::new();
+            for value in &values {
+                match value {
+                    serde_json::Value::Array(items) => {
+                        next.extend(items.iter().filter_map(|item| item.get(segment)));
+                    }
+                    value => next.extend(value.get(segment)),
+                }
+            }
+            assert!(
+                !next.is_empty(),
+                "opt_fields path `{field}` does not resolve past `{segment}` on the sample; \
+                 does it match the struct's serde field names?"
+            );
+            values = next;
+        }
+    }
+}
+
 /// Wrapper for data exchanged with the Asana API.
 ///
 /// This wrapper is used to serialize data to the Asana API or deserialize from it, since the Asana API expects a
@@ -336,10 +504,174 @@ pub struct DataWrapper<D> {
     pub data: D,
 }
 
+/// An attachment created on a task, as returned by the upload endpoint.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Attachment {
+    /// Globally unique identifier of the attachment.
+    pub gid: String,
+    /// URL of the attachment in the Asana UI, when the API returns one.
+    pub permalink_url: Option<String>,
+}
+
 #[derive(Debug, Error)]
 enum ClientError {
     #[error("unable to refresh access token: {0}")]
     UnableToRefreshAccessToken(String),
+    /// Authorization is needed but the client may not prompt or open a browser; detected via
+    /// [`is_auth_required`] so scriptable callers can bail with a hint instead of an error dump.
+    #[error("authorization required; run `todo update` to authenticate")]
+    AuthRequired,
+}
+
+/// Error returned when a mutating request is attempted while the client is in dry-run mode.
+///
+/// Commands are expected to check the dry-run state themselves and narrate the skipped mutation;
+/// this error is the backstop for any path that forgets.
+#[derive(Clone, Copy, Debug, Error)]
+#[error("refusing to send a mutating request in dry-run mode")]
+pub struct DryRunError;
+
+/// Error returned when any request is attempted while the client is in offline mode.
+///
+/// Offline commands are expected to read exclusively from the cache; this error is the backstop
+/// for any path that falls through to the network anyway.
+#[derive(Clone, Copy, Debug, Error)]
+#[error("network disabled in offline mode; this data is not in the cache")]
+pub struct OfflineError;
+
+/// Error returned when the Asana API responds with a non-success status.
+///
+/// Carries the status and the first message from Asana's structured error body when one was
+/// present, so callers (and the top-level error renderer) can react to specific statuses
+/// instead of failing later with a deserialization error.
+#[derive(Debug, Error)]
+#[error("Asana returned {status}: {}", message.as_deref().unwrap_or("no further details"))]
+pub struct ApiError {
+    /// HTTP status of the response.
+    pub status: StatusCode,
+    /// First `errors[].message` from the response body, when Asana provided one.
+    pub message: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ApiErrorBody {
+    errors: Vec<ApiErrorDetail>,
+}
+
+#[derive(Deserialize)]
+struct ApiErrorDetail {
+    message: String,
+}
+
+/// Convert a non-success response into an [`ApiError`], pulling the message out of Asana's
+/// structured error body when it parses as one.
+async fn into_api_error(response: reqwest::Response) -> ApiError {
+    let status = response.status();
+    let message = response
+        .json::<ApiErrorBody>()
+        .await
+        .ok()
+        .and_then(|body| body.errors.into_iter().next())
+        .map(|detail| detail.message);
+    ApiError { status, message }
+}
+
+/// Timing record for a single API request.
+#[derive(Clone, Debug)]
+pub struct RequestTiming {
+    /// HTTP method of the request.
+    pub method: Method,
+    /// URL path with every gid segment redacted to `:gid`, so timings aggregate per endpoint.
+    pub path: String,
+    /// HTTP status of the response, unset when no response arrived (e.g. a connection failure).
+    pub status: Option<StatusCode>,
+    /// Wall-clock time the request took.
+    pub elapsed: std::time::Duration,
+}
+
+/// Per-run collection of request timings, shared by every clone of a [`Client`].
+///
+/// A plain mutex around a vector: each request takes the lock once for a push, which is far off
+/// the hot path compared to the network round trip it records.
+pub type RequestTimings = std::sync::Arc<std::sync::Mutex<Vec<RequestTiming>>>;
+
+/// Replace every numeric gid segment in a URL path with `:gid`.
+///
+/// Gids are both noise and (mildly) sensitive, so recorded paths keep only the endpoint shape.
+#[must_use]
+pub fn redact_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| {
+            if !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit()) {
+                ":gid"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Render the `--timings` table: count, total, and max elapsed per method and endpoint pattern.
+#[must_use]
+pub fn render_timings(timings: &[RequestTiming]) -> String {
+    use std::fmt::Write as _;
+
+    let mut rows: std::collections::BTreeMap<
+        (String, String),
+        (usize, std::time::Duration, std::time::Duration),
+    > = std::collections::BTreeMap::new();
+    for timing in timings {
+        let row = rows
+            .entry((timing.method.to_string(), timing.path.clone()))
+            .or_default();
+        row.0 += 1;
+        row.1 += timing.elapsed;
+        row.2 = row.2.max(timing.elapsed);
+    }
+
+    let mut string = String::from("count    total      max  endpoint\n");
+    for ((method, path), (count, total, max)) in rows {
+        let _ = writeln!(
+            string,
+            "{count:>5}  {total:>6}ms  {max:>5}ms  {method} {path}",
+            total = total.as_millis(),
+            max = max.as_millis(),
+        );
+    }
+    string
+}
+
+/// Whether an error chain bottoms out in a connection or timeout failure, i.e. the kind of
+/// error that suggests the machine is offline rather than the request being wrong.
+#[must_use]
+pub fn is_network_error(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .filter_map(|cause| cause.downcast_ref::<reqwest::Error>())
+        .any(|cause| cause.is_connect() || cause.is_timeout())
+}
+
+/// Whether an error chain bottoms out in the client refusing to start the interactive
+/// authorization flow, i.e. the caller needs `todo update` run in a real terminal first.
+#[must_use]
+pub fn is_auth_required(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<ClientError>(),
+            Some(ClientError::AuthRequired)
+        )
+    })
+}
+
+/// Whether an error chain contains an [`ApiError`] with a 404 status, i.e. the target of the
+/// request no longer exists — usually because it was deleted in the Asana UI.
+#[must_use]
+pub fn is_not_found(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .filter_map(|cause| cause.downcast_ref::<ApiError>())
+        .any(|api_error| api_error.status == StatusCode::NOT_FOUND)
 }
 
 /// Client for the Asana API.
@@ -352,14 +684,17 @@ enum ClientError {
 /// specify the data that is required to make the request, the data that is returned by the request, and the fields to
 /// query the Asana API for.
 ///
+/// Cloning the client is cheap and clones share one underlying HTTP connection pool (plus the
+/// per-run request timings), so spawned tasks should clone an existing client rather than
+/// construct a fresh one.
+///
 /// # Examples
 ///
 /// The following example shows how to use the client to get all the names of incomplete tasks in a user's task list.
 ///
 /// ```no_run
-/// # use todo::asana::{Client, DataRequest};
+/// # use todo::asana::{Client, Credentials, DataRequest};
 /// # use serde::{Deserialize, Serialize};
-/// # use todo::asana::execute_authorization_flow;
 ///
 /// #[derive(Deserialize, Serialize)]
 /// struct Task {
@@ -379,18 +714,17 @@ enum ClientError {
 ///     }
 ///
 ///     fn fields() -> &'static [&'static str] {
-///         &["this.name"]
+///         &["name"]
 ///     }
 ///
-///     fn params() -> Vec<(&'a str, String)> {
+///     fn params(_: &'a Self::RequestData) -> Vec<(&'a str, String)> {
 ///         vec![("completed_since", "now".to_string())]
 ///     }
 /// }
 ///
 /// # async fn run() -> anyhow::Result<()> {
-/// let credentials = execute_authorization_flow().await?;
-/// let mut client = Client::new(credentials)?;
-/// let tasks: Vec<Task> = client.get::<Task>("user_task_list_gid".to_string()).await?;
+/// let mut client = Client::new(Credentials::PersonalAccessToken("pat".to_string()))?;
+/// let tasks: Vec<Task> = client.get::<Task>(&"user_task_list_gid".to_string()).await?;
 /// # Ok(())
 /// # }
 /// ````
@@ -398,18 +732,130 @@ enum ClientError {
 pub struct Client {
     base_url: Url,
     credentials: Credentials,
-    inner_client: reqwest::Client,
+    dry_run: bool,
+    offline: bool,
+    interactive_auth: bool,
+    cache_path: Option<std::path::PathBuf>,
+    inner: reqwest::Client,
+    timings: RequestTimings,
 
     last_refresh_attempt: Option<DateTime<Local>>,
 }
 
+/// Connection settings for the HTTP client underneath a [`Client`].
+#[derive(Clone, Debug)]
+struct HttpOptions {
+    connect_timeout: std::time::Duration,
+    timeout: std::time::Duration,
+    user_agent: String,
+    proxy: Option<Url>,
+}
+
+impl Default for HttpOptions {
+    fn default() -> Self {
+        Self {
+            connect_timeout: std::time::Duration::from_secs(5),
+            timeout: std::time::Duration::from_secs(10),
+            user_agent: concat!("todo/", env!("CARGO_PKG_VERSION")).to_string(),
+            proxy: None,
+        }
+    }
+}
+
+/// Builder for a [`Client`], for when the defaults of [`Client::new`] do not fit: slow corporate
+/// proxies that need longer timeouts, tight prompt integrations that need shorter ones, or tests
+/// that point the client at a mock server.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use todo::asana::{Client, Credentials};
+/// # fn run() -> anyhow::Result<()> {
+/// let client = Client::builder(Credentials::PersonalAccessToken("pat".to_string()))
+///     .timeout(std::time::Duration::from_secs(30))
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ClientBuilder {
+    credentials: Credentials,
+    base_url: Option<Url>,
+    options: HttpOptions,
+}
+
+impl ClientBuilder {
+    /// Set the connection timeout, 5 seconds by default.
+    #[must_use]
+    pub fn connect_timeout(mut self, connect_timeout: std::time::Duration) -> Self {
+        self.options.connect_timeout = connect_timeout;
+        self
+    }
+
+    /// Set the overall per-request timeout, 10 seconds by default.
+    #[must_use]
+    pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.options.timeout = timeout;
+        self
+    }
+
+    /// Set the user agent sent with every request, `todo/<crate version>` by default.
+    #[must_use]
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.options.user_agent = user_agent.into();
+        self
+    }
+
+    /// Route all requests through the given HTTP(S) proxy; no proxy by default.
+    #[must_use]
+    pub fn proxy(mut self, proxy: Url) -> Self {
+        self.options.proxy = Some(proxy);
+        self
+    }
+
+    /// Talk to `base_url` instead of the real Asana API.
+    #[must_use]
+    pub fn base_url(mut self, base_url: Url) -> Self {
+        self.base_url = Some(base_url);
+        self
+    }
+
+    /// Build the client.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the inner HTTP client could not be constructed.
+    pub fn build(self) -> anyhow::Result<Client> {
+        tracing::debug!("Setting up Asana client...");
+        Ok(Client {
+            base_url: match self.base_url {
+                Some(base_url) => base_url,
+                None => Url::parse(API_BASE_URL)?,
+            },
+            inner: Client::construct_inner_client(&self.options)?,
+            credentials: self.credentials,
+            dry_run: false,
+            offline: false,
+            interactive_auth: true,
+            cache_path: None,
+            timings: RequestTimings::default(),
+            last_refresh_attempt: None,
+        })
+    }
+}
+
 impl Client {
-    fn construct_inner_client() -> anyhow::Result<reqwest::Client> {
-        reqwest::ClientBuilder::new()
-            .connect_timeout(Duration::seconds(5).to_std()?)
-            .timeout(Duration::seconds(10).to_std()?)
-            .build()
-            .context("could not build Asana client")
+    fn construct_inner_client(options: &HttpOptions) -> anyhow::Result<reqwest::Client> {
+        let mut builder = reqwest::ClientBuilder::new()
+            .connect_timeout(options.connect_timeout)
+            .timeout(options.timeout)
+            .user_agent(&options.user_agent);
+        if let Some(proxy) = &options.proxy {
+            builder = builder.proxy(
+                reqwest::Proxy::all(proxy.clone()).context("could not configure Asana proxy")?,
+            );
+        }
+        builder.build().context("could not build Asana client")
     }
 
     fn get_authorization_token(&self) -> &str {
@@ -422,29 +868,67 @@ impl Client {
         }
     }
 
+    /// Record a finished request into the per-run timings and emit it at debug level.
+    fn record_timing(
+        &self,
+        method: Method,
+        url: &Url,
+        status: Option<StatusCode>,
+        elapsed: std::time::Duration,
+    ) {
+        let path = redact_path(url.path());
+        tracing::debug!(
+            "{method} {path} -> {status} in {elapsed:?}",
+            status = status.map_or("no response".to_string(), |s| s.to_string())
+        );
+        if let Ok(mut timings) = self.timings.lock() {
+            timings.push(RequestTiming {
+                method,
+                path,
+                status,
+                elapsed,
+            });
+        }
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        name = "request",
+        skip_all,
+        fields(endpoint = %redact_path(url.path()))
+    )]
     async fn make_get_request(&self, url: &Url) -> anyhow::Result<reqwest::Response> {
-        self.inner_client
+        let started = std::time::Instant::now();
+        let response = self
+            .inner
             .get(url.clone())
             .bearer_auth(self.get_authorization_token())
             .send()
-            .await
-            .context("failed to make request")
+            .await;
+        self.record_timing(
+            Method::GET,
+            url,
+            response.as_ref().ok().map(reqwest::Response::status),
+            started.elapsed(),
+        );
+        response.context("failed to make request")
     }
 
     /// Make a POST or PUT request to the Asana API.
     ///
     /// # Errors
     ///
-    /// This function will return an error if the request could not be made.
+    /// This function will return an error if the request could not be made, an [`ApiError`] when
+    /// Asana responds with a non-success status, and a [`DryRunError`] without touching the
+    /// network when the client is in dry-run mode.
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// # use todo::asana::Client;
-    /// # use todo::asana::execute_authorization_flow;
+    /// # use serde::Serialize;
+    /// # use todo::asana::{Client, Credentials};
     /// # async fn run() -> anyhow::Result<()> {
-    /// let credentials = execute_authorization_flow().await?;
-    /// let mut client = Client::new(credentials)?;
+    /// let mut client = Client::new(Credentials::PersonalAccessToken("pat".to_string()))?;
     ///
     /// #[derive(Serialize)]
     /// struct TaskCreation {
@@ -457,19 +941,149 @@ impl Client {
     /// # Ok(())
     /// # }
     /// ```
+    #[tracing::instrument(
+        level = "debug",
+        name = "request",
+        skip_all,
+        fields(endpoint = %redact_path(url.path()), method = %method)
+    )]
     pub async fn mutate_request(
         &self,
         method: Method,
         url: &Url,
         body: impl Serialize,
     ) -> anyhow::Result<reqwest::Response> {
-        self.inner_client
-            .request(method, url.clone())
+        if self.offline {
+            return Err(OfflineError.into());
+        }
+        if self.dry_run {
+            return Err(DryRunError.into());
+        }
+        let started = std::time::Instant::now();
+        let response = self
+            .inner
+            .request(method.clone(), url.clone())
             .bearer_auth(self.get_authorization_token())
             .json(&body)
             .send()
+            .await;
+        self.record_timing(
+            method,
+            url,
+            response.as_ref().ok().map(reqwest::Response::status),
+            started.elapsed(),
+        );
+        let response = response.context("failed to make request")?;
+        if !response.status().is_success() {
+            return Err(into_api_error(response).await.into());
+        }
+        Ok(response)
+    }
+
+    /// Perform the mutation described by a [`MutationRequest`], returning its parsed response.
+    ///
+    /// The URL is the mutation's segments joined onto the client's base URL, so the same call
+    /// site reaches the real API and a mock server alike. Offline and dry-run modes refuse the
+    /// request exactly as [`Client::mutate_request`] does.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the client is offline or in dry-run mode, the
+    /// request fails, or the response could not be parsed.
+    pub async fn mutate<'a, M: MutationRequest<'a> + 'a>(
+        &self,
+        request_data: &'a M::RequestData,
+        body: M,
+    ) -> anyhow::Result<M::ResponseData> {
+        let url = self.base_url.join(&M::segments(request_data).join("/"))?;
+        let response = self
+            .mutate_request(M::method(), &url, DataWrapper { data: body })
+            .await?;
+        Ok(response
+            .json::<DataWrapper<M::ResponseData>>()
             .await
-            .context("failed to make request")
+            .context("unable to parse the mutation response")?
+            .data)
+    }
+
+    /// Upload the file at `path` as an attachment on the task with the given gid.
+    ///
+    /// The file is sent as `multipart/form-data` under the `file` field, carrying the file's
+    /// name and the given content type. Files over Asana's 100 MB attachment limit are
+    /// rejected before any bytes leave the machine. Offline and dry-run modes refuse the
+    /// request exactly as [`Client::mutate_request`] does.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the client is offline or in dry-run mode, the
+    /// file could not be read or is over the size limit, the content type is invalid, the
+    /// request fails, or the response could not be parsed.
+    #[tracing::instrument(
+        level = "debug",
+        name = "upload",
+        skip_all,
+        fields(task_gid = %task_gid)
+    )]
+    pub async fn upload(
+        &self,
+        task_gid: &str,
+        path: &std::path::Path,
+        content_type: &str,
+    ) -> anyhow::Result<Attachment> {
+        if self.offline {
+            return Err(OfflineError.into());
+        }
+        if self.dry_run {
+            return Err(DryRunError.into());
+        }
+        let metadata = tokio::fs::metadata(path)
+            .await
+            .with_context(|| format!("could not read {}", path.display()))?;
+        if metadata.len() > ATTACHMENT_SIZE_LIMIT_BYTES {
+            anyhow::bail!(
+                "{path} is {size} MB, over Asana's {limit} MB attachment limit",
+                path = path.display(),
+                size = metadata.len() / (1024 * 1024),
+                limit = ATTACHMENT_SIZE_LIMIT_BYTES / (1024 * 1024),
+            );
+        }
+        let file_name = path.file_name().map_or_else(
+            || "attachment".to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        );
+        let bytes = tokio::fs::read(path)
+            .await
+            .with_context(|| format!("could not read {}", path.display()))?;
+        let part = reqwest::multipart::Part::bytes(bytes)
+            .file_name(file_name)
+            .mime_str(content_type)
+            .with_context(|| format!("invalid content type `{content_type}`"))?;
+        let form = reqwest::multipart::Form::new().part("file", part);
+
+        let url = self.base_url.join(&format!("tasks/{task_gid}/attachments"))?;
+        let started = std::time::Instant::now();
+        let response = self
+            .inner
+            .request(Method::POST, url.clone())
+            .bearer_auth(self.get_authorization_token())
+            .multipart(form)
+            .send()
+            .await;
+        self.record_timing(
+            Method::POST,
+            &url,
+            response.as_ref().ok().map(reqwest::Response::status),
+            started.elapsed(),
+        );
+        let response = response.context("failed to make request")?;
+        if !response.status().is_success() {
+            return Err(into_api_error(response).await.into());
+        }
+        Ok(response
+            .json::<DataWrapper<Attachment>>()
+            .await
+            .context("unable to parse the attachment response")?
+            .data)
     }
 
     /// Create a new client with the given credentials.
@@ -481,22 +1095,43 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// # use todo::asana::Client;
-    /// # use todo::asana::execute_authorization_flow;
+    /// # use todo::asana::{Client, Credentials};
     /// # async fn run() -> anyhow::Result<()> {
-    /// let credentials = execute_authorization_flow().await?;
-    /// let client = Client::new(credentials)?;
+    /// let client = Client::new(Credentials::PersonalAccessToken("pat".to_string()))?;
     /// # Ok(())
     /// # }
     /// ```
     pub fn new(credentials: Credentials) -> anyhow::Result<Client> {
-        log::debug!("Setting up Asana client...");
-        Ok(Client {
-            base_url: Url::parse(API_BASE_URL)?,
-            inner_client: Client::construct_inner_client()?,
+        Client::builder(credentials).build()
+    }
+
+    /// Start building a client with the given credentials, for overriding the connection
+    /// defaults that [`Client::new`] uses. See [`ClientBuilder`] for the knobs.
+    #[must_use]
+    pub fn builder(credentials: Credentials) -> ClientBuilder {
+        ClientBuilder {
             credentials,
-            last_refresh_attempt: None,
-        })
+            base_url: None,
+            options: HttpOptions::default(),
+        }
+    }
+
+    /// Handle to the per-run request timings, shared with every clone of this client.
+    #[must_use]
+    pub fn timings(&self) -> RequestTimings {
+        std::sync::Arc::clone(&self.timings)
+    }
+
+    /// Create a client that talks to `base_url` instead of the real Asana API.
+    ///
+    /// Everything else behaves identically to [`Client::new`]; this is how tests point the
+    /// client at a local mock server.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the inner client could not be constructed.
+    pub fn new_with_base_url(credentials: Credentials, base_url: Url) -> anyhow::Result<Client> {
+        Client::builder(credentials).base_url(base_url).build()
     }
 
     /// Get a reference to the credentials that power this client.
@@ -505,6 +1140,56 @@ impl Client {
         &self.credentials
     }
 
+    /// The base URL requests are made against.
+    ///
+    /// Every [`Client::get`] and [`Client::mutate`] URL is joined onto this, so pointing it at
+    /// a local mock server via [`ClientBuilder::base_url`] redirects the whole client.
+    #[must_use]
+    pub fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+
+    /// Put the client in (or take it out of) dry-run mode, where mutating requests fail with
+    /// [`DryRunError`] instead of reaching the API. Read requests are unaffected.
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
+    /// Whether the client is in dry-run mode.
+    #[must_use]
+    pub fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Put the client in (or take it out of) offline mode, where every request — read or write —
+    /// fails immediately with [`OfflineError`] instead of touching the network.
+    pub fn set_offline(&mut self, offline: bool) {
+        self.offline = offline;
+    }
+
+    /// Whether the client is in offline mode.
+    #[must_use]
+    pub fn offline(&self) -> bool {
+        self.offline
+    }
+
+    /// Allow or forbid the interactive authorization flow when a token refresh has no refresh
+    /// token to fall back on; allowed by default. Forbidden, the refresh fails with an
+    /// "authorization required" error instead of opening a browser, which is what scriptable
+    /// commands like a shell-prompt `status` need.
+    pub fn set_interactive_auth(&mut self, interactive_auth: bool) {
+        self.interactive_auth = interactive_auth;
+    }
+
+    /// Point the client at the cache it was loaded from. The cache's sibling `auth.lock`
+    /// serializes interactive authorization and token refreshes across processes, and refreshed
+    /// credentials are persisted straight back to the cache so sibling processes (and the next
+    /// run) pick up the rotated refresh token instead of the revoked one. Without a path (e.g.
+    /// in tests) refreshes run unserialized and stay in memory.
+    pub fn set_cache_path(&mut self, cache_path: std::path::PathBuf) {
+        self.cache_path = Some(cache_path);
+    }
+
     /// Refresh the access token.
     ///
     /// If no refresh token is available, this will reinitiate the authorization flow.
@@ -516,11 +1201,9 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// # use todo::asana::Client;
-    /// # use todo::asana::execute_authorization_flow;
+    /// # use todo::asana::{Client, Credentials};
     /// # async fn run() -> anyhow::Result<()> {
-    /// let credentials = execute_authorization_flow().await?;
-    /// let mut client = Client::new(credentials)?;
+    /// let mut client = Client::new(Credentials::PersonalAccessToken("pat".to_string()))?;
     /// client.refresh().await?;
     /// # Ok(())
     /// # }
@@ -528,22 +1211,64 @@ impl Client {
     pub async fn refresh(&mut self) -> anyhow::Result<()> {
         match &self.credentials {
             Credentials::OAuth2 {
-                access_token: _,
+                access_token,
                 refresh_token,
             } => {
-                log::debug!("Attempting to refresh the Asana access token...");
+                tracing::debug!("Attempting to refresh the Asana access token...");
                 self.credentials = if let Some(refresh_token) = refresh_token {
-                    log::debug!(
+                    tracing::debug!(
                         "Found a refresh token, attempting to refresh authorization directly..."
                     );
+                    if let Some(cache_path) = self.cache_path.clone() {
+                        // Asana rotates the refresh token on every exchange, so concurrent
+                        // refreshes must be serialized: the loser of an uncoordinated race
+                        // would persist an already-revoked refresh token and log the user out.
+                        return self
+                            .refresh_against_cache(
+                                &cache_path,
+                                &access_token.clone(),
+                                &refresh_token.clone(),
+                            )
+                            .await;
+                    }
                     refresh_authorization(&oauth2::RefreshToken::new(refresh_token.clone())).await?
                 } else {
-                    log::debug!(
-                        "Could not find a refresh token, reinitiating the authorization flow..."
-                    );
-                    execute_authorization_flow().await?
+                    // Without a refresh token the only way forward is the interactive flow,
+                    // which needs a terminal and only exists under the `cli` feature.
+                    #[cfg(feature = "cli")]
+                    {
+                        if !self.interactive_auth {
+                            return Err(ClientError::AuthRequired.into());
+                        }
+                        // Hold the auth guard for the duration of the flow, so concurrent
+                        // commands whose tokens expired together do not open duplicate browser
+                        // tabs and clobber each other's tokens.
+                        let _auth_lock = match &self.cache_path {
+                            Some(cache_path) => {
+                                let Some(lock) = crate::cache::try_auth_lock(cache_path)? else {
+                                    return Err(ClientError::UnableToRefreshAccessToken(
+                                        "another process is already authenticating".to_string(),
+                                    )
+                                    .into());
+                                };
+                                Some(lock)
+                            }
+                            None => None,
+                        };
+                        tracing::debug!(
+                            "Could not find a refresh token, reinitiating the authorization flow..."
+                        );
+                        execute_authorization_flow().await?
+                    }
+                    #[cfg(not(feature = "cli"))]
+                    {
+                        // Without the `cli` feature the interactive flow does not exist at
+                        // all, which is just the always-on version of the same refusal.
+                        return Err(ClientError::AuthRequired.into());
+                    }
                 };
-                self.inner_client = Client::construct_inner_client()?;
+                // Only the bearer token changed; the inner HTTP client (and its warm
+                // connection pool) is untouched.
                 Ok(())
             }
 
@@ -554,6 +1279,57 @@ impl Client {
         }
     }
 
+    /// Exchange the refresh token with cross-process coordination through the cache.
+    ///
+    /// The exchange runs under the auth lock, and both before and after acquiring it the cache
+    /// is re-read: if a sibling process already stored a freshly refreshed token, that token is
+    /// adopted and no exchange happens at all. A successful exchange is persisted back to the
+    /// cache while the lock is still held.
+    async fn refresh_against_cache(
+        &mut self,
+        cache_path: &std::path::Path,
+        current_access_token: &str,
+        refresh_token: &str,
+    ) -> anyhow::Result<()> {
+        let mut attempts = 0;
+        let _auth_lock = loop {
+            if let Some(lock) = crate::cache::try_auth_lock(cache_path)? {
+                break lock;
+            }
+            // Whoever holds the lock is mid-exchange; its result may make ours unnecessary.
+            if let Some(newer) = freshly_stored_credentials(cache_path, current_access_token)? {
+                tracing::debug!("Another process refreshed the token first, adopting it...");
+                self.credentials = newer;
+                return Ok(());
+            }
+            attempts += 1;
+            if attempts > AUTH_LOCK_ATTEMPTS {
+                return Err(ClientError::UnableToRefreshAccessToken(
+                    "another process is already authenticating".to_string(),
+                )
+                .into());
+            }
+            tokio::time::sleep(AUTH_LOCK_RETRY_DELAY).await;
+        };
+        // A previous holder may have stored its result between our last look and the acquire.
+        if let Some(newer) = freshly_stored_credentials(cache_path, current_access_token)? {
+            tracing::debug!("Another process refreshed the token first, adopting it...");
+            self.credentials = newer;
+            return Ok(());
+        }
+
+        let credentials =
+            refresh_authorization(&oauth2::RefreshToken::new(refresh_token.to_string())).await?;
+        // Persist before releasing the lock: the rotated refresh token only exists here, and a
+        // crash between the exchange and the write is the weekly-logout bug all over again.
+        let mut cache = crate::cache::load(cache_path)?;
+        cache.creds = Some(credentials.clone());
+        cache.creds_refreshed_at = Some(Local::now());
+        crate::cache::save(cache_path, &cache)?;
+        self.credentials = credentials;
+        Ok(())
+    }
+
     /// Make a request to the Asana API.
     ///
     /// See documentation for [`DataRequest`] and [`Client`] for more information on how to use
@@ -562,18 +1338,18 @@ impl Client {
     /// # Errors
     ///
     /// This function will return an error if the request could not be made or if the response could not be
-    /// deserialized.
+    /// deserialized, an [`ApiError`] when Asana responds with a non-success status, and an [`OfflineError`]
+    /// without touching the network when the client is in offline mode.
     pub async fn get<'a, D: DataRequest<'a> + 'a>(
         &mut self,
         request_data: &'a D::RequestData,
     ) -> anyhow::Result<D::ResponseData> {
-        let mut url = self.base_url.join(&D::segments(request_data).join("/"))?;
-
-        let fields = D::fields().join(",");
-        let query = &[D::params(), vec![("opt_fields", fields)]].concat();
-        url.query_pairs_mut().extend_pairs(query).finish();
+        if self.offline {
+            return Err(OfflineError.into());
+        }
+        let url = self.request_url::<D>(request_data)?;
 
-        log::debug!("Making a request to {url}...");
+        tracing::debug!("Making a request to {url}...");
         let response = self.make_get_request(&url).await?;
 
         let response = if response.status() == StatusCode::UNAUTHORIZED {
@@ -590,9 +1366,345 @@ impl Client {
         } else {
             response
         };
+        if !response.status().is_success() {
+            let error = into_api_error(response).await;
+            // A 400 on a GET is almost always a bad `opt_fields` entry — Asana names the
+            // offending field in its message — so the fields this request asked for are
+            // attached alongside to make the typo findable without replaying the request.
+            if error.status == StatusCode::BAD_REQUEST {
+                let fields = opt_fields(D::fields());
+                return Err(anyhow::Error::new(error).context(format!(
+                    "Asana rejected the request; the requested opt_fields were: {fields}"
+                )));
+            }
+            return Err(error.into());
+        }
 
         Ok(response.json::<DataWrapper<D::ResponseData>>().await?.data)
     }
+
+    /// Build the full request URL for a [`DataRequest`]: segments joined onto the base URL, the
+    /// request's params, and the generated `opt_fields` value.
+    fn request_url<'a, D: DataRequest<'a> + 'a>(
+        &self,
+        request_data: &'a D::RequestData,
+    ) -> anyhow::Result<Url> {
+        let mut url = self.base_url.join(&D::segments(request_data).join("/"))?;
+        let fields = opt_fields(D::fields());
+        let query = &[D::params(request_data), vec![("opt_fields", fields)]].concat();
+        url.query_pairs_mut().extend_pairs(query).finish();
+        Ok(url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query_pairs(url: &Url) -> Vec<(String, String)> {
+        url.query_pairs().into_owned().collect()
+    }
+
+    #[test]
+    fn request_urls_pin_the_paths_and_query_strings() {
+        let client =
+            Client::new(Credentials::PersonalAccessToken("test-token".to_string())).unwrap();
+
+        let url = client
+            .request_url::<crate::task::UserTask>(&"utl1".to_string())
+            .unwrap();
+        assert_eq!(url.path(), "/api/1.0/user_task_lists/utl1/tasks");
+        assert_eq!(
+            query_pairs(&url),
+            [
+                ("completed_since".to_string(), "now".to_string()),
+                ("limit".to_string(), "100".to_string()),
+                (
+                    "opt_fields".to_string(),
+                    "this.gid,this.created_at,this.due_on,this.name,this.projects.gid,\
+                     this.projects.name,this.custom_fields.gid,this.custom_fields.display_value,\
+                     this.custom_fields.enum_value.gid,this.custom_fields.enum_value.name"
+                        .to_string()
+                ),
+            ]
+        );
+
+        let url = client
+            .request_url::<crate::focus::FocusTask>(&"section1".to_string())
+            .unwrap();
+        assert_eq!(url.path(), "/api/1.0/sections/section1/tasks");
+        assert_eq!(
+            query_pairs(&url),
+            [(
+                "opt_fields".to_string(),
+                "this.gid,this.name,this.notes,this.modified_at,\
+                 this.custom_fields.gid,this.custom_fields.number_value"
+                    .to_string()
+            )]
+        );
+
+        let url = client
+            .request_url::<crate::focus::Section>(&"project1".to_string())
+            .unwrap();
+        assert_eq!(url.path(), "/api/1.0/projects/project1/sections");
+        assert_eq!(
+            query_pairs(&url),
+            [("opt_fields".to_string(), "this.gid,this.name".to_string())]
+        );
+    }
+
+    #[test]
+    fn path_redaction_replaces_only_numeric_segments() {
+        assert_eq!(
+            redact_path("/api/1.0/user_task_lists/1204986416015644/tasks"),
+            "/api/1.0/user_task_lists/:gid/tasks"
+        );
+        assert_eq!(
+            redact_path("/api/1.0/sections/42/tasks"),
+            "/api/1.0/sections/:gid/tasks"
+        );
+        // Non-numeric segments (including the API version) are left alone.
+        assert_eq!(redact_path("/api/1.0/workspaces"), "/api/1.0/workspaces");
+        assert_eq!(redact_path("/api/1.0/users/me"), "/api/1.0/users/me");
+    }
+
+    #[tokio::test]
+    async fn recorded_timings_redact_gids_even_without_a_response() {
+        // Port 9 is unroutable here, so the request fails before any response; the timing must
+        // still be recorded, with the gid redacted and no status.
+        let mut client = Client::new_with_base_url(
+            Credentials::PersonalAccessToken("test-token".to_string()),
+            "http://127.0.0.1:9/api/1.0/".parse().unwrap(),
+        )
+        .unwrap();
+        let _ = client
+            .get::<crate::task::UserTask>(&"1204986416015644".to_string())
+            .await;
+
+        let timings = client.timings();
+        let timings = timings.lock().unwrap();
+        assert_eq!(timings.len(), 1);
+        assert_eq!(timings[0].method, Method::GET);
+        assert_eq!(timings[0].path, "/api/1.0/user_task_lists/:gid/tasks");
+        assert_eq!(timings[0].status, None);
+    }
+
+    #[test]
+    fn timings_table_aggregates_per_endpoint() {
+        let timing = |path: &str, millis: u64| RequestTiming {
+            method: Method::GET,
+            path: path.to_string(),
+            status: Some(StatusCode::OK),
+            elapsed: std::time::Duration::from_millis(millis),
+        };
+        let table = render_timings(&[
+            timing("/api/1.0/user_task_lists/:gid/tasks", 120),
+            timing("/api/1.0/user_task_lists/:gid/tasks", 80),
+            timing("/api/1.0/workspaces", 40),
+        ]);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("count"));
+        assert!(lines[1].contains('2'));
+        assert!(lines[1].contains("200ms"));
+        assert!(lines[1].contains("120ms"));
+        assert!(lines[1].ends_with("GET /api/1.0/user_task_lists/:gid/tasks"));
+        assert!(lines[2].ends_with("GET /api/1.0/workspaces"));
+    }
+
+    #[test]
+    fn opt_fields_generates_the_this_prefixes() {
+        assert_eq!(
+            opt_fields(&["gid", "projects.name"]),
+            "this.gid,this.projects.name"
+        );
+        assert_eq!(opt_fields(&[]), "");
+    }
+
+    #[cfg(feature = "cli")]
+    #[tokio::test]
+    async fn refresh_without_a_token_respects_the_interactive_gate() {
+        let mut client = Client::new(Credentials::OAuth2 {
+            access_token: "oauth-access-secret-abcd".to_string(),
+            refresh_token: None,
+        })
+        .unwrap();
+        client.set_interactive_auth(false);
+
+        // The refusal comes back before any browser or network is involved.
+        let error = client.refresh().await.unwrap_err();
+        assert!(
+            format!("{error:#}").contains("authorization required"),
+            "{error:#}"
+        );
+        // The refusal is typed, so callers can map it to a hint instead of an error dump.
+        assert!(is_auth_required(&error));
+        assert!(!is_auth_required(&anyhow::anyhow!("authorization required")));
+    }
+
+    #[cfg(feature = "cli")]
+    #[tokio::test]
+    async fn refresh_reports_contention_when_the_auth_lock_is_held() {
+        let dir = std::env::temp_dir()
+            .join("todo-asana-tests")
+            .join(format!("auth-contention-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let cache_path = dir.join("cache.json");
+        let _held = crate::cache::try_auth_lock(&cache_path).unwrap().unwrap();
+
+        let mut client = Client::new(Credentials::OAuth2 {
+            access_token: "oauth-access-secret-abcd".to_string(),
+            refresh_token: None,
+        })
+        .unwrap();
+        client.set_cache_path(cache_path.clone());
+
+        // The fall-back-to-full-flow branch stops at the held lock instead of opening a
+        // second browser tab.
+        let error = client.refresh().await.unwrap_err();
+        assert!(
+            format!("{error:#}").contains("another process is already authenticating"),
+            "{error:#}"
+        );
+    }
+
+    #[tokio::test]
+    async fn refresh_adopts_a_token_a_sibling_process_just_stored() {
+        let dir = std::env::temp_dir()
+            .join("todo-asana-tests")
+            .join(format!("refresh-adopt-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let cache_path = dir.join("cache.json");
+
+        // The "other process": a client whose exchange already finished and was persisted.
+        let winner = Client::new(Credentials::OAuth2 {
+            access_token: "rotated-access-secret-efgh".to_string(),
+            refresh_token: Some("rotated-refresh-secret-ijkl".to_string()),
+        })
+        .unwrap();
+        crate::cache::save(
+            &cache_path,
+            &crate::cache::Cache {
+                creds: Some(winner.credentials().clone()),
+                creds_refreshed_at: Some(Local::now()),
+                ..crate::cache::Cache::default()
+            },
+        )
+        .unwrap();
+
+        let mut loser = Client::new(Credentials::OAuth2 {
+            access_token: "oauth-access-secret-abcd".to_string(),
+            refresh_token: Some("oauth-refresh-secret-wxyz".to_string()),
+        })
+        .unwrap();
+        loser.set_cache_path(cache_path.clone());
+
+        // The stored token is fresh, so the loser adopts it instead of burning the (already
+        // rotated-away) refresh token on an exchange; no network is involved at all.
+        loser.refresh().await.unwrap();
+        assert_eq!(loser.credentials(), winner.credentials());
+    }
+
+    #[tokio::test]
+    async fn refresh_adopts_a_sibling_result_while_waiting_on_the_lock() {
+        let dir = std::env::temp_dir()
+            .join("todo-asana-tests")
+            .join(format!("refresh-wait-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let cache_path = dir.join("cache.json");
+
+        let winner = Client::new(Credentials::OAuth2 {
+            access_token: "rotated-access-secret-efgh".to_string(),
+            refresh_token: Some("rotated-refresh-secret-ijkl".to_string()),
+        })
+        .unwrap();
+        crate::cache::save(
+            &cache_path,
+            &crate::cache::Cache {
+                creds: Some(winner.credentials().clone()),
+                creds_refreshed_at: Some(Local::now()),
+                ..crate::cache::Cache::default()
+            },
+        )
+        .unwrap();
+        // The lock is still held, as if the winner had not finished releasing it yet.
+        let _held = crate::cache::try_auth_lock(&cache_path).unwrap().unwrap();
+
+        let mut loser = Client::new(Credentials::OAuth2 {
+            access_token: "oauth-access-secret-abcd".to_string(),
+            refresh_token: Some("oauth-refresh-secret-wxyz".to_string()),
+        })
+        .unwrap();
+        loser.set_cache_path(cache_path.clone());
+
+        // Rather than queueing behind the lock for its full retry budget, the loser notices
+        // the freshly stored token on its first failed acquire and adopts it.
+        loser.refresh().await.unwrap();
+        assert_eq!(loser.credentials(), winner.credentials());
+    }
+
+    #[test]
+    fn credential_debug_output_redacts_the_tokens() {
+        let pat = Credentials::PersonalAccessToken("2/12345/secret-pat-body-7890".to_string());
+        let rendered = format!("{pat:?}");
+        assert!(!rendered.contains("secret-pat-body"), "{rendered}");
+        assert!(rendered.contains("…7890"), "{rendered}");
+
+        let oauth = Credentials::OAuth2 {
+            access_token: "oauth-access-secret-abcd".to_string(),
+            refresh_token: Some("oauth-refresh-secret-wxyz".to_string()),
+        };
+        let rendered = format!("{oauth:#?}");
+        assert!(!rendered.contains("oauth-access-secret"), "{rendered}");
+        assert!(!rendered.contains("oauth-refresh-secret"), "{rendered}");
+        assert!(rendered.contains("…abcd"), "{rendered}");
+        assert!(rendered.contains("…wxyz"), "{rendered}");
+
+        // A token too short for its tail to be safe collapses to the ellipsis alone.
+        let short = Credentials::PersonalAccessToken("tiny".to_string());
+        assert!(!format!("{short:?}").contains("tiny"));
+    }
+
+    #[tokio::test]
+    async fn mutate_request_refuses_under_dry_run() {
+        let mut client =
+            Client::new(Credentials::PersonalAccessToken("test-token".to_string())).unwrap();
+        client.set_dry_run(true);
+
+        // The error comes back before any request is built, so no network is involved.
+        let error = client
+            .mutate_request(
+                Method::POST,
+                &"https://app.asana.com/api/1.0/tasks".parse().unwrap(),
+                serde_json::json!({"data": {"name": "test"}}),
+            )
+            .await
+            .unwrap_err();
+        assert!(error.downcast_ref::<DryRunError>().is_some());
+    }
+
+    #[tokio::test]
+    async fn requests_refuse_when_offline() {
+        let mut client =
+            Client::new(Credentials::PersonalAccessToken("test-token".to_string())).unwrap();
+        client.set_offline(true);
+
+        let error = client
+            .get::<crate::task::Workspace>(&())
+            .await
+            .unwrap_err();
+        assert!(error.downcast_ref::<OfflineError>().is_some());
+
+        let error = client
+            .mutate_request(
+                Method::POST,
+                &"https://app.asana.com/api/1.0/tasks".parse().unwrap(),
+                serde_json::json!({"data": {"name": "test"}}),
+            )
+            .await
+            .unwrap_err();
+        assert!(error.downcast_ref::<OfflineError>().is_some());
+    }
 }
 
 /// Definitions for for the serde serialization and deserialization of types that interact with the Asana API.
@@ -625,6 +1737,40 @@ pub mod serde_formats {
         }
     }
 
+    pub mod optional_datetime {
+        use chrono::{DateTime, Local, NaiveDateTime, Utc};
+        use serde::{self, Deserialize, Deserializer, Serializer};
+
+        const FORMAT: &str = "%Y-%m-%dT%H:%M:%S.%fZ";
+
+        pub fn serialize<S>(date: &Option<DateTime<Local>>, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            if let Some(date) = date {
+                let s = format!("{}", date.naive_utc().format(FORMAT));
+                serializer.serialize_str(&s)
+            } else {
+                serializer.serialize_unit()
+            }
+        }
+
+        pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<DateTime<Local>>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            if let Ok(s) = String::deserialize(deserializer) {
+                let dt =
+                    NaiveDateTime::parse_from_str(&s, FORMAT).map_err(serde::de::Error::custom)?;
+                Ok(Some(DateTime::from(
+                    DateTime::<Utc>::from_naive_utc_and_offset(dt, Utc),
+                )))
+            } else {
+                Ok(None)
+            }
+        }
+    }
+
     pub mod optional_date {
         use chrono::NaiveDate;
         use serde::{self, Deserialize, Deserializer, Serializer};
@@ -0,0 +1,808 @@
+//! On-disk cache of credentials and Asana data, so commands can run without hitting the API.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::Context;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::asana::Credentials;
+#[cfg(feature = "cli")]
+use crate::commands::gate::GateAcknowledgement;
+#[cfg(feature = "cli")]
+use crate::commands::notify::NotifyLimiter;
+#[cfg(feature = "cli")]
+use crate::commands::pause::PauseWindow;
+use crate::focus::{FocusDay, FocusDraft, StatEntry};
+use crate::task::{CompletedTask, User, UserTask, UserTaskList, WorkspaceUser};
+
+/// Cached credentials and Asana data.
+///
+/// Unknown or missing fields are tolerated so that caches written by older versions of the tool
+/// keep loading after new fields are added.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Cache {
+    /// Credentials used to authenticate against Asana.
+    pub creds: Option<Credentials>,
+    /// When the credentials were last refreshed against Asana.
+    ///
+    /// Concurrent processes use this to tell a token a sibling just rotated apart from one
+    /// that has been sitting in the cache long enough to have expired on its own.
+    pub creds_refreshed_at: Option<DateTime<Local>>,
+    /// Profile of the authenticated user, fetched once and refreshed opportunistically.
+    ///
+    /// Only conveniences hang off this — the greeting, the user gid in place of `"me"` — so a
+    /// missing profile never blocks a command.
+    pub user: Option<User>,
+    /// The user's task list, which tasks are fetched from.
+    pub user_task_list: Option<UserTaskList>,
+    /// Tasks fetched from the user's task list.
+    pub tasks: Option<Vec<UserTask>>,
+    /// Tasks completed since local midnight, refreshed alongside the task list.
+    ///
+    /// Each entry carries its completion timestamp, so once the calendar day rolls over the
+    /// stale entries simply stop counting instead of inflating the next day's number.
+    pub completed_today: Option<Vec<CompletedTask>>,
+    /// Users in the workspace, fetched lazily the first time an assignee or follower needs
+    /// resolving by email or name.
+    pub workspace_users: Option<Vec<WorkspaceUser>>,
+    /// The most recently fetched focus day.
+    pub focus_day: Option<FocusDay>,
+    /// Draft of a partially completed focus run, cleared once the run's syncs succeed.
+    pub focus_draft: Option<FocusDraft>,
+    /// Locally recorded history of when each focus stat value was entered.
+    ///
+    /// Asana number fields cannot store an entry timestamp, so this history only exists here;
+    /// it is what the overview's backfill notes and audit log are built from.
+    pub stat_entries: Option<Vec<StatEntry>>,
+    /// The most recent acknowledgement of the terminal gate.
+    ///
+    /// The acknowledgement type lives with the gate command, so without the `cli` feature the
+    /// field disappears; `#[serde(default)]` keeps caches written by the binary loading anyway.
+    #[cfg(feature = "cli")]
+    pub gate_acknowledged: Option<GateAcknowledgement>,
+    /// The active pause window, if reminders and focus tracking are paused.
+    ///
+    /// Like the gate acknowledgement, the type lives with its command and so the field only
+    /// exists with the `cli` feature.
+    #[cfg(feature = "cli")]
+    pub paused: Option<PauseWindow>,
+    /// When each category of update notification last fired, so scheduled one-shot updates
+    /// respect the rate limit across processes. Lives with the notify command, so the field
+    /// only exists with the `cli` feature.
+    #[cfg(feature = "cli")]
+    pub notified: Option<NotifyLimiter>,
+    /// When the cache was last refreshed by the update command.
+    pub last_updated: Option<DateTime<Local>>,
+    /// The UTC offset the machine was in when the cache was last refreshed, rendered the way
+    /// [`chrono::FixedOffset`] displays (e.g. `+02:00`).
+    ///
+    /// Every cached date was computed against this zone; after travel across timezones the
+    /// mismatch with the current offset is what marks those dates untrustworthy.
+    pub last_updated_offset: Option<String>,
+}
+
+impl Cache {
+    /// Record that the cache was just refreshed, remembering the UTC offset the refresh ran
+    /// under so a later load can tell the machine has since changed timezones.
+    pub fn mark_updated(&mut self, now: DateTime<Local>) {
+        self.last_updated_offset = Some(now.offset().to_string());
+        self.last_updated = Some(now);
+    }
+
+    /// The UTC offset the cache was last updated under, when it differs from `current_offset`
+    /// (both rendered the way [`chrono::FixedOffset`] displays, e.g. `+02:00`).
+    ///
+    /// Caches written before the offset was recorded compare as unchanged: there is nothing to
+    /// compare against, and treating every old cache as stale once would be pure noise.
+    #[must_use]
+    pub fn timezone_change(&self, current_offset: &str) -> Option<&str> {
+        self.last_updated_offset
+            .as_deref()
+            .filter(|stored| *stored != current_offset)
+    }
+}
+
+/// Load the cache from the given path, creating an empty one if it does not exist.
+///
+/// If the cache file cannot be deserialized it is wiped and replaced with an empty cache.
+///
+/// # Errors
+///
+/// This function will return an error if the cache file could not be created, read, or written.
+pub fn load(path: &Path) -> anyhow::Result<Cache> {
+    tracing::debug!("Checking if cache file exists at {}...", path.display());
+    if !path.exists() {
+        tracing::warn!(
+            "Could not find cache at {}, so creating and using an empty cache...",
+            path.display()
+        );
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("could not create path to cache file")?;
+        }
+        save(path, &Cache::default())?;
+    }
+
+    tracing::debug!("Loading cache from {}...", path.display());
+    let cache =
+        serde_json::from_str(&fs::read_to_string(path).context("could not read cache file")?);
+    match cache {
+        Ok(cache) => {
+            // The dump includes the credentials, whose `Debug` impl redacts the tokens; the
+            // full cache structure is safe to emit at trace level.
+            tracing::trace!("Loaded cache: {cache:#?}");
+            Ok(cache)
+        }
+        Err(err) => {
+            tracing::warn!(
+                "Could not deserialize cache file at {}, wiping it and trying again...",
+                path.display()
+            );
+            tracing::debug!("Cache deserialization error: {err}");
+            save(path, &Cache::default())?;
+            load(path)
+        }
+    }
+}
+
+/// Save the cache to the given path.
+///
+/// The cache is written to a temporary file and renamed into place, so readers never observe a
+/// partially written cache even if the process is interrupted mid-write.
+///
+/// # Errors
+///
+/// This function will return an error if the cache could not be serialized or written.
+pub fn save(path: &Path, cache: &Cache) -> anyhow::Result<()> {
+    tracing::debug!("Saving cache to {}...", path.display());
+    let temporary_path = path.with_extension("json.tmp");
+    fs::write(
+        &temporary_path,
+        serde_json::to_string_pretty(cache).context("could not serialize cache")?,
+    )
+    .context("could not write to cache file")?;
+    fs::rename(&temporary_path, path).context("could not move cache file into place")?;
+    tracing::trace!("Saved cache: {cache:#?}");
+
+    Ok(())
+}
+
+/// Named group of cache fields that a [`CacheStore`] can persist independently.
+///
+/// The grouping exists for backends that can write partially: a history-heavy section should
+/// not be rewritten because a three-field task refresh landed. The JSON file store ignores the
+/// grouping and always rewrites everything.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CacheSection {
+    /// The credentials.
+    Creds,
+    /// The task list and everything refreshed alongside it: the user profile, the user task
+    /// list, the tasks themselves, the workspace users, and the last-updated timestamp.
+    Tasks,
+    /// The focus day and any in-progress focus draft.
+    Focus,
+    /// Completed-task and stat-entry history.
+    History,
+    /// Command state: the gate acknowledgement, pause window, and notification limiter.
+    State,
+}
+
+impl CacheSection {
+    /// Every section, in the order stores persist them.
+    pub const ALL: [Self; 5] = [
+        Self::Creds,
+        Self::Tasks,
+        Self::Focus,
+        Self::History,
+        Self::State,
+    ];
+
+    /// The table (or key) the section is stored under.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Creds => "creds",
+            Self::Tasks => "tasks",
+            Self::Focus => "focus",
+            Self::History => "history",
+            Self::State => "state",
+        }
+    }
+
+    /// The top-level cache fields the section covers.
+    ///
+    /// Fields that only exist with the `cli` feature are listed unconditionally; splitting
+    /// simply finds nothing for them in a build without the feature. Only the `SQLite` backend
+    /// splits, so the mapping is compiled out without it.
+    #[cfg(feature = "sqlite")]
+    fn fields(self) -> &'static [&'static str] {
+        match self {
+            Self::Creds => &["creds", "creds_refreshed_at"],
+            Self::Tasks => &[
+                "user",
+                "user_task_list",
+                "tasks",
+                "workspace_users",
+                "last_updated",
+                "last_updated_offset",
+            ],
+            Self::Focus => &["focus_day", "focus_draft"],
+            Self::History => &["completed_today", "stat_entries"],
+            Self::State => &["gate_acknowledged", "paused", "notified"],
+        }
+    }
+}
+
+/// Serialize just the fields of `section` as a JSON object.
+#[cfg(feature = "sqlite")]
+fn section_object(
+    cache: &Cache,
+    section: CacheSection,
+) -> anyhow::Result<serde_json::Map<String, serde_json::Value>> {
+    let serde_json::Value::Object(mut whole) =
+        serde_json::to_value(cache).context("could not serialize cache")?
+    else {
+        anyhow::bail!("cache did not serialize to an object");
+    };
+    let mut object = serde_json::Map::new();
+    for field in section.fields() {
+        if let Some(value) = whole.remove(*field) {
+            object.insert((*field).to_string(), value);
+        }
+    }
+    Ok(object)
+}
+
+/// Persistence backend for the cache.
+///
+/// [`load`](CacheStore::load) and [`save`](CacheStore::save) move the whole cache;
+/// [`save_section`](CacheStore::save_section) lets backends that support it persist one
+/// [`CacheSection`] without rewriting the rest. The default falls back to a full save, which
+/// is all a single-blob backend can do anyway.
+pub trait CacheStore {
+    /// Load the whole cache, yielding an empty one when nothing has been stored yet.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the backing storage could not be read.
+    fn load(&self) -> anyhow::Result<Cache>;
+
+    /// Persist the whole cache.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the cache could not be serialized or written.
+    fn save(&self, cache: &Cache) -> anyhow::Result<()>;
+
+    /// Persist one section of the cache, leaving the others untouched where the backend
+    /// supports it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the cache could not be serialized or written.
+    fn save_section(&self, cache: &Cache, section: CacheSection) -> anyhow::Result<()> {
+        let _ = section;
+        self.save(cache)
+    }
+}
+
+/// The default [`CacheStore`]: the single pretty-printed JSON file that [`load`] and [`save`]
+/// read and write.
+#[derive(Clone, Debug)]
+pub struct JsonFileStore {
+    path: std::path::PathBuf,
+}
+
+impl JsonFileStore {
+    /// Wrap the JSON cache file at `path`.
+    #[must_use]
+    pub fn new(path: impl Into<std::path::PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl CacheStore for JsonFileStore {
+    fn load(&self) -> anyhow::Result<Cache> {
+        load(&self.path)
+    }
+
+    fn save(&self, cache: &Cache) -> anyhow::Result<()> {
+        save(&self.path, cache)
+    }
+}
+
+/// [`CacheStore`] backed by a `SQLite` database: one table per [`CacheSection`], each holding
+/// that section's JSON alongside the time it was written.
+///
+/// A partial save touches only its section's table, so history-heavy sections stop being
+/// rewritten on every small save the way the JSON blob is.
+#[cfg(feature = "sqlite")]
+#[derive(Debug)]
+pub struct SqliteStore {
+    connection: rusqlite::Connection,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteStore {
+    /// Open the database at `path`, creating it and the section tables as needed.
+    ///
+    /// On the first open of an empty database, an existing JSON cache at the same path with a
+    /// `.json` extension is migrated in automatically, so switching backends needs no manual
+    /// step. The JSON file is left in place as a fallback for older versions.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the database could not be opened or initialized,
+    /// or if a JSON cache exists but could not be migrated.
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("could not create path to cache database")?;
+        }
+        let connection =
+            rusqlite::Connection::open(path).context("could not open cache database")?;
+        for section in CacheSection::ALL {
+            connection
+                .execute(
+                    &format!(
+                        "CREATE TABLE IF NOT EXISTS {name} (
+                            id INTEGER PRIMARY KEY CHECK (id = 1),
+                            data TEXT NOT NULL,
+                            updated_at TEXT NOT NULL
+                        )",
+                        name = section.name()
+                    ),
+                    [],
+                )
+                .context("could not create cache table")?;
+        }
+
+        let store = Self { connection };
+        if store.is_empty()? {
+            let json_path = path.with_extension("json");
+            if json_path.exists() {
+                tracing::info!(
+                    "Migrating the JSON cache at {} into the database...",
+                    json_path.display()
+                );
+                store.save(&load(&json_path)?)?;
+            }
+        }
+        Ok(store)
+    }
+
+    /// Whether no section has ever been written.
+    fn is_empty(&self) -> anyhow::Result<bool> {
+        for section in CacheSection::ALL {
+            let rows: i64 = self
+                .connection
+                .query_row(
+                    &format!("SELECT COUNT(*) FROM {name}", name = section.name()),
+                    [],
+                    |row| row.get(0),
+                )
+                .context("could not count cache table rows")?;
+            if rows > 0 {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// When the section was last written, if it ever has been.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the table could not be read or its timestamp not
+    /// parsed.
+    pub fn section_updated_at(
+        &self,
+        section: CacheSection,
+    ) -> anyhow::Result<Option<DateTime<Local>>> {
+        use rusqlite::OptionalExtension as _;
+
+        let updated_at: Option<String> = self
+            .connection
+            .query_row(
+                &format!("SELECT updated_at FROM {name}", name = section.name()),
+                [],
+                |row| row.get(0),
+            )
+            .optional()
+            .context("could not read cache table timestamp")?;
+        updated_at
+            .map(|raw| {
+                Ok(DateTime::parse_from_rfc3339(&raw)
+                    .context("could not parse cache table timestamp")?
+                    .with_timezone(&Local))
+            })
+            .transpose()
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl CacheStore for SqliteStore {
+    fn load(&self) -> anyhow::Result<Cache> {
+        use rusqlite::OptionalExtension as _;
+
+        let mut whole = serde_json::Map::new();
+        for section in CacheSection::ALL {
+            let data: Option<String> = self
+                .connection
+                .query_row(
+                    &format!("SELECT data FROM {name}", name = section.name()),
+                    [],
+                    |row| row.get(0),
+                )
+                .optional()
+                .context("could not read cache table")?;
+            if let Some(data) = data {
+                let serde_json::Value::Object(object) =
+                    serde_json::from_str(&data).context("could not parse cache table data")?
+                else {
+                    anyhow::bail!("cache table data is not an object");
+                };
+                whole.extend(object);
+            }
+        }
+        serde_json::from_value(serde_json::Value::Object(whole))
+            .context("could not deserialize cache database contents")
+    }
+
+    fn save(&self, cache: &Cache) -> anyhow::Result<()> {
+        for section in CacheSection::ALL {
+            self.save_section(cache, section)?;
+        }
+        Ok(())
+    }
+
+    fn save_section(&self, cache: &Cache, section: CacheSection) -> anyhow::Result<()> {
+        let object = section_object(cache, section)?;
+        self.connection
+            .execute(
+                &format!(
+                    "INSERT INTO {name} (id, data, updated_at) VALUES (1, ?1, ?2)
+                     ON CONFLICT(id) DO UPDATE
+                     SET data = excluded.data, updated_at = excluded.updated_at",
+                    name = section.name()
+                ),
+                rusqlite::params![
+                    serde_json::Value::Object(object).to_string(),
+                    Local::now().to_rfc3339()
+                ],
+            )
+            .context("could not write cache table")?;
+        Ok(())
+    }
+}
+
+/// Maximum age of an update lock before it is presumed abandoned by a crashed holder.
+const UPDATE_LOCK_MAX_AGE: std::time::Duration = std::time::Duration::from_hours(1);
+
+/// An advisory file lock held for the lifetime of the guard.
+///
+/// The lock is a file created exclusively and removed on drop. A crashed holder leaves the file
+/// behind, so acquisition treats a lock older than its `max_age` as stale and takes it over.
+#[derive(Debug)]
+pub struct FileLock {
+    path: std::path::PathBuf,
+}
+
+impl FileLock {
+    /// Try to take the lock file at the given path, returning `None` when another process
+    /// already holds it and the lock is younger than `max_age`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the lock file could not be created or inspected.
+    pub fn acquire(path: &Path, max_age: std::time::Duration) -> anyhow::Result<Option<Self>> {
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)
+        {
+            Ok(_) => Ok(Some(Self {
+                path: path.to_path_buf(),
+            })),
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+                let age = fs::metadata(path)
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+                    .and_then(|modified| modified.elapsed().ok());
+                if age.is_some_and(|age| age > max_age) {
+                    tracing::warn!("Lock at {} looks stale, taking it over...", path.display());
+                    fs::remove_file(path).context("could not remove stale lock")?;
+                    return Self::acquire(path, max_age);
+                }
+                Ok(None)
+            }
+            Err(err) => Err(err).context("could not create lock file"),
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        if let Err(err) = fs::remove_file(&self.path) {
+            tracing::warn!("Could not remove lock at {}: {err}", self.path.display());
+        }
+    }
+}
+
+/// Try to take the exclusive update lock, an `update.lock` file next to the cache, so a
+/// scheduled and a manual `todo update` never write the cache (or create focus days)
+/// concurrently. Returns `None` when another update already holds it.
+///
+/// # Errors
+///
+/// This function will return an error if the lock file could not be created or inspected.
+pub fn try_update_lock(cache_path: &Path) -> anyhow::Result<Option<FileLock>> {
+    FileLock::acquire(&cache_path.with_file_name("update.lock"), UPDATE_LOCK_MAX_AGE)
+}
+
+/// Maximum age of an auth lock before it is presumed abandoned. Shorter than the update lock's,
+/// since an interactive flow abandoned at a browser tab should not block authorization for long.
+const AUTH_LOCK_MAX_AGE: std::time::Duration = std::time::Duration::from_mins(15);
+
+/// Try to take the exclusive auth lock, an `auth.lock` file next to the cache, so two commands
+/// whose tokens expired at once never run the interactive authorization flow or a refresh-token
+/// exchange concurrently — uncoordinated, the flows open duplicate browser tabs and the
+/// exchanges clobber each other's rotated refresh tokens. Returns `None` when another process
+/// already holds it.
+///
+/// # Errors
+///
+/// This function will return an error if the lock file could not be created or inspected.
+pub fn try_auth_lock(cache_path: &Path) -> anyhow::Result<Option<FileLock>> {
+    FileLock::acquire(&cache_path.with_file_name("auth.lock"), AUTH_LOCK_MAX_AGE)
+}
+
+/// Whether another process currently holds the auth lock, i.e. an interactive authorization
+/// flow is underway. A lock older than its max age counts as abandoned, not in progress.
+#[must_use]
+pub fn is_auth_in_progress(cache_path: &Path) -> bool {
+    fs::metadata(cache_path.with_file_name("auth.lock"))
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age <= AUTH_LOCK_MAX_AGE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn focus_draft_survives_a_cache_round_trip() {
+        let dir = std::env::temp_dir()
+            .join("todo-cache-tests")
+            .join(format!("draft-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let cache_path = dir.join("cache.json");
+
+        let cache = Cache {
+            focus_draft: Some(FocusDraft {
+                date: "2024-01-15".parse().unwrap(),
+                stats: vec![("1204172638538713".to_string(), 4)],
+                diary: Some("half-written paragraph".to_string()),
+                pending_subtasks: vec!["ship the fix".to_string()],
+            }),
+            ..Cache::default()
+        };
+        save(&cache_path, &cache).unwrap();
+
+        let reloaded = load(&cache_path).unwrap();
+        assert_eq!(reloaded.focus_draft, cache.focus_draft);
+    }
+
+    #[test]
+    fn a_formatted_cache_dump_contains_no_tokens() {
+        // Trace logging formats the whole cache, so the credential tokens must never survive
+        // into the rendered dump.
+        let cache = Cache {
+            creds: Some(Credentials::OAuth2 {
+                access_token: "oauth-access-secret-abcd".to_string(),
+                refresh_token: Some("oauth-refresh-secret-wxyz".to_string()),
+            }),
+            ..Cache::default()
+        };
+        let dump = format!("{cache:#?}");
+        assert!(!dump.contains("oauth-access-secret"), "{dump}");
+        assert!(!dump.contains("oauth-refresh-secret"), "{dump}");
+
+        let cache = Cache {
+            creds: Some(Credentials::PersonalAccessToken(
+                "2/12345/secret-pat-body-7890".to_string(),
+            )),
+            ..Cache::default()
+        };
+        let dump = format!("{cache:#?}");
+        assert!(!dump.contains("secret-pat-body"), "{dump}");
+    }
+
+    #[test]
+    fn timezone_change_compares_stored_and_current_offsets() {
+        // Caches from before the offset was recorded have nothing to compare against.
+        let mut cache = Cache::default();
+        assert_eq!(cache.timezone_change("+02:00"), None);
+
+        // A machine that updated the cache in New York and then landed in Berlin.
+        cache.last_updated_offset = Some("-05:00".to_string());
+        assert_eq!(cache.timezone_change("+02:00"), Some("-05:00"));
+        assert_eq!(cache.timezone_change("-05:00"), None);
+    }
+
+    #[test]
+    fn mark_updated_records_the_offset_it_ran_under() {
+        let mut cache = Cache::default();
+        let now = chrono::Local::now();
+        cache.mark_updated(now);
+        assert_eq!(cache.last_updated, Some(now));
+        assert_eq!(cache.last_updated_offset, Some(now.offset().to_string()));
+        // The offset it just recorded is, by definition, not a change.
+        assert_eq!(cache.timezone_change(&now.offset().to_string()), None);
+    }
+
+    fn completed(gid: &str) -> CompletedTask {
+        CompletedTask {
+            gid: gid.to_string(),
+            name: format!("task {gid}"),
+            completed_at: Some("2024-01-15T09:00:00Z".parse().unwrap()),
+            projects: Vec::new(),
+        }
+    }
+
+    fn task(gid: &str) -> UserTask {
+        UserTask {
+            gid: gid.to_string(),
+            created_at: "2024-01-01T00:00:00Z".parse().unwrap(),
+            due_on: None,
+            name: format!("task {gid}"),
+            projects: Vec::new(),
+            custom_fields: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn json_store_section_saves_fall_back_to_full_saves() {
+        let dir = std::env::temp_dir()
+            .join("todo-cache-tests")
+            .join(format!("json-store-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let store = JsonFileStore::new(dir.join("cache.json"));
+
+        // A single JSON blob cannot be written partially, so a section save must still land
+        // every section rather than dropping the others.
+        let cache = Cache {
+            tasks: Some(vec![task("1")]),
+            completed_today: Some(vec![completed("2")]),
+            ..Cache::default()
+        };
+        store.save_section(&cache, CacheSection::Tasks).unwrap();
+
+        let reloaded = store.load().unwrap();
+        assert_eq!(reloaded.tasks.unwrap()[0].gid, "1");
+        assert_eq!(reloaded.completed_today.unwrap()[0].gid, "2");
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn sqlite_partial_saves_leave_other_sections_untouched() {
+        let dir = std::env::temp_dir()
+            .join("todo-cache-tests")
+            .join(format!("sqlite-partial-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let store = SqliteStore::open(&dir.join("cache.db")).unwrap();
+
+        let mut cache = Cache {
+            tasks: Some(vec![task("1")]),
+            completed_today: Some(vec![completed("2")]),
+            ..Cache::default()
+        };
+        store.save(&cache).unwrap();
+
+        // Change both sections in memory but persist only the tasks: the history table must
+        // keep its old contents, which is the whole point of sectioned saves.
+        cache.tasks = Some(vec![task("1"), task("3")]);
+        cache.completed_today = Some(vec![completed("2"), completed("4")]);
+        store.save_section(&cache, CacheSection::Tasks).unwrap();
+
+        let reloaded = store.load().unwrap();
+        assert_eq!(reloaded.tasks.unwrap().len(), 2);
+        assert_eq!(reloaded.completed_today.unwrap().len(), 1);
+        assert!(store
+            .section_updated_at(CacheSection::History)
+            .unwrap()
+            .is_some());
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn sqlite_migrates_the_json_cache_on_first_open() {
+        let dir = std::env::temp_dir()
+            .join("todo-cache-tests")
+            .join(format!("sqlite-migrate-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let json_cache = Cache {
+            tasks: Some(vec![task("1")]),
+            last_updated: Some("2024-01-15T09:00:00Z".parse().unwrap()),
+            ..Cache::default()
+        };
+        save(&dir.join("cache.json"), &json_cache).unwrap();
+
+        let store = SqliteStore::open(&dir.join("cache.db")).unwrap();
+        let migrated = store.load().unwrap();
+        assert_eq!(migrated.tasks.unwrap()[0].gid, "1");
+        assert_eq!(migrated.last_updated, json_cache.last_updated);
+
+        // A later open of the now non-empty database must not re-migrate over fresher data.
+        let mut fresher = store.load().unwrap();
+        fresher.tasks = Some(vec![task("1"), task("2")]);
+        store.save(&fresher).unwrap();
+        drop(store);
+        let reopened = SqliteStore::open(&dir.join("cache.db")).unwrap();
+        assert_eq!(reopened.load().unwrap().tasks.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn update_lock_is_exclusive_and_released_on_drop() {
+        let dir = std::env::temp_dir()
+            .join("todo-cache-tests")
+            .join(format!("lock-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let cache_path = dir.join("cache.json");
+
+        let lock = try_update_lock(&cache_path).unwrap();
+        assert!(lock.is_some());
+        assert!(dir.join("update.lock").exists());
+        // The second acquisition fails fast instead of blocking.
+        assert!(try_update_lock(&cache_path).unwrap().is_none());
+
+        drop(lock);
+        assert!(!dir.join("update.lock").exists());
+        assert!(try_update_lock(&cache_path).unwrap().is_some());
+    }
+
+    #[test]
+    fn auth_lock_contention_is_visible_to_other_processes() {
+        let dir = std::env::temp_dir()
+            .join("todo-cache-tests")
+            .join(format!("auth-lock-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let cache_path = dir.join("cache.json");
+
+        assert!(!is_auth_in_progress(&cache_path));
+        let lock = try_auth_lock(&cache_path).unwrap();
+        assert!(lock.is_some());
+        // The loser of the race sees both the failed acquisition and the in-progress probe.
+        assert!(try_auth_lock(&cache_path).unwrap().is_none());
+        assert!(is_auth_in_progress(&cache_path));
+
+        drop(lock);
+        assert!(!is_auth_in_progress(&cache_path));
+        assert!(try_auth_lock(&cache_path).unwrap().is_some());
+    }
+
+    #[test]
+    fn a_stale_file_lock_is_taken_over() {
+        let dir = std::env::temp_dir()
+            .join("todo-cache-tests")
+            .join(format!("stale-lock-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("stale.lock");
+
+        let abandoned = FileLock::acquire(&path, std::time::Duration::from_hours(1)).unwrap();
+        // Simulate a crashed holder: the file stays behind without a guard to remove it.
+        std::mem::forget(abandoned);
+        std::thread::sleep(std::time::Duration::from_millis(10));
+
+        // Younger than its max age the lock holds; with a zero max age it counts as stale
+        // and is taken over.
+        assert!(FileLock::acquire(&path, std::time::Duration::from_hours(1))
+            .unwrap()
+            .is_none());
+        let reclaimed = FileLock::acquire(&path, std::time::Duration::ZERO).unwrap();
+        assert!(reclaimed.is_some());
+    }
+}
@@ -0,0 +1,547 @@
+//! Command line interface definitions, kept in the library so tooling like the completion
+//! generator can reference them without going through the binary.
+
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use clap::{Parser, Subcommand};
+
+use crate::commands::agenda::AgendaFormat;
+use crate::commands::count::CountFormat;
+use crate::commands::install::{CompletionShell, UninstallTarget};
+use crate::commands::list::{GroupBy, ListFormat};
+use crate::commands::log::LogFormat;
+use crate::commands::notify::NotifyPhase;
+use crate::commands::status::StatusFormat;
+use crate::commands::update::UpdateFormat;
+use crate::interop::taskwarrior::TaskwarriorFilter;
+
+/// Todo is a simple Asana helper script that pulls data from Asana and shows it in CLI settings
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Parser)]
+#[command(name = "todo", author, version, about, long_about = None)]
+pub struct Args {
+    /// Path to the cache file
+    #[arg(long, default_value = "~/.cache/todo/cache.json")]
+    pub cache_path: PathBuf,
+
+    /// Path to the configuration file
+    #[arg(long, default_value = "~/.config/todo/config.toml")]
+    pub config_path: PathBuf,
+
+    /// If set, uses the discouraged PAT flow (instead of OAuth)
+    #[arg(long)]
+    pub use_pat: bool,
+
+    /// If set, uses the cache instead of pulling from Asana
+    #[arg(long)]
+    pub use_cache: bool,
+
+    /// If set, never touches the network: reads come from the cache and mutations are refused
+    #[arg(long)]
+    pub offline: bool,
+
+    /// If set, suppresses all non-error, non-data output: progress strings and warnings about
+    /// stale or missing caches
+    #[arg(long)]
+    pub quiet: bool,
+
+    /// Increase log verbosity (-v for debug, -vv for trace) without needing `RUST_LOG`
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// If set, appends log events as JSON lines to this file instead of writing them to stderr;
+    /// meant for long-running invocations like `update --watch`
+    #[arg(long)]
+    pub log_file: Option<PathBuf>,
+
+    /// If set, never emits colors or styling (also triggered by the `NO_COLOR` env variable)
+    #[arg(long)]
+    pub no_color: bool,
+
+    /// If set, prints what each mutation would have done instead of calling the Asana API;
+    /// reads still happen, so the output reflects real data
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// If set, unrecognized keys in the configuration file are a hard error instead of a warning
+    #[arg(long)]
+    pub strict_config: bool,
+
+    /// If set, summary, list, and status encode state in the exit code: the bitwise OR of 2
+    /// (overdue tasks) and 4 (pending focus routine), or 0 when neither applies. Errors still
+    /// exit with 1
+    #[arg(long)]
+    pub exit_code: bool,
+
+    /// If set, prints a table of API request timings to stderr after the command finishes
+    #[arg(long)]
+    pub timings: bool,
+
+    /// Subcommand to run; `behavior.default_command` (summary unless configured) when omitted
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Subcommands exposed by the command line tool.
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Interactively set up authentication, the workspace, and integrations
+    Init,
+
+    /// Print out a summary of current todo tasks
+    Summary,
+
+    /// Print out a list of todo tasks ordered by due date
+    List {
+        /// If set, also shows tasks without a due date
+        #[arg(long)]
+        all: bool,
+
+        /// Output format to use
+        #[arg(long, value_enum, default_value_t)]
+        format: ListFormat,
+
+        /// How to group tasks in the plain output
+        #[arg(long, value_enum, default_value_t)]
+        group_by: GroupBy,
+
+        /// If set, always shows absolute due dates instead of relative ones
+        #[arg(long)]
+        absolute: bool,
+
+        /// If set, includes Asana permalinks for each task
+        #[arg(long)]
+        links: bool,
+    },
+
+    /// Print a day-by-day agenda of upcoming tasks, starting today
+    Agenda {
+        /// Number of weeks the agenda covers
+        #[arg(long, default_value_t = 1)]
+        weeks: u8,
+
+        /// Output format to use
+        #[arg(long, value_enum, default_value_t)]
+        format: AgendaFormat,
+    },
+
+    /// Print recently completed tasks grouped by completion day, newest first
+    Log {
+        /// How many days back to include, counting today
+        #[arg(long, default_value_t = 7)]
+        days: u64,
+
+        /// Output format to use
+        #[arg(long, value_enum, default_value_t)]
+        format: LogFormat,
+
+        /// Only show tasks belonging to the project with this name
+        #[arg(long)]
+        project: Option<String>,
+    },
+
+    /// Create a new task in your task list
+    Add {
+        /// Name of the task to create
+        name: String,
+
+        /// Due date; flexible dates like `fri`, `+3`, or `2024-12-31`
+        #[arg(long)]
+        due: Option<String>,
+
+        /// Start date for multi-day tasks; flexible dates, must not be after the due date
+        #[arg(long)]
+        start: Option<String>,
+
+        /// Due time of day in local `HH:MM`, combined with the due date
+        #[arg(long, value_name = "HH:MM", requires = "due")]
+        at: Option<String>,
+
+        /// Who to assign the task to: a user gid, an email, a name, or `me`
+        #[arg(long, default_value = "me")]
+        assignee: String,
+
+        /// Add a follower by gid, email, or name; repeatable
+        #[arg(long = "follower", value_name = "GID_OR_EMAIL")]
+        followers: Vec<String>,
+
+        /// If set, picks the assignee interactively instead of defaulting to `me`
+        #[arg(long, conflicts_with = "assignee")]
+        ask_assignee: bool,
+
+        /// If set, skips the likely-duplicate check against cached tasks
+        #[arg(long)]
+        force: bool,
+
+        /// Accept a due date in the past without asking for confirmation
+        #[arg(long)]
+        allow_past: bool,
+
+        /// Attach a file to the created task; repeatable
+        #[arg(long = "attach", value_name = "FILE")]
+        attachments: Vec<PathBuf>,
+    },
+
+    /// Attach files to an existing task
+    Attach {
+        /// Gid of the task to attach the files to
+        #[arg(long)]
+        gid: String,
+
+        /// Files to upload as attachments
+        #[arg(required = true, value_name = "FILE")]
+        files: Vec<PathBuf>,
+
+        /// Content type to upload under, instead of sniffing one from each file's extension
+        #[arg(long, value_name = "MIME")]
+        mime: Option<String>,
+    },
+
+    /// Interactively sweep overdue tasks: complete, reschedule, or skip them one at a time
+    Triage,
+
+    /// Print machine-readable task counts, with no styling
+    Count {
+        /// Output format to use
+        #[arg(long, value_enum, default_value_t)]
+        format: CountFormat,
+
+        /// Wall-clock budget in milliseconds for network calls before falling back to the cache
+        #[arg(long, value_name = "MS")]
+        max_wait: Option<u64>,
+    },
+
+    /// Print a compact status line for status bars, prompts, and shell conditionals
+    Status {
+        /// Output format to use
+        #[arg(long, value_enum, default_value_t)]
+        format: StatusFormat,
+
+        /// Wall-clock budget in milliseconds for network calls before falling back to the cache
+        #[arg(long, value_name = "MS")]
+        max_wait: Option<u64>,
+    },
+
+    /// Block a new shell while a focus routine is pending; meant to be called from .zshrc
+    Gate,
+
+    /// Send a desktop notification if a focus routine is still pending; meant to be scheduled
+    Notify {
+        /// Which focus routine to notify about
+        #[arg(long, value_enum, default_value_t)]
+        phase: NotifyPhase,
+
+        /// If set, prints what would be notified instead of sending anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Pause reminders and focus tracking, or show the current pause state when run bare
+    Pause {
+        /// Last day (inclusive) of the pause; flexible dates like `fri`, `+3`, or `2024-12-31`
+        #[arg(long, conflicts_with = "days")]
+        until: Option<String>,
+
+        /// Pause for this many days, starting today
+        #[arg(long)]
+        days: Option<u64>,
+    },
+
+    /// End an active pause immediately
+    Resume,
+
+    /// Manage the Focus project
+    Focus {
+        /// The date to focus on
+        #[arg(long)]
+        date: Option<NaiveDate>,
+
+        /// If set, forces the end of day to be considered to be starting
+        #[arg(long, default_value = "false")]
+        force_eod: bool,
+
+        /// If set, runs the focus routine even on a day the `focus.days` schedule excludes
+        #[arg(long, default_value = "false")]
+        force: bool,
+
+        /// If set, skips the remote-edit check and overwrites the task notes unconditionally
+        #[arg(long, default_value = "false")]
+        force_sync: bool,
+
+        /// Subcommand to run
+        #[command(subcommand)]
+        command: Option<FocusCommand>,
+    },
+
+    /// Read and edit the configuration file
+    Config {
+        /// What to do with the configuration
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+
+    /// Set up an integration with an external tool
+    Install {
+        /// Integration to set up
+        #[command(subcommand)]
+        command: InstallCommand,
+    },
+
+    /// Remove an installed integration's artifacts; safe to run when nothing is installed
+    Uninstall {
+        /// Integration to remove
+        #[arg(value_enum)]
+        target: UninstallTarget,
+
+        /// If set, lists what would be removed without touching anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Generate a markdown report for a review period
+    Report {
+        /// Period to report on
+        #[command(subcommand)]
+        command: ReportCommand,
+    },
+
+    /// Export cached tasks to an external format
+    Export {
+        /// Format to export to
+        #[command(subcommand)]
+        command: ExportCommand,
+    },
+
+    /// Pull and cache information about todo task and focus, printing a one-line diff of what
+    /// changed
+    Update {
+        /// If set, keeps running and refreshes the cache on an interval instead of exiting
+        #[arg(long)]
+        watch: bool,
+
+        /// Seconds between refreshes in watch mode
+        #[arg(long, default_value_t = 180)]
+        interval: u64,
+
+        /// Output format for the refresh summary
+        #[arg(long, value_enum, default_value_t)]
+        format: UpdateFormat,
+
+        /// If set, sends a desktop notification for new or newly-overdue tasks (watch mode
+        /// always does)
+        #[arg(long)]
+        notify: bool,
+    },
+
+    /// Print cached task names for dynamic shell completion; hidden since shells call it
+    /// mid-keystroke
+    #[command(name = "__complete-tasks", hide = true)]
+    CompleteTasks {
+        /// If set, prints the task gid before each name, tab-separated
+        #[arg(long)]
+        with_gids: bool,
+    },
+}
+
+/// Subcommands of the focus command.
+#[derive(Debug, Subcommand)]
+pub enum FocusCommand {
+    /// Run the focus routine
+    Run,
+    /// Print out an overview of the focus day, or of a range of past days
+    Overview {
+        /// Earliest day of a range to print, as a date or a flexible form like `2024-01-01`
+        #[arg(long, conflicts_with = "last")]
+        from: Option<String>,
+        /// Latest day of the range, defaulting to today
+        #[arg(long, requires = "from")]
+        to: Option<String>,
+        /// Shorthand for a trailing window ending today, like `7d` or `2w`
+        #[arg(long)]
+        last: Option<String>,
+
+        /// Show the whole current week, with its goals at the top
+        #[arg(long, conflicts_with_all = ["from", "last"])]
+        week: bool,
+
+        /// Annotate backfilled stats with how many days late they were entered
+        #[arg(long)]
+        verbose: bool,
+
+        /// Also print the recorded entry log: when each stat value was written
+        #[arg(long)]
+        audit: bool,
+    },
+    /// Show this week's goals, or edit them in $EDITOR with `--set`
+    Goals {
+        /// Open the goals in $EDITOR, creating the goals task if the week has none yet
+        #[arg(long)]
+        set: bool,
+    },
+    /// Complete the day tasks of old focus weeks and rename their sections out of the way
+    Archive {
+        /// How many of the most recent weeks to leave untouched
+        #[arg(long, default_value_t = 8)]
+        keep_weeks: u8,
+    },
+}
+
+/// Subcommands of the report command.
+#[derive(Debug, Subcommand)]
+pub enum ReportCommand {
+    /// Weekly review skeleton: focus stats, diary entries, completions, and leftovers
+    Week {
+        /// A date inside the week to report on; this week when omitted
+        #[arg(long)]
+        date: Option<NaiveDate>,
+
+        /// File to write the report to instead of stdout
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+}
+
+/// Subcommands of the export command.
+#[derive(Debug, Subcommand)]
+pub enum ExportCommand {
+    /// Write an iCalendar feed of tasks with due dates, as all-day events
+    Ical {
+        /// File to write the feed to instead of stdout
+        #[arg(long)]
+        out: Option<PathBuf>,
+
+        /// How many days ahead to include; overdue tasks are always included
+        #[arg(long, default_value_t = 30)]
+        days: u64,
+
+        /// If set, also emits a VTODO entry per task for calendar apps with task lists
+        #[arg(long)]
+        todos: bool,
+    },
+
+    /// Print taskwarrior-compatible JSON, suitable for `task import`
+    Taskwarrior {
+        /// If set, only exports one due bucket instead of every task
+        #[arg(long, value_enum)]
+        filter: Option<TaskwarriorFilter>,
+    },
+}
+
+/// Subcommands of the config command.
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Print every configuration key, its effective value, and where it came from
+    List,
+
+    /// Print the effective value of one key
+    Get {
+        /// Dotted configuration key, e.g. `status.max_width`
+        key: String,
+    },
+
+    /// Set one key and write the configuration file
+    Set {
+        /// Dotted configuration key, e.g. `status.max_width`
+        key: String,
+
+        /// Value to set the key to
+        value: String,
+    },
+
+    /// Open the configuration file in $EDITOR, re-validating it on save
+    Edit,
+}
+
+/// Subcommands of the install command.
+#[derive(Debug, Subcommand)]
+pub enum InstallCommand {
+    /// Probe the filesystem and report the real state of every integration
+    Show,
+
+    /// Print a custom command block for the starship prompt
+    Starship,
+
+    /// Print crontab lines scheduling conditional focus reminders
+    Notifications,
+
+    /// Print or write the .zshrc integration block (completions and the terminal gate)
+    Zsh {
+        /// If set, writes the block into the zshrc (replacing any previous one) instead of
+        /// printing it
+        #[arg(long)]
+        write: bool,
+
+        /// If set with --write, shows the resulting change without touching the file
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Path of the zshrc to write to
+        #[arg(long, default_value = "~/.zshrc")]
+        path: PathBuf,
+    },
+
+    /// Print or write the .tmux.conf status line integration
+    Tmux {
+        /// If set, writes the block into the tmux configuration (replacing any previous one)
+        /// instead of printing it
+        #[arg(long)]
+        write: bool,
+
+        /// If set with --write, shows the resulting change without touching the file
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Path of the tmux configuration to write to
+        #[arg(long, default_value = "~/.tmux.conf")]
+        path: PathBuf,
+
+        /// If set, targets the dracula theme's custom script slot instead of status-right
+        #[arg(long)]
+        dracula: bool,
+    },
+
+    /// Write Raycast script commands for the summary and quick task adding (macOS)
+    Raycast {
+        /// Directory to write the scripts to
+        #[arg(long, default_value = "~/.config/raycast/scripts")]
+        dir: PathBuf,
+    },
+
+    /// Write systemd user units for scheduled cache updates and focus reminders (Linux)
+    Systemd {
+        /// If set, also enables and starts the units with systemctl --user
+        #[arg(long)]
+        enable: bool,
+
+        /// If set, removes the units instead of writing them
+        #[arg(long)]
+        uninstall: bool,
+
+        /// If set, runs `todo update --watch` as a long-lived service instead of a timer
+        #[arg(long)]
+        service: bool,
+    },
+
+    /// Generate man pages from the command definitions
+    Man {
+        /// If set, writes the pages to the system man directory instead of stdout
+        #[arg(long)]
+        write: bool,
+
+        /// Directory to write the pages to; implies --write
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+
+    /// Generate shell completions
+    Completions {
+        /// Shell to generate completions for
+        #[arg(value_enum)]
+        shell: CompletionShell,
+
+        /// If set, writes to the conventional per-shell directory instead of stdout
+        #[arg(long)]
+        write: bool,
+    },
+}
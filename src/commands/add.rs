@@ -0,0 +1,141 @@
+//! Implementation of the `add` subcommand, which captures a new task into the task list.
+//!
+//! The network side lives in the binary like every other mutation; this module holds the pure
+//! parts: resolving assignee and follower specs against the workspace users, and spotting
+//! likely duplicates among the cached tasks before anything is created.
+
+use crate::task::{UserTask, WorkspaceUser};
+
+/// Similarity ratio at or above which an existing task counts as a likely duplicate.
+const DUPLICATE_THRESHOLD: f64 = 0.8;
+
+/// Resolve an assignee or follower spec — a user gid, an email, a name, or `me` — to a user
+/// gid against the workspace users.
+///
+/// `me` and all-digit gids pass through untouched; everything else is matched
+/// case-insensitively against emails first and names second.
+///
+/// # Errors
+///
+/// This function will return an error if the spec matches nobody, listing the closest
+/// workspace users rather than silently falling back to `me`.
+pub fn resolve_user(spec: &str, users: &[WorkspaceUser]) -> anyhow::Result<String> {
+    if spec == "me" || (!spec.is_empty() && spec.chars().all(|c| c.is_ascii_digit())) {
+        return Ok(spec.to_string());
+    }
+
+    if let Some(user) = users.iter().find(|user| {
+        user.email
+            .as_deref()
+            .is_some_and(|email| email.eq_ignore_ascii_case(spec))
+    }) {
+        return Ok(user.gid.clone());
+    }
+    if let Some(user) = users
+        .iter()
+        .find(|user| user.name.eq_ignore_ascii_case(spec))
+    {
+        return Ok(user.gid.clone());
+    }
+
+    let mut close: Vec<&WorkspaceUser> = users
+        .iter()
+        .filter(|user| {
+            crate::utils::name_similarity(&user.name, spec) > 0.5
+                || user.email.as_deref().is_some_and(|email| {
+                    crate::utils::name_similarity(email, spec) > 0.5
+                })
+        })
+        .collect();
+    close.truncate(3);
+    if close.is_empty() {
+        anyhow::bail!("no workspace user matches `{spec}`");
+    }
+    let suggestions: Vec<String> = close
+        .iter()
+        .map(|user| match &user.email {
+            Some(email) => format!("{} <{email}>", user.name),
+            None => user.name.clone(),
+        })
+        .collect();
+    anyhow::bail!(
+        "no workspace user matches `{spec}`; did you mean {}?",
+        suggestions.join(", ")
+    )
+}
+
+/// Find a cached incomplete task that is likely the same as the one about to be added, if any.
+///
+/// Matches are case-insensitive with whitespace collapsed, or fuzzy above a similarity
+/// threshold — close enough to catch "Book dentist" vs "book the dentist" without tripping on
+/// genuinely different tasks.
+#[must_use]
+pub fn find_duplicate<'a>(name: &str, tasks: &'a [UserTask]) -> Option<&'a UserTask> {
+    tasks
+        .iter()
+        .find(|task| crate::utils::name_similarity(&task.name, name) >= DUPLICATE_THRESHOLD)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(gid: &str, name: &str, email: Option<&str>) -> WorkspaceUser {
+        WorkspaceUser {
+            gid: gid.to_string(),
+            name: name.to_string(),
+            email: email.map(str::to_string),
+        }
+    }
+
+    fn users() -> Vec<WorkspaceUser> {
+        vec![
+            user("1", "Sam Doe", Some("sam@example.com")),
+            user("2", "Alex Roe", Some("alex@example.com")),
+            user("3", "No Email", None),
+        ]
+    }
+
+    #[test]
+    fn me_and_gids_pass_through_unresolved() {
+        assert_eq!(resolve_user("me", &[]).unwrap(), "me");
+        assert_eq!(resolve_user("120017989", &[]).unwrap(), "120017989");
+    }
+
+    #[test]
+    fn emails_and_names_resolve_case_insensitively() {
+        let users = users();
+        assert_eq!(resolve_user("SAM@example.com", &users).unwrap(), "1");
+        assert_eq!(resolve_user("alex roe", &users).unwrap(), "2");
+    }
+
+    #[test]
+    fn unknown_specs_error_with_close_matches() {
+        let users = users();
+        let message = resolve_user("sma@example.com", &users).unwrap_err().to_string();
+        assert!(message.contains("no workspace user matches"), "{message}");
+        assert!(message.contains("sam@example.com"), "{message}");
+
+        let message = resolve_user("zzz", &users).unwrap_err().to_string();
+        assert!(!message.contains("did you mean"), "{message}");
+    }
+
+    fn task(gid: &str, name: &str) -> UserTask {
+        UserTask {
+            gid: gid.to_string(),
+            created_at: "2024-01-01T00:00:00Z".parse().unwrap(),
+            due_on: None,
+            name: name.to_string(),
+            projects: Vec::new(),
+            custom_fields: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn near_duplicates_are_found_but_different_tasks_are_not() {
+        let tasks = vec![task("1", "Book dentist"), task("2", "Water the plants")];
+        assert_eq!(find_duplicate("book  Dentist", &tasks).unwrap().gid, "1");
+        assert_eq!(find_duplicate("Book dentists", &tasks).unwrap().gid, "1");
+        assert!(find_duplicate("File taxes", &tasks).is_none());
+    }
+}
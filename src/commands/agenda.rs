@@ -0,0 +1,288 @@
+//! The agenda command, which renders a day-by-day calendar of upcoming tasks.
+
+use std::{collections::BTreeMap, fmt::Write as _};
+
+use chrono::{Days, NaiveDate};
+use console::style;
+
+use crate::task::UserTask;
+
+/// Output format for the agenda command.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum AgendaFormat {
+    /// Human-readable, possibly styled output.
+    #[default]
+    Plain,
+    /// Markdown headings and bullets, for pasting into planning docs.
+    Markdown,
+}
+
+/// State of a focus day, shown as a marker next to its date heading.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FocusMarker {
+    /// All of the day's stats have been filled in.
+    Done,
+    /// Some of the day's stats are still missing.
+    Pending,
+}
+
+impl FocusMarker {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Done => "focus done",
+            Self::Pending => "focus pending",
+        }
+    }
+}
+
+/// Render a day-by-day agenda starting at `today` and spanning the given number of weeks.
+///
+/// Overdue tasks spill over under today's heading, which turns red when any exist. Runs of empty
+/// days collapse into a single "no tasks until ..." line, and dates with a known focus day carry
+/// a done/pending marker.
+///
+/// Styling follows the global console color settings, so output is unstyled when not attached to
+/// a terminal; the markdown format never emits styling.
+///
+/// `date_format` overrides the `%a %b %-d` date headings (and the `%b %-d` overdue dates) with a
+/// configured strftime string.
+#[must_use]
+pub fn render(
+    tasks: &[UserTask],
+    focus_days: &[(NaiveDate, FocusMarker)],
+    today: NaiveDate,
+    weeks: u8,
+    format: AgendaFormat,
+    date_format: Option<&str>,
+) -> String {
+    let end = today + Days::new(u64::from(weeks) * 7);
+
+    let mut overdue: Vec<&UserTask> = tasks
+        .iter()
+        .filter(|task| task.due_on.is_some_and(|due| due < today))
+        .collect();
+    overdue.sort_by_key(|task| task.due_on);
+
+    let mut by_day: BTreeMap<NaiveDate, Vec<&UserTask>> = BTreeMap::new();
+    for task in tasks {
+        if let Some(due) = task.due_on {
+            if (today..end).contains(&due) {
+                by_day.entry(due).or_default().push(task);
+            }
+        }
+    }
+
+    let mut string = String::new();
+    let mut gap_start: Option<NaiveDate> = None;
+    let mut date = today;
+    while date < end {
+        let day_tasks = by_day.get(&date).map(Vec::as_slice).unwrap_or_default();
+        let marker = focus_days
+            .iter()
+            .find(|(day, _)| *day == date)
+            .map(|(_, marker)| *marker);
+        let is_today = date == today;
+
+        if day_tasks.is_empty() && !is_today && marker.is_none() {
+            gap_start.get_or_insert(date);
+            date = date + Days::new(1);
+            continue;
+        }
+
+        if gap_start.take().is_some() {
+            push_gap(&mut string, Some(date), format);
+        }
+        push_heading(&mut string, date, marker, is_today, !overdue.is_empty(), format, date_format);
+        if is_today {
+            for task in overdue.drain(..) {
+                push_overdue_task(&mut string, task, format, date_format);
+            }
+        }
+        for task in day_tasks {
+            let _ = writeln!(string, "- {}", task.name);
+        }
+        string.push('\n');
+        date = date + Days::new(1);
+    }
+
+    if gap_start.is_some() {
+        push_gap(&mut string, None, format);
+    }
+
+    string
+}
+
+fn push_heading(
+    string: &mut String,
+    date: NaiveDate,
+    marker: Option<FocusMarker>,
+    is_today: bool,
+    has_spillover: bool,
+    format: AgendaFormat,
+    date_format: Option<&str>,
+) {
+    let heading = crate::utils::format_date(date, date_format.unwrap_or("%a %b %-d"));
+    match format {
+        AgendaFormat::Plain => {
+            let heading = match (is_today, has_spillover) {
+                (true, true) => style(heading).red().bold(),
+                (true, false) => style(heading).bold(),
+                _ => style(heading),
+            };
+            let _ = write!(string, "{heading}");
+            if is_today {
+                let _ = write!(string, " {}", style("(today)").dim());
+            }
+            if let Some(marker) = marker {
+                let label = match marker {
+                    FocusMarker::Done => style(marker.label()).green(),
+                    FocusMarker::Pending => style(marker.label()).yellow(),
+                };
+                let _ = write!(string, " {} {label}", style("·").dim());
+            }
+        }
+        AgendaFormat::Markdown => {
+            let _ = write!(string, "## {heading}");
+            if is_today {
+                let _ = write!(string, " (today)");
+            }
+            if let Some(marker) = marker {
+                let _ = write!(string, " · {}", marker.label());
+            }
+        }
+    }
+    string.push('\n');
+}
+
+fn push_gap(string: &mut String, until: Option<NaiveDate>, format: AgendaFormat) {
+    let line = match until {
+        Some(date) => format!("no tasks until {weekday}", weekday = date.format("%a")),
+        None => "no more tasks".to_string(),
+    };
+    let line = format!("— {line}");
+    match format {
+        AgendaFormat::Plain => {
+            let _ = writeln!(string, "{}", style(line).dim());
+        }
+        AgendaFormat::Markdown => {
+            let _ = writeln!(string, "{line}");
+        }
+    }
+    string.push('\n');
+}
+
+fn push_overdue_task(
+    string: &mut String,
+    task: &UserTask,
+    format: AgendaFormat,
+    date_format: Option<&str>,
+) {
+    // Spillover is only rendered under today's heading, so the due date always exists.
+    let since = format!(
+        "overdue since {}",
+        crate::utils::format_date(task.due_on.unwrap(), date_format.unwrap_or("%b %-d"))
+    );
+    let since = match format {
+        AgendaFormat::Plain => style(format!("({since})")).red().to_string(),
+        AgendaFormat::Markdown => format!("({since})"),
+    };
+    let _ = writeln!(string, "- {since} {}", task.name);
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Local, TimeZone};
+
+    use super::*;
+
+    fn task(gid: &str, due_on: Option<&str>) -> UserTask {
+        UserTask {
+            gid: gid.to_string(),
+            created_at: Local.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap(),
+            due_on: due_on.map(|d| d.parse().unwrap()),
+            name: format!("task {gid}"),
+            projects: Vec::new(),
+            custom_fields: Vec::new(),
+        }
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn plain_agenda_collapses_gaps_and_spills_overdue_under_today() {
+        let tasks = vec![
+            task("1", Some("2024-01-10")),
+            task("2", Some("2024-01-15")),
+            task("3", Some("2024-01-18")),
+            task("4", None),
+        ];
+        console::set_colors_enabled(false);
+        let agenda = render(&tasks, &[], date("2024-01-15"), 1, AgendaFormat::Plain, None);
+        assert_eq!(
+            agenda,
+            "Mon Jan 15 (today)\n\
+             - (overdue since Jan 10) task 1\n\
+             - task 2\n\
+             \n\
+             — no tasks until Thu\n\
+             \n\
+             Thu Jan 18\n\
+             - task 3\n\
+             \n\
+             — no more tasks\n\
+             \n"
+        );
+    }
+
+    #[test]
+    fn focus_markers_keep_their_day_visible() {
+        let tasks = vec![task("1", Some("2024-01-15"))];
+        console::set_colors_enabled(false);
+        let agenda = render(
+            &tasks,
+            &[(date("2024-01-16"), FocusMarker::Pending)],
+            date("2024-01-15"),
+            1,
+            AgendaFormat::Plain,
+            None,
+        );
+        assert!(agenda.contains("Tue Jan 16 · focus pending\n"));
+    }
+
+    #[test]
+    fn markdown_agenda_uses_headings_and_no_styling() {
+        let tasks = vec![task("1", Some("2024-01-10")), task("2", Some("2024-01-16"))];
+        let agenda = render(
+            &tasks,
+            &[(date("2024-01-15"), FocusMarker::Done)],
+            date("2024-01-15"),
+            1,
+            AgendaFormat::Markdown,
+            None,
+        );
+        assert_eq!(
+            agenda,
+            "## Mon Jan 15 (today) · focus done\n\
+             - (overdue since Jan 10) task 1\n\
+             \n\
+             ## Tue Jan 16\n\
+             - task 2\n\
+             \n\
+             — no more tasks\n\
+             \n"
+        );
+        assert!(!agenda.contains('\x1b'));
+    }
+
+    #[test]
+    fn weeks_bound_the_agenda_window() {
+        let tasks = vec![task("1", Some("2024-01-25"))];
+        console::set_colors_enabled(false);
+        let one_week = render(&tasks, &[], date("2024-01-15"), 1, AgendaFormat::Plain, None);
+        let two_weeks = render(&tasks, &[], date("2024-01-15"), 2, AgendaFormat::Plain, None);
+        assert!(!one_week.contains("task 1"));
+        assert!(two_weeks.contains("Thu Jan 25\n- task 1\n"));
+    }
+}
@@ -0,0 +1,58 @@
+//! Implementation of the `attach` subcommand, which uploads files onto tasks.
+//!
+//! The upload itself lives in the client like every other network call; this module holds the
+//! pure part: picking a content type for a file when the user did not pass one explicitly.
+
+use std::path::Path;
+
+/// The content type to upload a file under, sniffed from its extension.
+///
+/// Covers the kinds of files that usually end up on a task — screenshots, logs, documents,
+/// archives — and falls back to `application/octet-stream` for anything unrecognized, which
+/// Asana accepts for arbitrary binaries. `--mime` overrides the sniff entirely.
+#[must_use]
+pub fn sniff_mime(path: &Path) -> &'static str {
+    match path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .as_deref()
+    {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("svg") => "image/svg+xml",
+        Some("pdf") => "application/pdf",
+        Some("txt" | "log") => "text/plain",
+        Some("md") => "text/markdown",
+        Some("csv") => "text/csv",
+        Some("html") => "text/html",
+        Some("json") => "application/json",
+        Some("zip") => "application/zip",
+        Some("gz") => "application/gzip",
+        Some("mp4") => "video/mp4",
+        Some("mov") => "video/quicktime",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn common_extensions_map_to_their_types_case_insensitively() {
+        assert_eq!(sniff_mime(Path::new("shot.PNG")), "image/png");
+        assert_eq!(sniff_mime(Path::new("crash.log")), "text/plain");
+        assert_eq!(sniff_mime(Path::new("report.pdf")), "application/pdf");
+    }
+
+    #[test]
+    fn unknown_or_missing_extensions_fall_back_to_octet_stream() {
+        assert_eq!(
+            sniff_mime(Path::new("core.bin")),
+            "application/octet-stream"
+        );
+        assert_eq!(sniff_mime(Path::new("Makefile")), "application/octet-stream");
+    }
+}
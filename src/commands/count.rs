@@ -0,0 +1,165 @@
+//! Implementation of the `count` subcommand, which prints machine-readable task counts.
+
+use serde::Serialize;
+
+use crate::context::GroupedTasks;
+use crate::focus::FocusDay;
+
+/// Output format for the `count` subcommand.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum CountFormat {
+    /// `KEY=value` pairs on a single line, suitable for `eval` in a shell.
+    #[default]
+    Shell,
+    /// Flat JSON object.
+    Json,
+}
+
+/// Task counts and focus progress flags, with no styling applied.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct Counts {
+    /// Number of overdue tasks.
+    pub overdue: usize,
+    /// Number of tasks due today.
+    pub due_today: usize,
+    /// Number of tasks due within the next week.
+    pub due_week: usize,
+    /// Number of tasks without a due date.
+    pub no_due_date: usize,
+    /// Whether today's morning focus stats have been filled in.
+    pub morning_done: bool,
+    /// Whether today's evening focus stats have been filled in.
+    pub evening_done: bool,
+    /// Whether the counts come from a cache fallback after the network budget expired, rather
+    /// than a fresh fetch.
+    pub stale: bool,
+}
+
+impl Counts {
+    /// Compute counts from grouped tasks and today's focus day, if one is cached.
+    ///
+    /// A missing focus day counts as neither morning nor evening being done.
+    #[must_use]
+    pub fn new(grouped: &GroupedTasks, focus_day: Option<&FocusDay>) -> Self {
+        Self {
+            overdue: grouped.overdue.len(),
+            due_today: grouped.due_today.len(),
+            due_week: grouped.due_week.len(),
+            no_due_date: grouped.no_due_date.len(),
+            morning_done: focus_day.is_some_and(FocusDay::is_morning_done),
+            evening_done: focus_day.is_some_and(FocusDay::is_evening_done),
+            stale: false,
+        }
+    }
+}
+
+/// Render the counts as a single line of `KEY=value` pairs, with booleans as `1`/`0`.
+#[must_use]
+pub fn render_shell(counts: &Counts) -> String {
+    format!(
+        "OVERDUE={overdue} DUE_TODAY={due_today} DUE_WEEK={due_week} NO_DUE_DATE={no_due_date} MORNING_DONE={morning_done} EVENING_DONE={evening_done} STALE={stale}",
+        overdue = counts.overdue,
+        due_today = counts.due_today,
+        due_week = counts.due_week,
+        no_due_date = counts.no_due_date,
+        morning_done = u8::from(counts.morning_done),
+        evening_done = u8::from(counts.evening_done),
+        stale = u8::from(counts.stale),
+    )
+}
+
+/// Render the counts as a flat JSON object.
+///
+/// # Errors
+///
+/// This function will return an error if the counts could not be serialized.
+pub fn render_json(counts: &Counts) -> anyhow::Result<String> {
+    Ok(serde_json::to_string(counts)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::focus::{FocusDayStats, FocusTask};
+    use crate::task::UserTask;
+
+    use super::*;
+
+    fn task(gid: &str, due_on: Option<&str>) -> UserTask {
+        UserTask {
+            gid: gid.to_string(),
+            created_at: "2024-01-01T00:00:00Z".parse().unwrap(),
+            due_on: due_on.map(|d| d.parse().unwrap()),
+            name: format!("task {gid}"),
+            projects: Vec::new(),
+            custom_fields: Vec::new(),
+        }
+    }
+
+    fn focus_day(stats: FocusDayStats) -> FocusDay {
+        FocusDay {
+            task: FocusTask {
+                gid: "1".to_string(),
+                name: "Daily Focus for Mon (2024-01-15)".to_string(),
+                notes: String::new(),
+                modified_at: None,
+                custom_fields: None,
+            },
+            date: NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+            stats,
+            diary: String::new(),
+            prompts: vec![],
+            subtasks: None,
+        }
+    }
+
+    fn counts(tasks: &[UserTask], focus_day: Option<&FocusDay>) -> Counts {
+        let today = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        Counts::new(&GroupedTasks::group(tasks, today), focus_day)
+    }
+
+    #[test]
+    fn shell_format_is_a_single_line_of_key_value_pairs() {
+        let tasks = vec![
+            task("1", Some("2024-01-10")),
+            task("2", Some("2024-01-10")),
+            task("3", Some("2024-01-15")),
+            task("4", Some("2024-01-18")),
+            task("5", None),
+        ];
+        assert_eq!(
+            render_shell(&counts(&tasks, None)),
+            "OVERDUE=2 DUE_TODAY=1 DUE_WEEK=1 NO_DUE_DATE=1 MORNING_DONE=0 EVENING_DONE=0 STALE=0"
+        );
+
+        let mut stale = counts(&tasks, None);
+        stale.stale = true;
+        assert!(render_shell(&stale).ends_with(" STALE=1"));
+    }
+
+    #[test]
+    fn json_format_is_a_flat_object() {
+        let tasks = vec![task("1", Some("2024-01-15"))];
+        let parsed: serde_json::Value =
+            serde_json::from_str(&render_json(&counts(&tasks, None)).unwrap()).unwrap();
+        assert_eq!(parsed["overdue"], 0);
+        assert_eq!(parsed["due_today"], 1);
+        assert_eq!(parsed["due_week"], 0);
+        assert_eq!(parsed["no_due_date"], 0);
+        assert_eq!(parsed["morning_done"], false);
+        assert_eq!(parsed["evening_done"], false);
+        assert_eq!(parsed["stale"], false);
+    }
+
+    #[test]
+    fn morning_and_evening_flags_follow_the_focus_day_stats() {
+        let mut stats = FocusDayStats::default();
+        stats.sleep.set_value(Some(7));
+        stats.energy.set_value(Some(8));
+        let day = focus_day(stats);
+        let counts = counts(&[], Some(&day));
+        assert!(counts.morning_done);
+        assert!(!counts.evening_done);
+    }
+}
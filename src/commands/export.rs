@@ -0,0 +1,221 @@
+//! The export command, which converts cached tasks into external formats.
+
+use chrono::{Days, NaiveDate, Utc};
+
+use crate::task::UserTask;
+
+/// Escape text for an iCalendar property value per RFC 5545 section 3.3.11.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '\\' => escaped.push_str(r"\\"),
+            ';' => escaped.push_str(r"\;"),
+            ',' => escaped.push_str(r"\,"),
+            '\n' => escaped.push_str(r"\n"),
+            '\r' => {}
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+/// Append one content line, folded at 75 octets with CRLF-space continuations per RFC 5545
+/// section 3.1.
+fn push_line(string: &mut String, line: &str) {
+    let mut octets = 0;
+    for character in line.chars() {
+        if octets + character.len_utf8() > 75 {
+            string.push_str("\r\n ");
+            // The leading space of the continuation counts against the next line's limit.
+            octets = 1;
+        }
+        string.push(character);
+        octets += character.len_utf8();
+    }
+    string.push_str("\r\n");
+}
+
+/// Render cached tasks as an iCalendar feed of all-day VEVENT entries, one per task with a due
+/// date no further out than `days` days from `today`. Overdue tasks are always included.
+///
+/// UIDs are derived from task gids, so re-importing a fresh export updates events in place
+/// instead of duplicating them. With `todos` set, each task additionally gets a VTODO entry
+/// (under its own UID) for calendar apps that show task lists.
+///
+/// # Panics
+///
+/// This function will panic if a selected task has no due date, which the due-date filter
+/// guarantees cannot happen.
+#[must_use]
+pub fn render_ical(tasks: &[UserTask], today: NaiveDate, days: u64, todos: bool) -> String {
+    let end = today + Days::new(days);
+
+    let mut due_tasks: Vec<&UserTask> = tasks
+        .iter()
+        .filter(|task| task.due_on.is_some_and(|due| due <= end))
+        .collect();
+    due_tasks.sort_by_key(|task| (task.due_on, task.gid.as_str()));
+
+    let mut string = String::new();
+    push_line(&mut string, "BEGIN:VCALENDAR");
+    push_line(&mut string, "VERSION:2.0");
+    push_line(&mut string, "PRODID:-//todo//todo//EN");
+    push_line(&mut string, "CALSCALE:GREGORIAN");
+
+    for task in &due_tasks {
+        let due = task.due_on.unwrap();
+        let stamp = task
+            .created_at
+            .with_timezone(&Utc)
+            .format("%Y%m%dT%H%M%SZ");
+        push_line(&mut string, "BEGIN:VEVENT");
+        push_line(&mut string, &format!("UID:task-{}@todo", task.gid));
+        push_line(&mut string, &format!("DTSTAMP:{stamp}"));
+        push_line(
+            &mut string,
+            &format!("DTSTART;VALUE=DATE:{}", due.format("%Y%m%d")),
+        );
+        // All-day events end on the next day, since DTEND is exclusive.
+        push_line(
+            &mut string,
+            &format!(
+                "DTEND;VALUE=DATE:{}",
+                (due + Days::new(1)).format("%Y%m%d")
+            ),
+        );
+        push_line(&mut string, &format!("SUMMARY:{}", escape(&task.name)));
+        push_line(
+            &mut string,
+            &format!("URL:{}", crate::render::task_permalink(&task.gid)),
+        );
+        push_line(&mut string, "END:VEVENT");
+
+        if todos {
+            push_line(&mut string, "BEGIN:VTODO");
+            push_line(&mut string, &format!("UID:todo-{}@todo", task.gid));
+            push_line(&mut string, &format!("DTSTAMP:{stamp}"));
+            push_line(
+                &mut string,
+                &format!("DUE;VALUE=DATE:{}", due.format("%Y%m%d")),
+            );
+            push_line(&mut string, &format!("SUMMARY:{}", escape(&task.name)));
+            push_line(&mut string, "STATUS:NEEDS-ACTION");
+            push_line(&mut string, "END:VTODO");
+        }
+    }
+
+    push_line(&mut string, "END:VCALENDAR");
+    string
+}
+
+/// Count of the VEVENT entries in a rendered feed, used for the confirmation message.
+#[must_use]
+pub fn event_count(ical: &str) -> usize {
+    ical.matches("BEGIN:VEVENT").count()
+}
+
+/// Human-readable event count, e.g. "1 event" or "3 events".
+#[must_use]
+pub fn event_or_events(count: usize) -> String {
+    format!(
+        "{count} event{plural}",
+        plural = if count == 1 { "" } else { "s" }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Local, TimeZone};
+
+    use super::*;
+
+    fn task(gid: &str, name: &str, due_on: Option<&str>) -> UserTask {
+        UserTask {
+            gid: gid.to_string(),
+            created_at: Local.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap(),
+            due_on: due_on.map(|d| d.parse().unwrap()),
+            name: name.to_string(),
+            projects: Vec::new(),
+            custom_fields: Vec::new(),
+        }
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn ical_renders_all_day_events_with_stable_uids() {
+        let tasks = vec![
+            task("2", "buy milk", Some("2024-01-16")),
+            task("1", "call mom; urgently", Some("2024-01-10")),
+            task("3", "undated", None),
+        ];
+        let stamp = Local
+            .with_ymd_and_hms(2024, 1, 1, 12, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc)
+            .format("%Y%m%dT%H%M%SZ");
+        let ical = render_ical(&tasks, date("2024-01-15"), 30, false);
+        assert_eq!(
+            ical,
+            format!(
+                "BEGIN:VCALENDAR\r\n\
+                 VERSION:2.0\r\n\
+                 PRODID:-//todo//todo//EN\r\n\
+                 CALSCALE:GREGORIAN\r\n\
+                 BEGIN:VEVENT\r\n\
+                 UID:task-1@todo\r\n\
+                 DTSTAMP:{stamp}\r\n\
+                 DTSTART;VALUE=DATE:20240110\r\n\
+                 DTEND;VALUE=DATE:20240111\r\n\
+                 SUMMARY:call mom\\; urgently\r\n\
+                 URL:https://app.asana.com/0/0/1/f\r\n\
+                 END:VEVENT\r\n\
+                 BEGIN:VEVENT\r\n\
+                 UID:task-2@todo\r\n\
+                 DTSTAMP:{stamp}\r\n\
+                 DTSTART;VALUE=DATE:20240116\r\n\
+                 DTEND;VALUE=DATE:20240117\r\n\
+                 SUMMARY:buy milk\r\n\
+                 URL:https://app.asana.com/0/0/2/f\r\n\
+                 END:VEVENT\r\n\
+                 END:VCALENDAR\r\n"
+            )
+        );
+        assert_eq!(event_count(&ical), 2);
+    }
+
+    #[test]
+    fn ical_days_bound_the_window_but_overdue_always_exports() {
+        let tasks = vec![
+            task("1", "long overdue", Some("2023-06-01")),
+            task("2", "far out", Some("2024-06-01")),
+        ];
+        let ical = render_ical(&tasks, date("2024-01-15"), 30, false);
+        assert!(ical.contains("UID:task-1@todo"));
+        assert!(!ical.contains("UID:task-2@todo"));
+    }
+
+    #[test]
+    fn ical_todos_flag_adds_vtodo_entries() {
+        let tasks = vec![task("1", "buy milk", Some("2024-01-16"))];
+        let ical = render_ical(&tasks, date("2024-01-15"), 30, true);
+        assert!(ical.contains("BEGIN:VTODO\r\n"));
+        assert!(ical.contains("UID:todo-1@todo\r\n"));
+        assert!(ical.contains("DUE;VALUE=DATE:20240116\r\n"));
+        assert!(ical.contains("STATUS:NEEDS-ACTION\r\n"));
+    }
+
+    #[test]
+    fn ical_escapes_and_folds_long_summaries() {
+        let tasks = vec![task("1", &"a".repeat(100), Some("2024-01-16"))];
+        let ical = render_ical(&tasks, date("2024-01-15"), 30, false);
+        assert!(ical.contains(&format!("SUMMARY:{}\r\n {}\r\n", "a".repeat(67), "a".repeat(33))));
+        for line in ical.split("\r\n") {
+            assert!(line.len() <= 75, "line too long: {line}");
+        }
+        assert_eq!(escape("a\\b;c,d\ne"), r"a\\b\;c\,d\ne");
+    }
+}
@@ -0,0 +1,436 @@
+//! Looking up and creating focus days in the Asana focus project.
+//!
+//! [`find_focus_day`] is strictly read-only; [`ensure_focus_day`] additionally creates the week
+//! section and the day task when they are missing. Read-only commands — summary, status, the
+//! focus overview — must use the former so that looking at the day can never mutate the project;
+//! creation stays with `update` and the focus run.
+
+use anyhow::Context;
+use chrono::{Datelike, NaiveDate, Weekday};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+use crate::asana::{Client, MutationRequest};
+use crate::focus::{FocusDay, FocusTask, FocusWeek, Section, WEEKLY_GOALS_TASK_NAME};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct CreateSectionRequest {
+    name: String,
+    insert_before: String,
+}
+
+impl<'a> MutationRequest<'a> for CreateSectionRequest {
+    type RequestData = String;
+    type ResponseData = Section;
+
+    fn segments(project_gid: &'a Self::RequestData) -> Vec<String> {
+        vec![
+            "projects".to_string(),
+            project_gid.clone(),
+            "sections".to_string(),
+        ]
+    }
+
+    fn method() -> Method {
+        Method::POST
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct CreateSectionTaskRequest {
+    name: String,
+    projects: Vec<String>,
+    memberships: Vec<CreateSectionTaskRequestMembership>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct CreateSectionTaskRequestMembership {
+    project: String,
+    section: String,
+}
+
+impl<'a> MutationRequest<'a> for CreateSectionTaskRequest {
+    type RequestData = ();
+    type ResponseData = FocusTask;
+
+    fn segments((): &'a Self::RequestData) -> Vec<String> {
+        vec!["tasks".to_string()]
+    }
+
+    fn method() -> Method {
+        Method::POST
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct AddTaskToSectionRequest {
+    task: String,
+    insert_after: String,
+}
+
+impl<'a> MutationRequest<'a> for AddTaskToSectionRequest {
+    type RequestData = String;
+    type ResponseData = serde_json::Value;
+
+    fn segments(section_gid: &'a Self::RequestData) -> Vec<String> {
+        vec![
+            "sections".to_string(),
+            section_gid.clone(),
+            "addTask".to_string(),
+        ]
+    }
+
+    fn method() -> Method {
+        Method::POST
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct AddTaskToSectionTopRequest {
+    task: String,
+    insert_before: String,
+}
+
+impl<'a> MutationRequest<'a> for AddTaskToSectionTopRequest {
+    type RequestData = String;
+    type ResponseData = serde_json::Value;
+
+    fn segments(section_gid: &'a Self::RequestData) -> Vec<String> {
+        vec![
+            "sections".to_string(),
+            section_gid.clone(),
+            "addTask".to_string(),
+        ]
+    }
+
+    fn method() -> Method {
+        Method::POST
+    }
+}
+
+/// Focus days in the week containing `day`, or an empty list when no week section (or none of
+/// its days) exists yet. Unlike [`ensure_focus_day`] this never creates anything, since reports
+/// are read-only.
+///
+/// # Errors
+///
+/// This function will return an error if the sections or tasks could not be fetched.
+pub async fn fetch_focus_week_days(
+    day: NaiveDate,
+    client: &mut Client,
+    focus_project_gid: &str,
+) -> anyhow::Result<Vec<FocusDay>> {
+    let sections = client
+        .get::<Section>(&focus_project_gid.to_string())
+        .await?;
+    let Some(week) = sections
+        .into_iter()
+        .filter(|s| s.name.starts_with("Daily Focuses"))
+        .filter_map(|s| TryInto::<FocusWeek>::try_into(s).ok())
+        .find(|w| w.from <= day && w.to >= day)
+    else {
+        return Ok(Vec::new());
+    };
+    let tasks = client.get::<FocusTask>(&week.section.gid).await?;
+    Ok(tasks
+        .into_iter()
+        .filter(|t| t.name.starts_with("Daily Focus for"))
+        .filter_map(|t| t.try_into().ok())
+        .collect())
+}
+
+/// The focus day for `day` alone, or `None` when it does not exist yet.
+///
+/// Read-only counterpart of [`ensure_focus_day`] for commands like summary and the focus
+/// overview that must not create weeks or day tasks as a side effect.
+///
+/// # Errors
+///
+/// This function will return an error if the sections or tasks could not be fetched.
+pub async fn find_focus_day(
+    day: NaiveDate,
+    client: &mut Client,
+    focus_project_gid: &str,
+) -> anyhow::Result<Option<FocusDay>> {
+    Ok(fetch_focus_week_days(day, client, focus_project_gid)
+        .await?
+        .into_iter()
+        .find(|d| d.date == day))
+}
+
+/// The weekly goals task in the week section covering `day`, or `None` when the week or the
+/// task does not exist yet. Read-only, like [`find_focus_day`].
+///
+/// # Errors
+///
+/// This function will return an error if the sections or tasks could not be fetched.
+pub async fn find_weekly_goals_task(
+    day: NaiveDate,
+    client: &mut Client,
+    focus_project_gid: &str,
+) -> anyhow::Result<Option<FocusTask>> {
+    let sections = client
+        .get::<Section>(&focus_project_gid.to_string())
+        .await?;
+    let Some(week) = sections
+        .into_iter()
+        .filter(|s| s.name.starts_with("Daily Focuses"))
+        .filter_map(|s| TryInto::<FocusWeek>::try_into(s).ok())
+        .find(|w| w.from <= day && w.to >= day)
+    else {
+        return Ok(None);
+    };
+    Ok(client
+        .get::<FocusTask>(&week.section.gid)
+        .await?
+        .into_iter()
+        .find(|t| t.name == WEEKLY_GOALS_TASK_NAME))
+}
+
+/// The weekly goals task for the week covering `day`, creating and pinning it at the top of
+/// the week section when it is missing.
+///
+/// The week section itself must already exist — `update` and the focus run create it — so that
+/// editing goals cannot spawn half-initialized weeks as a side effect.
+///
+/// # Errors
+///
+/// This function will return an error if any fetch or creation request fails, if no week
+/// section covers `day` yet, or in dry-run mode when the task does not exist.
+pub async fn ensure_weekly_goals_task(
+    day: NaiveDate,
+    client: &mut Client,
+    focus_project_gid: &str,
+) -> anyhow::Result<FocusTask> {
+    let sections = client
+        .get::<Section>(&focus_project_gid.to_string())
+        .await?;
+    let week = sections
+        .into_iter()
+        .filter(|s| s.name.starts_with("Daily Focuses"))
+        .filter_map(|s| TryInto::<FocusWeek>::try_into(s).ok())
+        .find(|w| w.from <= day && w.to >= day)
+        .with_context(|| {
+            format!("no focus week section covers {day} yet; run `todo update` first")
+        })?;
+
+    let tasks = client.get::<FocusTask>(&week.section.gid).await?;
+    if let Some(task) = tasks
+        .iter()
+        .find(|t| t.name == WEEKLY_GOALS_TASK_NAME)
+    {
+        return Ok(task.clone());
+    }
+
+    if client.dry_run() {
+        anyhow::bail!(
+            "no weekly goals task exists for {week} and dry-run mode skipped creating it"
+        );
+    }
+    tracing::info!("Creating the weekly goals task for {week}...");
+    let task = client
+        .mutate(
+            &(),
+            CreateSectionTaskRequest {
+                name: WEEKLY_GOALS_TASK_NAME.to_string(),
+                projects: vec![focus_project_gid.to_string()],
+                memberships: vec![CreateSectionTaskRequestMembership {
+                    project: focus_project_gid.to_string(),
+                    section: week.section.gid.clone(),
+                }],
+            },
+        )
+        .await
+        .context("issue creating weekly goals task")?;
+
+    // Pin the goals above the day tasks so the week always opens on them.
+    if let Some(first) = tasks.first() {
+        client
+            .mutate(
+                &week.section.gid,
+                AddTaskToSectionTopRequest {
+                    task: task.gid.clone(),
+                    insert_before: first.gid.clone(),
+                },
+            )
+            .await
+            .context("issue pinning weekly goals task")?;
+    }
+
+    Ok(task)
+}
+
+/// The focus day for `day`, creating the week section and the day task when they are missing.
+///
+/// Only `update` and the focus run may call this; read-only paths use [`find_focus_day`] instead
+/// so that looking at a summary or an overview can never mutate the focus project.
+///
+/// # Errors
+///
+/// This function will return an error if any fetch or creation request fails, or in dry-run mode
+/// when the day does not exist (creating it is exactly what a dry run must skip).
+#[allow(clippy::too_many_lines)]
+pub async fn ensure_focus_day(
+    day: NaiveDate,
+    client: &mut Client,
+    focus_project_gid: &str,
+) -> anyhow::Result<FocusDay> {
+    tracing::info!("Getting focus sections...");
+    let sections = client
+        .get::<Section>(&focus_project_gid.to_string())
+        .await?;
+    tracing::debug!("Got {} sections", sections.len());
+    tracing::trace!("Sections: {sections:#?}", sections = sections);
+
+    tracing::info!("Constructing focus weeks...");
+    let focus_weeks = sections
+        .into_iter()
+        .filter(|s| s.name.starts_with("Daily Focuses"))
+        .filter_map(|s| match s.try_into() {
+            Ok(s) => Some(s),
+            Err(err) => {
+                tracing::warn!("Could not parse focus section name: {}", err);
+                None
+            }
+        })
+        .collect::<Vec<FocusWeek>>();
+    tracing::debug!("Constructed {} focus weeks", focus_weeks.len());
+    tracing::trace!("Focus weeks: {focus_weeks:#?}", focus_weeks = focus_weeks);
+
+    tracing::info!("Finding current focus week...");
+    let mut week_created = false;
+    let current_week =
+        if let Some(current_week) = focus_weeks.iter().find(|w| w.from <= day && w.to >= day) {
+            tracing::debug!(
+                "Found current focus week: {current_week}",
+                current_week = current_week
+            );
+            current_week.clone()
+        } else {
+            tracing::warn!("Could not find current focus week, so creating it...");
+            let week = day.week(Weekday::Mon);
+            let current_week: FocusWeek = client
+                .mutate(
+                    &focus_project_gid.to_string(),
+                    CreateSectionRequest {
+                        name: format!(
+                            "Daily Focuses ({from} to {to})",
+                            from = week.first_day().format("%Y-%m-%d"),
+                            to = week.last_day().format("%Y-%m-%d")
+                        ),
+                        insert_before: focus_weeks
+                            .first()
+                            .context("unable to get any focus weeks")?
+                            .section
+                            .gid
+                            .clone(),
+                    },
+                )
+                .await
+                .context("issue creating focus week")?
+                .try_into()?;
+            tracing::debug!(
+                "Created current focus week: {current_week}",
+                current_week = current_week
+            );
+            week_created = true;
+            current_week
+        };
+    tracing::debug!(
+        "Got current focus week: {current_week}",
+        current_week = current_week
+    );
+
+    tracing::info!("Getting tasks in current focus week...");
+    // A section created a moment ago cannot contain any tasks yet, so skipping the fetch is safe
+    // and saves a round trip right after the creation mutation.
+    let tasks = if week_created {
+        Vec::new()
+    } else {
+        client.get::<FocusTask>(&current_week.section.gid).await?
+    };
+    tracing::debug!("Got {} tasks", tasks.len());
+
+    tracing::info!("Constructing focus days...");
+    let focus_days = tasks
+        .into_iter()
+        .filter(|t| t.name.starts_with("Daily Focus for"))
+        .filter_map(|t| match t.try_into() {
+            Ok(t) => Some(t),
+            Err(err) => {
+                tracing::warn!("Could not parse focus task name: {}", err);
+                None
+            }
+        })
+        .collect::<Vec<FocusDay>>();
+    tracing::debug!("Constructed {} focus days", focus_days.len());
+    tracing::trace!("Focus days: {focus_days:#?}", focus_days = focus_days);
+
+    tracing::info!("Finding current focus day...");
+    let current_day = if let Some(current_day) = focus_days.iter().find(|d| d.date == day) {
+        tracing::debug!(
+            "Found current focus day: {current_day}",
+            current_day = current_day
+        );
+        current_day.clone()
+    } else {
+        tracing::warn!("Could not find current focus day, so creating it...");
+        if client.dry_run() {
+            println!(
+                "would have created a focus day for {day} ({date})",
+                day = day.weekday(),
+                date = day.format("%Y-%m-%d")
+            );
+            anyhow::bail!("no focus day exists for {day} and dry-run mode skipped creating it");
+        }
+        let current_day: FocusDay = client
+            .mutate(
+                &(),
+                CreateSectionTaskRequest {
+                    name: format!(
+                        "Daily Focus for {day} ({date})",
+                        day = day.weekday(),
+                        date = day.format("%Y-%m-%d")
+                    ),
+                    projects: vec![focus_project_gid.to_string()],
+                    memberships: vec![CreateSectionTaskRequestMembership {
+                        project: focus_project_gid.to_string(),
+                        section: current_week.section.gid.clone(),
+                    }],
+                },
+            )
+            .await
+            .context("issue creating focus day")?
+            .try_into()?;
+        tracing::debug!(
+            "Created current focus day: {current_day}",
+            current_day = current_day
+        );
+
+        if let Some(previous_closest_day) = focus_days
+            .iter()
+            .filter(|d| d.date < day)
+            .max_by_key(|d| d.date)
+        {
+            tracing::debug!("Ordering the created focus day correctly...");
+            client
+                .mutate(
+                    &current_week.section.gid,
+                    AddTaskToSectionRequest {
+                        task: current_day.task.gid.clone(),
+                        insert_after: previous_closest_day.task.gid.clone(),
+                    },
+                )
+                .await
+                .context("issue ordering focus day")?;
+        }
+
+        current_day
+    };
+    tracing::debug!(
+        "Got current focus day: {current_day}",
+        current_day = current_day
+    );
+
+    Ok(current_day)
+}
@@ -0,0 +1,130 @@
+//! The gate command, which blocks a new shell while a focus routine is pending.
+//!
+//! Intended to be called from .zshrc with `--use-cache`, so it has to be instant and silent
+//! whenever there is nothing to nag about.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::commands::status::Status;
+
+/// Phase of the focus routine the gate is nagging about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GatePhase {
+    /// The morning focus routine.
+    Morning,
+    /// The evening focus routine.
+    Evening,
+}
+
+impl std::fmt::Display for GatePhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Morning => write!(f, "morning"),
+            Self::Evening => write!(f, "evening"),
+        }
+    }
+}
+
+/// Record of the user dismissing the gate, kept in the cache so subsequent shells that day
+/// don't nag again.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct GateAcknowledgement {
+    /// Date the gate was acknowledged on.
+    pub date: NaiveDate,
+    /// Phase of the focus routine that was acknowledged.
+    pub phase: GatePhase,
+}
+
+/// Get the phase of the focus routine the gate should nag about, if any.
+///
+/// The evening routine takes precedence once it is pending, since [`Status`] only marks it
+/// pending towards the end of the day.
+#[must_use]
+pub fn pending_phase(status: &Status) -> Option<GatePhase> {
+    if status.evening_pending {
+        Some(GatePhase::Evening)
+    } else if status.morning_pending {
+        Some(GatePhase::Morning)
+    } else {
+        None
+    }
+}
+
+/// Check whether the gate was already acknowledged today for the given phase.
+#[must_use]
+pub fn is_acknowledged(
+    acknowledgement: Option<&GateAcknowledgement>,
+    today: NaiveDate,
+    phase: GatePhase,
+) -> bool {
+    acknowledgement.is_some_and(|a| a.date == today && a.phase == phase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(morning: bool, evening: bool) -> Status {
+        Status {
+            overdue: 0,
+            due_today: 0,
+            due_week: 0,
+            done_today: 0,
+            focus_subtasks_overdue: 0,
+            focus_subtasks_pending: 0,
+            morning_pending: morning,
+            evening_pending: evening,
+            paused: false,
+            stale: false,
+        }
+    }
+
+    #[test]
+    fn pending_phase_prefers_the_evening_routine() {
+        assert_eq!(pending_phase(&status(false, false)), None);
+        assert_eq!(pending_phase(&status(true, false)), Some(GatePhase::Morning));
+        assert_eq!(pending_phase(&status(false, true)), Some(GatePhase::Evening));
+        assert_eq!(pending_phase(&status(true, true)), Some(GatePhase::Evening));
+    }
+
+    #[test]
+    fn acknowledgement_only_counts_for_the_same_date_and_phase() {
+        let today = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let acknowledgement = GateAcknowledgement {
+            date: today,
+            phase: GatePhase::Morning,
+        };
+
+        assert!(is_acknowledged(
+            Some(&acknowledgement),
+            today,
+            GatePhase::Morning
+        ));
+        assert!(!is_acknowledged(
+            Some(&acknowledgement),
+            today,
+            GatePhase::Evening
+        ));
+        assert!(!is_acknowledged(
+            Some(&acknowledgement),
+            today.succ_opt().unwrap(),
+            GatePhase::Morning
+        ));
+        assert!(!is_acknowledged(None, today, GatePhase::Morning));
+    }
+
+    #[test]
+    fn acknowledgement_round_trips_through_serialization() {
+        let acknowledgement = GateAcknowledgement {
+            date: NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+            phase: GatePhase::Evening,
+        };
+        let json = serde_json::to_string(&acknowledgement).unwrap();
+        assert_eq!(json, r#"{"date":"2024-01-15","phase":"evening"}"#);
+        let parsed: GateAcknowledgement = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.date, acknowledgement.date);
+        assert_eq!(parsed.phase, acknowledgement.phase);
+    }
+}
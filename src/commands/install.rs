@@ -0,0 +1,880 @@
+//! Implementation of the `install` subcommand, which sets up integrations with external tools.
+
+use anyhow::Context as _;
+use clap::CommandFactory as _;
+
+/// Directory man pages are written to when no `--out` directory is given.
+pub const MAN_DIR: &str = "/usr/local/share/man/man1";
+
+/// Shells the install command can generate completions for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum CompletionShell {
+    /// Bourne Again Shell.
+    Bash,
+    /// Z Shell.
+    Zsh,
+    /// Friendly Interactive Shell.
+    Fish,
+    /// Nushell.
+    Nushell,
+}
+
+/// Render the `[custom.todo]` block to paste into a starship configuration.
+///
+/// The command reads only from the cache and keeps quiet on stderr, so a stale cache or missing
+/// credentials never slow down or break the prompt.
+#[must_use]
+pub fn render_starship() -> String {
+    r#"[custom.todo]
+command = "todo --use-cache --quiet status --format starship"
+when = "test -f ~/.cache/todo/cache.json"
+shell = "sh"
+"#
+    .to_string()
+}
+
+/// Render the completion script for the given shell.
+#[must_use]
+pub fn render_completions(shell: CompletionShell) -> String {
+    let mut command = crate::cli::Args::command();
+    let mut buffer = Vec::new();
+    match shell {
+        CompletionShell::Bash => {
+            clap_complete::generate(clap_complete::shells::Bash, &mut command, "todo", &mut buffer);
+        }
+        CompletionShell::Zsh => {
+            clap_complete::generate(clap_complete::shells::Zsh, &mut command, "todo", &mut buffer);
+        }
+        CompletionShell::Fish => {
+            clap_complete::generate(clap_complete::shells::Fish, &mut command, "todo", &mut buffer);
+        }
+        CompletionShell::Nushell => {
+            clap_complete::generate(clap_complete_nushell::Nushell, &mut command, "todo", &mut buffer);
+        }
+    }
+    String::from_utf8_lossy(&buffer).into_owned()
+}
+
+/// The conventional per-shell path to write the completion script to, relative to the home
+/// directory.
+///
+/// The zsh path has to be on `fpath` before `compinit` runs, which the install zsh instructions
+/// already set up.
+#[must_use]
+pub fn completions_path(shell: CompletionShell) -> &'static str {
+    match shell {
+        CompletionShell::Bash => "~/.local/share/bash-completion/completions/todo",
+        CompletionShell::Zsh => "~/.local/share/zsh/site-functions/_todo",
+        CompletionShell::Fish => "~/.config/fish/completions/todo.fish",
+        CompletionShell::Nushell => "~/.config/nushell/completions/todo.nu",
+    }
+}
+
+/// First line of the block the install command writes into shell and tmux configuration files.
+pub const INTEGRATION_BLOCK_START: &str = "# >>> todo integration >>>";
+
+/// Last line of the block the install command writes into shell and tmux configuration files.
+pub const INTEGRATION_BLOCK_END: &str = "# <<< todo integration <<<";
+
+/// Path the dracula tmux theme loads custom scripts from, relative to the home directory.
+pub const DRACULA_SCRIPT_PATH: &str = "~/.tmux/plugins/tmux/custom/todo.sh";
+
+/// Render the block to put in .zshrc: completions on `fpath` and the terminal gate.
+#[must_use]
+pub fn render_zsh() -> String {
+    r"# Load todo's completions and block new shells while a focus routine is pending.
+fpath=(~/.local/share/zsh/site-functions $fpath)
+todo --use-cache --quiet gate
+"
+    .to_string()
+}
+
+/// Render the block to put in .tmux.conf: the short status string in the status line, or the
+/// dracula theme's custom script slot when `dracula` is set.
+#[must_use]
+pub fn render_tmux(dracula: bool) -> String {
+    if dracula {
+        r#"set -g @dracula-plugins "custom:todo.sh"
+"#
+        .to_string()
+    } else {
+        r"set -g status-right '#(todo --use-cache --quiet status) | %H:%M'
+set -g status-interval 60
+"
+        .to_string()
+    }
+}
+
+/// Render the script the dracula tmux theme runs for its custom plugin slot.
+#[must_use]
+pub fn render_dracula_script() -> String {
+    r"#!/usr/bin/env bash
+todo --use-cache --quiet status
+"
+    .to_string()
+}
+
+/// Wrap a snippet in the integration block markers.
+#[must_use]
+pub fn integration_block(snippet: &str) -> String {
+    format!("{INTEGRATION_BLOCK_START}\n{snippet}{INTEGRATION_BLOCK_END}\n")
+}
+
+/// The existing integration block in the file contents, markers included.
+#[must_use]
+pub fn find_integration_block(contents: &str) -> Option<&str> {
+    let start = contents.find(INTEGRATION_BLOCK_START)?;
+    let end = contents[start..].find(INTEGRATION_BLOCK_END)?;
+    Some(&contents[start..start + end + INTEGRATION_BLOCK_END.len()])
+}
+
+/// Return the file contents with the integration block replaced in place, or appended when no
+/// markers are present yet. Applying the same block twice is a no-op.
+#[must_use]
+pub fn upsert_integration_block(contents: &str, block: &str) -> String {
+    if let Some(existing) = find_integration_block(contents) {
+        contents.replace(existing, block.trim_end())
+    } else if contents.is_empty() {
+        block.to_string()
+    } else {
+        format!("{}\n{block}", contents.trim_end())
+    }
+}
+
+/// Integrations the uninstall command can remove.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum UninstallTarget {
+    /// The xbar plugin script, whatever refresh interval its filename carries.
+    Xbar,
+    /// The integration block in .zshrc.
+    Zsh,
+    /// The integration block in .tmux.conf and the dracula script.
+    Tmux,
+    /// The systemd user units.
+    Systemd,
+    /// Every integration above.
+    All,
+}
+
+/// Directory xbar loads plugins from, relative to the home directory.
+pub const XBAR_PLUGIN_DIR: &str = "~/Library/Application Support/xbar/plugins";
+
+/// The todo plugin scripts in an xbar plugin directory.
+///
+/// The filename encodes the refresh interval (e.g. `todo.30s.sh`), which may have changed since
+/// installation, so this matches on the `todo.` prefix and `.sh` suffix instead of an exact
+/// name. A missing directory yields no matches.
+#[must_use]
+pub fn xbar_plugin_files(dir: &std::path::Path) -> Vec<std::path::PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<_> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("todo."))
+                && path.extension().is_some_and(|extension| extension == "sh")
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Return the file contents with the integration block (markers included) surgically removed,
+/// leaving everything else untouched. Contents without a block come back unchanged.
+#[must_use]
+pub fn remove_integration_block(contents: &str) -> String {
+    let Some(block) = find_integration_block(contents) else {
+        return contents.to_string();
+    };
+    let with_trailing_newline = format!("{block}\n");
+    let removed = if contents.contains(&with_trailing_newline) {
+        contents.replace(&with_trailing_newline, "")
+    } else {
+        contents.replace(block, "")
+    };
+    if removed.trim().is_empty() {
+        String::new()
+    } else {
+        removed
+    }
+}
+
+/// Directory systemd user units are written to, relative to the home directory.
+pub const SYSTEMD_UNIT_DIR: &str = "~/.config/systemd/user";
+
+/// Names of the systemd user units the installer manages.
+pub const SYSTEMD_UNITS: &[&str] = &[
+    "todo-update.service",
+    "todo-update.timer",
+    "todo-notify-morning.service",
+    "todo-notify-morning.timer",
+    "todo-notify-evening.service",
+    "todo-notify-evening.timer",
+];
+
+/// Name of the long-lived watch service written instead of the update timer pair.
+pub const SYSTEMD_WATCH_UNIT: &str = "todo-watch.service";
+
+/// Render the long-lived watch service unit, which keeps `todo update --watch` running instead
+/// of firing a fresh process from a timer.
+#[must_use]
+pub fn render_systemd_watch_unit(
+    binary: &std::path::Path,
+    update_interval_minutes: u64,
+) -> (String, String) {
+    let binary = binary.display();
+    let interval_seconds = update_interval_minutes * 60;
+    (
+        SYSTEMD_WATCH_UNIT.to_string(),
+        format!(
+            "[Unit]\n\
+             Description=Keep the todo cache fresh from Asana\n\
+             \n\
+             [Service]\n\
+             Type=simple\n\
+             ExecStart={binary} --quiet update --watch --interval {interval_seconds}\n\
+             Restart=on-failure\n\
+             RestartSec=30\n\
+             \n\
+             [Install]\n\
+             WantedBy=default.target\n"
+        ),
+    )
+}
+
+/// Render the systemd user units as (file name, contents) pairs: a cache refresh service and
+/// timer running every `update_interval_minutes`, and morning/evening focus reminder timers.
+#[must_use]
+pub fn render_systemd_units(
+    binary: &std::path::Path,
+    update_interval_minutes: u64,
+) -> Vec<(String, String)> {
+    let binary = binary.display();
+    let mut units = vec![
+        (
+            "todo-update.service".to_string(),
+            format!(
+                "[Unit]\n\
+                 Description=Refresh the todo cache from Asana\n\
+                 \n\
+                 [Service]\n\
+                 Type=oneshot\n\
+                 ExecStart={binary} --quiet update\n"
+            ),
+        ),
+        (
+            "todo-update.timer".to_string(),
+            format!(
+                "[Unit]\n\
+                 Description=Refresh the todo cache every {update_interval_minutes} minutes\n\
+                 \n\
+                 [Timer]\n\
+                 OnBootSec=1min\n\
+                 OnUnitActiveSec={update_interval_minutes}min\n\
+                 \n\
+                 [Install]\n\
+                 WantedBy=timers.target\n"
+            ),
+        ),
+    ];
+
+    for (phase, time) in [("morning", "09:00:00"), ("evening", "20:00:00")] {
+        units.push((
+            format!("todo-notify-{phase}.service"),
+            format!(
+                "[Unit]\n\
+                 Description=Remind about the {phase} focus routine if it is still pending\n\
+                 \n\
+                 [Service]\n\
+                 Type=oneshot\n\
+                 ExecStart={binary} --use-cache --quiet notify --phase {phase}\n"
+            ),
+        ));
+        units.push((
+            format!("todo-notify-{phase}.timer"),
+            format!(
+                "[Unit]\n\
+                 Description=Schedule the {phase} focus reminder\n\
+                 \n\
+                 [Timer]\n\
+                 OnCalendar=*-*-* {time}\n\
+                 Persistent=true\n\
+                 \n\
+                 [Install]\n\
+                 WantedBy=timers.target\n"
+            ),
+        ));
+    }
+
+    units
+}
+
+/// Render the man pages from the command definitions as (file name, roff contents) pairs:
+/// `todo.1` first, then one page per visible subcommand.
+///
+/// # Errors
+///
+/// This function will return an error if a page could not be rendered.
+pub fn render_man_pages() -> anyhow::Result<Vec<(String, Vec<u8>)>> {
+    let command = crate::cli::Args::command();
+    let mut pages = Vec::new();
+
+    let mut buffer = Vec::new();
+    clap_mangen::Man::new(command.clone())
+        .render(&mut buffer)
+        .context("could not render man page for todo")?;
+    pages.push(("todo.1".to_string(), buffer));
+
+    for subcommand in command.get_subcommands().filter(|s| !s.is_hide_set()) {
+        let name = format!("todo-{}", subcommand.get_name());
+        let mut buffer = Vec::new();
+        clap_mangen::Man::new(subcommand.clone().name(name.clone()))
+            .render(&mut buffer)
+            .with_context(|| format!("could not render man page for {name}"))?;
+        pages.push((format!("{name}.1"), buffer));
+    }
+
+    Ok(pages)
+}
+
+/// Render the crontab lines to paste into `crontab -e` for focus reminders.
+///
+/// The notify command reads only from the cache and exits silently when the routine is already
+/// done, so these fire conditionally instead of nagging on a timer.
+#[must_use]
+pub fn render_notifications() -> String {
+    r"# todo: conditional focus reminders
+0 9 * * * todo --use-cache --quiet notify --phase morning
+0 20 * * * todo --use-cache --quiet notify --phase evening
+"
+    .to_string()
+}
+
+/// Render the Raycast script commands as (file name, script contents) pairs: a full-output
+/// summary and a silent quick-add taking the task name as its argument.
+///
+/// The binary path is embedded absolutely since Raycast does not inherit a shell PATH.
+#[must_use]
+pub fn render_raycast_scripts(binary: &std::path::Path) -> Vec<(String, String)> {
+    let binary = binary.display();
+    vec![
+        (
+            "todo-summary.sh".to_string(),
+            format!(
+                r"#!/bin/bash
+
+# Required parameters:
+# @raycast.schemaVersion 1
+# @raycast.title Todo Summary
+# @raycast.mode fullOutput
+
+# Optional parameters:
+# @raycast.icon ✅
+# @raycast.packageName Todo
+
+{binary} --use-cache --no-color summary
+"
+            ),
+        ),
+        (
+            "todo-quick-add-task.sh".to_string(),
+            format!(
+                r#"#!/bin/bash
+
+# Required parameters:
+# @raycast.schemaVersion 1
+# @raycast.title Quick Add Task
+# @raycast.mode silent
+
+# Optional parameters:
+# @raycast.icon ✅
+# @raycast.packageName Todo
+# @raycast.argument1 {{ "type": "text", "placeholder": "Task name" }}
+
+{binary} --no-color add "$1"
+"#
+            ),
+        ),
+    ]
+}
+
+/// Installation state of one integration, probed from the filesystem rather than assumed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InstallState {
+    /// Every artifact is present (and active, where that can be checked).
+    Installed,
+    /// Some artifacts are present but others are missing or inactive.
+    ConfiguredButMissing,
+    /// No artifacts were found.
+    NotInstalled,
+}
+
+impl std::fmt::Display for InstallState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Installed => "installed",
+            Self::ConfiguredButMissing => "configured but missing",
+            Self::NotInstalled => "not installed",
+        })
+    }
+}
+
+/// The probed state of one integration, with an optional hint for fixing mismatches.
+#[derive(Clone, Debug)]
+pub struct InstallStatus {
+    /// Name of the integration, as shown to the user.
+    pub name: &'static str,
+    /// Probed installation state.
+    pub state: InstallState,
+    /// How to fix a missing or mismatched installation, when there is something to fix.
+    pub hint: Option<String>,
+}
+
+/// Probe whether any todo xbar plugin script exists in the given plugin directory.
+#[must_use]
+pub fn probe_xbar(plugin_dir: &std::path::Path) -> InstallStatus {
+    let state = if xbar_plugin_files(plugin_dir).is_empty() {
+        InstallState::NotInstalled
+    } else {
+        InstallState::Installed
+    };
+    InstallStatus {
+        name: "xbar",
+        state,
+        hint: None,
+    }
+}
+
+/// Probe whether the shell file at `path` carries the marker-delimited integration block.
+#[must_use]
+pub fn probe_shell_block(
+    name: &'static str,
+    path: &std::path::Path,
+    install_command: &str,
+) -> InstallStatus {
+    let has_block = std::fs::read_to_string(path)
+        .is_ok_and(|contents| find_integration_block(&contents).is_some());
+    if has_block {
+        InstallStatus {
+            name,
+            state: InstallState::Installed,
+            hint: None,
+        }
+    } else {
+        InstallStatus {
+            name,
+            state: InstallState::NotInstalled,
+            hint: Some(format!("run `{install_command}` to add the block")),
+        }
+    }
+}
+
+/// Probe whether the systemd user units exist in `unit_dir` and whether the timers are enabled.
+///
+/// The enablement check is injected so callers can use `systemctl --user is-enabled` best-effort
+/// (`None` when systemctl is unavailable) and tests can avoid it entirely.
+#[must_use]
+pub fn probe_systemd(
+    unit_dir: &std::path::Path,
+    is_enabled: &dyn Fn(&str) -> Option<bool>,
+) -> InstallStatus {
+    let missing: Vec<&&str> = SYSTEMD_UNITS
+        .iter()
+        .filter(|unit| !unit_dir.join(unit).exists())
+        .collect();
+    if missing.len() == SYSTEMD_UNITS.len() {
+        return InstallStatus {
+            name: "systemd",
+            state: InstallState::NotInstalled,
+            hint: Some("run `todo install systemd --enable` to set up the timers".to_string()),
+        };
+    }
+    if !missing.is_empty() {
+        return InstallStatus {
+            name: "systemd",
+            state: InstallState::ConfiguredButMissing,
+            hint: Some(format!(
+                "{missing} missing; run `todo install systemd` to rewrite every unit",
+                missing = missing
+                    .iter()
+                    .map(|unit| (**unit).to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        };
+    }
+
+    let disabled: Vec<&&str> = SYSTEMD_UNITS
+        .iter()
+        .filter(|unit| {
+            std::path::Path::new(unit)
+                .extension()
+                .is_some_and(|extension| extension == "timer")
+                && is_enabled(unit) == Some(false)
+        })
+        .collect();
+    if disabled.is_empty() {
+        InstallStatus {
+            name: "systemd",
+            state: InstallState::Installed,
+            hint: None,
+        }
+    } else {
+        InstallStatus {
+            name: "systemd",
+            state: InstallState::ConfiguredButMissing,
+            hint: Some(format!(
+                "units written but not enabled; run `systemctl --user enable --now {timers}`",
+                timers = disabled
+                    .iter()
+                    .map(|unit| (**unit).to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            )),
+        }
+    }
+}
+
+/// Probe whether the scheduled cache updates appear to be working, from the cache timestamp.
+///
+/// A cache younger than twice the configured update interval counts as working; anything older
+/// suggests the schedule exists but is not firing.
+#[must_use]
+pub fn probe_update_schedule(
+    cache_last_updated: Option<chrono::DateTime<chrono::Local>>,
+    now: chrono::DateTime<chrono::Local>,
+    update_interval_minutes: u64,
+) -> InstallStatus {
+    let Some(last_updated) = cache_last_updated else {
+        return InstallStatus {
+            name: "updates",
+            state: InstallState::NotInstalled,
+            hint: Some("no cache yet; run `todo update` once to seed it".to_string()),
+        };
+    };
+    let age_minutes = (now - last_updated).num_minutes();
+    if age_minutes <= i64::try_from(update_interval_minutes).unwrap_or(i64::MAX).saturating_mul(2) {
+        InstallStatus {
+            name: "updates",
+            state: InstallState::Installed,
+            hint: None,
+        }
+    } else {
+        InstallStatus {
+            name: "updates",
+            state: InstallState::ConfiguredButMissing,
+            hint: Some(format!(
+                "cache last updated {age_minutes} minutes ago; is the update schedule running?"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integration_block_is_appended_once_and_replaced_in_place() {
+        let block = integration_block(&render_zsh());
+
+        let fresh = upsert_integration_block("", &block);
+        assert!(fresh.starts_with(INTEGRATION_BLOCK_START));
+        assert!(fresh.trim_end().ends_with(INTEGRATION_BLOCK_END));
+
+        let appended = upsert_integration_block("export EDITOR=vim\n", &block);
+        assert!(appended.starts_with("export EDITOR=vim\n"));
+        assert_eq!(appended.matches(INTEGRATION_BLOCK_START).count(), 1);
+
+        // Writing again replaces the block instead of stacking duplicates.
+        let again = upsert_integration_block(&appended, &block);
+        assert_eq!(again.matches(INTEGRATION_BLOCK_START).count(), 1);
+
+        // An updated snippet replaces the old block but keeps the surrounding content.
+        let updated = upsert_integration_block(&format!("{appended}\nalias g=git\n"), &integration_block("new snippet\n"));
+        assert!(updated.contains("new snippet"));
+        assert!(!updated.contains("todo --use-cache --quiet gate"));
+        assert!(updated.contains("export EDITOR=vim"));
+        assert!(updated.contains("alias g=git"));
+    }
+
+    #[test]
+    fn dracula_tmux_snippet_targets_the_custom_script_slot() {
+        assert!(render_tmux(true).contains("custom:todo.sh"));
+        assert!(render_tmux(false).contains("status-right"));
+        assert!(render_dracula_script().starts_with("#!"));
+    }
+
+    #[test]
+    fn integration_block_removal_is_surgical() {
+        let block = integration_block(&render_zsh());
+        let contents = upsert_integration_block("export EDITOR=vim\n", &block);
+        let contents = format!("{contents}\nalias g=git\n");
+
+        let removed = remove_integration_block(&contents);
+        assert!(!removed.contains(INTEGRATION_BLOCK_START));
+        assert!(!removed.contains("todo --use-cache --quiet gate"));
+        assert!(removed.contains("export EDITOR=vim"));
+        assert!(removed.contains("alias g=git"));
+
+        // No markers means no change; a file holding only the block comes back empty.
+        assert_eq!(remove_integration_block("alias g=git\n"), "alias g=git\n");
+        assert_eq!(remove_integration_block(&block), "");
+    }
+
+    #[test]
+    fn xbar_plugins_match_any_refresh_interval() {
+        let dir = std::env::temp_dir()
+            .join("todo-install-tests")
+            .join(format!("xbar-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["todo.5s.sh", "todo.30s.sh", "other.5s.sh", "todo.txt"] {
+            std::fs::write(dir.join(name), "").unwrap();
+        }
+
+        let files: Vec<_> = xbar_plugin_files(&dir)
+            .into_iter()
+            .filter_map(|path| path.file_name().map(|name| name.to_string_lossy().into_owned()))
+            .collect();
+        assert_eq!(files, ["todo.30s.sh", "todo.5s.sh"]);
+
+        assert!(xbar_plugin_files(&dir.join("missing")).is_empty());
+    }
+
+    #[test]
+    fn systemd_units_cover_every_managed_unit_name() {
+        let units = render_systemd_units(std::path::Path::new("/usr/local/bin/todo"), 5);
+        let names: Vec<_> = units.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, SYSTEMD_UNITS);
+    }
+
+    #[test]
+    fn systemd_update_units_resolve_the_binary_and_interval() {
+        let units = render_systemd_units(std::path::Path::new("/usr/local/bin/todo"), 5);
+
+        let (_, service) = &units[0];
+        assert_eq!(
+            service,
+            "[Unit]\n\
+             Description=Refresh the todo cache from Asana\n\
+             \n\
+             [Service]\n\
+             Type=oneshot\n\
+             ExecStart=/usr/local/bin/todo --quiet update\n"
+        );
+
+        let (_, timer) = &units[1];
+        assert!(timer.contains("OnUnitActiveSec=5min"));
+        assert!(timer.contains("WantedBy=timers.target"));
+    }
+
+    #[test]
+    fn systemd_notify_timers_fire_morning_and_evening() {
+        let units = render_systemd_units(std::path::Path::new("/usr/local/bin/todo"), 3);
+        let unit = |name: &str| {
+            units
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, contents)| contents.as_str())
+                .unwrap()
+        };
+        assert!(unit("todo-notify-morning.timer").contains("OnCalendar=*-*-* 09:00:00"));
+        assert!(unit("todo-notify-evening.timer").contains("OnCalendar=*-*-* 20:00:00"));
+        assert!(unit("todo-notify-morning.service").contains("notify --phase morning"));
+        assert!(unit("todo-notify-evening.service").contains("notify --phase evening"));
+    }
+
+    #[test]
+    fn man_pages_cover_the_tool_and_its_visible_subcommands() {
+        let pages = render_man_pages().unwrap();
+        assert_eq!(pages[0].0, "todo.1");
+        let names: Vec<_> = pages.iter().map(|(name, _)| name.as_str()).collect();
+        assert!(names.contains(&"todo-summary.1"));
+        assert!(names.contains(&"todo-install.1"));
+        assert!(
+            !names.iter().any(|name| name.contains("complete-tasks")),
+            "hidden commands should not get pages: {names:?}"
+        );
+        for (name, contents) in &pages {
+            let roff = String::from_utf8_lossy(contents);
+            assert!(roff.contains(".TH"), "missing .TH header in {name}");
+        }
+    }
+
+    #[test]
+    fn completion_scripts_mention_the_subcommands() {
+        for shell in [
+            CompletionShell::Bash,
+            CompletionShell::Zsh,
+            CompletionShell::Fish,
+            CompletionShell::Nushell,
+        ] {
+            let script = render_completions(shell);
+            assert!(script.contains("todo"), "empty script for {shell:?}");
+            assert!(script.contains("summary"), "missing subcommands for {shell:?}");
+        }
+    }
+
+    #[test]
+    fn completion_paths_are_home_relative() {
+        for shell in [
+            CompletionShell::Bash,
+            CompletionShell::Zsh,
+            CompletionShell::Fish,
+            CompletionShell::Nushell,
+        ] {
+            assert!(completions_path(shell).starts_with("~/"));
+        }
+    }
+
+    #[test]
+    fn notifications_snippet_schedules_both_phases() {
+        let snippet = render_notifications();
+        assert!(snippet.contains("todo --use-cache --quiet notify --phase morning"));
+        assert!(snippet.contains("todo --use-cache --quiet notify --phase evening"));
+    }
+
+    #[test]
+    fn starship_snippet_is_a_custom_command_block() {
+        let snippet = render_starship();
+        assert!(snippet.starts_with("[custom.todo]"));
+        assert!(snippet.contains("todo --use-cache --quiet status --format starship"));
+        assert!(snippet.contains("when = "));
+    }
+
+    #[test]
+    fn systemd_watch_unit_runs_the_watch_loop() {
+        let (name, contents) =
+            render_systemd_watch_unit(std::path::Path::new("/usr/local/bin/todo"), 3);
+        assert_eq!(name, SYSTEMD_WATCH_UNIT);
+        assert!(contents
+            .contains("ExecStart=/usr/local/bin/todo --quiet update --watch --interval 180"));
+        assert!(contents.contains("Restart=on-failure"));
+        assert!(contents.contains("WantedBy=default.target"));
+    }
+
+    #[test]
+    fn raycast_summary_script_snapshot() {
+        let scripts = render_raycast_scripts(std::path::Path::new("/usr/local/bin/todo"));
+        let (name, contents) = &scripts[0];
+        assert_eq!(name, "todo-summary.sh");
+        assert_eq!(
+            contents,
+            r"#!/bin/bash
+
+# Required parameters:
+# @raycast.schemaVersion 1
+# @raycast.title Todo Summary
+# @raycast.mode fullOutput
+
+# Optional parameters:
+# @raycast.icon ✅
+# @raycast.packageName Todo
+
+/usr/local/bin/todo --use-cache --no-color summary
+"
+        );
+    }
+
+    #[test]
+    fn raycast_quick_add_script_snapshot() {
+        let scripts = render_raycast_scripts(std::path::Path::new("/usr/local/bin/todo"));
+        let (name, contents) = &scripts[1];
+        assert_eq!(name, "todo-quick-add-task.sh");
+        assert_eq!(
+            contents,
+            r#"#!/bin/bash
+
+# Required parameters:
+# @raycast.schemaVersion 1
+# @raycast.title Quick Add Task
+# @raycast.mode silent
+
+# Optional parameters:
+# @raycast.icon ✅
+# @raycast.packageName Todo
+# @raycast.argument1 { "type": "text", "placeholder": "Task name" }
+
+/usr/local/bin/todo --no-color add "$1"
+"#
+        );
+    }
+
+    #[test]
+    fn shell_block_probe_reads_the_actual_file() {
+        let dir = std::env::temp_dir()
+            .join("todo-install-tests")
+            .join(format!("probe-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let missing = probe_shell_block("zsh", &dir.join("no-such-rc"), "todo install zsh --write");
+        assert_eq!(missing.state, InstallState::NotInstalled);
+        assert!(missing.hint.unwrap().contains("todo install zsh --write"));
+
+        let rc = dir.join("zshrc");
+        std::fs::write(&rc, "export EDITOR=vim\n").unwrap();
+        assert_eq!(
+            probe_shell_block("zsh", &rc, "todo install zsh --write").state,
+            InstallState::NotInstalled
+        );
+
+        std::fs::write(
+            &rc,
+            upsert_integration_block("export EDITOR=vim\n", &integration_block(&render_zsh())),
+        )
+        .unwrap();
+        let installed = probe_shell_block("zsh", &rc, "todo install zsh --write");
+        assert_eq!(installed.state, InstallState::Installed);
+        assert!(installed.hint.is_none());
+    }
+
+    #[test]
+    fn systemd_probe_distinguishes_missing_partial_and_disabled() {
+        let dir = std::env::temp_dir()
+            .join("todo-install-tests")
+            .join(format!("probe-systemd-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let enabled = |_: &str| Some(true);
+
+        assert_eq!(
+            probe_systemd(&dir, &enabled).state,
+            InstallState::NotInstalled
+        );
+
+        for unit in SYSTEMD_UNITS {
+            std::fs::write(dir.join(unit), "").unwrap();
+        }
+        assert_eq!(probe_systemd(&dir, &enabled).state, InstallState::Installed);
+
+        let disabled = probe_systemd(&dir, &|_| Some(false));
+        assert_eq!(disabled.state, InstallState::ConfiguredButMissing);
+        assert!(disabled.hint.unwrap().contains("enable --now"));
+
+        std::fs::remove_file(dir.join(SYSTEMD_UNITS[0])).unwrap();
+        let partial = probe_systemd(&dir, &enabled);
+        assert_eq!(partial.state, InstallState::ConfiguredButMissing);
+        assert!(partial.hint.unwrap().contains(SYSTEMD_UNITS[0]));
+    }
+
+    #[test]
+    fn update_schedule_probe_uses_the_cache_age() {
+        use chrono::{Duration, Local, TimeZone};
+        let now = Local.with_ymd_and_hms(2024, 1, 15, 9, 0, 0).unwrap();
+
+        assert_eq!(
+            probe_update_schedule(None, now, 3).state,
+            InstallState::NotInstalled
+        );
+        assert_eq!(
+            probe_update_schedule(Some(now - Duration::minutes(5)), now, 3).state,
+            InstallState::Installed
+        );
+        let stale = probe_update_schedule(Some(now - Duration::minutes(45)), now, 3);
+        assert_eq!(stale.state, InstallState::ConfiguredButMissing);
+        assert!(stale.hint.unwrap().contains("45 minutes"));
+    }
+}
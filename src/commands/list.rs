@@ -0,0 +1,633 @@
+//! The list command, which prints out todo tasks ordered by due date.
+
+use std::{collections::BTreeMap, fmt::Write as _};
+
+use chrono::NaiveDate;
+use console::style;
+
+use crate::context::{task_or_tasks, AppContext, GroupedTasks, TaskBuckets};
+use crate::render::Theme;
+use crate::task::UserTask;
+
+/// Run the list command: render the grouped tasks in the requested format through the
+/// context's output sink.
+///
+/// # Errors
+///
+/// This function will return an error if the tasks could not be serialized or the output sink
+/// could not be written to.
+pub fn run(
+    ctx: &mut AppContext,
+    grouped: &GroupedTasks<'_>,
+    format: ListFormat,
+    group_by: GroupBy,
+    options: ListOptions<'_>,
+) -> anyhow::Result<()> {
+    match format {
+        ListFormat::Plain => {
+            let string = match group_by {
+                GroupBy::Due => render_plain(grouped, options),
+                GroupBy::Project => render_by_project(grouped, options),
+            };
+            if string.is_empty() {
+                ctx.writer.line(
+                    &ctx.theme
+                        .ok
+                        .clone()
+                        .bold()
+                        .apply_to("Nice! Everything done for now!")
+                        .to_string(),
+                )?;
+            } else {
+                ctx.writer.line(string.trim())?;
+            }
+        }
+        ListFormat::Json => {
+            ctx.writer.line(&render_json(grouped, options.all)?)?;
+        }
+        ListFormat::Tsv => {
+            // TSV rows carry their own newlines, so the trailing one is dropped rather than
+            // doubled by the sink.
+            ctx.writer.line(render_tsv(grouped, options.all).trim_end())?;
+        }
+    }
+    Ok(())
+}
+
+/// Output format for the list command.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ListFormat {
+    /// Human-readable, possibly styled output.
+    #[default]
+    Plain,
+    /// JSON object of due-date buckets, each an array of task objects.
+    Json,
+    /// Tab-separated values, one line per task, with no styling.
+    Tsv,
+}
+
+/// How tasks are grouped in the plain list output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum GroupBy {
+    /// Group tasks into due-date buckets (the default).
+    #[default]
+    Due,
+    /// Group tasks under their project names.
+    Project,
+}
+
+/// How task links are rendered in the human-readable list output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LinkMode {
+    /// No links at all (the default).
+    #[default]
+    None,
+    /// Append the Asana permalink, dimmed, after each task line.
+    Plain,
+    /// Wrap task names in OSC 8 hyperlink escape sequences.
+    Hyperlink,
+}
+
+/// Options controlling the human-readable list rendering.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ListOptions<'a> {
+    /// Also show tasks without a due date.
+    pub all: bool,
+    /// When set, render due dates relative to this date instead of absolutely.
+    pub relative_to: Option<NaiveDate>,
+    /// How task links are rendered.
+    pub links: LinkMode,
+    /// Gid of the enum custom field used as priority; when set, tasks carrying the field get a
+    /// colored `[P1]`-style prefix.
+    pub priority_field: Option<&'a str>,
+    /// Styles for the semantic color roles; the built-in scheme when unset.
+    pub theme: Option<&'a Theme>,
+    /// strftime-style format for absolute due dates; ISO `%Y-%m-%d` when unset.
+    pub date_format: Option<&'a str>,
+}
+
+impl ListOptions<'_> {
+    /// The effective theme: the configured one, or the built-in scheme.
+    fn theme(&self) -> Theme {
+        self.theme.cloned().unwrap_or_default()
+    }
+}
+
+/// Single task row as exposed by the TSV format, with its due bucket flattened into a label.
+#[derive(Debug)]
+struct ListedTask<'a> {
+    gid: &'a str,
+    name: &'a str,
+    due_on: Option<NaiveDate>,
+    bucket: &'static str,
+}
+
+fn rows<'a>(grouped: &'a GroupedTasks<'a>, all: bool) -> Vec<ListedTask<'a>> {
+    let mut rows = Vec::new();
+    for (bucket, tasks) in [
+        ("overdue", &grouped.overdue),
+        ("today", &grouped.due_today),
+        ("week", &grouped.due_week),
+    ] {
+        for task in tasks {
+            rows.push(ListedTask {
+                gid: &task.gid,
+                name: &task.name,
+                due_on: task.due_on,
+                bucket,
+            });
+        }
+    }
+    if all {
+        for task in &grouped.no_due_date {
+            rows.push(ListedTask {
+                gid: &task.gid,
+                name: &task.name,
+                due_on: task.due_on,
+                bucket: "none",
+            });
+        }
+    }
+    rows
+}
+
+/// Render the grouped tasks as human-readable output, ordered by due date.
+///
+/// Styling follows the global console color settings, so output is unstyled when not attached to
+/// a terminal.
+///
+/// # Panics
+///
+/// This function will panic if a task in a dated bucket has no due date, which
+/// [`GroupedTasks::group`] guarantees cannot happen.
+#[must_use]
+pub fn render_plain(grouped: &GroupedTasks, options: ListOptions) -> String {
+    let theme = options.theme();
+    let mut string = String::new();
+
+    if !grouped.overdue.is_empty() {
+        let _ = writeln!(
+            string,
+            "{} {}",
+            theme
+                .overdue
+                .clone()
+                .bold()
+                .apply_to(task_or_tasks(grouped.overdue.len())),
+            style("overdue:").bold()
+        );
+        for task in &grouped.overdue {
+            let _ = writeln!(
+                string,
+                "- ({}) {}",
+                theme
+                    .overdue
+                    .apply_to(render_date(task.due_on.unwrap(), options)),
+                render_name(task, options)
+            );
+        }
+        string.push('\n');
+    }
+
+    if !grouped.due_today.is_empty() {
+        let _ = writeln!(
+            string,
+            "{} {}",
+            theme
+                .due_today
+                .apply_to(task_or_tasks(grouped.due_today.len())),
+            style("due today:").bold()
+        );
+        for task in &grouped.due_today {
+            let _ = writeln!(string, "- {}", render_name(task, options));
+        }
+        string.push('\n');
+    }
+
+    if !grouped.due_week.is_empty() {
+        let _ = writeln!(
+            string,
+            "{} {}",
+            theme
+                .due_week
+                .apply_to(task_or_tasks(grouped.due_week.len())),
+            style("due within a week:").bold()
+        );
+        for task in &grouped.due_week {
+            let _ = writeln!(
+                string,
+                "- ({}) {}",
+                theme
+                    .due_week
+                    .apply_to(render_date(task.due_on.unwrap(), options)),
+                render_name(task, options)
+            );
+        }
+        string.push('\n');
+    }
+
+    if options.all && !grouped.no_due_date.is_empty() {
+        let _ = writeln!(
+            string,
+            "{} {}",
+            theme.dim.apply_to(task_or_tasks(grouped.no_due_date.len())),
+            style("no due date:").bold()
+        );
+        for task in &grouped.no_due_date {
+            let _ = writeln!(string, "- {}", render_name(task, options));
+        }
+    }
+
+    string
+}
+
+/// Render the grouped tasks nested under bold project headings.
+///
+/// Tasks in multiple projects are listed once under each of them, and tasks in no project at all
+/// are listed under a trailing "(no project)" heading. Within each project, tasks keep their
+/// due-date ordering, with due dates styled by bucket as in the due-grouped output.
+#[must_use]
+pub fn render_by_project(grouped: &GroupedTasks, options: ListOptions) -> String {
+    let mut buckets = vec![
+        ("overdue", &grouped.overdue),
+        ("today", &grouped.due_today),
+        ("week", &grouped.due_week),
+    ];
+    if options.all {
+        buckets.push(("none", &grouped.no_due_date));
+    }
+
+    let mut by_project: BTreeMap<&str, Vec<(&UserTask, &str)>> = BTreeMap::new();
+    let mut no_project: Vec<(&UserTask, &str)> = Vec::new();
+    for (bucket, tasks) in buckets {
+        for task in tasks {
+            if task.projects.is_empty() {
+                no_project.push((task, bucket));
+            } else {
+                for project in &task.projects {
+                    by_project
+                        .entry(project.name.as_str())
+                        .or_default()
+                        .push((task, bucket));
+                }
+            }
+        }
+    }
+
+    let mut string = String::new();
+    for (project, tasks) in &by_project {
+        let _ = writeln!(string, "{}", style(project).bold());
+        for (task, bucket) in tasks {
+            push_project_row(&mut string, task, bucket, options);
+        }
+        string.push('\n');
+    }
+    if !no_project.is_empty() {
+        let _ = writeln!(string, "{}", style("(no project)").bold());
+        for (task, bucket) in &no_project {
+            push_project_row(&mut string, task, bucket, options);
+        }
+    }
+    string
+}
+
+fn render_date(due: NaiveDate, options: ListOptions) -> String {
+    match options.relative_to {
+        Some(today) => crate::utils::format_relative_date(due, today),
+        None => crate::utils::format_date(due, options.date_format.unwrap_or("%Y-%m-%d")),
+    }
+}
+
+fn push_project_row(string: &mut String, task: &UserTask, bucket: &str, options: ListOptions) {
+    if let Some(due) = task.due_on {
+        let theme = options.theme();
+        let role = match bucket {
+            "overdue" => &theme.overdue,
+            "today" => &theme.due_today,
+            _ => &theme.due_week,
+        };
+        let due = role.apply_to(render_date(due, options));
+        let _ = writeln!(string, "- ({due}) {}", render_name(task, options));
+    } else {
+        let _ = writeln!(string, "- {}", render_name(task, options));
+    }
+}
+
+fn render_name(task: &UserTask, options: ListOptions) -> String {
+    let prefix = options
+        .priority_field
+        .and_then(|field_gid| task.custom_field_label(field_gid))
+        .map(|label| format!("{} ", style(format!("[{label}]")).magenta().bold()))
+        .unwrap_or_default();
+    let name = match options.links {
+        LinkMode::None => task.name.clone(),
+        LinkMode::Plain => format!(
+            "{} {}",
+            task.name,
+            options
+                .theme()
+                .dim
+                .apply_to(crate::render::task_permalink(&task.gid))
+        ),
+        LinkMode::Hyperlink => {
+            crate::render::hyperlink(&task.name, &crate::render::task_permalink(&task.gid))
+        }
+    };
+    format!("{prefix}{name}")
+}
+
+/// Render the grouped tasks as a JSON object in the shared [`TaskBuckets`] schema.
+///
+/// Each bucket key holds an array of task objects carrying gid, name, due date, and creation
+/// time; without `all` the `no_due_date` bucket is present but empty. No styling is ever
+/// emitted.
+///
+/// # Errors
+///
+/// This function will return an error if the tasks could not be serialized.
+pub fn render_json(grouped: &GroupedTasks, all: bool) -> anyhow::Result<String> {
+    let mut buckets = TaskBuckets::from(grouped);
+    if !all {
+        buckets.no_due_date.clear();
+    }
+    Ok(serde_json::to_string_pretty(&buckets)?)
+}
+
+/// Render the grouped tasks as tab-separated values, one line per task.
+///
+/// Columns are gid, due date (empty when unset), due bucket, and name, with no styling ever
+/// emitted.
+#[must_use]
+pub fn render_tsv(grouped: &GroupedTasks, all: bool) -> String {
+    let mut string = String::new();
+    for row in rows(grouped, all) {
+        let _ = writeln!(
+            string,
+            "{}\t{}\t{}\t{}",
+            row.gid,
+            row.due_on.map(|d| d.to_string()).unwrap_or_default(),
+            row.bucket,
+            row.name
+        );
+    }
+    string
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Local, TimeZone};
+
+    use crate::task::ProjectRef;
+
+    use super::*;
+
+    fn task(gid: &str, due_on: Option<&str>) -> UserTask {
+        UserTask {
+            gid: gid.to_string(),
+            created_at: Local.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap(),
+            due_on: due_on.map(|d| d.parse().unwrap()),
+            name: format!("task {gid}"),
+            projects: Vec::new(),
+            custom_fields: Vec::new(),
+        }
+    }
+
+    fn grouped(tasks: &[UserTask]) -> GroupedTasks<'_> {
+        GroupedTasks::group(tasks, "2024-01-15".parse().unwrap())
+    }
+
+    #[test]
+    fn json_format_buckets_tasks() {
+        let tasks = vec![task("1", Some("2024-01-10")), task("2", None)];
+        let json = render_json(&grouped(&tasks), true).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed["overdue"][0]["gid"], "1");
+        assert_eq!(parsed["overdue"][0]["due_on"], "2024-01-10");
+        assert_eq!(parsed["no_due_date"][0]["gid"], "2");
+        assert_eq!(parsed["no_due_date"][0]["due_on"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn json_format_excludes_undated_without_all() {
+        let tasks = vec![task("1", Some("2024-01-10")), task("2", None)];
+        let json = render_json(&grouped(&tasks), false).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed["overdue"].as_array().unwrap().len(), 1);
+        // The bucket stays in the schema even when nothing fills it, so consumers can index it
+        // unconditionally.
+        assert_eq!(parsed["no_due_date"].as_array().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn tsv_format_has_one_line_per_task() {
+        let tasks = vec![task("1", Some("2024-01-10")), task("2", Some("2024-01-15"))];
+        let tsv = render_tsv(&grouped(&tasks), false);
+        assert_eq!(
+            tsv,
+            "1\t2024-01-10\toverdue\ttask 1\n2\t2024-01-15\ttoday\ttask 2\n"
+        );
+    }
+
+    #[test]
+    fn machine_formats_contain_no_ansi_codes() {
+        let tasks = vec![task("1", Some("2024-01-10"))];
+        let g = grouped(&tasks);
+        assert!(!render_json(&g, true).unwrap().contains('\x1b'));
+        assert!(!render_tsv(&g, true).contains('\x1b'));
+    }
+
+    #[test]
+    fn project_grouping_nests_tasks_under_each_project() {
+        let mut shared = task("1", Some("2024-01-10"));
+        shared.projects = vec![
+            ProjectRef {
+                gid: "p1".to_string(),
+                name: "Home".to_string(),
+            },
+            ProjectRef {
+                gid: "p2".to_string(),
+                name: "Work".to_string(),
+            },
+        ];
+        let tasks = vec![shared, task("2", Some("2024-01-15"))];
+        console::set_colors_enabled(false);
+        let string = render_by_project(&grouped(&tasks), ListOptions::default());
+        let home = string.find("Home").unwrap();
+        let work = string.find("Work").unwrap();
+        let none = string.find("(no project)").unwrap();
+        // Projects are alphabetical, with project-less tasks trailing.
+        assert!(home < work && work < none);
+        assert_eq!(string.matches("task 1").count(), 2);
+        assert_eq!(string.matches("task 2").count(), 1);
+    }
+
+    #[test]
+    fn plain_links_append_a_permalink_per_line() {
+        let tasks = vec![task("1", Some("2024-01-10"))];
+        console::set_colors_enabled(false);
+        let plain = render_plain(
+            &grouped(&tasks),
+            ListOptions {
+                links: LinkMode::Plain,
+                ..ListOptions::default()
+            },
+        );
+        assert!(plain.contains("task 1 https://app.asana.com/0/0/1/f"));
+    }
+
+    #[test]
+    fn hyperlink_mode_wraps_names_in_osc8() {
+        let tasks = vec![task("1", Some("2024-01-10"))];
+        console::set_colors_enabled(false);
+        let plain = render_plain(
+            &grouped(&tasks),
+            ListOptions {
+                links: LinkMode::Hyperlink,
+                ..ListOptions::default()
+            },
+        );
+        assert!(plain.contains("\x1b]8;;https://app.asana.com/0/0/1/f\x1b\\task 1\x1b]8;;\x1b\\"));
+    }
+
+    #[test]
+    fn priority_labels_prefix_task_names() {
+        use crate::task::{EnumOptionRef, TaskCustomField};
+
+        let mut prioritized = task("1", Some("2024-01-10"));
+        prioritized.custom_fields = vec![TaskCustomField {
+            gid: "priority".to_string(),
+            display_value: Some("P1".to_string()),
+            enum_value: Some(EnumOptionRef {
+                gid: "o1".to_string(),
+                name: "P1".to_string(),
+            }),
+        }];
+        let tasks = vec![prioritized, task("2", Some("2024-01-10"))];
+        console::set_colors_enabled(false);
+        let plain = render_plain(
+            &grouped(&tasks),
+            ListOptions {
+                priority_field: Some("priority"),
+                ..ListOptions::default()
+            },
+        );
+        assert!(plain.contains("[P1] task 1"));
+        assert!(plain.contains("- (2024-01-10) task 2"));
+    }
+
+    #[test]
+    fn plain_format_renders_relative_dates_when_asked() {
+        let tasks = vec![task("1", Some("2024-01-14"))];
+        console::set_colors_enabled(false);
+        let plain = render_plain(
+            &grouped(&tasks),
+            ListOptions {
+                relative_to: Some("2024-01-15".parse().unwrap()),
+                ..ListOptions::default()
+            },
+        );
+        assert!(plain.contains("- (yesterday) task 1"));
+    }
+
+    #[test]
+    fn plain_format_renders_sections() {
+        let tasks = vec![task("1", Some("2024-01-10")), task("2", None)];
+        console::set_colors_enabled(false);
+        let plain = render_plain(
+            &grouped(&tasks),
+            ListOptions {
+                all: true,
+                ..ListOptions::default()
+            },
+        );
+        assert!(plain.contains("1 task overdue:"));
+        assert!(plain.contains("no due date:"));
+        assert!(plain.contains("- (2024-01-10) task 1"));
+    }
+
+    #[test]
+    fn a_configured_date_format_flows_through_the_plain_output() {
+        let tasks = vec![task("1", Some("2024-01-10"))];
+        console::set_colors_enabled(false);
+        let plain = render_plain(
+            &grouped(&tasks),
+            ListOptions {
+                date_format: Some("%d/%m/%Y"),
+                ..ListOptions::default()
+            },
+        );
+        assert!(plain.contains("- (10/01/2024) task 1"), "{plain}");
+
+        // The default stays ISO.
+        let plain = render_plain(&grouped(&tasks), ListOptions::default());
+        assert!(plain.contains("- (2024-01-10) task 1"), "{plain}");
+    }
+
+    #[test]
+    fn a_configured_theme_restyles_the_due_dates() {
+        let tasks = vec![task("1", Some("2024-01-10"))];
+        let theme = Theme {
+            overdue: console::Style::new()
+                .fg(console::Color::Color256(208))
+                .force_styling(true),
+            ..Theme::default()
+        };
+        let plain = render_plain(
+            &grouped(&tasks),
+            ListOptions {
+                theme: Some(&theme),
+                ..ListOptions::default()
+            },
+        );
+        assert!(plain.contains("\x1b[38;5;208m"), "{plain:?}");
+    }
+
+    fn context(buffer: &crate::context::BufferOutput) -> AppContext {
+        AppContext::new_with_now(
+            crate::config::Config::default(),
+            crate::cache::Cache::default(),
+            Box::new(buffer.clone()),
+            chrono::Local::now(),
+        )
+    }
+
+    #[test]
+    fn run_writes_the_plain_list_through_the_sink() {
+        console::set_colors_enabled(false);
+        let buffer = crate::context::BufferOutput::default();
+        let mut ctx = context(&buffer);
+
+        let tasks = vec![task("1", Some("2024-01-10"))];
+        run(
+            &mut ctx,
+            &grouped(&tasks),
+            ListFormat::Plain,
+            GroupBy::Due,
+            ListOptions::default(),
+        )
+        .unwrap();
+
+        let lines = buffer.lines();
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].starts_with("1 task overdue:"));
+        assert!(lines[0].contains("- (2024-01-10) task 1"));
+    }
+
+    #[test]
+    fn run_celebrates_an_empty_plain_list() {
+        console::set_colors_enabled(false);
+        let buffer = crate::context::BufferOutput::default();
+        let mut ctx = context(&buffer);
+
+        run(
+            &mut ctx,
+            &grouped(&[]),
+            ListFormat::Plain,
+            GroupBy::Due,
+            ListOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(buffer.lines(), ["Nice! Everything done for now!"]);
+    }
+}
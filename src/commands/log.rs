@@ -0,0 +1,226 @@
+//! Implementation of the `log` subcommand, which lists recently completed tasks grouped by the
+//! day they were finished on.
+
+use std::fmt::Write as _;
+
+use chrono::NaiveDate;
+use console::style;
+use serde::Serialize;
+
+use crate::task::CompletedTask;
+
+/// Output format for the `log` subcommand.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum LogFormat {
+    /// Day headers with indented completion times and names.
+    #[default]
+    Plain,
+    /// JSON array of day objects, each with its tasks.
+    Json,
+    /// Markdown with a heading per day, suitable for pasting into a weekly report.
+    Markdown,
+}
+
+/// One day of the log: the local completion date and its tasks, newest completion first.
+#[derive(Clone, Debug, Serialize)]
+pub struct LogDay<'a> {
+    /// Local date the tasks were completed on.
+    pub date: NaiveDate,
+    /// Tasks completed on that date, newest first.
+    pub tasks: Vec<&'a CompletedTask>,
+}
+
+/// Group completed tasks by their local completion day, newest day and newest completion first.
+///
+/// Tasks without a completion timestamp (the endpoint also returns still-open tasks) are
+/// dropped.
+#[must_use]
+pub fn group_by_day(tasks: &[CompletedTask]) -> Vec<LogDay<'_>> {
+    let mut completed: Vec<&CompletedTask> = tasks
+        .iter()
+        .filter(|task| task.completed_at.is_some())
+        .collect();
+    completed.sort_by_key(|task| std::cmp::Reverse(task.completed_at));
+
+    let mut days: Vec<LogDay<'_>> = Vec::new();
+    for task in completed {
+        let date = task.completed_at.unwrap_or_default().date_naive();
+        match days.last_mut() {
+            Some(day) if day.date == date => day.tasks.push(task),
+            _ => days.push(LogDay {
+                date,
+                tasks: vec![task],
+            }),
+        }
+    }
+    days
+}
+
+/// Render the log as day headers with an indented `HH:MM  name` row per task.
+///
+/// `date_format` and `time_format` override the `%A %Y-%m-%d` headers and `%H:%M` times with
+/// configured strftime strings.
+#[must_use]
+pub fn render_plain(
+    days: &[LogDay<'_>],
+    date_format: Option<&str>,
+    time_format: Option<&str>,
+) -> String {
+    if days.is_empty() {
+        return style("Nothing completed in this window.")
+            .dim()
+            .to_string();
+    }
+    let mut string = String::new();
+    for day in days {
+        let _ = writeln!(
+            string,
+            "{}",
+            style(crate::utils::format_date(
+                day.date,
+                date_format.unwrap_or("%A %Y-%m-%d")
+            ))
+            .bold()
+        );
+        for task in &day.tasks {
+            let time = task.completed_at.map_or_else(String::new, |at| {
+                crate::utils::format_datetime(at, time_format.unwrap_or("%H:%M"))
+            });
+            let _ = writeln!(string, "  {}  {}", style(time).dim(), task.name);
+        }
+    }
+    string.trim_end().to_string()
+}
+
+/// Render the log as markdown: a heading per day and a bullet per task.
+///
+/// `date_format` and `time_format` override the `%A %Y-%m-%d` headings and `%H:%M` times with
+/// configured strftime strings.
+#[must_use]
+pub fn render_markdown(
+    days: &[LogDay<'_>],
+    date_format: Option<&str>,
+    time_format: Option<&str>,
+) -> String {
+    if days.is_empty() {
+        return "Nothing completed in this window.".to_string();
+    }
+    let mut string = String::new();
+    for day in days {
+        let _ = writeln!(
+            string,
+            "## {}\n",
+            crate::utils::format_date(day.date, date_format.unwrap_or("%A %Y-%m-%d"))
+        );
+        for task in &day.tasks {
+            let time = task.completed_at.map_or_else(String::new, |at| {
+                crate::utils::format_datetime(at, time_format.unwrap_or("%H:%M"))
+            });
+            let _ = writeln!(string, "- {time} {name}", name = task.name);
+        }
+        string.push('\n');
+    }
+    string.trim_end().to_string()
+}
+
+/// Render the log as a JSON array of day objects.
+///
+/// # Errors
+///
+/// This function will return an error if the log could not be serialized.
+pub fn render_json(days: &[LogDay<'_>]) -> anyhow::Result<String> {
+    Ok(serde_json::to_string(days)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Local, TimeZone as _};
+
+    use super::*;
+
+    fn completed(gid: &str, name: &str, at: &str) -> CompletedTask {
+        CompletedTask {
+            gid: gid.to_string(),
+            name: name.to_string(),
+            completed_at: Some(
+                Local
+                    .from_local_datetime(&at.parse().unwrap())
+                    .unwrap(),
+            ),
+            projects: Vec::new(),
+        }
+    }
+
+    fn sample() -> Vec<CompletedTask> {
+        vec![
+            completed("1", "water the plants", "2024-01-15T09:12:00"),
+            completed("2", "write the report", "2024-01-15T18:30:00"),
+            completed("3", "file the taxes", "2024-01-14T20:05:00"),
+            CompletedTask {
+                gid: "4".to_string(),
+                name: "still open".to_string(),
+                completed_at: None,
+                projects: Vec::new(),
+            },
+        ]
+    }
+
+    #[test]
+    fn grouping_is_newest_first_and_drops_open_tasks() {
+        let tasks = sample();
+        let days = group_by_day(&tasks);
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].date, "2024-01-15".parse().unwrap());
+        assert_eq!(
+            days[0]
+                .tasks
+                .iter()
+                .map(|t| t.gid.as_str())
+                .collect::<Vec<_>>(),
+            ["2", "1"]
+        );
+        assert_eq!(days[1].tasks.len(), 1);
+    }
+
+    #[test]
+    fn plain_output_has_day_headers_and_times() {
+        console::set_colors_enabled(false);
+        let tasks = sample();
+        assert_eq!(
+            render_plain(&group_by_day(&tasks), None, None),
+            "Monday 2024-01-15\n\
+             \x20 18:30  write the report\n\
+             \x20 09:12  water the plants\n\
+             Sunday 2024-01-14\n\
+             \x20 20:05  file the taxes"
+        );
+        assert_eq!(render_plain(&[], None, None), "Nothing completed in this window.");
+    }
+
+    #[test]
+    fn markdown_output_has_a_heading_per_day() {
+        let tasks = sample();
+        assert_eq!(
+            render_markdown(&group_by_day(&tasks), None, None),
+            "## Monday 2024-01-15\n\
+             \n\
+             - 18:30 write the report\n\
+             - 09:12 water the plants\n\
+             \n\
+             ## Sunday 2024-01-14\n\
+             \n\
+             - 20:05 file the taxes"
+        );
+    }
+
+    #[test]
+    fn json_output_is_an_array_of_day_objects() {
+        let tasks = sample();
+        let parsed: serde_json::Value =
+            serde_json::from_str(&render_json(&group_by_day(&tasks)).unwrap()).unwrap();
+        assert_eq!(parsed.as_array().unwrap().len(), 2);
+        assert_eq!(parsed[0]["date"], "2024-01-15");
+        assert_eq!(parsed[0]["tasks"][0]["name"], "write the report");
+        assert_eq!(parsed[1]["tasks"][0]["gid"], "3");
+    }
+}
@@ -0,0 +1,39 @@
+//! Implementations of the subcommands exposed by the command line tool.
+
+pub mod add;
+pub mod agenda;
+pub mod attach;
+pub mod count;
+pub mod export;
+pub mod focus;
+pub mod gate;
+pub mod install;
+pub mod list;
+pub mod log;
+pub mod notify;
+pub mod pause;
+pub mod report;
+pub mod status;
+pub mod summary;
+pub mod triage;
+pub mod update;
+
+/// Outcome of a command, encoding task and focus state for shell scripting.
+///
+/// When exit codes are enabled the process exits with the bitwise OR of 2 (overdue tasks) and 4
+/// (pending focus routine), or 0 when neither applies. Real errors keep exiting with 1.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Outcome {
+    /// Whether any tasks are overdue.
+    pub overdue: bool,
+    /// Whether a focus routine is pending.
+    pub focus_pending: bool,
+}
+
+impl Outcome {
+    /// The process exit code encoding this outcome.
+    #[must_use]
+    pub fn exit_code(self) -> i32 {
+        (if self.overdue { 2 } else { 0 }) | (if self.focus_pending { 4 } else { 0 })
+    }
+}
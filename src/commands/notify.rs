@@ -0,0 +1,259 @@
+//! The notify command, which sends a desktop notification while a focus routine is pending.
+//!
+//! Meant to be scheduled (e.g. from cron) against the cache, so reminders only fire when the
+//! routine actually still needs doing. The update command shares the delivery plumbing here
+//! for its new-task and newly-overdue notifications.
+
+use anyhow::Context;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::commands::gate::{self, GatePhase};
+use crate::commands::status::Status;
+use crate::task::NotableChanges;
+
+/// Phase of the focus routine to notify about.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum NotifyPhase {
+    /// The morning focus routine.
+    Morning,
+    /// The evening focus routine.
+    Evening,
+    /// Whichever routine is pending for the current time of day, if any.
+    #[default]
+    Auto,
+}
+
+/// Resolve the phase to notify about, returning `None` when the relevant routine is already done
+/// (or, for `Auto`, when nothing is pending).
+#[must_use]
+pub fn resolve_phase(phase: NotifyPhase, status: &Status) -> Option<GatePhase> {
+    match phase {
+        NotifyPhase::Morning => status.morning_pending.then_some(GatePhase::Morning),
+        NotifyPhase::Evening => status.evening_pending.then_some(GatePhase::Evening),
+        NotifyPhase::Auto => gate::pending_phase(status),
+    }
+}
+
+/// Summary and body of the notification for the given phase.
+#[must_use]
+pub fn message(phase: GatePhase) -> (String, String) {
+    (
+        format!("Your {phase} focus routine is pending"),
+        "Run `todo focus` to fill it in.".to_string(),
+    )
+}
+
+/// Send a desktop notification for the given phase.
+///
+/// # Errors
+///
+/// This function will return an error if the notification could not be sent.
+pub fn send(phase: GatePhase) -> anyhow::Result<()> {
+    let (summary, body) = message(phase);
+    send_message(&summary, &body)
+}
+
+/// Send a desktop notification with the given summary and body.
+///
+/// # Errors
+///
+/// This function will return an error if the notification could not be sent.
+pub fn send_message(summary: &str, body: &str) -> anyhow::Result<()> {
+    notify_rust::Notification::new()
+        .appname("todo")
+        .summary(summary)
+        .body(body)
+        .show()
+        .context("could not send desktop notification")?;
+    Ok(())
+}
+
+/// Categories of task-change notifications sent after an update, rate-limited independently so
+/// a burst of new tasks cannot starve an overdue warning.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UpdateCategory {
+    /// Tasks that appeared in the list since the last refresh.
+    NewTasks,
+    /// Tasks whose due date crossed into the past since the last refresh.
+    NewlyOverdue,
+}
+
+/// When each category of update notification last fired.
+///
+/// Kept in the cache, so one-shot `update --notify` runs scheduled a few minutes apart share
+/// the rate limit with a long-running watch daemon instead of each starting fresh.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct NotifyLimiter {
+    /// When a new-tasks notification last fired.
+    new_tasks_sent: Option<DateTime<Local>>,
+    /// When a newly-overdue notification last fired.
+    newly_overdue_sent: Option<DateTime<Local>>,
+}
+
+impl NotifyLimiter {
+    /// Whether a notification for the category may fire at `now`, recording it if so.
+    ///
+    /// The clock is a parameter so tests can drive it without sleeping.
+    pub fn should_send(
+        &mut self,
+        category: UpdateCategory,
+        min_gap: chrono::Duration,
+        now: DateTime<Local>,
+    ) -> bool {
+        let slot = match category {
+            UpdateCategory::NewTasks => &mut self.new_tasks_sent,
+            UpdateCategory::NewlyOverdue => &mut self.newly_overdue_sent,
+        };
+        if slot.is_some_and(|last| now - last < min_gap) {
+            return false;
+        }
+        *slot = Some(now);
+        true
+    }
+}
+
+/// Summary and body of the batched notification for an update category, e.g. `2 new tasks`
+/// over a body naming each one.
+#[must_use]
+pub fn update_message(category: UpdateCategory, names: &[String]) -> (String, String) {
+    let summary = match (category, names.len()) {
+        (UpdateCategory::NewTasks, 1) => "1 new task".to_string(),
+        (UpdateCategory::NewTasks, count) => format!("{count} new tasks"),
+        (UpdateCategory::NewlyOverdue, 1) => "1 task is now overdue".to_string(),
+        (UpdateCategory::NewlyOverdue, count) => format!("{count} tasks are now overdue"),
+    };
+    (summary, names.join("\n"))
+}
+
+/// Send one batched notification per non-empty category the rate limiter allows.
+///
+/// Delivery failures are only logged — a missing notification daemon should never fail the
+/// refresh that produced the changes.
+pub fn send_update_changes(
+    changes: &NotableChanges,
+    limiter: &mut NotifyLimiter,
+    min_gap: chrono::Duration,
+    now: DateTime<Local>,
+) {
+    for (category, names) in [
+        (UpdateCategory::NewTasks, &changes.new_tasks),
+        (UpdateCategory::NewlyOverdue, &changes.newly_overdue),
+    ] {
+        if names.is_empty() || !limiter.should_send(category, min_gap, now) {
+            continue;
+        }
+        let (summary, body) = update_message(category, names);
+        if let Err(error) = send_message(&summary, &body) {
+            tracing::warn!("Could not send the desktop notification: {error:#}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(morning: bool, evening: bool) -> Status {
+        Status {
+            overdue: 0,
+            due_today: 0,
+            due_week: 0,
+            done_today: 0,
+            focus_subtasks_overdue: 0,
+            focus_subtasks_pending: 0,
+            morning_pending: morning,
+            evening_pending: evening,
+            paused: false,
+            stale: false,
+        }
+    }
+
+    #[test]
+    fn explicit_phases_only_fire_while_their_routine_is_pending() {
+        assert_eq!(
+            resolve_phase(NotifyPhase::Morning, &status(true, false)),
+            Some(GatePhase::Morning)
+        );
+        assert_eq!(resolve_phase(NotifyPhase::Morning, &status(false, true)), None);
+        assert_eq!(
+            resolve_phase(NotifyPhase::Evening, &status(false, true)),
+            Some(GatePhase::Evening)
+        );
+        assert_eq!(resolve_phase(NotifyPhase::Evening, &status(true, false)), None);
+    }
+
+    #[test]
+    fn auto_phase_follows_whichever_routine_is_pending() {
+        assert_eq!(resolve_phase(NotifyPhase::Auto, &status(false, false)), None);
+        assert_eq!(
+            resolve_phase(NotifyPhase::Auto, &status(true, false)),
+            Some(GatePhase::Morning)
+        );
+        assert_eq!(
+            resolve_phase(NotifyPhase::Auto, &status(true, true)),
+            Some(GatePhase::Evening)
+        );
+    }
+
+    #[test]
+    fn message_names_the_phase_and_hints_at_the_focus_command() {
+        let (summary, body) = message(GatePhase::Morning);
+        assert_eq!(summary, "Your morning focus routine is pending");
+        assert!(body.contains("todo focus"));
+    }
+
+    #[test]
+    fn update_messages_batch_the_names_into_one_notification() {
+        let names = ["write the report".to_string(), "book flights".to_string()];
+        let (summary, body) = update_message(UpdateCategory::NewTasks, &names);
+        assert_eq!(summary, "2 new tasks");
+        assert_eq!(body, "write the report\nbook flights");
+
+        let (summary, body) = update_message(UpdateCategory::NewlyOverdue, &names[..1]);
+        assert_eq!(summary, "1 task is now overdue");
+        assert_eq!(body, "write the report");
+    }
+
+    #[test]
+    fn the_rate_limit_allows_one_notification_per_category_per_gap() {
+        let gap = chrono::Duration::minutes(30);
+        let start = Local::now();
+        let mut limiter = NotifyLimiter::default();
+
+        assert!(limiter.should_send(UpdateCategory::NewTasks, gap, start));
+        assert!(!limiter.should_send(
+            UpdateCategory::NewTasks,
+            gap,
+            start + chrono::Duration::minutes(29)
+        ));
+        // Each category keeps its own clock, so an overdue warning is never starved by a burst
+        // of new tasks.
+        assert!(limiter.should_send(UpdateCategory::NewlyOverdue, gap, start));
+        // Once the gap has passed, the category may fire again and the clock restarts.
+        assert!(limiter.should_send(
+            UpdateCategory::NewTasks,
+            gap,
+            start + chrono::Duration::minutes(30)
+        ));
+        assert!(!limiter.should_send(
+            UpdateCategory::NewTasks,
+            gap,
+            start + chrono::Duration::minutes(59)
+        ));
+    }
+
+    #[test]
+    fn the_limiter_round_trips_through_the_cache_format() {
+        let gap = chrono::Duration::minutes(30);
+        let start = Local::now();
+        let mut limiter = NotifyLimiter::default();
+        assert!(limiter.should_send(UpdateCategory::NewTasks, gap, start));
+
+        let mut restored: NotifyLimiter =
+            serde_json::from_str(&serde_json::to_string(&limiter).unwrap()).unwrap();
+        assert!(!restored.should_send(UpdateCategory::NewTasks, gap, start));
+        assert!(restored.should_send(UpdateCategory::NewlyOverdue, gap, start));
+    }
+}
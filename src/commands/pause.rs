@@ -0,0 +1,60 @@
+//! The pause and resume commands, which suspend reminders and focus tracking for a while.
+//!
+//! While a pause window covers today, the status line shows a dim `paused` token, the gate and
+//! notifications stay quiet, and `todo update` stops auto-creating focus day tasks.
+
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// A pause window, kept in the cache so every command sees the same state without extra flags.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PauseWindow {
+    /// Last date (inclusive) the pause covers; reminders resume the day after.
+    pub until: NaiveDate,
+}
+
+impl PauseWindow {
+    /// The first date reminders and focus tracking are active again.
+    #[must_use]
+    pub fn resumes_on(&self) -> NaiveDate {
+        self.until + Days::new(1)
+    }
+}
+
+/// Check whether the pause window covers the given date.
+///
+/// An expired window does not count, so a stale entry in the cache resumes everything on its
+/// own without needing an explicit `todo resume`.
+#[must_use]
+pub fn is_paused(window: Option<&PauseWindow>, today: NaiveDate) -> bool {
+    window.is_some_and(|w| today <= w.until)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(date: &str) -> NaiveDate {
+        date.parse().unwrap()
+    }
+
+    #[test]
+    fn the_pause_covers_its_last_day_but_not_the_one_after() {
+        let window = PauseWindow {
+            until: date("2024-01-15"),
+        };
+        assert!(is_paused(Some(&window), date("2024-01-14")));
+        assert!(is_paused(Some(&window), date("2024-01-15")));
+        assert!(!is_paused(Some(&window), date("2024-01-16")));
+        assert_eq!(window.resumes_on(), date("2024-01-16"));
+    }
+
+    #[test]
+    fn an_expired_window_resumes_on_its_own() {
+        let window = PauseWindow {
+            until: date("2024-01-10"),
+        };
+        assert!(!is_paused(Some(&window), date("2024-01-15")));
+        assert!(!is_paused(None, date("2024-01-15")));
+    }
+}
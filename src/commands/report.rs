@@ -0,0 +1,283 @@
+//! The report command, which assembles markdown skeletons for weekly reviews.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use chrono::{Days, NaiveDate};
+
+use crate::focus::FocusDay;
+use crate::task::{CompletedTask, UserTask};
+
+/// Everything the weekly report covers, assembled by the caller from the API or cache.
+#[derive(Clone, Debug)]
+pub struct WeekReportData {
+    /// Monday of the week the report covers.
+    pub week_start: NaiveDate,
+    /// Focus days falling within the week, in any order.
+    pub focus_days: Vec<FocusDay>,
+    /// Tasks completed recently; entries outside the week are filtered out while rendering.
+    pub completed: Vec<CompletedTask>,
+    /// Currently open tasks; only ones still overdue going into next week are rendered.
+    pub open_tasks: Vec<UserTask>,
+}
+
+/// Render the weekly review skeleton as a markdown document.
+///
+/// Every section renders even when it has no data, saying so, so the skeleton's shape is stable
+/// from week to week.
+///
+/// # Panics
+///
+/// This function will panic if an overdue task has no due date, which the due-date filter
+/// guarantees cannot happen.
+#[must_use]
+pub fn render_week(data: &WeekReportData) -> String {
+    let week_end = data.week_start + Days::new(7);
+    let mut string = String::new();
+    let _ = writeln!(
+        string,
+        "# Week of {start} – {end}\n",
+        start = data.week_start.format("%b %-d"),
+        end = (week_end - Days::new(1)).format("%b %-d, %Y")
+    );
+
+    let mut focus_days: Vec<&FocusDay> = data
+        .focus_days
+        .iter()
+        .filter(|day| (data.week_start..week_end).contains(&day.date))
+        .collect();
+    focus_days.sort_by_key(|day| day.date);
+
+    let _ = writeln!(string, "## Focus stats\n");
+    push_stats(&mut string, &focus_days);
+
+    let _ = writeln!(string, "## Diary\n");
+    let diaries: Vec<&FocusDay> = focus_days
+        .iter()
+        .filter(|day| !day.diary.is_empty())
+        .copied()
+        .collect();
+    if diaries.is_empty() {
+        let _ = writeln!(string, "No diary entries this week.\n");
+    } else {
+        for day in diaries {
+            let _ = writeln!(
+                string,
+                "**{date}** — {diary}\n",
+                date = day.date.format("%a %b %-d"),
+                diary = day.diary
+            );
+        }
+    }
+
+    let _ = writeln!(string, "## Completed\n");
+    let mut completed: Vec<(NaiveDate, &CompletedTask)> = data
+        .completed
+        .iter()
+        .filter_map(|task| task.completed_at.map(|at| (at.date_naive(), task)))
+        .filter(|(date, _)| (data.week_start..week_end).contains(date))
+        .collect();
+    completed.sort_by_key(|(date, task)| (*date, task.gid.clone()));
+    if completed.is_empty() {
+        let _ = writeln!(string, "Nothing completed this week.\n");
+    } else {
+        for (date, task) in completed {
+            let _ = writeln!(
+                string,
+                "- {name} ({date})",
+                name = task.name,
+                date = date.format("%a")
+            );
+        }
+        string.push('\n');
+    }
+
+    let _ = writeln!(string, "## Still overdue\n");
+    let mut overdue: Vec<&UserTask> = data
+        .open_tasks
+        .iter()
+        .filter(|task| task.due_on.is_some_and(|due| due < week_end))
+        .collect();
+    overdue.sort_by_key(|task| task.due_on);
+    if overdue.is_empty() {
+        let _ = writeln!(string, "Nothing overdue going into next week.");
+    } else {
+        for task in overdue {
+            let _ = writeln!(
+                string,
+                "- {name} (due {due})",
+                name = task.name,
+                due = task.due_on.unwrap().format("%b %-d")
+            );
+        }
+    }
+
+    string
+}
+
+/// Append the stat averages and the best/worst days, scoring each day by the mean of its filled
+/// stats.
+fn push_stats(string: &mut String, focus_days: &[&FocusDay]) {
+    let mut totals: BTreeMap<&'static str, (u32, u32)> = BTreeMap::new();
+    let mut day_scores: Vec<(f64, &FocusDay)> = Vec::new();
+    for day in focus_days {
+        let filled: Vec<(&'static str, u32)> = day
+            .stats
+            .stats()
+            .into_iter()
+            .filter_map(|stat| stat.value().map(|value| (stat.name(), value)))
+            .collect();
+        for (name, value) in &filled {
+            let (total, count) = totals.entry(name).or_default();
+            *total += value;
+            *count += 1;
+        }
+        let (sum, count) = filled
+            .iter()
+            .fold((0_u32, 0_u32), |(sum, count), (_, value)| {
+                (sum + value, count + 1)
+            });
+        if count > 0 {
+            day_scores.push((f64::from(sum) / f64::from(count), day));
+        }
+    }
+
+    if totals.is_empty() {
+        let _ = writeln!(string, "No focus stats recorded this week.\n");
+        return;
+    }
+
+    let averages: Vec<String> = totals
+        .into_iter()
+        .map(|(name, (total, count))| {
+            format!("{name} {average:.1}", average = f64::from(total) / f64::from(count))
+        })
+        .collect();
+    let _ = writeln!(string, "{}\n", averages.join(" · "));
+
+    day_scores.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+    if let (Some((worst_score, worst)), Some((best_score, best))) =
+        (day_scores.first(), day_scores.last())
+    {
+        let _ = writeln!(
+            string,
+            "Best day: {best_date} ({best_score:.1}), worst day: {worst_date} ({worst_score:.1}).\n",
+            best_date = best.date.format("%a %b %-d"),
+            worst_date = worst.date.format("%a %b %-d")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Local, TimeZone};
+
+    use crate::focus::{FocusDayStat, FocusDayStats, FocusTask};
+
+    use super::*;
+
+    fn focus_day(date: &str, diary: &str, sleep: Option<u32>, energy: Option<u32>) -> FocusDay {
+        let mut stats = FocusDayStats::default();
+        stats.set_stat(FocusDayStat::Sleep(sleep));
+        stats.set_stat(FocusDayStat::Energy(energy));
+        FocusDay {
+            task: FocusTask {
+                gid: "t".to_string(),
+                name: format!("Daily Focus for day ({date})"),
+                notes: diary.to_string(),
+                modified_at: None,
+                custom_fields: None,
+            },
+            date: date.parse().unwrap(),
+            stats,
+            diary: diary.to_string(),
+            prompts: vec![],
+            subtasks: None,
+        }
+    }
+
+    fn completed(gid: &str, name: &str, at: &str) -> CompletedTask {
+        CompletedTask {
+            gid: gid.to_string(),
+            name: name.to_string(),
+            completed_at: Some(
+                Local
+                    .from_local_datetime(&format!("{at}T12:00:00").parse().unwrap())
+                    .unwrap(),
+            ),
+            projects: Vec::new(),
+        }
+    }
+
+    fn open_task(gid: &str, name: &str, due_on: Option<&str>) -> UserTask {
+        UserTask {
+            gid: gid.to_string(),
+            created_at: Local.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap(),
+            due_on: due_on.map(|d| d.parse().unwrap()),
+            name: name.to_string(),
+            projects: Vec::new(),
+            custom_fields: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn report_renders_every_section_from_data() {
+        let data = WeekReportData {
+            week_start: "2024-01-15".parse().unwrap(),
+            focus_days: vec![
+                focus_day("2024-01-15", "rough start.", Some(2), Some(2)),
+                focus_day("2024-01-16", "much better.", Some(5), Some(4)),
+                // Outside the week, so it never shows up.
+                focus_day("2024-01-08", "last week.", Some(1), None),
+            ],
+            completed: vec![
+                completed("1", "ship report", "2024-01-16"),
+                completed("2", "not this week", "2024-01-10"),
+            ],
+            open_tasks: vec![
+                open_task("3", "pay rent", Some("2024-01-12")),
+                open_task("4", "next month", Some("2024-02-20")),
+                open_task("5", "someday", None),
+            ],
+        };
+        assert_eq!(
+            render_week(&data),
+            "# Week of Jan 15 – Jan 21, 2024\n\
+             \n\
+             ## Focus stats\n\
+             \n\
+             energy 3.0 · sleep 3.5\n\
+             \n\
+             Best day: Tue Jan 16 (4.5), worst day: Mon Jan 15 (2.0).\n\
+             \n\
+             ## Diary\n\
+             \n\
+             **Mon Jan 15** — rough start.\n\
+             \n\
+             **Tue Jan 16** — much better.\n\
+             \n\
+             ## Completed\n\
+             \n\
+             - ship report (Tue)\n\
+             \n\
+             ## Still overdue\n\
+             \n\
+             - pay rent (due Jan 12)\n"
+        );
+    }
+
+    #[test]
+    fn empty_sections_say_so_instead_of_vanishing() {
+        let data = WeekReportData {
+            week_start: "2024-01-15".parse().unwrap(),
+            focus_days: Vec::new(),
+            completed: Vec::new(),
+            open_tasks: Vec::new(),
+        };
+        let report = render_week(&data);
+        assert!(report.contains("No focus stats recorded this week."));
+        assert!(report.contains("No diary entries this week."));
+        assert!(report.contains("Nothing completed this week."));
+        assert!(report.contains("Nothing overdue going into next week."));
+    }
+}
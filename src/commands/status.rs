@@ -0,0 +1,1113 @@
+//! Implementation of the `status` subcommand, which prints a compact state snapshot for status
+//! bars, prompts, and shell conditionals.
+
+use std::fmt::Write as _;
+
+use chrono::{DateTime, Local, NaiveDate};
+use serde::Serialize;
+
+use crate::commands::Outcome;
+use crate::context::GroupedTasks;
+use crate::focus::{FocusDay, FocusDayStats};
+
+/// Output format for the `status` subcommand.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum StatusFormat {
+    /// Compact single-line string for status bars.
+    #[default]
+    Short,
+    /// Flat JSON object.
+    Json,
+    /// JSON object in the shape waybar and i3blocks status modules expect.
+    Waybar,
+    /// Multi-line output in the shape xbar menu bar plugins expect.
+    Xbar,
+    /// Plain short string for a starship custom command: no ANSI codes, capped width.
+    Starship,
+    /// Prometheus exposition-format gauges, for `node_exporter`'s textfile collector.
+    Prometheus,
+}
+
+/// Hard cap on the width of the starship output, so a busy day cannot blow up the prompt.
+const STARSHIP_MAX_WIDTH: usize = 30;
+
+/// Maximum number of task rows in the xbar dropdown before the "and N more" row takes over.
+const XBAR_MAX_TASKS: usize = 10;
+
+/// Maximum width of a task name in the xbar dropdown.
+const XBAR_TASK_MAX_WIDTH: usize = 40;
+
+/// Truncate a string to `max_width` characters, replacing the tail with an ellipsis.
+fn truncate_chars(string: &str, max_width: usize) -> String {
+    if string.chars().count() > max_width {
+        let mut truncated: String = string.chars().take(max_width.saturating_sub(1)).collect();
+        truncated.push('…');
+        truncated
+    } else {
+        string.to_string()
+    }
+}
+
+/// Resolved set of symbols and limits used to build the short status string.
+#[derive(Clone, Debug)]
+pub struct StatusSymbols {
+    /// Prefix for the overdue count.
+    pub overdue_prefix: String,
+    /// Prefix for the due-today count.
+    pub due_today_prefix: String,
+    /// Symbol shown while the morning focus routine is pending.
+    pub morning_pending: String,
+    /// Symbol shown while the evening focus routine is pending.
+    pub evening_pending: String,
+    /// Symbol shown when nothing is overdue, due, or pending.
+    pub all_clear: String,
+    /// If set, the string is truncated to this many characters with an ellipsis.
+    pub max_width: Option<usize>,
+    /// If set, the due-today count is omitted entirely.
+    pub hide_due_today: bool,
+    /// If set, pending focus subtasks show up as an `f:`-prefixed count.
+    pub show_focus_subtasks: bool,
+    /// If set, tasks due within the next week show up as a `~`-prefixed count.
+    pub show_due_week: bool,
+}
+
+impl Default for StatusSymbols {
+    fn default() -> Self {
+        Self {
+            overdue_prefix: "!".to_string(),
+            due_today_prefix: "+".to_string(),
+            morning_pending: "focus:am".to_string(),
+            evening_pending: "focus:pm".to_string(),
+            all_clear: "✓".to_string(),
+            max_width: None,
+            hide_due_today: false,
+            show_focus_subtasks: false,
+            show_due_week: false,
+        }
+    }
+}
+
+impl StatusSymbols {
+    /// ASCII-only symbol set, for terminals and bars that cannot render emoji or symbols.
+    #[must_use]
+    pub fn ascii() -> Self {
+        Self {
+            morning_pending: "AM".to_string(),
+            evening_pending: "PM".to_string(),
+            all_clear: "OK".to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Resolve the symbol set from the status configuration, falling back to the defaults (or
+    /// the ASCII set when `ascii_only` is set) for anything left unset.
+    #[must_use]
+    pub fn resolve(config: &crate::config::StatusConfig) -> Self {
+        let defaults = if config.ascii_only {
+            Self::ascii()
+        } else {
+            Self::default()
+        };
+        Self {
+            overdue_prefix: config
+                .overdue_prefix
+                .clone()
+                .unwrap_or(defaults.overdue_prefix),
+            due_today_prefix: config
+                .due_today_prefix
+                .clone()
+                .unwrap_or(defaults.due_today_prefix),
+            morning_pending: config
+                .morning_pending
+                .clone()
+                .unwrap_or(defaults.morning_pending),
+            evening_pending: config
+                .evening_pending
+                .clone()
+                .unwrap_or(defaults.evening_pending),
+            all_clear: config.all_clear.clone().unwrap_or(defaults.all_clear),
+            max_width: config.max_width,
+            hide_due_today: config.hide_due_today,
+            show_focus_subtasks: config.show_focus_subtasks,
+            show_due_week: config.show_due_week,
+        }
+    }
+}
+
+/// JSON shape expected by waybar and i3blocks custom modules.
+#[derive(Clone, Debug, Serialize)]
+struct WaybarOutput {
+    text: String,
+    tooltip: String,
+    class: &'static str,
+}
+
+/// Snapshot of the current todo state.
+// Each flag is an independent piece of reported state, not a hidden mode switch.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct Status {
+    /// Number of overdue tasks.
+    pub overdue: usize,
+    /// Number of tasks due today.
+    pub due_today: usize,
+    /// Number of tasks due within the next week.
+    pub due_week: usize,
+    /// Number of tasks completed today, from the cached completed-today window.
+    pub done_today: usize,
+    /// Number of incomplete focus subtasks whose due date has passed.
+    pub focus_subtasks_overdue: usize,
+    /// Number of incomplete focus subtasks due today.
+    pub focus_subtasks_pending: usize,
+    /// Whether the morning focus routine is still pending.
+    pub morning_pending: bool,
+    /// Whether the evening focus routine is still pending.
+    pub evening_pending: bool,
+    /// Whether a pause window covers today.
+    pub paused: bool,
+    /// Whether the counts come from a cache fallback after the network budget expired, rather
+    /// than a fresh fetch.
+    pub stale: bool,
+}
+
+impl Status {
+    /// Compute the status from grouped tasks and today's focus day, if one is cached.
+    ///
+    /// A missing focus day counts as the routines being pending; the evening routine only counts
+    /// as pending once `eod` is set. The focus subtask counts come from the subtasks cached on
+    /// the focus day, so they are zero until `todo update` has loaded them. When the
+    /// `focus.days` schedule excludes today (`focus_scheduled` is unset), the routines count as
+    /// fully done and the subtask counts are zero. A pause does the same, except the short
+    /// string shows a `paused` token instead of pretending everything is done.
+    #[must_use]
+    pub fn new(
+        grouped: &GroupedTasks,
+        focus_day: Option<&FocusDay>,
+        eod: bool,
+        today: NaiveDate,
+        focus_scheduled: bool,
+        paused: bool,
+        done_today: usize,
+    ) -> Self {
+        let focus_active = focus_scheduled && !paused;
+        let (focus_subtasks_overdue, focus_subtasks_pending) = if focus_active {
+            focus_day.map_or((0, 0), |d| d.pending_subtask_counts(today))
+        } else {
+            (0, 0)
+        };
+        Self {
+            overdue: grouped.overdue.len(),
+            due_today: grouped.due_today.len(),
+            due_week: grouped.due_week.len(),
+            done_today,
+            focus_subtasks_overdue,
+            focus_subtasks_pending,
+            morning_pending: focus_active && !focus_day.is_some_and(FocusDay::is_morning_done),
+            evening_pending: eod
+                && focus_active
+                && !focus_day.is_some_and(FocusDay::is_evening_done),
+            paused,
+            stale: false,
+        }
+    }
+
+    /// Build a status directly from raw counts, with every focus flag unset.
+    ///
+    /// Prefer [`Status::new`], which derives the counts from [`GroupedTasks`] and so cannot mix
+    /// them up; this constructor stays for library callers that never group tasks.
+    #[must_use]
+    pub fn from_counts(overdue: usize, due_today: usize, due_week: usize, done_today: usize) -> Self {
+        Self {
+            overdue,
+            due_today,
+            due_week,
+            done_today,
+            focus_subtasks_overdue: 0,
+            focus_subtasks_pending: 0,
+            morning_pending: false,
+            evening_pending: false,
+            paused: false,
+            stale: false,
+        }
+    }
+
+    /// Render the status as a compact single-line string, e.g. `!2 +1 focus:am` with the default
+    /// symbol set.
+    #[must_use]
+    pub fn to_short_string(&self, symbols: &StatusSymbols) -> String {
+        let mut parts = Vec::new();
+        if self.overdue > 0 {
+            parts.push(format!("{}{}", symbols.overdue_prefix, self.overdue));
+        }
+        if self.due_today > 0 && !symbols.hide_due_today {
+            parts.push(format!("{}{}", symbols.due_today_prefix, self.due_today));
+        }
+        if symbols.show_due_week && self.due_week > 0 {
+            parts.push(format!("~{}", self.due_week));
+        }
+        let focus_subtasks = self.focus_subtasks_overdue + self.focus_subtasks_pending;
+        if symbols.show_focus_subtasks && focus_subtasks > 0 {
+            parts.push(format!("f:{focus_subtasks}"));
+        }
+        if self.paused {
+            parts.push("paused".to_string());
+        }
+        if self.morning_pending {
+            parts.push(symbols.morning_pending.clone());
+        }
+        if self.evening_pending {
+            parts.push(symbols.evening_pending.clone());
+        }
+        if self.stale {
+            parts.push("stale".to_string());
+        }
+
+        let string = if parts.is_empty() {
+            symbols.all_clear.clone()
+        } else {
+            parts.join(" ")
+        };
+        match symbols.max_width {
+            Some(max_width) => truncate_chars(&string, max_width),
+            None => string,
+        }
+    }
+
+    /// Render the status as xbar plugin output: a menu bar line, a separator, the counts, a
+    /// clickable row per overdue or due-today task, then a footer with the cache age, a link to
+    /// today's focus task, and a "run update" action.
+    ///
+    /// The menu bar line leads with a sun or moon while the corresponding focus routine is
+    /// pending, or `AM`/`PM` when `ascii_only` is set. Task rows link to the Asana permalink via
+    /// `href=`, are truncated to [`XBAR_TASK_MAX_WIDTH`] characters, and are capped at
+    /// [`XBAR_MAX_TASKS`] with an "and N more" row. The `SwiftBar` flavor adds an SF Symbols icon
+    /// to the menu bar line. The focus row only appears when [`XbarExtras`] carries a gid.
+    #[must_use]
+    pub fn to_xbar_string(
+        &self,
+        grouped: &GroupedTasks,
+        symbols: &StatusSymbols,
+        menubar: &crate::config::MenubarConfig,
+        ascii_only: bool,
+        extras: &XbarExtras<'_>,
+    ) -> String {
+        let mut menu_line = if self.is_all_clear() {
+            symbols.all_clear.clone()
+        } else {
+            let mut parts = Vec::new();
+            if self.overdue > 0 {
+                parts.push(format!("{}{}", symbols.overdue_prefix, self.overdue));
+            }
+            if self.due_today > 0 && !symbols.hide_due_today {
+                parts.push(format!("{}{}", symbols.due_today_prefix, self.due_today));
+            }
+            if self.morning_pending {
+                parts.push(if ascii_only { "AM" } else { "☀️" }.to_string());
+            } else if self.evening_pending {
+                parts.push(if ascii_only { "PM" } else { "🌙" }.to_string());
+            }
+            parts.join(" ")
+        };
+        if menubar.flavor == crate::config::MenubarFlavor::Swiftbar {
+            let sfimage = if self.overdue > 0 {
+                "exclamationmark.circle"
+            } else if self.is_all_clear() {
+                "checkmark.circle"
+            } else {
+                "checklist"
+            };
+            let _ = write!(menu_line, " | sfimage={sfimage}");
+        }
+
+        let mut string = format!(
+            "{menu_line}\n---\n{overdue} overdue\n{due_today} due today\n",
+            overdue = self.overdue,
+            due_today = self.due_today,
+        );
+        if self.due_week > 0 {
+            let _ = writeln!(string, "{} due this week", self.due_week);
+        }
+        if self.done_today > 0 {
+            let _ = writeln!(string, "{} completed today", self.done_today);
+        }
+
+        let tasks: Vec<_> = grouped
+            .overdue
+            .iter()
+            .chain(grouped.due_today.iter())
+            .collect();
+        if !tasks.is_empty() {
+            string.push_str("---\n");
+            for task in tasks.iter().take(XBAR_MAX_TASKS) {
+                let _ = writeln!(
+                    string,
+                    "{name} | href={url}",
+                    name = truncate_chars(&task.name, XBAR_TASK_MAX_WIDTH),
+                    url = crate::render::task_permalink(&task.gid),
+                );
+            }
+            if tasks.len() > XBAR_MAX_TASKS {
+                let _ = writeln!(string, "… and {} more", tasks.len() - XBAR_MAX_TASKS);
+            }
+        }
+
+        // The footer is where stale "Pending" gets debugged from: how old the data is, a way
+        // into today's focus task, and a refresh without opening a terminal.
+        string.push_str("---\n");
+        match extras.last_updated {
+            Some(last_updated) => {
+                let _ = writeln!(
+                    string,
+                    "Last updated {}m ago | color=gray",
+                    (extras.now - last_updated).num_minutes()
+                );
+            }
+            None => string.push_str("Last updated never | color=gray\n"),
+        }
+        if let Some(gid) = extras.focus_gid {
+            let _ = writeln!(
+                string,
+                "Open today's focus in Asana | href={}",
+                crate::render::task_permalink(gid)
+            );
+        }
+        string.push_str("Run update now | shell=todo param1=update terminal=false refresh=true\n");
+        string
+    }
+
+    /// Whether nothing is overdue, due today, or pending.
+    #[must_use]
+    pub fn is_all_clear(&self) -> bool {
+        self.overdue == 0 && self.due_today == 0 && !self.morning_pending && !self.evening_pending
+    }
+
+    /// The outcome encoded by this status, for exit-code mapping.
+    #[must_use]
+    pub fn outcome(&self) -> Outcome {
+        Outcome {
+            overdue: self.overdue > 0,
+            focus_pending: self.morning_pending || self.evening_pending,
+        }
+    }
+}
+
+/// Context for the xbar dropdown footer that is not part of [`Status`] itself: the cache's
+/// freshness and a handle on today's focus task.
+#[derive(Clone, Copy, Debug)]
+pub struct XbarExtras<'a> {
+    /// Gid of today's focus task, if one is cached; `None` drops the "open in Asana" row.
+    pub focus_gid: Option<&'a str>,
+    /// When the cache was last refreshed; `None` renders as "never".
+    pub last_updated: Option<DateTime<Local>>,
+    /// The run's clock, which the cache age is measured against.
+    pub now: DateTime<Local>,
+}
+
+/// Extended status emitted by the JSON format, for dashboards and other external consumers.
+///
+/// Field names are stable and parsed by external consumers — extend, don't rename. Timestamps
+/// are ISO-8601 with the local offset.
+#[derive(Clone, Debug, Serialize)]
+pub struct StatusReport {
+    /// The counts and pending flags, flattened to the top level.
+    #[serde(flatten)]
+    pub status: Status,
+    /// When this report was generated.
+    pub generated_at: DateTime<Local>,
+    /// When the cache was last refreshed by the update command, if ever.
+    pub cache_last_updated: Option<DateTime<Local>>,
+    /// Age of the cache in whole seconds, if it has ever been updated.
+    pub cache_age_seconds: Option<i64>,
+    /// Date of the cached focus day, if one is cached for today.
+    pub focus_date: Option<NaiveDate>,
+    /// Names of the focus stats still unfilled for the cached focus day; all of them when no
+    /// focus day is cached.
+    pub pending_stats: Vec<&'static str>,
+}
+
+impl StatusReport {
+    /// Build the report from the status and the cached focus day and timestamps; everything
+    /// comes from the cache, so no API calls are involved.
+    #[must_use]
+    pub fn new(
+        status: Status,
+        focus_day: Option<&FocusDay>,
+        cache_last_updated: Option<DateTime<Local>>,
+        generated_at: DateTime<Local>,
+    ) -> Self {
+        Self {
+            status,
+            generated_at,
+            cache_last_updated,
+            cache_age_seconds: cache_last_updated.map(|t| (generated_at - t).num_seconds()),
+            focus_date: focus_day.map(|d| d.date),
+            pending_stats: focus_day
+                .map_or(&FocusDayStats::default(), |d| &d.stats)
+                .pending_names(true),
+        }
+    }
+}
+
+/// Render the status report as a flat JSON object.
+///
+/// # Errors
+///
+/// This function will return an error if the report could not be serialized.
+pub fn render_json(report: &StatusReport) -> anyhow::Result<String> {
+    Ok(serde_json::to_string(report)?)
+}
+
+/// Render the status for a starship custom command.
+///
+/// Starship treats stderr noise or slow invocations as failures, so this is the short string with
+/// no ANSI codes, hard capped at [`STARSHIP_MAX_WIDTH`] characters.
+#[must_use]
+pub fn render_starship(status: &Status, symbols: &StatusSymbols) -> String {
+    status
+        .to_short_string(symbols)
+        .chars()
+        .take(STARSHIP_MAX_WIDTH)
+        .collect()
+}
+
+/// Render the status as a single-line JSON object for waybar and i3blocks status modules.
+///
+/// The text is the short string with no ANSI codes, the tooltip lists the counts and focus state
+/// on separate lines, and the class is one of `ok`/`pending`/`overdue` for CSS styling.
+///
+/// # Errors
+///
+/// This function will return an error if the output could not be serialized.
+pub fn render_waybar(status: &Status, symbols: &StatusSymbols) -> anyhow::Result<String> {
+    let text = status.to_short_string(symbols);
+
+    let mut tooltip_lines = vec![
+        format!("{} overdue", status.overdue),
+        format!("{} due today", status.due_today),
+    ];
+    if status.morning_pending {
+        tooltip_lines.push("morning focus pending".to_string());
+    }
+    if status.evening_pending {
+        tooltip_lines.push("evening focus pending".to_string());
+    }
+    if status.paused {
+        tooltip_lines.push("paused".to_string());
+    } else if !status.morning_pending && !status.evening_pending {
+        tooltip_lines.push("focus done".to_string());
+    }
+    if status.stale {
+        tooltip_lines.push("cache stale".to_string());
+    }
+
+    let class = if status.overdue > 0 {
+        "overdue"
+    } else if status.is_all_clear() {
+        "ok"
+    } else {
+        "pending"
+    };
+
+    Ok(serde_json::to_string(&WaybarOutput {
+        text,
+        tooltip: tooltip_lines.join("\n"),
+        class,
+    })?)
+}
+
+/// Render the status as Prometheus exposition-format gauges.
+///
+/// Each metric gets `# HELP` and `# TYPE` lines, values are plain integers with no ANSI codes,
+/// and the output is newline-terminated, as `node_exporter`'s textfile collector requires. The
+/// pending flags are inverted into `_done` gauges so dashboards can sum completions directly.
+/// `todo_cache_age_seconds` is omitted entirely when the cache has never been updated, which is
+/// how Prometheus conventionally represents "no data".
+#[must_use]
+pub fn render_prometheus(status: &Status, cache_age_seconds: Option<i64>) -> String {
+    fn gauge(output: &mut String, name: &str, help: &str, value: i64) {
+        let _ = writeln!(output, "# HELP {name} {help}");
+        let _ = writeln!(output, "# TYPE {name} gauge");
+        let _ = writeln!(output, "{name} {value}");
+    }
+
+    let counts: &[(&str, &str, usize)] = &[
+        ("todo_tasks_overdue", "Number of overdue tasks.", status.overdue),
+        ("todo_tasks_due_today", "Number of tasks due today.", status.due_today),
+        ("todo_tasks_due_week", "Number of tasks due within the next week.", status.due_week),
+        ("todo_tasks_done_today", "Number of tasks completed today.", status.done_today),
+    ];
+    let flags: &[(&str, &str, bool)] = &[
+        ("todo_focus_morning_done", "Whether the morning focus routine is done (1) or pending (0).", !status.morning_pending),
+        ("todo_focus_evening_done", "Whether the evening focus routine is done (1) or pending (0).", !status.evening_pending),
+        ("todo_paused", "Whether a pause window covers today.", status.paused),
+    ];
+
+    let mut output = String::new();
+    for &(name, help, value) in counts {
+        gauge(&mut output, name, help, i64::try_from(value).unwrap_or(i64::MAX));
+    }
+    for &(name, help, value) in flags {
+        gauge(&mut output, name, help, i64::from(value));
+    }
+    if let Some(age) = cache_age_seconds {
+        gauge(&mut output, "todo_cache_age_seconds", "Seconds since the cache was last updated.", age);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::task::UserTask;
+
+    use super::*;
+
+    fn status(overdue: usize, due_today: usize, morning: bool, evening: bool) -> Status {
+        Status {
+            overdue,
+            due_today,
+            due_week: 0,
+            done_today: 0,
+            focus_subtasks_overdue: 0,
+            focus_subtasks_pending: 0,
+            morning_pending: morning,
+            evening_pending: evening,
+            paused: false,
+            stale: false,
+        }
+    }
+
+    #[test]
+    fn short_string_joins_the_pending_parts() {
+        assert_eq!(status(2, 1, true, false).to_short_string(&StatusSymbols::default()), "!2 +1 focus:am");
+        assert_eq!(status(0, 0, false, true).to_short_string(&StatusSymbols::default()), "focus:pm");
+        assert_eq!(status(3, 0, false, false).to_short_string(&StatusSymbols::default()), "!3");
+
+        let mut stale = status(3, 0, false, false);
+        stale.stale = true;
+        assert_eq!(stale.to_short_string(&StatusSymbols::default()), "!3 stale");
+    }
+
+    fn report(focus_day: Option<&FocusDay>) -> StatusReport {
+        use chrono::TimeZone as _;
+        StatusReport::new(
+            status(1, 2, true, false),
+            focus_day,
+            Some(Local.with_ymd_and_hms(2024, 1, 15, 9, 0, 0).unwrap()),
+            Local.with_ymd_and_hms(2024, 1, 15, 9, 5, 0).unwrap(),
+        )
+    }
+
+    #[test]
+    fn json_is_a_flat_object() {
+        let parsed: serde_json::Value =
+            serde_json::from_str(&render_json(&report(None)).unwrap()).unwrap();
+        assert_eq!(parsed["overdue"], 1);
+        assert_eq!(parsed["due_today"], 2);
+        assert_eq!(parsed["morning_pending"], true);
+        assert_eq!(parsed["evening_pending"], false);
+    }
+
+    #[test]
+    fn json_report_field_names_are_stable() {
+        let parsed: serde_json::Value =
+            serde_json::from_str(&render_json(&report(None)).unwrap()).unwrap();
+        let mut keys: Vec<_> = parsed.as_object().unwrap().keys().cloned().collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            [
+                "cache_age_seconds",
+                "cache_last_updated",
+                "done_today",
+                "due_today",
+                "due_week",
+                "evening_pending",
+                "focus_date",
+                "focus_subtasks_overdue",
+                "focus_subtasks_pending",
+                "generated_at",
+                "morning_pending",
+                "overdue",
+                "paused",
+                "pending_stats",
+                "stale",
+            ]
+        );
+    }
+
+    #[test]
+    fn json_report_includes_cache_age_and_iso_8601_timestamps() {
+        let parsed: serde_json::Value =
+            serde_json::from_str(&render_json(&report(None)).unwrap()).unwrap();
+        assert_eq!(parsed["cache_age_seconds"], 300);
+        for key in ["generated_at", "cache_last_updated"] {
+            chrono::DateTime::parse_from_rfc3339(parsed[key].as_str().unwrap()).unwrap();
+        }
+    }
+
+    #[test]
+    fn json_report_lists_the_pending_stats() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let mut focus_day = FocusDay {
+            task: crate::focus::FocusTask {
+                gid: "1".to_string(),
+                name: format!("Friday {date}"),
+                notes: String::new(),
+                modified_at: None,
+                custom_fields: None,
+            },
+            date,
+            stats: FocusDayStats::default(),
+            diary: String::new(),
+            prompts: vec![],
+            subtasks: None,
+        };
+        focus_day.stats.sleep.set_value(Some(7));
+        focus_day.stats.energy.set_value(Some(5));
+
+        let parsed: serde_json::Value =
+            serde_json::from_str(&render_json(&report(Some(&focus_day))).unwrap()).unwrap();
+        assert_eq!(parsed["focus_date"], "2024-01-15");
+        assert_eq!(
+            parsed["pending_stats"],
+            serde_json::json!(["flow", "hydration", "health", "satisfaction", "stress"])
+        );
+
+        let without_focus_day: serde_json::Value =
+            serde_json::from_str(&render_json(&report(None)).unwrap()).unwrap();
+        assert_eq!(without_focus_day["focus_date"], serde_json::Value::Null);
+        assert_eq!(without_focus_day["pending_stats"].as_array().unwrap().len(), 7);
+    }
+
+    #[test]
+    fn short_string_uses_custom_symbols() {
+        let symbols = StatusSymbols {
+            overdue_prefix: "▲".to_string(),
+            due_today_prefix: "●".to_string(),
+            morning_pending: "☀".to_string(),
+            evening_pending: "☾".to_string(),
+            all_clear: "✓".to_string(),
+            ..StatusSymbols::default()
+        };
+        assert_eq!(status(2, 1, true, false).to_short_string(&symbols), "▲2 ●1 ☀");
+        assert_eq!(status(0, 0, false, true).to_short_string(&symbols), "☾");
+        assert_eq!(status(0, 0, false, false).to_short_string(&symbols), "✓");
+    }
+
+    #[test]
+    fn short_string_can_hide_the_due_today_count() {
+        let symbols = StatusSymbols {
+            hide_due_today: true,
+            ..StatusSymbols::default()
+        };
+        assert_eq!(status(2, 5, false, false).to_short_string(&symbols), "!2");
+    }
+
+    #[test]
+    fn focus_subtask_counts_skip_completed_and_undated_subtasks() {
+        use crate::focus::{FocusTask, FocusTaskSubtask};
+
+        let subtask = |gid: &str, completed: bool, due_on: Option<&str>| FocusTaskSubtask {
+            gid: gid.to_string(),
+            name: format!("subtask {gid}"),
+            completed,
+            due_on: due_on.map(|d| d.parse().unwrap()),
+        };
+        let focus_day = FocusDay {
+            task: FocusTask {
+                gid: "1".to_string(),
+                name: "Daily Focus for Monday (2024-01-15)".to_string(),
+                notes: String::new(),
+                modified_at: None,
+                custom_fields: None,
+            },
+            date: NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+            stats: FocusDayStats::default(),
+            diary: String::new(),
+            prompts: vec![],
+            subtasks: Some(vec![
+                subtask("yesterday", false, Some("2024-01-14")),
+                subtask("today", false, Some("2024-01-15")),
+                subtask("undated", false, None),
+                subtask("done", true, Some("2024-01-14")),
+            ]),
+        };
+
+        let today = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let status = Status::new(&grouped(&[]), Some(&focus_day), false, today, true, false, 0);
+        assert_eq!(status.focus_subtasks_overdue, 1);
+        assert_eq!(status.focus_subtasks_pending, 1);
+
+        let unloaded = FocusDay {
+            subtasks: None,
+            ..focus_day
+        };
+        let status = Status::new(&grouped(&[]), Some(&unloaded), false, today, true, false, 0);
+        assert_eq!(status.focus_subtasks_overdue, 0);
+        assert_eq!(status.focus_subtasks_pending, 0);
+    }
+
+    #[test]
+    fn an_unscheduled_day_counts_as_fully_done() {
+        let today = NaiveDate::from_ymd_opt(2024, 1, 6).unwrap();
+        let status = Status::new(&grouped(&[]), None, true, today, false, false, 0);
+        assert!(!status.morning_pending);
+        assert!(!status.evening_pending);
+        assert_eq!(status.focus_subtasks_overdue, 0);
+        assert_eq!(status.focus_subtasks_pending, 0);
+    }
+
+    #[test]
+    fn a_pause_quiets_the_routines_but_shows_instead_of_claiming_all_clear() {
+        let today = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let status = Status::new(&grouped(&[]), None, true, today, true, true, 0);
+        assert!(!status.morning_pending);
+        assert!(!status.evening_pending);
+        assert_eq!(
+            status.to_short_string(&StatusSymbols::default()),
+            "paused"
+        );
+
+        let mut with_overdue = status;
+        with_overdue.overdue = 2;
+        assert_eq!(
+            with_overdue.to_short_string(&StatusSymbols::default()),
+            "!2 paused"
+        );
+    }
+
+    #[test]
+    fn short_string_shows_focus_subtasks_only_when_enabled() {
+        let mut status = status(2, 0, false, false);
+        status.focus_subtasks_overdue = 1;
+        status.focus_subtasks_pending = 1;
+        assert_eq!(status.to_short_string(&StatusSymbols::default()), "!2");
+
+        let symbols = StatusSymbols {
+            show_focus_subtasks: true,
+            ..StatusSymbols::default()
+        };
+        assert_eq!(status.to_short_string(&symbols), "!2 f:2");
+        status.focus_subtasks_overdue = 0;
+        status.focus_subtasks_pending = 0;
+        assert_eq!(status.to_short_string(&symbols), "!2");
+    }
+
+    #[test]
+    fn short_string_shows_due_week_only_when_enabled() {
+        let mut status = status(1, 0, false, false);
+        status.due_week = 3;
+        assert_eq!(status.to_short_string(&StatusSymbols::default()), "!1");
+
+        let symbols = StatusSymbols {
+            show_due_week: true,
+            ..StatusSymbols::default()
+        };
+        assert_eq!(status.to_short_string(&symbols), "!1 ~3");
+        status.due_week = 0;
+        assert_eq!(status.to_short_string(&symbols), "!1");
+    }
+
+    #[test]
+    fn from_counts_matches_a_grouped_build() {
+        let tasks = vec![
+            task("1", "write the report", Some("2024-01-10")),
+            task("2", "water the plants", Some("2024-01-15")),
+            task("3", "book flights", Some("2024-01-18")),
+        ];
+        let today = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let built = Status::new(&grouped(&tasks), None, false, today, false, false, 4);
+        let compat = Status::from_counts(1, 1, 1, 4);
+        assert_eq!(
+            (built.overdue, built.due_today, built.due_week, built.done_today),
+            (compat.overdue, compat.due_today, compat.due_week, compat.done_today),
+        );
+        assert!(!compat.morning_pending && !compat.evening_pending && !compat.paused);
+    }
+
+    #[test]
+    fn short_string_truncates_to_the_max_width_with_an_ellipsis() {
+        let symbols = StatusSymbols {
+            max_width: Some(8),
+            ..StatusSymbols::default()
+        };
+        let string = status(12, 34, true, true).to_short_string(&symbols);
+        assert_eq!(string, "!12 +34…");
+        assert_eq!(string.chars().count(), 8);
+    }
+
+    #[test]
+    fn symbols_resolve_from_the_config_with_defaults() {
+        let config = crate::config::StatusConfig {
+            overdue_prefix: Some("od:".to_string()),
+            max_width: Some(10),
+            ..crate::config::StatusConfig::default()
+        };
+        let symbols = StatusSymbols::resolve(&config);
+        assert_eq!(symbols.overdue_prefix, "od:");
+        assert_eq!(symbols.due_today_prefix, "+");
+        assert_eq!(symbols.max_width, Some(10));
+        assert!(!symbols.hide_due_today);
+    }
+
+    #[test]
+    fn all_clear_symbol_is_a_proper_checkmark() {
+        let string = status(0, 0, false, false).to_short_string(&StatusSymbols::default());
+        assert_eq!(string, "\u{2713}");
+        assert!(!string.contains('\u{e2}'));
+    }
+
+    #[test]
+    fn ascii_set_swaps_symbols_for_ascii_equivalents() {
+        let symbols = StatusSymbols::ascii();
+        assert_eq!(status(0, 0, false, false).to_short_string(&symbols), "OK");
+        assert_eq!(status(0, 0, true, false).to_short_string(&symbols), "AM");
+        assert_eq!(status(1, 0, false, true).to_short_string(&symbols), "!1 PM");
+        assert!(status(2, 3, true, true)
+            .to_short_string(&symbols)
+            .is_ascii());
+    }
+
+    fn task(gid: &str, name: &str, due_on: Option<&str>) -> UserTask {
+        UserTask {
+            gid: gid.to_string(),
+            created_at: "2024-01-01T00:00:00Z".parse().unwrap(),
+            due_on: due_on.map(|d| d.parse().unwrap()),
+            name: name.to_string(),
+            projects: Vec::new(),
+            custom_fields: Vec::new(),
+        }
+    }
+
+    fn grouped(tasks: &[UserTask]) -> GroupedTasks<'_> {
+        GroupedTasks::group(tasks, NaiveDate::from_ymd_opt(2024, 1, 15).unwrap())
+    }
+
+    fn extras(focus_gid: Option<&'static str>, minutes_ago: Option<i64>) -> XbarExtras<'static> {
+        use chrono::TimeZone as _;
+
+        let now = Local.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).unwrap();
+        XbarExtras {
+            focus_gid,
+            last_updated: minutes_ago.map(|minutes| now - chrono::Duration::minutes(minutes)),
+            now,
+        }
+    }
+
+    #[test]
+    fn xbar_output_lists_clickable_tasks_after_the_counts() {
+        let tasks = vec![
+            task("11", "write the report", Some("2024-01-10")),
+            task("12", "water the plants", Some("2024-01-15")),
+        ];
+        let string = status(1, 1, true, false).to_xbar_string(
+            &grouped(&tasks),
+            &StatusSymbols::default(),
+            &crate::config::MenubarConfig::default(),
+            false,
+            &extras(Some("99"), Some(12)),
+        );
+        assert_eq!(
+            string,
+            "!1 +1 \u{2600}\u{fe0f}\n\
+             ---\n\
+             1 overdue\n\
+             1 due today\n\
+             ---\n\
+             write the report | href=https://app.asana.com/0/0/11/f\n\
+             water the plants | href=https://app.asana.com/0/0/12/f\n\
+             ---\n\
+             Last updated 12m ago | color=gray\n\
+             Open today's focus in Asana | href=https://app.asana.com/0/0/99/f\n\
+             Run update now | shell=todo param1=update terminal=false refresh=true\n"
+        );
+    }
+
+    #[test]
+    fn xbar_output_truncates_names_and_caps_the_task_rows() {
+        let mut tasks = vec![task(
+            "1",
+            "a very long task name that definitely will not fit on one row",
+            Some("2024-01-10"),
+        )];
+        for gid in 2..=13 {
+            tasks.push(task(&gid.to_string(), "short task", Some("2024-01-10")));
+        }
+        let string = status(13, 0, false, false).to_xbar_string(
+            &grouped(&tasks),
+            &StatusSymbols::default(),
+            &crate::config::MenubarConfig::default(),
+            false,
+            &extras(None, None),
+        );
+        assert!(string.contains("a very long task name that definitely w… | href="));
+        // Ten task links; without a focus gid the footer adds no eleventh.
+        assert_eq!(string.matches("| href=").count(), 10);
+        assert!(string.contains("… and 3 more\n---\n"));
+    }
+
+    #[test]
+    fn xbar_output_mentions_due_this_week_only_when_there_are_any() {
+        let mut status = status(0, 0, false, false);
+        status.due_week = 2;
+        let string = status.to_xbar_string(
+            &grouped(&[]),
+            &StatusSymbols::default(),
+            &crate::config::MenubarConfig::default(),
+            false,
+            &extras(None, None),
+        );
+        assert!(string.contains("0 due today\n2 due this week\n"));
+
+        status.due_week = 0;
+        let string = status.to_xbar_string(
+            &grouped(&[]),
+            &StatusSymbols::default(),
+            &crate::config::MenubarConfig::default(),
+            false,
+            &extras(None, None),
+        );
+        assert!(!string.contains("due this week"));
+    }
+
+    #[test]
+    fn xbar_output_mentions_completions_only_when_there_are_any() {
+        let mut with_completions = status(0, 0, false, false);
+        with_completions.done_today = 3;
+        let string = with_completions.to_xbar_string(
+            &grouped(&[]),
+            &StatusSymbols::default(),
+            &crate::config::MenubarConfig::default(),
+            false,
+            &extras(None, None),
+        );
+        assert!(string.contains("3 completed today\n"));
+    }
+
+    #[test]
+    fn xbar_output_respects_ascii_only() {
+        let symbols = StatusSymbols::ascii();
+        let string = status(0, 0, true, false).to_xbar_string(
+            &grouped(&[]),
+            &symbols,
+            &crate::config::MenubarConfig::default(),
+            true,
+            &extras(None, None),
+        );
+        assert_eq!(
+            string,
+            "AM\n---\n0 overdue\n0 due today\n\
+             ---\n\
+             Last updated never | color=gray\n\
+             Run update now | shell=todo param1=update terminal=false refresh=true\n"
+        );
+        assert!(string.is_ascii());
+    }
+
+    #[test]
+    fn swiftbar_flavor_adds_sf_symbols_icons() {
+        let menubar = crate::config::MenubarConfig {
+            flavor: crate::config::MenubarFlavor::Swiftbar,
+        };
+        let string = status(2, 0, false, false).to_xbar_string(
+            &grouped(&[]),
+            &StatusSymbols::default(),
+            &menubar,
+            false,
+            &extras(None, None),
+        );
+        assert!(string.starts_with("!2 | sfimage=exclamationmark.circle\n"));
+
+        let string = status(0, 0, false, false).to_xbar_string(
+            &grouped(&[]),
+            &StatusSymbols::default(),
+            &menubar,
+            false,
+            &extras(None, None),
+        );
+        assert!(string.starts_with("\u{2713} | sfimage=checkmark.circle\n"));
+    }
+
+    #[test]
+    fn starship_output_is_plain_and_capped() {
+        let string = render_starship(&status(12_345_678, 12_345_678, true, true), &StatusSymbols::default());
+        assert!(string.chars().count() <= STARSHIP_MAX_WIDTH);
+        assert!(!string.contains('\x1b'));
+        assert!(string.starts_with("!12345678 +12345678"));
+    }
+
+    #[test]
+    fn waybar_output_maps_the_status_to_text_tooltip_and_class() {
+        let parsed: serde_json::Value =
+            serde_json::from_str(&render_waybar(&status(2, 1, true, false), &StatusSymbols::default()).unwrap())
+                .unwrap();
+        assert_eq!(parsed["text"], "!2 +1 focus:am");
+        assert_eq!(parsed["tooltip"], "2 overdue\n1 due today\nmorning focus pending");
+        assert_eq!(parsed["class"], "overdue");
+
+        let parsed: serde_json::Value =
+            serde_json::from_str(&render_waybar(&status(0, 0, true, true), &StatusSymbols::default()).unwrap())
+                .unwrap();
+        assert_eq!(parsed["class"], "pending");
+        assert_eq!(
+            parsed["tooltip"],
+            "0 overdue\n0 due today\nmorning focus pending\nevening focus pending"
+        );
+
+        let parsed: serde_json::Value =
+            serde_json::from_str(&render_waybar(&status(0, 0, false, false), &StatusSymbols::default()).unwrap())
+                .unwrap();
+        assert_eq!(parsed["class"], "ok");
+        assert_eq!(parsed["tooltip"], "0 overdue\n0 due today\nfocus done");
+    }
+
+    #[test]
+    fn waybar_all_clear_text_respects_ascii_only() {
+        let symbols = StatusSymbols::resolve(&crate::config::StatusConfig {
+            ascii_only: true,
+            ..crate::config::StatusConfig::default()
+        });
+        let parsed: serde_json::Value =
+            serde_json::from_str(&render_waybar(&status(0, 0, false, false), &symbols).unwrap())
+                .unwrap();
+        assert_eq!(parsed["text"], "OK");
+    }
+
+    #[test]
+    fn prometheus_output_follows_the_exposition_format() {
+        let output = render_prometheus(&status(2, 1, false, true), Some(94));
+        assert!(output.contains("todo_tasks_overdue 2\n"));
+        assert!(output.contains("todo_tasks_due_today 1\n"));
+        assert!(output.contains("todo_focus_morning_done 1\n"));
+        assert!(output.contains("todo_focus_evening_done 0\n"));
+        assert!(output.contains("todo_cache_age_seconds 94\n"));
+        assert!(output.ends_with('\n'));
+
+        // Every metric name shows up exactly once, with its HELP and TYPE lines right before
+        // the sample, and nothing carries ANSI codes.
+        let mut seen = std::collections::HashSet::new();
+        for chunk in output.split("# HELP ").skip(1) {
+            let name = chunk.split_whitespace().next().unwrap();
+            assert!(seen.insert(name), "duplicate metric {name}");
+            assert!(chunk.contains(&format!("# TYPE {name} gauge\n{name} ")));
+        }
+        assert!(!output.contains('\u{1b}'));
+    }
+
+    #[test]
+    fn prometheus_output_drops_the_cache_age_when_never_updated() {
+        let output = render_prometheus(&status(0, 0, true, false), None);
+        assert!(!output.contains("todo_cache_age_seconds"));
+        assert!(output.contains("todo_focus_morning_done 0\n"));
+    }
+
+    #[test]
+    fn outcome_reflects_overdue_and_focus_state() {
+        assert_eq!(status(0, 0, false, false).outcome().exit_code(), 0);
+        assert_eq!(status(1, 0, false, false).outcome().exit_code(), 2);
+        assert_eq!(status(0, 0, true, false).outcome().exit_code(), 4);
+        assert_eq!(status(1, 0, false, true).outcome().exit_code(), 6);
+    }
+}
@@ -0,0 +1,461 @@
+//! Implementation of the `summary` subcommand, which prints a one-line overview of tasks.
+
+use crate::context::{task_or_tasks, AppContext, GroupedTasks};
+use crate::render::Theme;
+
+/// Run the summary command: the one-line summary plus the task list permalink and, when
+/// offline, the cache's age, all through the context's output sink.
+///
+/// `focus_unknown` means the cache lacks today's focus day and the live fallback was skipped
+/// (disabled, offline, or `--use-cache`); instead of guessing at pending stats, a dim note
+/// flags that the focus status is stale.
+///
+/// # Errors
+///
+/// This function will return an error if the output sink could not be written to.
+pub fn run(
+    ctx: &mut AppContext,
+    grouped: &GroupedTasks<'_>,
+    pending_focus_subtasks: usize,
+    done_today: usize,
+    pending_stats: &[&str],
+    focus_unknown: bool,
+    offline: bool,
+) -> anyhow::Result<()> {
+    let greeting_name = if ctx.config.summary.personal_greeting {
+        ctx.cache
+            .user
+            .as_ref()
+            .and_then(|user| user.name.split_whitespace().next())
+    } else {
+        None
+    };
+    let string = render(
+        grouped,
+        ctx.config.summary.show_undated,
+        pending_focus_subtasks,
+        done_today,
+        pending_stats,
+        greeting_name,
+        &ctx.theme,
+    );
+    let line = match &ctx.cache.user_task_list {
+        Some(user_task_list) => format!(
+            "{string} {}",
+            ctx.theme.dim.apply_to(format!(
+                "(https://app.asana.com/0/{user_task_list_gid}/list)",
+                user_task_list_gid = user_task_list.gid
+            ))
+        ),
+        None => string,
+    };
+    ctx.writer.line(&line)?;
+
+    if focus_unknown {
+        ctx.writer.line(
+            &ctx.theme
+                .dim
+                .apply_to("focus status unknown (cache stale)")
+                .to_string(),
+        )?;
+    }
+    if offline {
+        if let Some(last_updated) = ctx.cache.last_updated {
+            let age_minutes = (ctx.now - last_updated).num_minutes();
+            ctx.writer.line(
+                &ctx.theme
+                    .dim
+                    .apply_to(format!("(offline: cache is {age_minutes} minutes old)"))
+                    .to_string(),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Render the one-line summary of the grouped tasks.
+///
+/// `pending_focus_subtasks` is the number of incomplete focus subtasks due today or overdue; it
+/// gets its own sentence since those live on the focus day rather than in the task list.
+/// `done_today` is the number of tasks completed since local midnight, mentioned for a sense of
+/// progress rather than another scolding. `pending_stats` names the focus stats still unfilled
+/// for the open routine windows; they are appended dimmed, capped at three names, so a glance
+/// shows whether the reflection is worth doing now. `greeting_name` personalizes the all-clear
+/// line when the user opted in and a profile is cached; `None` keeps the generic wording.
+#[must_use]
+pub fn render(
+    grouped: &GroupedTasks,
+    show_undated: bool,
+    pending_focus_subtasks: usize,
+    done_today: usize,
+    pending_stats: &[&str],
+    greeting_name: Option<&str>,
+    theme: &Theme,
+) -> String {
+    let mut string = String::new();
+    string.push_str(&match (grouped.overdue.len(), grouped.due_today.len()) {
+        (0, 0) => theme
+            .ok
+            .clone()
+            .bold()
+            .apply_to(greeting_name.map_or_else(
+                || "Nice! Everything done for now!".to_string(),
+                |name| format!("Nice work, {name} — everything done!"),
+            ))
+            .to_string(),
+        (o, 0) => theme
+            .overdue
+            .clone()
+            .bold()
+            .apply_to(format!("You have {} overdue.", task_or_tasks(o)))
+            .to_string(),
+        (0, t) => theme
+            .due_today
+            .clone()
+            .bold()
+            .apply_to(format!("You have {} due today.", task_or_tasks(t)))
+            .to_string(),
+        (o, t) => theme
+            .overdue
+            .clone()
+            .bold()
+            .apply_to(format!(
+                "You have {} overdue or due today",
+                task_or_tasks(o + t)
+            ))
+            .to_string(),
+    });
+
+    string.push_str(&match grouped.due_week.len() {
+        0 => String::new(),
+        w => theme
+            .due_week
+            .apply_to(format!(
+                " You have another {} due within a week.",
+                task_or_tasks(w)
+            ))
+            .to_string(),
+    });
+
+    if done_today > 0 {
+        string.push_str(
+            &theme
+                .ok
+                .apply_to(format!(
+                    " You completed {} today.",
+                    task_or_tasks(done_today)
+                ))
+                .to_string(),
+        );
+    }
+
+    if pending_focus_subtasks > 0 {
+        string.push_str(
+            &theme
+                .pending
+                .apply_to(format!(
+                    " You have {} on today's focus list.",
+                    task_or_tasks(pending_focus_subtasks)
+                ))
+                .to_string(),
+        );
+    }
+
+    if !pending_stats.is_empty() {
+        use std::fmt::Write as _;
+
+        let mut names = pending_stats.iter().take(3).copied().collect::<Vec<_>>().join(", ");
+        if pending_stats.len() > 3 {
+            let _ = write!(names, " +{} more", pending_stats.len() - 3);
+        }
+        string.push_str(
+            &theme
+                .dim
+                .apply_to(format!(" (missing: {names})"))
+                .to_string(),
+        );
+    }
+
+    if show_undated && !grouped.no_due_date.is_empty() {
+        string.push_str(
+            &theme
+                .dim
+                .apply_to(format!(
+                    " You have {} with no due date.",
+                    task_or_tasks(grouped.no_due_date.len())
+                ))
+                .to_string(),
+        );
+    }
+
+    string
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::cache::Cache;
+    use crate::config::Config;
+    use crate::context::BufferOutput;
+    use crate::task::{UserTask, UserTaskList};
+
+    use super::*;
+
+    fn context(cache: Cache, buffer: &BufferOutput) -> AppContext {
+        AppContext::new_with_now(
+            Config::default(),
+            cache,
+            Box::new(buffer.clone()),
+            chrono::Local::now(),
+        )
+    }
+
+    fn task(gid: &str, due_on: Option<&str>) -> UserTask {
+        UserTask {
+            gid: gid.to_string(),
+            created_at: "2024-01-01T00:00:00Z".parse().unwrap(),
+            due_on: due_on.map(|d| d.parse().unwrap()),
+            name: format!("task {gid}"),
+            projects: Vec::new(),
+            custom_fields: Vec::new(),
+        }
+    }
+
+    fn render_for(tasks: &[UserTask], show_undated: bool) -> String {
+        console::set_colors_enabled(false);
+        let today = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        render(
+            &GroupedTasks::group(tasks, today),
+            show_undated,
+            0,
+            0,
+            &[],
+            None,
+            &Theme::default(),
+        )
+    }
+
+    /// A theme whose styles emit escapes regardless of the (test-global) color switches, so the
+    /// assertions can see the configured codes.
+    fn forced(colors: &crate::config::ColorsConfig) -> Theme {
+        let theme = Theme::resolve(colors);
+        Theme {
+            overdue: theme.overdue.force_styling(true),
+            due_today: theme.due_today.force_styling(true),
+            due_week: theme.due_week.force_styling(true),
+            ok: theme.ok.force_styling(true),
+            pending: theme.pending.force_styling(true),
+            dim: theme.dim.force_styling(true),
+        }
+    }
+
+    #[test]
+    fn all_clear_when_nothing_is_due() {
+        assert_eq!(render_for(&[], false), "Nice! Everything done for now!");
+    }
+
+    #[test]
+    fn mentions_overdue_and_upcoming_tasks() {
+        let tasks = vec![
+            task("1", Some("2024-01-10")),
+            task("2", Some("2024-01-10")),
+            task("3", Some("2024-01-18")),
+        ];
+        assert_eq!(
+            render_for(&tasks, false),
+            "You have 2 tasks overdue. You have another 1 task due within a week."
+        );
+    }
+
+    #[test]
+    fn mentions_pending_focus_subtasks() {
+        console::set_colors_enabled(false);
+        let today = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        assert_eq!(
+            render(&GroupedTasks::group(&[], today), false, 2, 0, &[], None, &Theme::default()),
+            "Nice! Everything done for now! You have 2 tasks on today's focus list."
+        );
+    }
+
+    #[test]
+    fn pending_stats_are_capped_at_three_names() {
+        console::set_colors_enabled(false);
+        let today = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let grouped = GroupedTasks::group(&[], today);
+        assert_eq!(
+            render(&grouped, false, 0, 0, &["hydration", "stress"], None, &Theme::default()),
+            "Nice! Everything done for now! (missing: hydration, stress)"
+        );
+        assert_eq!(
+            render(
+                &grouped,
+                false,
+                0,
+                0,
+                &["flow", "hydration", "health", "satisfaction", "stress"],
+                None,
+                &Theme::default(),
+            ),
+            "Nice! Everything done for now! (missing: flow, hydration, health +2 more)"
+        );
+    }
+
+    #[test]
+    fn the_all_clear_greets_by_first_name_when_opted_in() {
+        console::set_colors_enabled(false);
+        let today = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let grouped = GroupedTasks::group(&[], today);
+        assert_eq!(
+            render(&grouped, false, 0, 0, &[], Some("Ziyad"), &Theme::default()),
+            "Nice work, Ziyad — everything done!"
+        );
+        // A pending task list keeps its usual wording; only the all-clear is personalized.
+        let tasks = vec![task("1", Some("2024-01-10"))];
+        assert_eq!(
+            render(
+                &GroupedTasks::group(&tasks, today),
+                false,
+                0,
+                0,
+                &[],
+                Some("Ziyad"),
+                &Theme::default(),
+            ),
+            "You have 1 task overdue."
+        );
+    }
+
+    #[test]
+    fn run_uses_the_cached_profile_for_the_greeting() {
+        console::set_colors_enabled(false);
+        let buffer = BufferOutput::default();
+        let cache = Cache {
+            user: Some(crate::task::User {
+                gid: "42".to_string(),
+                name: "Ziyad Edher".to_string(),
+                email: None,
+                photo: None,
+            }),
+            ..Cache::default()
+        };
+        let mut ctx = context(cache, &buffer);
+        ctx.config.summary.personal_greeting = true;
+
+        let today = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        run(&mut ctx, &GroupedTasks::group(&[], today), 0, 0, &[], false, false).unwrap();
+
+        assert_eq!(buffer.lines(), ["Nice work, Ziyad — everything done!"]);
+    }
+
+    #[test]
+    fn mentions_completed_tasks_for_a_sense_of_progress() {
+        console::set_colors_enabled(false);
+        let today = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        assert_eq!(
+            render(&GroupedTasks::group(&[], today), false, 0, 4, &[], None, &Theme::default()),
+            "Nice! Everything done for now! You completed 4 tasks today."
+        );
+        assert_eq!(
+            render(&GroupedTasks::group(&[], today), false, 0, 0, &[], None, &Theme::default()),
+            "Nice! Everything done for now!"
+        );
+    }
+
+    #[test]
+    fn mentions_undated_tasks_only_when_asked() {
+        let tasks = vec![task("1", None)];
+        assert_eq!(render_for(&tasks, false), "Nice! Everything done for now!");
+        assert_eq!(
+            render_for(&tasks, true),
+            "Nice! Everything done for now! You have 1 task with no due date."
+        );
+    }
+
+    #[test]
+    fn a_configured_theme_changes_the_emitted_escape_codes() {
+        let tasks = vec![task("1", Some("2024-01-10"))];
+        let today = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let grouped = GroupedTasks::group(&tasks, today);
+
+        let default = render(&grouped, false, 0, 0, &[], None, &forced(&crate::config::ColorsConfig::default()));
+        assert!(default.contains("\x1b[31m"), "{default:?}");
+
+        let remapped = render(
+            &grouped,
+            false,
+            0,
+            0,
+            &[],
+            None,
+            &forced(&crate::config::ColorsConfig {
+                overdue: Some("208".to_string()),
+                ..crate::config::ColorsConfig::default()
+            }),
+        );
+        assert!(remapped.contains("\x1b[38;5;208m"), "{remapped:?}");
+        assert!(!remapped.contains("\x1b[31m"), "{remapped:?}");
+    }
+
+    #[test]
+    fn run_writes_the_summary_and_permalink_through_the_sink() {
+        console::set_colors_enabled(false);
+        let buffer = BufferOutput::default();
+        let cache = Cache {
+            user_task_list: Some(UserTaskList {
+                gid: "utl1".to_string(),
+            }),
+            ..Cache::default()
+        };
+        let mut ctx = context(cache, &buffer);
+
+        let tasks = vec![task("1", Some("2024-01-10"))];
+        let today = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        run(&mut ctx, &GroupedTasks::group(&tasks, today), 0, 0, &[], false, false).unwrap();
+
+        assert_eq!(
+            buffer.lines(),
+            ["You have 1 task overdue. (https://app.asana.com/0/utl1/list)"]
+        );
+    }
+
+    #[test]
+    fn run_mentions_the_cache_age_when_offline() {
+        console::set_colors_enabled(false);
+        let buffer = BufferOutput::default();
+        let cache = Cache {
+            last_updated: Some(chrono::Local::now()),
+            ..Cache::default()
+        };
+        let mut ctx = context(cache, &buffer);
+
+        let today = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        run(&mut ctx, &GroupedTasks::group(&[], today), 0, 0, &[], false, true).unwrap();
+
+        let lines = buffer.lines();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], "Nice! Everything done for now!");
+        assert_eq!(lines[1], "(offline: cache is 0 minutes old)");
+    }
+
+    #[test]
+    fn a_skipped_focus_fallback_is_flagged_instead_of_guessed_at() {
+        console::set_colors_enabled(false);
+        let buffer = BufferOutput::default();
+        let mut ctx = context(Cache::default(), &buffer);
+
+        let today = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        run(&mut ctx, &GroupedTasks::group(&[], today), 0, 0, &[], true, false).unwrap();
+
+        let lines = buffer.lines();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], "Nice! Everything done for now!");
+        assert_eq!(lines[1], "focus status unknown (cache stale)");
+
+        // With the focus state known, the note stays out of the way.
+        let buffer = BufferOutput::default();
+        let mut ctx = context(Cache::default(), &buffer);
+        run(&mut ctx, &GroupedTasks::group(&[], today), 0, 0, &[], false, false).unwrap();
+        assert_eq!(buffer.lines(), ["Nice! Everything done for now!"]);
+    }
+}
@@ -0,0 +1,78 @@
+//! Bookkeeping for the `triage` subcommand's interactive sweep over overdue tasks.
+
+use chrono::NaiveDate;
+
+/// What triage did with one task.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TriageAction {
+    /// The task was completed.
+    Completed,
+    /// The task was rescheduled to the given date.
+    Rescheduled(NaiveDate),
+    /// The task was left untouched.
+    Skipped,
+}
+
+/// Tally of the actions taken across one triage session.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TriageSummary {
+    /// Number of tasks completed.
+    pub completed: usize,
+    /// Number of tasks rescheduled.
+    pub rescheduled: usize,
+    /// Number of tasks skipped.
+    pub skipped: usize,
+}
+
+impl TriageSummary {
+    /// Record one action in the tally.
+    pub fn record(&mut self, action: TriageAction) {
+        match action {
+            TriageAction::Completed => self.completed += 1,
+            TriageAction::Rescheduled(_) => self.rescheduled += 1,
+            TriageAction::Skipped => self.skipped += 1,
+        }
+    }
+
+    /// Render the end-of-session summary line.
+    #[must_use]
+    pub fn render(&self) -> String {
+        format!(
+            "triaged {total}: {completed} completed, {rescheduled} rescheduled, {skipped} skipped",
+            total = crate::context::task_or_tasks(self.completed + self.rescheduled + self.skipped),
+            completed = self.completed,
+            rescheduled = self.rescheduled,
+            skipped = self.skipped
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_tallies_actions() {
+        let mut summary = TriageSummary::default();
+        summary.record(TriageAction::Completed);
+        summary.record(TriageAction::Completed);
+        summary.record(TriageAction::Rescheduled(
+            NaiveDate::from_ymd_opt(2024, 1, 16).unwrap(),
+        ));
+        summary.record(TriageAction::Skipped);
+        assert_eq!(summary.completed, 2);
+        assert_eq!(summary.rescheduled, 1);
+        assert_eq!(summary.skipped, 1);
+    }
+
+    #[test]
+    fn summary_renders_one_line() {
+        let mut summary = TriageSummary::default();
+        summary.record(TriageAction::Completed);
+        summary.record(TriageAction::Skipped);
+        assert_eq!(
+            summary.render(),
+            "triaged 2 tasks: 1 completed, 0 rescheduled, 1 skipped"
+        );
+    }
+}
@@ -0,0 +1,317 @@
+//! Scheduling and fetch helpers for the `update` subcommand.
+
+use std::future::Future;
+use std::time::Duration;
+
+use anyhow::Context;
+
+use serde::Serialize;
+
+use crate::asana::Client;
+use crate::task::{TaskDiff, UserTask, UserTaskList};
+
+/// How many times the interval is allowed to double before the backoff is capped.
+const MAX_BACKOFF_DOUBLINGS: u32 = 4;
+
+/// Output format for the `update` subcommand's refresh summary.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum UpdateFormat {
+    /// Compact single-line summary of what the refresh changed.
+    #[default]
+    Short,
+    /// Flat JSON object, for the watch daemon's logs.
+    Json,
+}
+
+/// What a refresh changed: the task-list diff plus whether the focus day moved.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct UpdateReport {
+    /// How the fetched task list differs from the cached one.
+    #[serde(flatten)]
+    pub tasks: TaskDiff,
+    /// Whether the focus day's stats or diary changed, by the same equality the daily-note
+    /// sync uses to decide whether a rewrite is worthwhile.
+    pub focus_changed: bool,
+}
+
+impl UpdateReport {
+    /// Render the compact summary line, e.g. `update: +2 new, 3 completed/removed, 1 due date
+    /// changed, focus day updated` — or `update: no changes` when the refresh was a no-op.
+    #[must_use]
+    pub fn render(&self) -> String {
+        let mut parts = Vec::new();
+        if self.tasks.added > 0 {
+            parts.push(format!("+{} new", self.tasks.added));
+        }
+        if self.tasks.removed > 0 {
+            parts.push(format!("{} completed/removed", self.tasks.removed));
+        }
+        if self.tasks.renamed > 0 {
+            parts.push(format!("{} renamed", self.tasks.renamed));
+        }
+        match self.tasks.due_changed {
+            0 => {}
+            1 => parts.push("1 due date changed".to_string()),
+            n => parts.push(format!("{n} due dates changed")),
+        }
+        if self.focus_changed {
+            parts.push("focus day updated".to_string());
+        }
+        if parts.is_empty() {
+            "update: no changes".to_string()
+        } else {
+            format!("update: {}", parts.join(", "))
+        }
+    }
+}
+
+/// Fetch the user's tasks, lazily re-resolving a stale user task list gid.
+///
+/// The gid essentially never changes, so callers keep the cached one without any TTL instead of
+/// paying a resolution round trip on every run. When the fetch 404s against it — the workspace
+/// changed under us — the gid is re-resolved once and the fetch retried transparently. The list
+/// actually used is returned alongside the tasks so callers can write a corrected gid back to
+/// the cache.
+///
+/// # Errors
+///
+/// This function will return an error if the fetch fails for any other reason, or if the
+/// re-resolved gid still cannot be fetched.
+pub async fn fetch_tasks(
+    client: &mut Client,
+    user_task_list: UserTaskList,
+    workspace_gid: &str,
+) -> anyhow::Result<(Vec<UserTask>, UserTaskList)> {
+    match client.get::<UserTask>(&user_task_list.gid).await {
+        Ok(tasks) => Ok((tasks, user_task_list)),
+        Err(error) if crate::asana::is_not_found(&error) => {
+            tracing::warn!(
+                "User task list {gid} no longer exists, re-resolving it...",
+                gid = user_task_list.gid
+            );
+            let request = ("me".to_string(), workspace_gid.to_string());
+            let user_task_list = client.get::<UserTaskList>(&request).await?;
+            let tasks = client.get::<UserTask>(&user_task_list.gid).await?;
+            Ok((tasks, user_task_list))
+        }
+        Err(error) => Err(error),
+    }
+}
+
+/// Await the task fetch and the focus fetch concurrently.
+///
+/// The two halves are independent, so the update's wall-clock time drops to whichever half is
+/// slower instead of their sum. Each error is labeled with the half it came from, so watch-mode
+/// failure logs stay diagnosable.
+///
+/// # Errors
+///
+/// This function will return an error if either fetch fails; when both fail, the task fetch's
+/// error wins.
+pub async fn join_fetches<T, F>(
+    task_fetch: impl Future<Output = anyhow::Result<T>>,
+    focus_fetch: impl Future<Output = anyhow::Result<F>>,
+) -> anyhow::Result<(T, F)> {
+    let (tasks, focus) = tokio::join!(task_fetch, focus_fetch);
+    Ok((
+        tasks.context("could not refresh the task list")?,
+        focus.context("could not refresh the focus day")?,
+    ))
+}
+
+/// The delay before the next refresh cycle, doubling per consecutive failure.
+///
+/// The backoff is capped at sixteen times the base interval so a long outage never pushes
+/// refreshes out indefinitely.
+#[must_use]
+pub fn backoff_interval(base: Duration, consecutive_failures: u32) -> Duration {
+    base * 2_u32.pow(consecutive_failures.min(MAX_BACKOFF_DOUBLINGS))
+}
+
+/// Spread the interval by up to ±10%, so a fleet of machines started together does not keep
+/// hitting the API in lockstep.
+///
+/// The offset is derived from the seed instead of a random source, which keeps the function
+/// deterministic; callers pass something that varies per cycle, like the clock's nanoseconds.
+#[must_use]
+pub fn jittered_interval(interval: Duration, seed: u64) -> Duration {
+    let spread_millis = u64::try_from(interval.as_millis() / 10).unwrap_or(u64::MAX);
+    if spread_millis == 0 {
+        return interval;
+    }
+    let offset_millis = seed % (2 * spread_millis + 1);
+    interval.saturating_sub(Duration::from_millis(spread_millis))
+        + Duration::from_millis(offset_millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::asana::{Client, Credentials, DataRequest};
+
+    use super::*;
+
+    /// Trivial request against the mock server below; the response body is always `{"data":[]}`.
+    struct Ping;
+
+    impl DataRequest<'_> for Ping {
+        type RequestData = ();
+        type ResponseData = Vec<String>;
+
+        fn segments((): &Self::RequestData) -> Vec<String> {
+            vec!["ping".to_string()]
+        }
+
+        fn fields() -> &'static [&'static str] {
+            &["gid"]
+        }
+    }
+
+    /// Serve an empty data response to every request after `latency`, returning the base URL.
+    async fn mock_server(latency: Duration) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    break;
+                };
+                tokio::spawn(async move {
+                    use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+                    let mut buffer = [0_u8; 4096];
+                    let _ = socket.read(&mut buffer).await;
+                    tokio::time::sleep(latency).await;
+                    let body = r#"{"data":[]}"#;
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        format!("http://{addr}/")
+    }
+
+    #[tokio::test]
+    async fn concurrent_fetches_beat_sequential_against_a_slow_server() {
+        let latency = Duration::from_millis(150);
+        let base_url = mock_server(latency).await;
+        let client = Client::new_with_base_url(
+            Credentials::PersonalAccessToken("test-token".to_string()),
+            base_url.parse().unwrap(),
+        )
+        .unwrap();
+
+        let started = std::time::Instant::now();
+        client.clone().get::<Ping>(&()).await.unwrap();
+        client.clone().get::<Ping>(&()).await.unwrap();
+        let sequential = started.elapsed();
+
+        let started = std::time::Instant::now();
+        let mut first = client.clone();
+        let mut second = client;
+        join_fetches(first.get::<Ping>(&()), second.get::<Ping>(&()))
+            .await
+            .unwrap();
+        let concurrent = started.elapsed();
+
+        assert!(sequential >= latency * 2, "sequential took {sequential:?}");
+        assert!(concurrent < latency * 2, "concurrent took {concurrent:?}");
+        assert!(concurrent < sequential);
+    }
+
+    #[tokio::test]
+    async fn join_fetches_labels_the_failing_half() {
+        let error = join_fetches(async { Ok(()) }, async {
+            Err::<(), _>(anyhow::anyhow!("boom"))
+        })
+        .await
+        .unwrap_err();
+        assert!(format!("{error:#}").contains("could not refresh the focus day"));
+
+        let error = join_fetches(
+            async { Err::<(), _>(anyhow::anyhow!("boom")) },
+            async { Ok(()) },
+        )
+        .await
+        .unwrap_err();
+        assert!(format!("{error:#}").contains("could not refresh the task list"));
+    }
+
+    #[test]
+    fn the_update_summary_reads_naturally() {
+        let report = UpdateReport {
+            tasks: TaskDiff {
+                added: 2,
+                removed: 3,
+                renamed: 0,
+                due_changed: 1,
+            },
+            focus_changed: true,
+        };
+        assert_eq!(
+            report.render(),
+            "update: +2 new, 3 completed/removed, 1 due date changed, focus day updated"
+        );
+
+        let report = UpdateReport {
+            tasks: TaskDiff {
+                renamed: 1,
+                due_changed: 2,
+                ..TaskDiff::default()
+            },
+            focus_changed: false,
+        };
+        assert_eq!(report.render(), "update: 1 renamed, 2 due dates changed");
+
+        let report = UpdateReport {
+            tasks: TaskDiff::default(),
+            focus_changed: false,
+        };
+        assert_eq!(report.render(), "update: no changes");
+    }
+
+    #[test]
+    fn the_json_report_flattens_the_task_diff() {
+        let report = UpdateReport {
+            tasks: TaskDiff {
+                added: 1,
+                ..TaskDiff::default()
+            },
+            focus_changed: false,
+        };
+        assert_eq!(
+            serde_json::to_string(&report).unwrap(),
+            r#"{"added":1,"removed":0,"renamed":0,"due_changed":0,"focus_changed":false}"#
+        );
+    }
+
+    #[test]
+    fn backoff_doubles_per_failure_and_caps() {
+        let base = Duration::from_secs(90);
+        assert_eq!(backoff_interval(base, 0), base);
+        assert_eq!(backoff_interval(base, 1), base * 2);
+        assert_eq!(backoff_interval(base, 3), base * 8);
+        assert_eq!(backoff_interval(base, 4), base * 16);
+        assert_eq!(backoff_interval(base, 30), base * 16);
+    }
+
+    #[test]
+    fn jitter_stays_within_ten_percent_and_is_deterministic() {
+        let interval = Duration::from_secs(90);
+        let spread = interval / 10;
+        for seed in [0, 1, 12, 999, u64::MAX] {
+            let jittered = jittered_interval(interval, seed);
+            assert!(jittered + spread >= interval);
+            assert!(jittered <= interval + spread);
+            assert_eq!(jittered, jittered_interval(interval, seed));
+        }
+        assert_eq!(jittered_interval(interval, 0) + spread, interval);
+    }
+
+    #[test]
+    fn sub_second_intervals_are_left_alone() {
+        let interval = Duration::from_millis(5);
+        assert_eq!(jittered_interval(interval, 42), interval);
+    }
+}
@@ -0,0 +1,915 @@
+//! Configuration for the command line tool.
+
+use std::{fs, path::Path};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+/// Top-level configuration for the command line tool.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    /// Configuration for which Asana workspace and projects to use.
+    pub asana: AsanaConfig,
+    /// Configuration for general command behavior.
+    pub behavior: BehaviorConfig,
+    /// Colors for the semantic roles used across human-readable output.
+    pub colors: ColorsConfig,
+    /// Date and time formats used across human-readable output.
+    pub display: DisplayConfig,
+    /// Configuration for the focus command.
+    pub focus: FocusConfig,
+    /// Configuration for integrations with external tools.
+    pub integrations: IntegrationsConfig,
+    /// Configuration for the list command.
+    pub list: ListConfig,
+    /// Configuration for menu bar (xbar/SwiftBar) output.
+    pub menubar: MenubarConfig,
+    /// Configuration for desktop notifications.
+    pub notify: NotifyConfig,
+    /// Configuration for the status command.
+    pub status: StatusConfig,
+    /// Configuration for the summary command.
+    pub summary: SummaryConfig,
+    /// Configuration for terminal integration.
+    pub terminal: TerminalConfig,
+}
+
+/// Configuration for terminal integration.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TerminalConfig {
+    /// If set, the gate command blocks new shells while a focus routine is pending, until it is
+    /// done or acknowledged for the day. Off by default.
+    pub blocking: bool,
+}
+
+/// Configuration for which Asana workspace and projects to use.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AsanaConfig {
+    /// Gid of the workspace tasks are pulled from; falls back to the built-in default when
+    /// unset. Set interactively by `todo init`.
+    pub workspace_gid: Option<String>,
+    /// Gid of the project holding daily focus tasks; falls back to the built-in default when
+    /// unset. Set interactively by `todo init`.
+    pub focus_project_gid: Option<String>,
+    /// Connection timeout for Asana requests, in seconds; 5 by default.
+    pub connect_timeout_seconds: Option<u64>,
+    /// Overall per-request timeout for Asana requests, in seconds; 10 by default.
+    pub request_timeout_seconds: Option<u64>,
+    /// User agent sent with Asana requests; `todo/<crate version>` by default.
+    pub user_agent: Option<String>,
+    /// HTTP(S) proxy URL to route Asana requests through; none by default.
+    pub proxy_url: Option<String>,
+}
+
+/// Configuration for menu bar (xbar/SwiftBar) output.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MenubarConfig {
+    /// Which menu bar app the output targets; `SwiftBar` gets extra params like `sfimage` icons.
+    pub flavor: MenubarFlavor,
+}
+
+/// Menu bar apps the xbar-style output can target.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MenubarFlavor {
+    /// Plain xbar plugin output.
+    #[default]
+    Xbar,
+    /// `SwiftBar` output, with SF Symbols icons on the menu bar line.
+    Swiftbar,
+}
+
+/// Configuration for desktop notifications.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct NotifyConfig {
+    /// Minimum minutes between update notifications of the same category (new tasks, newly
+    /// overdue), so a flapping connection cannot spam the desktop; 30 by default.
+    pub update_min_gap_minutes: u64,
+}
+
+impl Default for NotifyConfig {
+    fn default() -> Self {
+        Self {
+            update_min_gap_minutes: 30,
+        }
+    }
+}
+
+impl NotifyConfig {
+    /// The minimum gap between update notifications of the same category.
+    #[must_use]
+    pub fn update_min_gap(&self) -> chrono::Duration {
+        // `Duration::minutes` panics out of range, so absurd configured values clamp to the
+        // largest representable gap instead.
+        const MAX_MINUTES: i64 = i64::MAX / 60_000;
+        let minutes = i64::try_from(self.update_min_gap_minutes).unwrap_or(MAX_MINUTES);
+        chrono::Duration::minutes(minutes.min(MAX_MINUTES))
+    }
+}
+
+/// Configuration for the status command.
+// Each toggle is an independent on/off switch in the configuration file, not hidden state.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct StatusConfig {
+    /// If set, status output swaps emoji and special symbols for ASCII equivalents.
+    pub ascii_only: bool,
+    /// Prefix for the overdue count in the short status string, `!` by default.
+    pub overdue_prefix: Option<String>,
+    /// Prefix for the due-today count in the short status string, `+` by default.
+    pub due_today_prefix: Option<String>,
+    /// Symbol shown while the morning focus routine is pending, `focus:am` by default.
+    pub morning_pending: Option<String>,
+    /// Symbol shown while the evening focus routine is pending, `focus:pm` by default.
+    pub evening_pending: Option<String>,
+    /// Symbol shown when nothing is overdue, due, or pending.
+    pub all_clear: Option<String>,
+    /// If set, the short status string is truncated to this many characters with an ellipsis.
+    pub max_width: Option<usize>,
+    /// If set, the short status string omits the due-today count.
+    pub hide_due_today: bool,
+    /// If set, the short status string includes a count of pending focus subtasks, e.g. `f:2`.
+    pub show_focus_subtasks: bool,
+    /// If set, the short status string includes a dimmed count of tasks due within the next
+    /// week, e.g. `~3`.
+    pub show_due_week: bool,
+    /// Wall-clock budget in milliseconds that the status and count commands may spend on
+    /// network fallbacks before degrading to cached data; 800 by default.
+    pub max_wait_ms: Option<u64>,
+}
+
+impl StatusConfig {
+    /// The network budget for the prompt-critical commands, with the CLI override taking
+    /// precedence over the configured value.
+    #[must_use]
+    pub fn max_wait(&self, override_ms: Option<u64>) -> std::time::Duration {
+        /// Well under the default connect timeout, but enough for one quick round trip.
+        const DEFAULT_MAX_WAIT_MS: u64 = 800;
+        std::time::Duration::from_millis(
+            override_ms
+                .or(self.max_wait_ms)
+                .unwrap_or(DEFAULT_MAX_WAIT_MS),
+        )
+    }
+}
+
+/// Configuration for general command behavior.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BehaviorConfig {
+    /// If set, commands encode state in their exit code as if `--exit-code` were passed. Off by
+    /// default so cron jobs that treat non-zero exits as failures keep working.
+    pub exit_codes: bool,
+    /// If set, unrecognized keys in the configuration file are a hard error instead of a
+    /// warning, as if `--strict-config` were passed.
+    pub strict_config: bool,
+    /// Command that runs when `todo` is invoked with no subcommand.
+    pub default_command: DefaultCommand,
+    /// How often scheduled updates (e.g. the systemd timer) refresh the cache, in minutes.
+    pub update_interval_minutes: u64,
+}
+
+impl Default for BehaviorConfig {
+    fn default() -> Self {
+        Self {
+            exit_codes: false,
+            strict_config: false,
+            default_command: DefaultCommand::default(),
+            update_interval_minutes: 3,
+        }
+    }
+}
+
+/// Colors for the semantic roles used across human-readable output.
+///
+/// Each entry is a basic color name (`red`, `green`, ...) or a 256-color index (`0` through
+/// `255`); roles left unset keep the built-in scheme. Resolved once into a
+/// [`Theme`](crate::render::Theme) at startup, so renderers never consult this directly.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ColorsConfig {
+    /// Color for overdue counts, dates, and messages; red by default.
+    pub overdue: Option<String>,
+    /// Color for due-today counts, dates, and messages; yellow by default.
+    pub due_today: Option<String>,
+    /// Color for due-within-a-week counts and dates; blue by default.
+    pub due_week: Option<String>,
+    /// Color for all-clear and progress messages; green by default.
+    pub ok: Option<String>,
+    /// Color for pending focus work; magenta by default.
+    pub pending: Option<String>,
+    /// Color for de-emphasized text like permalinks and undated counts; the terminal's dim
+    /// attribute by default.
+    pub dim: Option<String>,
+}
+
+#[cfg(feature = "cli")]
+impl ColorsConfig {
+    /// Validate every configured color, so a typo fails loading instead of being silently
+    /// rendered as the default.
+    fn validate(&self) -> anyhow::Result<()> {
+        for (role, value) in [
+            ("overdue", &self.overdue),
+            ("due_today", &self.due_today),
+            ("due_week", &self.due_week),
+            ("ok", &self.ok),
+            ("pending", &self.pending),
+            ("dim", &self.dim),
+        ] {
+            if let Some(value) = value {
+                crate::render::parse_color(value)
+                    .with_context(|| format!("invalid color for `colors.{role}`"))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Date and time formats used across human-readable output.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DisplayConfig {
+    /// strftime-style format for dates in human-readable output, e.g. `%d/%m/%Y`. Each view
+    /// keeps its current format (ISO dates in most places) when unset.
+    pub date_format: Option<String>,
+    /// strftime-style format for times of day in human-readable output; `%H:%M` when unset.
+    pub time_format: Option<String>,
+}
+
+impl DisplayConfig {
+    /// Validate the configured format strings by rendering a probe date, so a bad specifier
+    /// fails loading instead of garbling every view.
+    fn validate(&self) -> anyhow::Result<()> {
+        use std::fmt::Write as _;
+
+        let probe = chrono::NaiveDate::from_ymd_opt(2024, 1, 15)
+            .unwrap_or_default()
+            .and_hms_opt(12, 0, 0)
+            .unwrap_or_default();
+        for (key, value) in [
+            ("date_format", &self.date_format),
+            ("time_format", &self.time_format),
+        ] {
+            if let Some(format) = value {
+                let mut rendered = String::new();
+                write!(rendered, "{}", probe.format(format)).map_err(|_| {
+                    anyhow::anyhow!(
+                        "invalid strftime format `{format}` for `display.{key}`"
+                    )
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Configuration for the focus command.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FocusConfig {
+    /// If set, the focus run prints a compact diff of the stat and diary changes and asks for
+    /// confirmation before syncing them. A cleared diary always asks, even when unset.
+    pub confirm_sync: bool,
+    /// Days of the week the focus routine runs on, as three-letter names (`"mon"` through
+    /// `"sun"`); all seven by default. Excluded days count as fully done in status, summary,
+    /// gate, and notification output, and are never auto-created by `todo update`.
+    pub days: Vec<String>,
+    /// Custom short-answer prompts the focus run asks beyond the numeric stats, as
+    /// `[[focus.prompts]]` entries. Answers are stored in a marker-delimited block of the day
+    /// task's notes, alongside the diary.
+    pub prompts: Vec<FocusPromptConfig>,
+}
+
+impl Default for FocusConfig {
+    fn default() -> Self {
+        Self {
+            confirm_sync: false,
+            days: ["mon", "tue", "wed", "thu", "fri", "sat", "sun"]
+                .map(str::to_string)
+                .to_vec(),
+            prompts: Vec::new(),
+        }
+    }
+}
+
+/// A single custom prompt asked during the focus run.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct FocusPromptConfig {
+    /// Stable key the answer is stored under in the task notes; changing it makes previous
+    /// answers invisible to the run (they stay in the notes).
+    pub key: String,
+    /// The question asked during the run.
+    pub question: String,
+    /// Phase of the run that asks the question (`"morning"` or `"evening"`). Like the stats,
+    /// morning prompts stay pending into the evening, while evening ones only come up once the
+    /// reflection window opens.
+    pub phase: crate::focus::FocusPhase,
+}
+
+impl FocusConfig {
+    /// Whether the focus routine is scheduled to run on the given date.
+    #[must_use]
+    pub fn is_scheduled(&self, date: chrono::NaiveDate) -> bool {
+        use chrono::Datelike;
+        let name = match date.weekday() {
+            chrono::Weekday::Mon => "mon",
+            chrono::Weekday::Tue => "tue",
+            chrono::Weekday::Wed => "wed",
+            chrono::Weekday::Thu => "thu",
+            chrono::Weekday::Fri => "fri",
+            chrono::Weekday::Sat => "sat",
+            chrono::Weekday::Sun => "sun",
+        };
+        self.days.iter().any(|day| day.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Commands that can run when `todo` is invoked with no subcommand.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DefaultCommand {
+    /// The summary command.
+    #[default]
+    Summary,
+    /// The list command.
+    List,
+    /// The status command.
+    Status,
+    /// The focus command.
+    Focus,
+}
+
+/// Configuration for integrations with external tools.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct IntegrationsConfig {
+    /// Configuration for mirroring the focus diary into a markdown daily note.
+    pub daily_note: DailyNoteConfig,
+}
+
+/// Configuration for mirroring the focus diary into a markdown (e.g. Obsidian) daily note.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DailyNoteConfig {
+    /// Directory holding the daily notes; the integration is off while unset.
+    pub directory: Option<String>,
+    /// strftime-style template for the note's filename.
+    pub filename: String,
+    /// Heading the managed focus block lives under.
+    pub heading: String,
+}
+
+impl Default for DailyNoteConfig {
+    fn default() -> Self {
+        Self {
+            directory: None,
+            filename: "%Y-%m-%d.md".to_string(),
+            heading: "## Focus".to_string(),
+        }
+    }
+}
+
+/// Configuration for the list command.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ListConfig {
+    /// If set, due dates are rendered relative to today (e.g. "tomorrow" or "in 3 days (Thu)")
+    /// instead of as plain dates. On by default.
+    pub relative_dates: bool,
+    /// Gid of an enum custom field (e.g. "Priority") used to rank tasks within each due bucket
+    /// and prefix their names in list output. Off while unset.
+    pub priority_field_gid: Option<String>,
+}
+
+impl Default for ListConfig {
+    fn default() -> Self {
+        Self {
+            relative_dates: true,
+            priority_field_gid: None,
+        }
+    }
+}
+
+/// Configuration for the summary command.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SummaryConfig {
+    /// If set (the default), a summary whose cache lacks today's focus day falls back to a live
+    /// read-only fetch. Unset, it notes that the focus status is unknown instead of hitting the
+    /// API; `--offline` and `--use-cache` skip the fallback regardless.
+    pub fetch_focus_fallback: bool,
+    /// If set, the summary also mentions how many tasks have no due date.
+    pub show_undated: bool,
+    /// If set, the all-clear line greets the user by first name when a profile is cached.
+    pub personal_greeting: bool,
+}
+
+impl Default for SummaryConfig {
+    fn default() -> Self {
+        Self {
+            fetch_focus_fallback: true,
+            show_undated: false,
+            personal_greeting: false,
+        }
+    }
+}
+
+/// Load the configuration from the given path, creating an empty file if one does not exist.
+///
+/// Unrecognized keys in the file would otherwise be silently ignored (and the affected section
+/// fall back to its defaults), so they produce a styled warning on stderr with a did-you-mean
+/// suggestion — or a hard error when `strict` or `behavior.strict_config` is set.
+///
+/// # Errors
+///
+/// This function will return an error if the configuration file could not be created, read, or
+/// deserialized, or if it contains unrecognized keys while strictness is enabled.
+pub fn load(path: &Path, strict: bool) -> anyhow::Result<Config> {
+    tracing::debug!(
+        "Checking if configuration file exists at {}...",
+        path.display()
+    );
+    if !path.exists() {
+        tracing::warn!(
+            "Could not find configuration at {}, so creating and using an empty configuration...",
+            path.display()
+        );
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("could not create path to configuration file")?;
+        }
+        fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .context("could not create configuration file")?;
+    }
+
+    tracing::debug!("Loading configuration from {}...", path.display());
+    let raw = fs::read_to_string(path).context("could not read configuration file")?;
+    let config: Config = toml::from_str(&raw).with_context(|| {
+        format!(
+            "could not deserialize configuration file at {}",
+            path.display()
+        )
+    })?;
+    tracing::trace!("Loaded configuration: {config:#?}");
+
+    let file: toml::Value =
+        toml::from_str(&raw).context("could not parse configuration file")?;
+    let unknown = unknown_keys(&file);
+    if !unknown.is_empty() {
+        let lines: Vec<String> = unknown
+            .iter()
+            .map(|(key, suggestion)| match suggestion {
+                Some(suggestion) => {
+                    format!("unrecognized configuration key `{key}` (did you mean `{suggestion}`?)")
+                }
+                None => format!("unrecognized configuration key `{key}`"),
+            })
+            .collect();
+        if strict || config.behavior.strict_config {
+            anyhow::bail!("{}", lines.join("\n"));
+        }
+        for line in &lines {
+            #[cfg(feature = "cli")]
+            eprintln!("{}", console::style(format!("Warning: {line}")).yellow());
+            #[cfg(not(feature = "cli"))]
+            eprintln!("Warning: {line}");
+        }
+    }
+
+    #[cfg(feature = "cli")]
+    config.colors.validate()?;
+    config.display.validate()?;
+
+    Ok(config)
+}
+
+/// Dotted keys in the parsed configuration file that are not part of the schema, each paired
+/// with the closest valid key when one is close enough to be a plausible typo.
+#[must_use]
+pub fn unknown_keys(file: &toml::Value) -> Vec<(String, Option<String>)> {
+    let mut unknown = Vec::new();
+    let Some(table) = file.as_table() else {
+        return unknown;
+    };
+
+    let sections: Vec<&str> = {
+        let mut sections: Vec<&str> = KEYS
+            .iter()
+            .filter_map(|(key, _)| key.split('.').next())
+            .collect();
+        sections.dedup();
+        sections
+    };
+
+    for (name, value) in table {
+        if sections.contains(&name.as_str()) {
+            let Some(section) = value.as_table() else {
+                unknown.push((name.clone(), None));
+                continue;
+            };
+            for (inner_name, inner_value) in section {
+                let key = format!("{name}.{inner_name}");
+                if KEYS.iter().any(|(known, _)| *known == key) {
+                    continue;
+                }
+                // Some keys live one table deeper (e.g. `integrations.daily_note.heading`), in
+                // which case the middle segment is a sub-table to descend into, not a key.
+                if KEYS
+                    .iter()
+                    .any(|(known, _)| known.starts_with(&format!("{key}.")))
+                {
+                    let Some(subsection) = inner_value.as_table() else {
+                        unknown.push((key, None));
+                        continue;
+                    };
+                    for sub_name in subsection.keys() {
+                        let sub_key = format!("{key}.{sub_name}");
+                        if !KEYS.iter().any(|(known, _)| *known == sub_key) {
+                            unknown.push((sub_key.clone(), suggest(&sub_key, keys())));
+                        }
+                    }
+                    continue;
+                }
+                unknown.push((key.clone(), suggest(&key, keys())));
+            }
+        } else {
+            unknown.push((name.clone(), suggest(name, sections.clone())));
+        }
+    }
+    unknown
+}
+
+/// The candidate closest to `key` by edit distance, if it is close enough to be a typo.
+fn suggest(key: &str, candidates: Vec<&'static str>) -> Option<String> {
+    candidates
+        .into_iter()
+        .map(|candidate| (crate::utils::edit_distance(key, candidate), candidate))
+        .min_by_key(|(distance, _)| *distance)
+        .filter(|(distance, _)| *distance <= 3)
+        .map(|(_, candidate)| candidate.to_string())
+}
+
+/// Save the configuration to the given path.
+///
+/// # Errors
+///
+/// This function will return an error if the configuration could not be serialized or written.
+pub fn save(path: &Path, config: &Config) -> anyhow::Result<()> {
+    tracing::debug!("Saving configuration to {}...", path.display());
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("could not create path to configuration file")?;
+    }
+    fs::write(
+        path,
+        toml::to_string_pretty(config).context("could not serialize configuration")?,
+    )
+    .context("could not write to configuration file")?;
+    tracing::trace!("Saved configuration: {config:#?}");
+
+    Ok(())
+}
+
+/// Kinds of values configuration keys accept.
+#[derive(Clone, Copy, Debug)]
+enum KeyKind {
+    Bool,
+    Integer,
+    String,
+    /// A list of strings, written as comma-separated values on the command line.
+    StringList,
+}
+
+/// Every valid dotted configuration key and the kind of value it accepts.
+const KEYS: &[(&str, KeyKind)] = &[
+    ("asana.workspace_gid", KeyKind::String),
+    ("asana.focus_project_gid", KeyKind::String),
+    ("asana.connect_timeout_seconds", KeyKind::Integer),
+    ("asana.request_timeout_seconds", KeyKind::Integer),
+    ("asana.user_agent", KeyKind::String),
+    ("asana.proxy_url", KeyKind::String),
+    ("behavior.exit_codes", KeyKind::Bool),
+    ("behavior.strict_config", KeyKind::Bool),
+    ("behavior.default_command", KeyKind::String),
+    ("behavior.update_interval_minutes", KeyKind::Integer),
+    ("colors.overdue", KeyKind::String),
+    ("colors.due_today", KeyKind::String),
+    ("colors.due_week", KeyKind::String),
+    ("colors.ok", KeyKind::String),
+    ("colors.pending", KeyKind::String),
+    ("colors.dim", KeyKind::String),
+    ("display.date_format", KeyKind::String),
+    ("display.time_format", KeyKind::String),
+    ("focus.confirm_sync", KeyKind::Bool),
+    ("focus.days", KeyKind::StringList),
+    ("integrations.daily_note.directory", KeyKind::String),
+    ("integrations.daily_note.filename", KeyKind::String),
+    ("integrations.daily_note.heading", KeyKind::String),
+    ("list.relative_dates", KeyKind::Bool),
+    ("list.priority_field_gid", KeyKind::String),
+    ("menubar.flavor", KeyKind::String),
+    ("notify.update_min_gap_minutes", KeyKind::Integer),
+    ("status.ascii_only", KeyKind::Bool),
+    ("status.overdue_prefix", KeyKind::String),
+    ("status.due_today_prefix", KeyKind::String),
+    ("status.morning_pending", KeyKind::String),
+    ("status.evening_pending", KeyKind::String),
+    ("status.all_clear", KeyKind::String),
+    ("status.max_width", KeyKind::Integer),
+    ("status.hide_due_today", KeyKind::Bool),
+    ("status.show_focus_subtasks", KeyKind::Bool),
+    ("status.show_due_week", KeyKind::Bool),
+    ("summary.fetch_focus_fallback", KeyKind::Bool),
+    ("summary.personal_greeting", KeyKind::Bool),
+    ("summary.show_undated", KeyKind::Bool),
+    ("terminal.blocking", KeyKind::Bool),
+];
+
+/// Every valid dotted configuration key, in the order they are listed by the config command.
+#[must_use]
+pub fn keys() -> Vec<&'static str> {
+    KEYS.iter().map(|(key, _)| *key).collect()
+}
+
+/// Get the kind of value the key accepts, or an error listing every valid key.
+fn kind_of(key: &str) -> anyhow::Result<KeyKind> {
+    KEYS.iter()
+        .find(|(name, _)| *name == key)
+        .map(|(_, kind)| *kind)
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "unknown configuration key `{key}`; valid keys are: {}",
+                keys().join(", ")
+            )
+        })
+}
+
+/// Look up a dotted key in a TOML table.
+fn lookup<'a>(value: &'a toml::Value, key: &str) -> Option<&'a toml::Value> {
+    key.split('.')
+        .try_fold(value, |value, segment| value.get(segment))
+}
+
+/// Render a TOML value the way the config command prints it: strings without quotes, `unset` for
+/// missing optional values.
+fn render(value: Option<&toml::Value>) -> String {
+    match value {
+        Some(toml::Value::String(string)) => string.clone(),
+        Some(value) => value.to_string(),
+        None => "unset".to_string(),
+    }
+}
+
+/// Get the effective value of a dotted configuration key.
+///
+/// # Errors
+///
+/// This function will return an error if the key is unknown or the configuration could not be
+/// serialized.
+pub fn get(config: &Config, key: &str) -> anyhow::Result<String> {
+    kind_of(key)?;
+    let table = toml::Value::try_from(config).context("could not serialize configuration")?;
+    Ok(render(lookup(&table, key)))
+}
+
+/// Return a copy of the configuration with the dotted key set to the value, parsed and validated
+/// against the key's type.
+///
+/// # Errors
+///
+/// This function will return an error if the key is unknown, the value does not parse as the
+/// key's type, or the resulting configuration is invalid (e.g. an unrecognized enum value).
+pub fn set(config: &Config, key: &str, value: &str) -> anyhow::Result<Config> {
+    let parsed = match kind_of(key)? {
+        KeyKind::Bool => toml::Value::Boolean(
+            value
+                .parse()
+                .with_context(|| format!("`{key}` expects true or false, got `{value}`"))?,
+        ),
+        KeyKind::Integer => toml::Value::Integer(
+            value
+                .parse()
+                .with_context(|| format!("`{key}` expects an integer, got `{value}`"))?,
+        ),
+        KeyKind::String => toml::Value::String(value.to_string()),
+        KeyKind::StringList => toml::Value::Array(
+            value
+                .split(',')
+                .map(|entry| toml::Value::String(entry.trim().to_string()))
+                .collect(),
+        ),
+    };
+
+    let mut table = toml::Value::try_from(config).context("could not serialize configuration")?;
+    let mut target = &mut table;
+    let mut segments = key.split('.').peekable();
+    while let Some(segment) = segments.next() {
+        if segments.peek().is_none() {
+            target
+                .as_table_mut()
+                .context("configuration is not a table")?
+                .insert(segment.to_string(), parsed);
+            break;
+        }
+        target = target
+            .as_table_mut()
+            .context("configuration is not a table")?
+            .entry(segment)
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+    }
+
+    let config: Config = table
+        .try_into()
+        .with_context(|| format!("invalid value for `{key}`"))?;
+    #[cfg(feature = "cli")]
+    config.colors.validate()?;
+    config.display.validate()?;
+    Ok(config)
+}
+
+/// One row of the effective configuration: the key, its rendered value, and whether it came from
+/// the configuration file or the built-in defaults.
+#[derive(Clone, Debug)]
+pub struct ConfigEntry {
+    /// Dotted configuration key.
+    pub key: &'static str,
+    /// Rendered effective value, `unset` for missing optional values.
+    pub value: String,
+    /// Where the value came from: `file` or `default`.
+    pub source: &'static str,
+}
+
+/// Get every configuration key with its effective value and provenance, given the raw contents
+/// of the configuration file.
+///
+/// # Errors
+///
+/// This function will return an error if the configuration could not be serialized.
+pub fn entries(config: &Config, file: &toml::Value) -> anyhow::Result<Vec<ConfigEntry>> {
+    let table = toml::Value::try_from(config).context("could not serialize configuration")?;
+    Ok(keys()
+        .into_iter()
+        .map(|key| ConfigEntry {
+            key,
+            value: render(lookup(&table, key)),
+            source: if lookup(file, key).is_some() {
+                "file"
+            } else {
+                "default"
+            },
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_parses_and_validates_values() {
+        let config = set(&Config::default(), "status.max_width", "12").unwrap();
+        assert_eq!(config.status.max_width, Some(12));
+        let config = set(&config, "terminal.blocking", "true").unwrap();
+        assert!(config.terminal.blocking);
+        let config = set(&config, "menubar.flavor", "swiftbar").unwrap();
+        assert!(matches!(config.menubar.flavor, MenubarFlavor::Swiftbar));
+
+        assert!(set(&Config::default(), "status.max_width", "wide").is_err());
+        assert!(set(&Config::default(), "menubar.flavor", "polybar").is_err());
+    }
+
+    #[test]
+    fn focus_days_schedule_excludes_weekends_when_set() {
+        let saturday = chrono::NaiveDate::from_ymd_opt(2024, 1, 6).unwrap();
+        let monday = chrono::NaiveDate::from_ymd_opt(2024, 1, 8).unwrap();
+        assert!(FocusConfig::default().is_scheduled(saturday));
+
+        let config = set(&Config::default(), "focus.days", "mon,tue,wed,thu,fri").unwrap();
+        assert!(!config.focus.is_scheduled(saturday));
+        assert!(config.focus.is_scheduled(monday));
+    }
+
+    #[test]
+    fn focus_prompts_parse_from_an_array_of_tables() {
+        let config: Config = toml::from_str(
+            "[[focus.prompts]]\n\
+             key = \"gratitude\"\n\
+             question = \"One thing you're grateful for?\"\n\
+             phase = \"evening\"\n\
+             [[focus.prompts]]\n\
+             key = \"priority\"\n\
+             question = \"Tomorrow's top priority?\"\n\
+             phase = \"morning\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.focus.prompts.len(), 2);
+        assert_eq!(config.focus.prompts[0].key, "gratitude");
+        assert_eq!(
+            config.focus.prompts[0].phase,
+            crate::focus::FocusPhase::Evening
+        );
+        assert_eq!(
+            config.focus.prompts[1].phase,
+            crate::focus::FocusPhase::Morning
+        );
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn colors_are_validated_when_set() {
+        let config = set(&Config::default(), "colors.overdue", "cyan").unwrap();
+        assert_eq!(config.colors.overdue.as_deref(), Some("cyan"));
+        let config = set(&config, "colors.due_today", "208").unwrap();
+        assert_eq!(config.colors.due_today.as_deref(), Some("208"));
+
+        let err = set(&Config::default(), "colors.ok", "chartreuse").unwrap_err();
+        let message = format!("{err:#}");
+        assert!(message.contains("invalid color for `colors.ok`"), "{message}");
+        assert!(message.contains("256-color index"), "{message}");
+    }
+
+    #[test]
+    fn display_formats_are_validated_when_set() {
+        let config = set(&Config::default(), "display.date_format", "%d/%m/%Y").unwrap();
+        assert_eq!(config.display.date_format.as_deref(), Some("%d/%m/%Y"));
+
+        let err = set(&Config::default(), "display.date_format", "%Q").unwrap_err();
+        assert!(
+            format!("{err:#}").contains("invalid strftime format"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn unknown_keys_error_with_the_valid_key_list() {
+        let err = set(&Config::default(), "notficiations.enabled", "true").unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("unknown configuration key"));
+        assert!(message.contains("terminal.blocking"));
+
+        assert!(get(&Config::default(), "status.maxwidth").is_err());
+    }
+
+    #[test]
+    fn get_renders_unset_optionals_and_plain_strings() {
+        assert_eq!(get(&Config::default(), "status.max_width").unwrap(), "unset");
+        assert_eq!(get(&Config::default(), "menubar.flavor").unwrap(), "xbar");
+        assert_eq!(
+            get(&Config::default(), "behavior.exit_codes").unwrap(),
+            "false"
+        );
+    }
+
+    #[test]
+    fn unknown_keys_are_detected_with_suggestions() {
+        let file: toml::Value =
+            toml::from_str("[status]\nmaxwidth = 3\n\n[behavor]\nexit_codes = true\n").unwrap();
+        let unknown = unknown_keys(&file);
+        assert_eq!(unknown.len(), 2);
+        assert!(unknown.contains(&(
+            "behavor".to_string(),
+            Some("behavior".to_string())
+        )));
+        assert!(unknown.contains(&(
+            "status.maxwidth".to_string(),
+            Some("status.max_width".to_string())
+        )));
+
+        let file: toml::Value = toml::from_str("[terminal]\nblocking = true\n").unwrap();
+        assert!(unknown_keys(&file).is_empty());
+    }
+
+    #[test]
+    fn strict_loading_rejects_unrecognized_keys() {
+        let dir = std::env::temp_dir()
+            .join("todo-config-tests")
+            .join(format!("strict-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        fs::write(&path, "[summray]\nshow_undated = true\n").unwrap();
+
+        let err = load(&path, true).unwrap_err();
+        assert!(err.to_string().contains("did you mean `summary`?"));
+
+        // Without strictness the typo only warns, and the valid parts still load.
+        assert!(load(&path, false).is_ok());
+    }
+
+    #[test]
+    fn entries_report_file_provenance() {
+        let file: toml::Value = toml::from_str("[terminal]\nblocking = true\n").unwrap();
+        let config = set(&Config::default(), "terminal.blocking", "true").unwrap();
+        let entries = entries(&config, &file).unwrap();
+        let blocking = entries.iter().find(|e| e.key == "terminal.blocking").unwrap();
+        assert_eq!((blocking.value.as_str(), blocking.source), ("true", "file"));
+        let flavor = entries.iter().find(|e| e.key == "menubar.flavor").unwrap();
+        assert_eq!((flavor.value.as_str(), flavor.source), ("xbar", "default"));
+    }
+}
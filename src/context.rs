@@ -0,0 +1,634 @@
+//! Shared state for commands, including due-date grouping of tasks.
+
+use chrono::{DateTime, Local, NaiveDate};
+
+use crate::cache::Cache;
+use crate::config::Config;
+use crate::task::UserTask;
+
+/// Which categories of output are allowed, derived from the `--quiet` flag and the TTY state.
+///
+/// Data output (the thing a command exists to print) is never suppressed; this only governs the
+/// chrome around it.
+#[derive(Clone, Copy, Debug)]
+pub struct OutputMode {
+    quiet: bool,
+    attended: bool,
+}
+
+impl OutputMode {
+    /// Build an output mode from the `--quiet` flag and whether stdout is an attended terminal.
+    #[must_use]
+    pub fn new(quiet: bool, attended: bool) -> Self {
+        Self { quiet, attended }
+    }
+
+    /// Whether transient progress strings ("Loading focus day...") may be written.
+    ///
+    /// Progress is cosmetic, so it is dropped under `--quiet` and whenever stdout is not a TTY —
+    /// cron mail and pipelines should never see it.
+    #[must_use]
+    pub fn show_progress(self) -> bool {
+        !self.quiet && self.attended
+    }
+
+    /// Whether advisory warnings (e.g. cache staleness) may be written to stderr.
+    ///
+    /// Warnings survive redirection since stderr stays visible in pipelines, but `--quiet`
+    /// drops them entirely.
+    #[must_use]
+    pub fn show_warnings(self) -> bool {
+        !self.quiet
+    }
+}
+
+/// Sink for user-facing output.
+///
+/// Commands write through this instead of `println!` or a raw terminal handle, so the binary
+/// can point it at the terminal while tests capture everything in a buffer.
+pub trait Output: std::fmt::Debug {
+    /// Write one line of output.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the sink could not be written to.
+    fn line(&mut self, line: &str) -> anyhow::Result<()>;
+
+    /// Write a transient status string without a trailing newline, expected to be cleared by
+    /// [`clear_status`](Output::clear_status) once the work it narrates finishes.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the sink could not be written to.
+    fn status(&mut self, status: &str) -> anyhow::Result<()>;
+
+    /// Clear the transient status string written by [`status`](Output::status).
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the sink could not be written to.
+    fn clear_status(&mut self) -> anyhow::Result<()>;
+
+    /// Whether the sink is an attended terminal.
+    fn is_attended(&self) -> bool;
+}
+
+/// A transient status line ("Loading focus day...") that cleans up after itself.
+///
+/// Construct one through [`StatusLine::show`] before slow work and let it drop (or call
+/// [`finish_with`](StatusLine::finish_with)) when the work ends; an early `?` return clears the
+/// line on the way out instead of leaving it dangling in front of the next line of output. When
+/// [`OutputMode::show_progress`] is false the whole thing is a silent no-op, so call sites no
+/// longer need their own `show_progress` checks around every write/clear pair.
+#[must_use = "dropping a status line immediately clears it"]
+#[derive(Debug)]
+pub struct StatusLine<'a> {
+    output: Option<&'a mut dyn Output>,
+}
+
+impl<'a> StatusLine<'a> {
+    /// Show `message` if `mode` allows progress output; otherwise a silent no-op.
+    ///
+    /// Write errors are swallowed — a status line that failed to appear is not worth failing
+    /// the command over, and the clear on drop is a no-op on the same broken sink.
+    pub fn show(output: &'a mut dyn Output, mode: OutputMode, message: &str) -> Self {
+        if mode.show_progress() {
+            let _ = output.status(message);
+            Self {
+                output: Some(output),
+            }
+        } else {
+            Self { output: None }
+        }
+    }
+
+    /// A status line that was never shown, for call sites that only sometimes have one.
+    pub fn none() -> Self {
+        Self { output: None }
+    }
+
+    /// Clear the status and write `line` in its place.
+    ///
+    /// A suppressed status line suppresses the finishing line too: it is progress chrome, not
+    /// data output.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the sink could not be written to.
+    pub fn finish_with(mut self, line: &str) -> anyhow::Result<()> {
+        if let Some(output) = self.output.take() {
+            output.clear_status()?;
+            output.line(line)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for StatusLine<'_> {
+    fn drop(&mut self) {
+        if let Some(output) = self.output.take() {
+            let _ = output.clear_status();
+        }
+    }
+}
+
+/// [`Output`] backed by a [`console::Term`], used by the binary.
+#[cfg(feature = "cli")]
+#[derive(Clone, Debug)]
+pub struct TermOutput {
+    term: console::Term,
+}
+
+#[cfg(feature = "cli")]
+impl TermOutput {
+    /// Wrap a terminal handle.
+    #[must_use]
+    pub fn new(term: console::Term) -> Self {
+        Self { term }
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Output for TermOutput {
+    fn line(&mut self, line: &str) -> anyhow::Result<()> {
+        Ok(self.term.write_line(line)?)
+    }
+
+    fn status(&mut self, status: &str) -> anyhow::Result<()> {
+        Ok(self.term.write_str(status)?)
+    }
+
+    fn clear_status(&mut self) -> anyhow::Result<()> {
+        Ok(self.term.clear_line()?)
+    }
+
+    fn is_attended(&self) -> bool {
+        self.term.features().is_attended()
+    }
+}
+
+/// [`Output`] that captures everything in memory, for asserting command output in tests.
+///
+/// Clones share the same buffer (the same pattern as [`crate::asana::RequestTimings`]), so a
+/// test can keep one handle while the context owns another.
+#[derive(Clone, Debug, Default)]
+pub struct BufferOutput {
+    state: std::sync::Arc<std::sync::Mutex<BufferState>>,
+}
+
+#[derive(Debug, Default)]
+struct BufferState {
+    lines: Vec<String>,
+    status: Option<String>,
+}
+
+impl BufferOutput {
+    /// Lines written so far, not counting transient status strings.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if another holder of the buffer panicked mid-write.
+    #[must_use]
+    pub fn lines(&self) -> Vec<String> {
+        self.state.lock().unwrap().lines.clone()
+    }
+
+    /// The transient status string currently on display, if any.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if another holder of the buffer panicked mid-write.
+    #[must_use]
+    pub fn status(&self) -> Option<String> {
+        self.state.lock().unwrap().status.clone()
+    }
+}
+
+impl Output for BufferOutput {
+    fn line(&mut self, line: &str) -> anyhow::Result<()> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| anyhow::anyhow!("output buffer lock poisoned"))?;
+        state.lines.push(line.to_string());
+        Ok(())
+    }
+
+    fn status(&mut self, status: &str) -> anyhow::Result<()> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| anyhow::anyhow!("output buffer lock poisoned"))?;
+        state.status = Some(status.to_string());
+        Ok(())
+    }
+
+    fn clear_status(&mut self) -> anyhow::Result<()> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| anyhow::anyhow!("output buffer lock poisoned"))?;
+        state.status = None;
+        Ok(())
+    }
+
+    fn is_attended(&self) -> bool {
+        false
+    }
+}
+
+/// Shared state threaded through command implementations.
+#[derive(Debug)]
+pub struct AppContext {
+    /// Loaded configuration.
+    pub config: Config,
+    /// Loaded cache, which commands may mutate before it is saved back.
+    pub cache: Cache,
+    /// Whether output may use colors and styling.
+    ///
+    /// False when `--no-color` is passed or the `NO_COLOR` environment variable is set; the
+    /// global `console` styling switches are flipped off to match.
+    pub color: bool,
+    /// Which categories of non-data output are allowed.
+    pub output: OutputMode,
+    /// Styles for the semantic color roles, resolved from the `[colors]` configuration table.
+    #[cfg(feature = "cli")]
+    pub theme: crate::render::Theme,
+    /// Whether mutations should be narrated instead of performed.
+    ///
+    /// Commands check this before every mutating API call and cache write that would reflect a
+    /// remote change; the client independently refuses mutating requests as a backstop.
+    pub dry_run: bool,
+    /// Per-run API request timings, shared with the client and every clone of it.
+    ///
+    /// Empty until the client is constructed (and forever when the command never talks to the
+    /// API); `--timings` renders it after the command finishes.
+    pub timings: crate::asana::RequestTimings,
+    /// The moment the run started, captured once when the context is built.
+    ///
+    /// Time-dependent behavior — the EOD threshold, due-date buckets, cache-age notes — reads
+    /// this instead of the wall clock, so one run always agrees with itself on what "now" is
+    /// and tests can pin it. Long-running loops are the exception: they must keep reading the
+    /// real clock, since this value never advances.
+    pub now: DateTime<Local>,
+    /// Sink for user-facing output: the terminal in the binary, a buffer in tests.
+    pub writer: Box<dyn Output>,
+}
+
+impl AppContext {
+    /// Build a context pinned to `now`, for tests that exercise time-dependent behavior
+    /// against a fixed clock.
+    ///
+    /// Everything the caller does not pass takes a quiet default: no styling, unattended
+    /// output, no dry run, empty timings. The binary builds its context by hand instead,
+    /// capturing the real clock once at startup.
+    #[must_use]
+    pub fn new_with_now(
+        config: Config,
+        cache: Cache,
+        writer: Box<dyn Output>,
+        now: DateTime<Local>,
+    ) -> Self {
+        Self {
+            config,
+            cache,
+            color: false,
+            output: OutputMode::new(false, false),
+            #[cfg(feature = "cli")]
+            theme: crate::render::Theme::default(),
+            dry_run: false,
+            timings: crate::asana::RequestTimings::default(),
+            now,
+            writer,
+        }
+    }
+
+    /// The run's calendar date in the local timezone, derived from [`AppContext::now`].
+    ///
+    /// Commands ask the context instead of chrono directly so that every "today" in a run is
+    /// computed the same way — after a timezone change mid-cache this is what keeps the due
+    /// buckets, the focus schedule, and the status line agreeing with each other.
+    #[must_use]
+    pub fn today(&self) -> NaiveDate {
+        self.now.date_naive()
+    }
+}
+
+/// Tasks grouped into due-date buckets for display.
+///
+/// Every task ends up in exactly one bucket: overdue, due today, due within the next week, or
+/// without a due date entirely. Tasks due more than a week out are not grouped since no view
+/// currently renders them.
+#[derive(Debug, Default)]
+pub struct GroupedTasks<'a> {
+    /// Tasks whose due date has passed, soonest-due first.
+    pub overdue: Vec<&'a UserTask>,
+    /// Tasks due today.
+    pub due_today: Vec<&'a UserTask>,
+    /// Tasks due within the next seven days, soonest-due first.
+    pub due_week: Vec<&'a UserTask>,
+    /// Tasks with no due date at all, most recently created first.
+    pub no_due_date: Vec<&'a UserTask>,
+}
+
+impl<'a> GroupedTasks<'a> {
+    /// Group tasks into due-date buckets relative to `today`.
+    #[must_use]
+    pub fn group(tasks: &'a [UserTask], today: NaiveDate) -> Self {
+        let mut grouped = Self::default();
+        for task in tasks {
+            match task.due_on {
+                Some(due) if due < today => grouped.overdue.push(task),
+                Some(due) if due == today => grouped.due_today.push(task),
+                Some(due) if due <= today + chrono::Days::new(7) => grouped.due_week.push(task),
+                Some(_) => {}
+                None => grouped.no_due_date.push(task),
+            }
+        }
+        grouped.overdue.sort_by_key(|t| t.due_on);
+        grouped.due_today.sort_by_key(|t| t.due_on);
+        grouped.due_week.sort_by_key(|t| t.due_on);
+        grouped.no_due_date.sort_by_key(|t| std::cmp::Reverse(t.created_at));
+        grouped
+    }
+
+    /// Re-sort every bucket high-priority-first by the given enum custom field.
+    ///
+    /// Options rank by name, so `P1` outranks `P2`; tasks without the field (or with it unset)
+    /// sort last. The sort is stable, so each bucket's original ordering holds within equal
+    /// priorities.
+    pub fn sort_by_priority(&mut self, field_gid: &str) {
+        for bucket in [
+            &mut self.overdue,
+            &mut self.due_today,
+            &mut self.due_week,
+            &mut self.no_due_date,
+        ] {
+            bucket.sort_by_key(|task| match task.custom_field_label(field_gid) {
+                Some(label) => (0, label.to_string()),
+                None => (1, String::new()),
+            });
+        }
+    }
+}
+
+/// Owned snapshot of one task, for machine-readable output and library callers that cannot
+/// hold a borrow of the task slice across an await point.
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct TaskSummary {
+    /// Globally unique identifier of the task in Asana.
+    pub gid: String,
+    /// Human-readable name of the task.
+    pub name: String,
+    /// When the task is due, if it has a due date at all.
+    #[serde(with = "crate::asana::serde_formats::optional_date")]
+    pub due_on: Option<NaiveDate>,
+    /// When the task was created.
+    #[serde(with = "crate::asana::serde_formats::datetime")]
+    pub created_at: chrono::DateTime<chrono::Local>,
+}
+
+impl From<&UserTask> for TaskSummary {
+    fn from(task: &UserTask) -> Self {
+        Self {
+            gid: task.gid.clone(),
+            name: task.name.clone(),
+            due_on: task.due_on,
+            created_at: task.created_at,
+        }
+    }
+}
+
+/// The due-date buckets as owned rows, one [`TaskSummary`] per task.
+///
+/// This is the schema every machine-readable task listing shares; consumers parse one shape
+/// regardless of which command produced it. Serialized it looks like:
+///
+/// ```json
+/// {
+///   "overdue": [
+///     {
+///       "gid": "1205000000000001",
+///       "name": "water the plants",
+///       "due_on": "2024-01-10",
+///       "created_at": "2024-01-01T12:00:00-05:00"
+///     }
+///   ],
+///   "due_today": [],
+///   "due_week": [],
+///   "no_due_date": []
+/// }
+/// ```
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct TaskBuckets {
+    /// Tasks whose due date has passed, soonest-due first.
+    pub overdue: Vec<TaskSummary>,
+    /// Tasks due today.
+    pub due_today: Vec<TaskSummary>,
+    /// Tasks due within the next seven days, soonest-due first.
+    pub due_week: Vec<TaskSummary>,
+    /// Tasks with no due date at all, most recently created first.
+    pub no_due_date: Vec<TaskSummary>,
+}
+
+impl From<&GroupedTasks<'_>> for TaskBuckets {
+    fn from(grouped: &GroupedTasks<'_>) -> Self {
+        let summarize = |bucket: &[&UserTask]| bucket.iter().copied().map(Into::into).collect();
+        Self {
+            overdue: summarize(&grouped.overdue),
+            due_today: summarize(&grouped.due_today),
+            due_week: summarize(&grouped.due_week),
+            no_due_date: summarize(&grouped.no_due_date),
+        }
+    }
+}
+
+/// Format a count of tasks for display, e.g. "1 task" or "3 tasks".
+#[must_use]
+pub fn task_or_tasks(num: usize) -> String {
+    if num == 1 {
+        "1 task".to_string()
+    } else {
+        format!("{num} tasks")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Local, TimeZone};
+
+    use super::*;
+
+    fn task(gid: &str, created_days_ago: i64, due_on: Option<NaiveDate>) -> UserTask {
+        UserTask {
+            gid: gid.to_string(),
+            created_at: Local.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap()
+                - chrono::Duration::days(created_days_ago),
+            due_on,
+            name: format!("task {gid}"),
+            projects: Vec::new(),
+            custom_fields: Vec::new(),
+        }
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn output_suppression_matrix() {
+        // (quiet, attended) -> (progress, warnings)
+        let cases = [
+            ((false, true), (true, true)),
+            ((false, false), (false, true)),
+            ((true, true), (false, false)),
+            ((true, false), (false, false)),
+        ];
+        for ((quiet, attended), (progress, warnings)) in cases {
+            let mode = OutputMode::new(quiet, attended);
+            assert_eq!(mode.show_progress(), progress, "quiet={quiet} attended={attended}");
+            assert_eq!(mode.show_warnings(), warnings, "quiet={quiet} attended={attended}");
+        }
+    }
+
+    #[test]
+    fn a_dropped_status_line_leaves_no_dangling_text() {
+        let buffer = BufferOutput::default();
+        let mut writer: Box<dyn Output> = Box::new(buffer.clone());
+        let mut failing = || -> anyhow::Result<()> {
+            let _status = StatusLine::show(
+                writer.as_mut(),
+                OutputMode::new(false, true),
+                "Loading focus day...",
+            );
+            assert_eq!(buffer.status().as_deref(), Some("Loading focus day..."));
+            anyhow::bail!("network down")
+        };
+        assert!(failing().is_err());
+        assert_eq!(buffer.status(), None);
+        assert!(buffer.lines().is_empty());
+    }
+
+    #[test]
+    fn finish_with_replaces_the_status_with_a_line() {
+        let buffer = BufferOutput::default();
+        let mut writer: Box<dyn Output> = Box::new(buffer.clone());
+        let status = StatusLine::show(writer.as_mut(), OutputMode::new(false, true), "Syncing...");
+        status.finish_with("Synced.").unwrap();
+        assert_eq!(buffer.status(), None);
+        assert_eq!(buffer.lines(), ["Synced.".to_string()]);
+    }
+
+    #[test]
+    fn status_lines_are_silent_when_progress_is_suppressed() {
+        for mode in [OutputMode::new(true, true), OutputMode::new(false, false)] {
+            let buffer = BufferOutput::default();
+            let mut writer: Box<dyn Output> = Box::new(buffer.clone());
+            let status = StatusLine::show(writer.as_mut(), mode, "Loading...");
+            assert_eq!(buffer.status(), None);
+            status.finish_with("Loaded.").unwrap();
+            assert!(buffer.lines().is_empty());
+        }
+        StatusLine::none().finish_with("never shown").unwrap();
+    }
+
+    #[test]
+    fn groups_tasks_into_due_buckets() {
+        let today = date(2024, 1, 15);
+        let tasks = vec![
+            task("overdue", 0, Some(date(2024, 1, 10))),
+            task("today", 0, Some(date(2024, 1, 15))),
+            task("week", 0, Some(date(2024, 1, 20))),
+            task("later", 0, Some(date(2024, 3, 1))),
+        ];
+        let grouped = GroupedTasks::group(&tasks, today);
+        assert_eq!(grouped.overdue.len(), 1);
+        assert_eq!(grouped.due_today.len(), 1);
+        assert_eq!(grouped.due_week.len(), 1);
+        assert!(grouped.no_due_date.is_empty());
+    }
+
+    #[test]
+    fn sorts_dated_buckets_by_due_date() {
+        let today = date(2024, 1, 15);
+        let tasks = vec![
+            task("b", 0, Some(date(2024, 1, 12))),
+            task("a", 0, Some(date(2024, 1, 10))),
+        ];
+        let grouped = GroupedTasks::group(&tasks, today);
+        assert_eq!(grouped.overdue[0].gid, "a");
+        assert_eq!(grouped.overdue[1].gid, "b");
+    }
+
+    #[test]
+    fn priority_sorting_ranks_labeled_tasks_first() {
+        use crate::task::{EnumOptionRef, TaskCustomField};
+
+        let today = date(2024, 1, 15);
+        let mut tasks = vec![
+            task("unlabeled", 0, Some(date(2024, 1, 10))),
+            task("p2", 0, Some(date(2024, 1, 11))),
+            task("p1", 0, Some(date(2024, 1, 12))),
+        ];
+        for task in &mut tasks {
+            if task.gid != "unlabeled" {
+                task.custom_fields = vec![TaskCustomField {
+                    gid: "priority".to_string(),
+                    display_value: Some(task.gid.to_uppercase()),
+                    enum_value: Some(EnumOptionRef {
+                        gid: format!("option-{}", task.gid),
+                        name: task.gid.to_uppercase(),
+                    }),
+                }];
+            }
+        }
+
+        let mut grouped = GroupedTasks::group(&tasks, today);
+        grouped.sort_by_priority("priority");
+        let order: Vec<&str> = grouped.overdue.iter().map(|t| t.gid.as_str()).collect();
+        assert_eq!(order, ["p1", "p2", "unlabeled"]);
+    }
+
+    #[test]
+    fn the_bucket_schema_field_names_are_pinned() {
+        // Downstream scripts index these names; renaming a field must fail here first.
+        let tasks = vec![
+            task("overdue", 0, Some(date(2024, 1, 10))),
+            task("undated", 0, None),
+        ];
+        let grouped = GroupedTasks::group(&tasks, date(2024, 1, 15));
+        let json = serde_json::to_value(TaskBuckets::from(&grouped)).unwrap();
+
+        let buckets: Vec<&String> = json.as_object().unwrap().keys().collect();
+        assert_eq!(buckets, ["due_today", "due_week", "no_due_date", "overdue"]);
+        let fields: Vec<&String> = json["overdue"][0].as_object().unwrap().keys().collect();
+        assert_eq!(fields, ["created_at", "due_on", "gid", "name"]);
+        assert_eq!(json["overdue"][0]["due_on"], "2024-01-10");
+        assert_eq!(json["no_due_date"][0]["due_on"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn task_summaries_round_trip_through_json() {
+        let tasks = vec![task("a", 0, Some(date(2024, 1, 10)))];
+        let buckets = TaskBuckets::from(&GroupedTasks::group(&tasks, date(2024, 1, 15)));
+        let json = serde_json::to_string(&buckets).unwrap();
+        let parsed: TaskBuckets = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.overdue[0].gid, "a");
+        assert_eq!(parsed.overdue[0].created_at, tasks[0].created_at);
+    }
+
+    #[test]
+    fn undated_tasks_land_in_no_due_date_bucket() {
+        let today = date(2024, 1, 15);
+        let tasks = vec![
+            task("old", 10, None),
+            task("new", 1, None),
+            task("dated", 0, Some(date(2024, 1, 15))),
+        ];
+        let grouped = GroupedTasks::group(&tasks, today);
+        assert_eq!(grouped.no_due_date.len(), 2);
+        // Most recently created first.
+        assert_eq!(grouped.no_due_date[0].gid, "new");
+        assert_eq!(grouped.no_due_date[1].gid, "old");
+    }
+}
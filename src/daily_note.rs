@@ -0,0 +1,177 @@
+//! Mirroring the focus diary into a markdown (e.g. Obsidian) daily note.
+//!
+//! The integration owns one block of the note, fenced by marker comments under a configurable
+//! heading, so repeated syncs replace the block in place instead of duplicating it and never
+//! touch the rest of the note.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use chrono::NaiveDate;
+
+use crate::focus::{FocusDay, FocusDayStats};
+
+/// Marker comment opening the managed block.
+pub const BLOCK_START: &str = "<!-- todo:focus:start -->";
+/// Marker comment closing the managed block.
+pub const BLOCK_END: &str = "<!-- todo:focus:end -->";
+
+/// Filename of the daily note for a date, from a strftime-style template like `%Y-%m-%d.md`.
+#[must_use]
+pub fn note_filename(template: &str, date: NaiveDate) -> String {
+    date.format(template).to_string()
+}
+
+/// One-line summary of the day's stats, e.g. "sleep 4 · energy 3", or a placeholder while none
+/// have been filled in.
+#[must_use]
+pub fn stat_summary(stats: &FocusDayStats) -> String {
+    let filled: Vec<String> = stats
+        .stats()
+        .into_iter()
+        .filter_map(|stat| stat.value().map(|value| format!("{} {value}", stat.name())))
+        .collect();
+    if filled.is_empty() {
+        "no stats recorded yet".to_string()
+    } else {
+        filled.join(" · ")
+    }
+}
+
+/// Render the managed block: the diary text and a one-line stat summary between the markers.
+#[must_use]
+pub fn render_block(diary: &str, stats: &FocusDayStats) -> String {
+    let diary = if diary.is_empty() {
+        "no diary entry — yet."
+    } else {
+        diary
+    };
+    format!(
+        "{BLOCK_START}\n{diary}\n\n{summary}\n{BLOCK_END}",
+        summary = stat_summary(stats)
+    )
+}
+
+/// Insert or replace the managed block in the note contents, under the given heading.
+///
+/// An existing managed block is replaced in place wherever it lives. Otherwise the block goes
+/// directly below an existing heading line, or the heading and block are appended to the end of
+/// the note when the heading is missing too.
+#[must_use]
+pub fn upsert_block(contents: &str, heading: &str, block: &str) -> String {
+    if let (Some(start), Some(end)) = (contents.find(BLOCK_START), contents.find(BLOCK_END)) {
+        let mut updated = String::with_capacity(contents.len() + block.len());
+        updated.push_str(&contents[..start]);
+        updated.push_str(block);
+        updated.push_str(&contents[end + BLOCK_END.len()..]);
+        return updated;
+    }
+
+    if let Some(line) = contents.lines().find(|line| line.trim() == heading) {
+        let heading_start = contents.find(line).unwrap_or_default();
+        let heading_end = heading_start + line.len();
+        let mut updated = String::with_capacity(contents.len() + block.len());
+        updated.push_str(&contents[..heading_end]);
+        updated.push_str("\n\n");
+        updated.push_str(block);
+        updated.push_str(&contents[heading_end..]);
+        return updated;
+    }
+
+    let mut updated = contents.to_string();
+    if !updated.is_empty() && !updated.ends_with('\n') {
+        updated.push('\n');
+    }
+    if !updated.is_empty() {
+        updated.push('\n');
+    }
+    updated.push_str(heading);
+    updated.push_str("\n\n");
+    updated.push_str(block);
+    updated.push('\n');
+    updated
+}
+
+/// Write the focus day's diary and stats into the daily note for its date, creating the note if
+/// it does not exist, and return the path that was written.
+///
+/// # Errors
+///
+/// This function will return an error if the note could not be read or written.
+pub fn sync(
+    directory: &Path,
+    filename_template: &str,
+    heading: &str,
+    day: &FocusDay,
+) -> anyhow::Result<PathBuf> {
+    let path = directory.join(note_filename(filename_template, day.date));
+    fs::create_dir_all(directory).context("could not create path to daily note")?;
+    let contents = if path.exists() {
+        fs::read_to_string(&path).context("could not read daily note")?
+    } else {
+        String::new()
+    };
+    let updated = upsert_block(&contents, heading, &render_block(&day.diary, &day.stats));
+    fs::write(&path, updated).context("could not write daily note")?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::focus::FocusDayStat;
+
+    use super::*;
+
+    fn stats(sleep: Option<u32>, energy: Option<u32>) -> FocusDayStats {
+        let mut stats = FocusDayStats::default();
+        stats.set_stat(FocusDayStat::Sleep(sleep));
+        stats.set_stat(FocusDayStat::Energy(energy));
+        stats
+    }
+
+    #[test]
+    fn filenames_come_from_the_template() {
+        let date: NaiveDate = "2024-01-15".parse().unwrap();
+        assert_eq!(note_filename("%Y-%m-%d.md", date), "2024-01-15.md");
+        assert_eq!(note_filename("daily-%d.%m.%Y.md", date), "daily-15.01.2024.md");
+    }
+
+    #[test]
+    fn stat_summaries_list_filled_stats_only() {
+        assert_eq!(stat_summary(&stats(Some(4), Some(3))), "sleep 4 · energy 3");
+        assert_eq!(stat_summary(&stats(None, None)), "no stats recorded yet");
+    }
+
+    #[test]
+    fn upsert_appends_heading_and_block_to_a_missing_file() {
+        let block = render_block("slept well.", &stats(Some(4), None));
+        assert_eq!(
+            upsert_block("", "## Focus", &block),
+            "## Focus\n\n<!-- todo:focus:start -->\nslept well.\n\nsleep 4\n<!-- todo:focus:end -->\n"
+        );
+    }
+
+    #[test]
+    fn upsert_slots_the_block_under_an_existing_heading() {
+        let note = "# Monday\n\nsome journaling.\n\n## Focus\n\n## Gratitude\n\nmore.\n";
+        let updated = upsert_block(note, "## Focus", &render_block("", &stats(None, None)));
+        let focus = updated.find("## Focus").unwrap();
+        let start = updated.find(BLOCK_START).unwrap();
+        let gratitude = updated.find("## Gratitude").unwrap();
+        assert!(focus < start && start < gratitude);
+        assert!(updated.contains("no diary entry — yet."));
+        assert!(updated.starts_with("# Monday"));
+        assert!(updated.ends_with("more.\n"));
+    }
+
+    #[test]
+    fn upsert_replaces_an_existing_managed_block() {
+        let note = upsert_block("", "## Focus", &render_block("draft.", &stats(None, None)));
+        let updated = upsert_block(&note, "## Focus", &render_block("final.", &stats(Some(5), None)));
+        assert_eq!(updated.matches(BLOCK_START).count(), 1);
+        assert_eq!(updated.matches("## Focus").count(), 1);
+        assert!(!updated.contains("draft."));
+        assert!(updated.contains("final.\n\nsleep 5\n"));
+    }
+}
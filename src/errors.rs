@@ -0,0 +1,170 @@
+//! Friendly rendering of top-level errors.
+//!
+//! A bare anyhow chain like "could not deserialize configuration file: expected newline" tells
+//! the user neither where to look nor what to do. This module maps the failure classes the tool
+//! actually runs into — configuration parse errors, Asana API statuses, network trouble — to a
+//! short styled message with a `try:` suggestion, keeping the full chain behind `-v`.
+
+use std::fmt::Write as _;
+
+use console::style;
+
+use crate::asana::{is_network_error, ApiError, OfflineError};
+
+/// Render an error for the end of a run: a short message for recognized failure classes plus a
+/// `try:` suggestion, or the chain's top message otherwise.
+///
+/// The full error chain is only included when `verbose` is set; otherwise a dim pointer to `-v`
+/// takes its place.
+#[must_use]
+pub fn render(error: &anyhow::Error, verbose: bool) -> String {
+    let (message, hint) = classify(error);
+    let mut rendered = format!("{} {message}", style("error:").red().bold().for_stderr());
+    if let Some(hint) = hint {
+        let _ = write!(rendered, "\n  {} {hint}", style("try:").yellow().for_stderr());
+    }
+    if verbose {
+        let _ = write!(rendered, "\n\n{error:?}");
+    } else {
+        let _ = write!(
+            rendered,
+            "\n  {}",
+            style("rerun with -v for the full error chain")
+                .dim()
+                .for_stderr()
+        );
+    }
+    rendered
+}
+
+/// Map an error chain to a display message and an optional suggestion.
+fn classify(error: &anyhow::Error) -> (String, Option<String>) {
+    if let Some(toml_error) = error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<toml::de::Error>())
+    {
+        // The toml error's display starts with "TOML parse error at line X, column Y"; the
+        // position is not otherwise exposed, so it is lifted out of that line.
+        let position = toml_error
+            .to_string()
+            .lines()
+            .next()
+            .and_then(|line| line.split_once(" at ").map(|(_, at)| at.to_string()));
+        let mut message = error.to_string();
+        if let Some(position) = position {
+            let _ = write!(message, " ({position})");
+        }
+        if !toml_error.message().is_empty() {
+            let _ = write!(message, ": {}", toml_error.message());
+        }
+        return (
+            message,
+            Some("`todo config edit` opens the file and re-validates it on save".to_string()),
+        );
+    }
+
+    if let Some(api_error) = error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<ApiError>())
+    {
+        let hint = match api_error.status.as_u16() {
+            401 | 403 => Some("`todo init` re-runs authentication from scratch".to_string()),
+            404 => Some(
+                "a configured gid probably points at something deleted; `todo config list` \
+                 shows the current values"
+                    .to_string(),
+            ),
+            429 => Some("Asana is rate limiting; wait a minute and rerun".to_string()),
+            _ => None,
+        };
+        return (api_error.to_string(), hint);
+    }
+
+    if error.chain().any(<dyn std::error::Error + 'static>::is::<OfflineError>) {
+        return (
+            error.to_string(),
+            Some("rerun without --offline once the data has been fetched at least once".to_string()),
+        );
+    }
+
+    if is_network_error(error) {
+        return (
+            format!("could not reach Asana: {error}"),
+            Some("check your connection, or pass --offline to read from the cache".to_string()),
+        );
+    }
+
+    (error.to_string(), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::StatusCode;
+
+    use super::*;
+
+    fn render_plain(error: &anyhow::Error, verbose: bool) -> String {
+        console::set_colors_enabled_stderr(false);
+        render(error, verbose)
+    }
+
+    #[test]
+    fn config_parse_errors_point_at_the_position_and_the_editor() {
+        let error = anyhow::Error::from(toml::from_str::<toml::Table>("a = 1\na = 2").unwrap_err())
+            .context("could not deserialize configuration file at /home/me/.config/todo/config.toml");
+        let rendered = render_plain(&error, false);
+        assert!(rendered.contains("/home/me/.config/todo/config.toml"));
+        assert!(rendered.contains("(line 2, column 1)"));
+        assert!(rendered.contains("try: `todo config edit`"));
+        assert!(rendered.contains("rerun with -v"));
+    }
+
+    #[test]
+    fn authentication_statuses_suggest_reinitializing() {
+        let error = anyhow::Error::from(ApiError {
+            status: StatusCode::UNAUTHORIZED,
+            message: Some("Not Authorized".to_string()),
+        });
+        let rendered = render_plain(&error, false);
+        assert!(rendered.contains("error: Asana returned 401 Unauthorized: Not Authorized"));
+        assert!(rendered.contains("try: `todo init`"));
+    }
+
+    #[test]
+    fn rate_limiting_suggests_waiting() {
+        let error = anyhow::Error::from(ApiError {
+            status: StatusCode::TOO_MANY_REQUESTS,
+            message: None,
+        });
+        let rendered = render_plain(&error, false);
+        assert!(rendered.contains("429"));
+        assert!(rendered.contains("wait a minute"));
+    }
+
+    #[test]
+    fn offline_misses_explain_the_cache_requirement() {
+        let error = anyhow::Error::from(crate::asana::OfflineError);
+        let rendered = render_plain(&error, false);
+        assert!(rendered.contains("try: rerun without --offline"));
+    }
+
+    #[tokio::test]
+    async fn connection_failures_suggest_offline_mode() {
+        // Port 9 is unroutable here, so this produces a real reqwest connection error.
+        let error = anyhow::Error::from(
+            reqwest::get("http://127.0.0.1:9/api/1.0/users/me").await.unwrap_err(),
+        );
+        let rendered = render_plain(&error, false);
+        assert!(rendered.contains("error: could not reach Asana"));
+        assert!(rendered.contains("pass --offline"));
+    }
+
+    #[test]
+    fn verbose_rendering_appends_the_full_chain() {
+        let error = anyhow::anyhow!("root cause").context("outer context");
+        let rendered = render_plain(&error, true);
+        assert!(rendered.contains("error: outer context"));
+        assert!(rendered.contains("root cause"));
+        assert!(!rendered.contains("rerun with -v"));
+    }
+}
@@ -0,0 +1,1592 @@
+//! Types for the Focus project: weekly sections, daily focus tasks, and their stats.
+
+use std::fmt::Display;
+#[cfg(feature = "cli")]
+use std::fmt::Write as _;
+
+use anyhow::Context;
+#[cfg(feature = "cli")]
+use chrono::Datelike;
+use chrono::{DateTime, Local, NaiveDate};
+#[cfg(feature = "cli")]
+use console::style;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::asana::{Client, DataRequest};
+
+/// Gid of the Asana project that holds the weekly sections of daily focus tasks.
+pub const ASANA_FOCUS_PROJECT_GID: &str = "1200179899177794";
+/// Pattern matched by the name of a weekly focus section.
+pub const FOCUS_WEEK_PATTERN: &str =
+    r"^Daily Focuses \((?<from>\d{4}-\d{2}-\d{2}) to (?<to>\d{4}-\d{2}-\d{2})\)$";
+/// Pattern matched by the name of a daily focus task.
+pub const FOCUS_DAY_PATTERN: &str = r"^Daily Focus for \w+ \((?<date>\d{4}-\d{2}-\d{2})\)$";
+/// Prefix prepended to archived focus week sections.
+///
+/// [`FOCUS_WEEK_PATTERN`] is anchored at the start of the name, so prefixed sections fall out of
+/// every focus week lookup without any extra filtering.
+pub const ARCHIVED_FOCUS_WEEK_PREFIX: &str = "[archived] ";
+/// Local hour at which the evening reflection window opens.
+pub const START_HOUR_FOR_EOD: u32 = 20;
+
+/// Phase of the focus routine a given moment falls in, relative to the day's date.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FocusPhase {
+    /// Before the end-of-day window: only the morning stats are due.
+    Morning,
+    /// The end-of-day reflection window: every stat is due.
+    Evening,
+}
+
+/// Section within an Asana project.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Section {
+    /// Globally unique identifier of the section in Asana.
+    pub gid: String,
+    /// Human-readable name of the section.
+    pub name: String,
+}
+
+impl<'a> DataRequest<'a> for Section {
+    type RequestData = String;
+    type ResponseData = Vec<Self>;
+
+    fn segments(request_data: &'a Self::RequestData) -> Vec<String> {
+        vec![
+            "projects".to_string(),
+            request_data.clone(),
+            "sections".to_string(),
+        ]
+    }
+
+    fn fields() -> &'a [&'a str] {
+        &["gid", "name"]
+    }
+}
+
+/// Task in the Focus project representing a single focus day.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FocusTask {
+    /// Globally unique identifier of the task in Asana.
+    pub gid: String,
+    /// Human-readable name of the task.
+    pub name: String,
+    /// Free-form notes on the task, used as the diary.
+    pub notes: String,
+    /// When the task was last modified in Asana, used to detect remote edits before a sync
+    /// overwrites the notes.
+    #[serde(default)]
+    pub modified_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Custom fields holding the focus day stats.
+    pub custom_fields: Option<Vec<FocusTaskCustomField>>,
+}
+
+impl<'a> DataRequest<'a> for FocusTask {
+    type RequestData = String;
+    type ResponseData = Vec<Self>;
+
+    fn segments(request_data: &'a Self::RequestData) -> Vec<String> {
+        vec![
+            "sections".to_string(),
+            request_data.clone(),
+            "tasks".to_string(),
+        ]
+    }
+
+    fn fields() -> &'a [&'a str] {
+        // The nested paths pull in the `custom_fields` objects themselves, so the bare parent
+        // field does not need to be requested on top.
+        &[
+            "gid",
+            "name",
+            "notes",
+            "modified_at",
+            "custom_fields.gid",
+            "custom_fields.number_value",
+        ]
+    }
+}
+
+/// Slim fetch of a single task's notes and modification time, re-requested just before the
+/// focus sync to detect edits made remotely (say, on a phone) since the run loaded the day.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FocusTaskSnapshot {
+    /// Globally unique identifier of the task in Asana.
+    pub gid: String,
+    /// Free-form notes on the task at the time of this fetch.
+    pub notes: String,
+    /// When the task was last modified in Asana.
+    #[serde(default)]
+    pub modified_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl FocusTaskSnapshot {
+    /// Whether the remote task changed since `loaded` was fetched in a way the sync's blanket
+    /// notes overwrite would destroy: the modification time moved and the notes no longer
+    /// match what the run started from. Stats-only edits move `modified_at` without touching
+    /// the notes and are not conflicts.
+    #[must_use]
+    pub fn conflicts_with(&self, loaded: &FocusTask) -> bool {
+        self.modified_at != loaded.modified_at && self.notes != loaded.notes
+    }
+}
+
+impl DataRequest<'_> for FocusTaskSnapshot {
+    type RequestData = String;
+    type ResponseData = Self;
+
+    fn segments(request_data: &Self::RequestData) -> Vec<String> {
+        vec!["tasks".to_string(), request_data.clone()]
+    }
+
+    fn fields() -> &'static [&'static str] {
+        &["gid", "notes", "modified_at"]
+    }
+}
+
+/// Custom field on a focus task holding a single numeric stat.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FocusTaskCustomField {
+    /// Globally unique identifier of the custom field in Asana.
+    pub gid: String,
+    /// Value of the field, if it has been filled in.
+    pub number_value: Option<u32>,
+}
+
+/// Subtask of a focus task, used for ad-hoc daily todos.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FocusTaskSubtask {
+    /// Globally unique identifier of the subtask in Asana.
+    pub gid: String,
+    /// Human-readable name of the subtask.
+    pub name: String,
+    /// Whether the subtask has been completed.
+    pub completed: bool,
+    /// When the subtask is due, if it has a due date at all.
+    #[serde(default, with = "crate::asana::serde_formats::optional_date")]
+    pub due_on: Option<NaiveDate>,
+}
+
+impl DataRequest<'_> for FocusTaskSubtask {
+    type RequestData = String;
+    type ResponseData = Vec<Self>;
+
+    fn segments(request_data: &Self::RequestData) -> Vec<String> {
+        vec![
+            "tasks".to_string(),
+            request_data.clone(),
+            "subtasks".to_string(),
+        ]
+    }
+
+    fn fields() -> &'static [&'static str] {
+        &["gid", "name", "completed", "due_on"]
+    }
+}
+
+/// Weekly section of the Focus project holding a batch of focus days.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FocusWeek {
+    /// Underlying Asana section.
+    pub section: Section,
+    /// First day covered by the week.
+    pub from: NaiveDate,
+    /// Last day covered by the week.
+    pub to: NaiveDate,
+}
+
+impl FocusWeek {
+    /// Whether the week ended before the archive cutoff of `keep_weeks` weeks before `today`.
+    ///
+    /// The cutoff is a whole number of weeks, so with the default of 8 a week whose `to` date is
+    /// exactly 8 weeks old is kept.
+    #[must_use]
+    pub fn is_past_archive_cutoff(&self, today: NaiveDate, keep_weeks: u8) -> bool {
+        self.to < today - chrono::Days::new(7 * u64::from(keep_weeks))
+    }
+
+    /// The section name with [`ARCHIVED_FOCUS_WEEK_PREFIX`] prepended.
+    #[must_use]
+    pub fn archived_name(&self) -> String {
+        format!("{ARCHIVED_FOCUS_WEEK_PREFIX}{}", self.section.name)
+    }
+}
+
+impl TryFrom<Section> for FocusWeek {
+    type Error = anyhow::Error;
+
+    fn try_from(section: Section) -> Result<Self, Self::Error> {
+        let captures = Regex::new(FOCUS_WEEK_PATTERN)
+            .context("unable to parse focus section pattern")?
+            .captures(&section.name)
+            .context(section.name.clone())?;
+        Ok(Self {
+            section: section.clone(),
+            from: NaiveDate::parse_from_str(&captures["from"], "%Y-%m-%d")
+                .context(section.name.clone())?,
+            to: NaiveDate::parse_from_str(&captures["to"], "%Y-%m-%d")
+                .context(section.name.clone())?,
+        })
+    }
+}
+
+impl Display for FocusWeek {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Focus Week ({from} to {to})",
+            from = self.from.format("%Y-%m-%d"),
+            to = self.to.format("%Y-%m-%d")
+        )
+    }
+}
+
+/// Single focus day: a dated focus task along with its parsed stats and diary.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FocusDay {
+    /// Underlying Asana task.
+    pub task: FocusTask,
+    /// Date the focus day covers.
+    pub date: NaiveDate,
+    /// Stats recorded for the day.
+    pub stats: FocusDayStats,
+    /// Diary entry for the day.
+    pub diary: String,
+    /// Answers to the configured custom prompts, parsed out of the marker-delimited block the
+    /// task notes carry alongside the diary.
+    #[serde(default)]
+    pub prompts: Vec<PromptAnswer>,
+    /// Subtasks of the focus task, if they have been loaded.
+    pub subtasks: Option<Vec<FocusTaskSubtask>>,
+}
+
+/// A single answered custom prompt, keyed by the `[[focus.prompts]]` entry it came from.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PromptAnswer {
+    /// Stable key the answer is stored under in the notes block.
+    pub key: String,
+    /// The answer text, possibly spanning multiple lines.
+    pub answer: String,
+}
+
+/// Opening marker of the prompt-answers block embedded in a focus task's notes.
+const PROMPTS_BLOCK_START: &str = "<!-- todo:prompts -->";
+/// Closing marker of the prompt-answers block.
+const PROMPTS_BLOCK_END: &str = "<!-- /todo:prompts -->";
+
+/// Split a focus task's raw notes into the diary text and the prompt answers stored in the
+/// marker-delimited block, wherever in the notes the block sits.
+///
+/// Inside the block every `[key]` line starts an answer and the lines until the next key (or
+/// the closing marker) are its text. Notes without a block — or with a half-deleted one — come
+/// back entirely as diary, so a hand-edited field can never corrupt the diary.
+#[must_use]
+pub fn split_prompt_block(notes: &str) -> (String, Vec<PromptAnswer>) {
+    let Some(start) = notes.find(PROMPTS_BLOCK_START) else {
+        return (notes.trim().to_string(), Vec::new());
+    };
+    let Some(end) = notes[start..].find(PROMPTS_BLOCK_END).map(|e| start + e) else {
+        return (notes.trim().to_string(), Vec::new());
+    };
+
+    let mut answers: Vec<PromptAnswer> = Vec::new();
+    for line in notes[start + PROMPTS_BLOCK_START.len()..end].lines() {
+        if let Some(key) = line
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            answers.push(PromptAnswer {
+                key: key.to_string(),
+                answer: String::new(),
+            });
+        } else if let Some(answer) = answers.last_mut() {
+            if !answer.answer.is_empty() {
+                answer.answer.push('\n');
+            }
+            answer.answer.push_str(line);
+        }
+        // Text before the first key has nothing to attach to and is dropped.
+    }
+    for answer in &mut answers {
+        answer.answer = answer.answer.trim().to_string();
+    }
+    answers.retain(|a| !a.answer.is_empty());
+
+    let before = notes[..start].trim();
+    let after = notes[end + PROMPTS_BLOCK_END.len()..].trim();
+    let diary = match (before.is_empty(), after.is_empty()) {
+        (false, false) => format!("{before}\n\n{after}"),
+        (false, true) => before.to_string(),
+        (true, _) => after.to_string(),
+    };
+    (diary, answers)
+}
+
+/// Join diary text and prompt answers back into the notes field, placing the block after the
+/// diary. Answers that are empty after trimming are dropped, and with none left the notes are
+/// just the diary — [`split_prompt_block`] round-trips either way.
+#[must_use]
+pub fn join_prompt_block(diary: &str, answers: &[PromptAnswer]) -> String {
+    use std::fmt::Write as _;
+
+    let answers = answers
+        .iter()
+        .filter(|a| !a.answer.trim().is_empty())
+        .collect::<Vec<_>>();
+    let diary = diary.trim();
+    if answers.is_empty() {
+        return diary.to_string();
+    }
+
+    let mut notes = diary.to_string();
+    if !notes.is_empty() {
+        notes.push_str("\n\n");
+    }
+    notes.push_str(PROMPTS_BLOCK_START);
+    for answer in answers {
+        let _ = write!(
+            notes,
+            "\n[{key}]\n{answer}",
+            key = answer.key,
+            answer = answer.answer.trim()
+        );
+    }
+    notes.push('\n');
+    notes.push_str(PROMPTS_BLOCK_END);
+    notes
+}
+
+impl FocusDay {
+    /// Render a multi-line human-readable overview of the focus day.
+    ///
+    /// `date_format` overrides the ISO date in the heading with a configured strftime string.
+    #[cfg(feature = "cli")]
+    #[must_use]
+    pub fn to_full_string(&self, date_format: Option<&str>) -> String {
+        self.render(date_format, false, &[])
+    }
+
+    /// Like [`to_full_string`] but omitting the stats that were never filled in, so stacking
+    /// many days in a range overview stays readable. A day with no filled stats drops the
+    /// statistics section entirely.
+    #[cfg(feature = "cli")]
+    #[must_use]
+    pub fn to_compact_string(&self, date_format: Option<&str>) -> String {
+        self.render(date_format, true, &[])
+    }
+
+    /// Like [`to_full_string`], with a dim `entered Nd late` note next to every stat whose
+    /// recorded entry landed after the day itself.
+    #[cfg(feature = "cli")]
+    #[must_use]
+    pub fn to_audited_string(&self, date_format: Option<&str>, entries: &[StatEntry]) -> String {
+        self.render(date_format, false, entries)
+    }
+
+    /// Like [`to_compact_string`], with the same backfill notes as [`to_audited_string`].
+    #[cfg(feature = "cli")]
+    #[must_use]
+    pub fn to_compact_audited_string(
+        &self,
+        date_format: Option<&str>,
+        entries: &[StatEntry],
+    ) -> String {
+        self.render(date_format, true, entries)
+    }
+
+    /// Render the focus day as a single line for agenda rows, logs, and reports: weekday, date,
+    /// stat initials with their values (dimmed when unset), and the start of the diary, e.g.
+    /// `Mon 2024-01-15  s7 e6 f5 h4 H8 S7 st3  "shipped the refactor…"`.
+    ///
+    /// The diary is truncated on a character boundary with an ellipsis so the line fits in
+    /// `width` columns, and the output never contains a newline. The fixed parts are never
+    /// truncated, so a very small `width` just drops the diary.
+    #[cfg(feature = "cli")]
+    #[must_use]
+    pub fn to_line_string(&self, width: usize) -> String {
+        let stats = self.stats.stats();
+        let initials = stat_initials(&stats.iter().map(|s| s.name()).collect::<Vec<_>>());
+        let mut line = format!(
+            "{weekday} {date}  ",
+            weekday = self.date.weekday(),
+            date = self.date.format("%Y-%m-%d")
+        );
+        line.push_str(
+            &initials
+                .iter()
+                .zip(&stats)
+                .map(|(initial, stat)| match stat.value() {
+                    Some(value) => format!("{initial}{value}"),
+                    None => style(format!("{initial}-")).dim().to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(" "),
+        );
+
+        let diary = self.diary.replace(['\n', '\r'], " ");
+        let diary = diary.trim();
+        if !diary.is_empty() {
+            // Two separating spaces plus the surrounding quotes frame the diary snippet.
+            let budget =
+                width.saturating_sub(console::measure_text_width(&line).saturating_add(4));
+            if budget > 0 {
+                let snippet = if diary.chars().count() > budget {
+                    let mut snippet: String =
+                        diary.chars().take(budget.saturating_sub(1)).collect();
+                    snippet.push('…');
+                    snippet
+                } else {
+                    diary.to_string()
+                };
+                let _ = write!(line, "  \"{snippet}\"");
+            }
+        }
+        line
+    }
+
+    #[cfg(feature = "cli")]
+    fn render(&self, date_format: Option<&str>, compact: bool, entries: &[StatEntry]) -> String {
+        let mut string = String::new();
+
+        let _ = write!(
+            string,
+            "🧠 {} {}",
+            style(format!(
+                "Focus Day: {}",
+                style(self.date.weekday().to_string()).blue()
+            ))
+            .bold(),
+            style(format!(
+                "({})",
+                crate::utils::format_date(self.date, date_format.unwrap_or("%Y-%m-%d"))
+            ))
+            .dim(),
+        );
+        let _ = write!(
+            string,
+            "\n\n{}",
+            if self.diary.is_empty() {
+                style("no diary entry — yet.").dim()
+            } else {
+                style(self.diary.as_str())
+            },
+        );
+        if !self.prompts.is_empty() {
+            let _ = write!(string, "\n\n{}", style("💬 Prompts").bold().magenta());
+            for prompt in &self.prompts {
+                let _ = write!(
+                    string,
+                    "\n   {key}: {answer}",
+                    key = style(prompt.key.as_str()).bold(),
+                    // A multi-line answer hangs under its own first line.
+                    answer = prompt.answer.replace('\n', "\n      ")
+                );
+            }
+        }
+
+        let stats = self
+            .stats
+            .stats()
+            .into_iter()
+            .filter(|s| !compact || s.value().is_some())
+            .collect::<Vec<_>>();
+        if compact && stats.is_empty() {
+            return string;
+        }
+        let _ = write!(string, "\n\n{}\n", style("❤️ Statistics").bold().cyan());
+
+        for stat in stats {
+            let line = format!(
+                "{name}: {value}",
+                name = style(stat.name().to_string()).bold(),
+                value = style(stat.value().map_or("-".to_string(), |v| v.to_string()))
+            );
+            // The first recorded entry is when the value was originally logged; later
+            // corrections do not make a same-day entry retroactively late.
+            let late_note = entries
+                .iter()
+                .find(|e| e.date == self.date && e.stat == stat.name())
+                .map(|e| (e.entered_at.date_naive() - self.date).num_days())
+                .filter(|days| *days > 0)
+                .map(|days| format!(" {}", style(format!("(entered {days}d late)")).dim()))
+                .unwrap_or_default();
+            let _ = writeln!(
+                string,
+                "   {}{late_note}",
+                if stat.value().is_some() {
+                    style(line)
+                } else {
+                    style(line).dim()
+                }
+            );
+        }
+        string
+    }
+
+    /// Whether the morning stats (sleep and energy) have all been filled in.
+    #[must_use]
+    pub fn is_morning_done(&self) -> bool {
+        self.stats
+            .stats()
+            .into_iter()
+            .filter(|s| s.is_morning())
+            .all(|s| s.value().is_some())
+    }
+
+    /// Whether the evening stats (everything besides sleep and energy) have all been filled in.
+    #[must_use]
+    pub fn is_evening_done(&self) -> bool {
+        self.stats
+            .stats()
+            .into_iter()
+            .filter(|s| !s.is_morning())
+            .all(|s| s.value().is_some())
+    }
+
+    /// Phase of the routine `now` falls in for this day.
+    ///
+    /// The evening opens at [`START_HOUR_FOR_EOD`]; a day in the past is always in its evening
+    /// (there is no point prompting only sleep and energy for a day that is over), and
+    /// `force_eod` jumps straight there for early reflections.
+    #[must_use]
+    pub fn phase(&self, now: chrono::DateTime<chrono::Local>, force_eod: bool) -> FocusPhase {
+        use chrono::Timelike as _;
+
+        if force_eod || self.date < now.date_naive() || now.hour() >= START_HOUR_FOR_EOD {
+            FocusPhase::Evening
+        } else {
+            FocusPhase::Morning
+        }
+    }
+
+    /// Stats still unfilled and due in the phase `now` falls in, in display order.
+    ///
+    /// Morning stats are due all day; the rest only once [`Self::phase`] reaches the evening.
+    #[must_use]
+    pub fn pending_stats(
+        &self,
+        now: chrono::DateTime<chrono::Local>,
+        force_eod: bool,
+    ) -> Vec<&FocusDayStat> {
+        let include_evening = self.phase(now, force_eod) == FocusPhase::Evening;
+        self.stats
+            .stats()
+            .into_iter()
+            .filter(|s| s.value().is_none() && (s.is_morning() || include_evening))
+            .collect()
+    }
+
+    /// Count incomplete subtasks that are overdue or due today, as `(overdue, due_today)`.
+    ///
+    /// Undated subtasks are never counted, and unloaded subtasks count as none — callers that
+    /// want accurate figures must have called [`Self::load_subtasks`] first.
+    #[must_use]
+    pub fn pending_subtask_counts(&self, today: NaiveDate) -> (usize, usize) {
+        let mut overdue = 0;
+        let mut due_today = 0;
+        for subtask in self.subtasks.iter().flatten().filter(|s| !s.completed) {
+            match subtask.due_on {
+                Some(due) if due < today => overdue += 1,
+                Some(due) if due == today => due_today += 1,
+                _ => {}
+            }
+        }
+        (overdue, due_today)
+    }
+
+    /// Load the subtasks of the focus task from Asana, caching them on the day.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the subtasks could not be fetched.
+    pub async fn load_subtasks(
+        &mut self,
+        client: &mut Client,
+    ) -> anyhow::Result<&[FocusTaskSubtask]> {
+        let subtasks = client.get::<FocusTaskSubtask>(&self.task.gid).await?;
+        Ok(self.subtasks.insert(subtasks))
+    }
+}
+
+/// Short unique initials for the stat names, in display order: the first letter, uppercased
+/// when taken, then a lengthening prefix until one is free (`sleep` → `s`, `health` → `H`,
+/// `stress` → `st`). Deriving them keeps the line renderer in step if the stat set changes.
+#[cfg(feature = "cli")]
+fn stat_initials(names: &[&str]) -> Vec<String> {
+    let mut initials: Vec<String> = Vec::with_capacity(names.len());
+    for name in names {
+        let mut chosen = None;
+        'prefixes: for length in 1..=name.chars().count() {
+            let prefix: String = name.chars().take(length).collect();
+            for candidate in [prefix.to_lowercase(), prefix.to_uppercase()] {
+                if !initials.contains(&candidate) {
+                    chosen = Some(candidate);
+                    break 'prefixes;
+                }
+            }
+        }
+        initials.push(chosen.unwrap_or_else(|| (*name).to_string()));
+    }
+    initials
+}
+
+impl TryFrom<FocusTask> for FocusDay {
+    type Error = anyhow::Error;
+
+    fn try_from(task: FocusTask) -> Result<Self, Self::Error> {
+        let captures = Regex::new(FOCUS_DAY_PATTERN)
+            .context("unable to parse focus section pattern")?
+            .captures(&task.name)
+            .context(task.name.clone())?;
+        let (diary, prompts) = split_prompt_block(&task.notes);
+        Ok(Self {
+            task: task.clone(),
+            date: NaiveDate::parse_from_str(&captures["date"], "%Y-%m-%d")
+                .context(task.name.clone())?,
+            stats: task
+                .custom_fields
+                .context("could not find custom fields")?
+                .try_into()?,
+            diary,
+            prompts,
+            subtasks: None,
+        })
+    }
+}
+
+impl Display for FocusDay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Focus Day ({date}) (stats: {stats})",
+            date = self.date.format("%Y-%m-%d"),
+            stats = self.stats
+        )
+    }
+}
+
+/// Draft of a partially completed focus run, persisted in the cache after every prompt so an
+/// interrupted run (Ctrl-C, a dead network mid-sync) can resume without losing entered data.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct FocusDraft {
+    /// Date of the focus day the draft belongs to; drafts for other dates are ignored.
+    pub date: NaiveDate,
+    /// Stat values entered so far, as (custom field gid, value) pairs.
+    pub stats: Vec<(String, u32)>,
+    /// Diary text, once the diary prompt has completed.
+    pub diary: Option<String>,
+    /// Names of subtasks whose creation was never confirmed, to be retried on resume.
+    pub pending_subtasks: Vec<String>,
+}
+
+impl FocusDraft {
+    /// Fold the draft's entered stat values into `stats`, leaving other stats untouched.
+    pub fn apply_to(&self, stats: &mut FocusDayStats) {
+        let entered: Vec<FocusDayStat> = stats
+            .stats()
+            .into_iter()
+            .filter_map(|stat| {
+                self.stats
+                    .iter()
+                    .find(|(gid, _)| gid == stat.field_gid())
+                    .map(|(_, value)| {
+                        let mut stat = stat.clone();
+                        stat.set_value(Some(*value));
+                        stat
+                    })
+            })
+            .collect();
+        for stat in entered {
+            stats.set_stat(stat);
+        }
+    }
+}
+
+/// Name of the pinned task that holds the week's goals inside a focus week section.
+///
+/// Asana sections cannot carry notes of their own, so the goals live in this task's notes
+/// instead; [`parse_weekly_goals`] reads them back out.
+pub const WEEKLY_GOALS_TASK_NAME: &str = "Weekly Goals";
+
+/// One goal parsed from the weekly goals task's notes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WeeklyGoal {
+    /// The goal text, without the completion marker.
+    pub text: String,
+    /// Whether the goal is marked done with a leading `[x]`.
+    pub done: bool,
+}
+
+/// Parse weekly goals out of the goals task's notes.
+///
+/// Every non-empty line is one goal. A leading `[x]` (case-insensitive) marks it done; a
+/// leading `[ ]` is accepted as an explicit not-done marker and stripped.
+#[must_use]
+pub fn parse_weekly_goals(notes: &str) -> Vec<WeeklyGoal> {
+    notes
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.is_empty() {
+                return None;
+            }
+            let (rest, done) = if let Some(rest) = line
+                .strip_prefix("[x]")
+                .or_else(|| line.strip_prefix("[X]"))
+            {
+                (rest, true)
+            } else if let Some(rest) = line.strip_prefix("[ ]") {
+                (rest, false)
+            } else {
+                (line, false)
+            };
+            Some(WeeklyGoal {
+                text: rest.trim().to_string(),
+                done,
+            })
+        })
+        .collect()
+}
+
+/// Render goals back into the notes format [`parse_weekly_goals`] reads: one `[x]`- or
+/// `[ ]`-prefixed line per goal.
+#[must_use]
+pub fn render_weekly_goals(goals: &[WeeklyGoal]) -> String {
+    use std::fmt::Write as _;
+
+    let mut notes = String::new();
+    for goal in goals {
+        let _ = writeln!(
+            notes,
+            "{marker} {text}",
+            marker = if goal.done { "[x]" } else { "[ ]" },
+            text = goal.text
+        );
+    }
+    notes
+}
+
+/// Render the goals as a styled display block for the overview and the focus run, with done
+/// goals checked off and dimmed.
+#[cfg(feature = "cli")]
+#[must_use]
+pub fn format_weekly_goals(goals: &[WeeklyGoal]) -> String {
+    let mut string = style("🎯 Weekly Goals").bold().yellow().to_string();
+    if goals.is_empty() {
+        let _ = write!(string, "\n   {}", style("none set — yet.").dim());
+        return string;
+    }
+    for goal in goals {
+        let _ = write!(
+            string,
+            "\n   {}",
+            if goal.done {
+                style(format!("✓ {}", goal.text)).dim()
+            } else {
+                style(format!("• {}", goal.text))
+            }
+        );
+    }
+    string
+}
+
+/// One locally recorded stat entry: which value was written for which day's stat, and when.
+///
+/// Asana number fields cannot carry an entry timestamp, so this history only exists in the
+/// local cache; it is what tells a stat logged the same morning apart from one backfilled days
+/// later.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct StatEntry {
+    /// Date of the focus day the value belongs to.
+    pub date: NaiveDate,
+    /// Name of the stat, as [`FocusDayStat::name`] reports it.
+    pub stat: String,
+    /// The value that was written.
+    pub value: u32,
+    /// When the value was entered; re-entries of the same value keep the first timestamp.
+    pub entered_at: DateTime<Local>,
+}
+
+/// Record a stat entry into the local history, deduplicating re-entries.
+///
+/// Replaying the value already on record for the day's stat — a resumed draft walking its
+/// entered values again — keeps the first `entered_at`. A different value is a correction and
+/// gets its own entry, so the original stays visible in the audit log.
+pub fn record_stat_entry(
+    entries: &mut Vec<StatEntry>,
+    date: NaiveDate,
+    stat: &str,
+    value: u32,
+    entered_at: DateTime<Local>,
+) {
+    if entries
+        .iter()
+        .rev()
+        .find(|e| e.date == date && e.stat == stat)
+        .is_some_and(|e| e.value == value)
+    {
+        return;
+    }
+    entries.push(StatEntry {
+        date,
+        stat: stat.to_string(),
+        value,
+        entered_at,
+    });
+}
+
+/// Render the recorded stat entries for dates in `from..=to`, most recent day first, with a
+/// dim lateness note on every backfilled value.
+#[cfg(feature = "cli")]
+#[must_use]
+pub fn render_stat_audit(entries: &[StatEntry], from: NaiveDate, to: NaiveDate) -> String {
+    let mut entries: Vec<&StatEntry> = entries
+        .iter()
+        .filter(|e| e.date >= from && e.date <= to)
+        .collect();
+    entries.sort_by(|a, b| b.date.cmp(&a.date).then(a.entered_at.cmp(&b.entered_at)));
+    if entries.is_empty() {
+        return style("no recorded stat entries in this range")
+            .dim()
+            .to_string();
+    }
+
+    let mut string = String::new();
+    for entry in entries {
+        let _ = write!(
+            string,
+            "{date}  {stat}={value}  entered {entered}",
+            date = entry.date,
+            stat = style(entry.stat.as_str()).bold(),
+            value = entry.value,
+            entered = entry.entered_at.format("%Y-%m-%d %H:%M"),
+        );
+        let days_late = (entry.entered_at.date_naive() - entry.date).num_days();
+        if days_late > 0 {
+            let _ = write!(string, " {}", style(format!("({days_late}d late)")).dim());
+        }
+        string.push('\n');
+    }
+    string
+}
+
+/// Compact description of what a focus sync would change, shown for confirmation before the
+/// sync request fires.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FocusSyncDiff {
+    /// One line per changed stat, e.g. `sleep: - → 4`.
+    pub stat_changes: Vec<String>,
+    /// Summary of the diary change (`+120 chars`, `-8 chars`, `edited`, `cleared!`), when the
+    /// diary changed at all.
+    pub diary_change: Option<String>,
+    /// Whether the change would wipe a previously non-empty diary.
+    pub diary_cleared: bool,
+}
+
+impl FocusSyncDiff {
+    /// Compute the diff between the day's synced stats and diary and the newly entered ones.
+    #[must_use]
+    pub fn compute(
+        old_stats: &FocusDayStats,
+        new_stats: &FocusDayStats,
+        old_diary: &str,
+        new_diary: &str,
+    ) -> Self {
+        let stat_changes = old_stats
+            .stats()
+            .into_iter()
+            .zip(new_stats.stats())
+            .filter(|(old, new)| old.value() != new.value())
+            .map(|(old, new)| {
+                format!(
+                    "{name}: {old} → {new}",
+                    name = new.name(),
+                    old = old.value().map_or("-".to_string(), |v| v.to_string()),
+                    new = new.value().map_or("-".to_string(), |v| v.to_string()),
+                )
+            })
+            .collect();
+
+        let diary_cleared = new_diary.is_empty() && !old_diary.is_empty();
+        let diary_change = if new_diary == old_diary {
+            None
+        } else if diary_cleared {
+            Some("cleared!".to_string())
+        } else {
+            let (old_length, new_length) = (old_diary.chars().count(), new_diary.chars().count());
+            Some(match new_length.cmp(&old_length) {
+                std::cmp::Ordering::Greater => format!("+{} chars", new_length - old_length),
+                std::cmp::Ordering::Less => format!("-{} chars", old_length - new_length),
+                std::cmp::Ordering::Equal => "edited".to_string(),
+            })
+        };
+
+        Self {
+            stat_changes,
+            diary_change,
+            diary_cleared,
+        }
+    }
+
+    /// Whether the sync would change anything at all.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.stat_changes.is_empty() && self.diary_change.is_none()
+    }
+}
+
+/// Full set of stats tracked for a focus day.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct FocusDayStats {
+    /// Quality of last night's sleep.
+    pub sleep: FocusDayStat,
+    /// Energy level during the day.
+    pub energy: FocusDayStat,
+    /// How much flow was achieved during the day.
+    pub flow: FocusDayStat,
+    /// How well-hydrated the day was.
+    pub hydration: FocusDayStat,
+    /// General physical health during the day.
+    pub health: FocusDayStat,
+    /// Satisfaction with how the day went.
+    pub satisfaction: FocusDayStat,
+    /// Stress level during the day.
+    pub stress: FocusDayStat,
+}
+
+impl FocusDayStats {
+    /// Get all stats in display order.
+    #[must_use]
+    pub fn stats(&self) -> Vec<&FocusDayStat> {
+        vec![
+            &self.sleep,
+            &self.energy,
+            &self.flow,
+            &self.hydration,
+            &self.health,
+            &self.satisfaction,
+            &self.stress,
+        ]
+    }
+
+    /// Names of the stats still unfilled, in display order.
+    ///
+    /// Evening stats only count once `include_evening` is set, mirroring how the evening
+    /// routine only becomes pending at the end of the day.
+    #[must_use]
+    pub fn pending_names(&self, include_evening: bool) -> Vec<&'static str> {
+        self.stats()
+            .into_iter()
+            .filter(|s| s.is_morning() || include_evening)
+            .filter(|s| s.value().is_none())
+            .map(FocusDayStat::name)
+            .collect()
+    }
+
+    /// Replace the stat matching the variant of `stat`.
+    pub fn set_stat(&mut self, stat: FocusDayStat) {
+        match stat {
+            FocusDayStat::Sleep(_) => self.sleep = stat,
+            FocusDayStat::Energy(_) => self.energy = stat,
+            FocusDayStat::Flow(_) => self.flow = stat,
+            FocusDayStat::Hydration(_) => self.hydration = stat,
+            FocusDayStat::Health(_) => self.health = stat,
+            FocusDayStat::Satisfaction(_) => self.satisfaction = stat,
+            FocusDayStat::Stress(_) => self.stress = stat,
+        }
+    }
+}
+
+impl Default for FocusDayStats {
+    fn default() -> Self {
+        Self {
+            sleep: FocusDayStat::Sleep(None),
+            energy: FocusDayStat::Energy(None),
+            flow: FocusDayStat::Flow(None),
+            hydration: FocusDayStat::Hydration(None),
+            health: FocusDayStat::Health(None),
+            satisfaction: FocusDayStat::Satisfaction(None),
+            stress: FocusDayStat::Stress(None),
+        }
+    }
+}
+
+impl TryFrom<Vec<FocusTaskCustomField>> for FocusDayStats {
+    type Error = anyhow::Error;
+
+    fn try_from(custom_fields: Vec<FocusTaskCustomField>) -> Result<Self, Self::Error> {
+        let mut stats = Self::default();
+        for custom_field in custom_fields {
+            stats.set_stat(FocusDayStat::try_from(custom_field)?);
+        }
+        Ok(stats)
+    }
+}
+
+impl Display for FocusDayStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{sleep}, {energy}, {flow}, {hydration}, {health}, {satisfaction}, {stress}",
+            sleep = self.sleep,
+            energy = self.energy,
+            flow = self.flow,
+            hydration = self.hydration,
+            health = self.health,
+            satisfaction = self.satisfaction,
+            stress = self.stress,
+        )
+    }
+}
+
+/// Single focus day stat along with its (possibly unset) value.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum FocusDayStat {
+    /// Quality of last night's sleep.
+    Sleep(Option<u32>),
+    /// Energy level during the day.
+    Energy(Option<u32>),
+    /// How much flow was achieved during the day.
+    Flow(Option<u32>),
+    /// How well-hydrated the day was.
+    Hydration(Option<u32>),
+    /// General physical health during the day.
+    Health(Option<u32>),
+    /// Satisfaction with how the day went.
+    Satisfaction(Option<u32>),
+    /// Stress level during the day.
+    Stress(Option<u32>),
+}
+
+impl FocusDayStat {
+    /// Human-readable name of the stat.
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Sleep(_) => "sleep",
+            Self::Energy(_) => "energy",
+            Self::Flow(_) => "flow",
+            Self::Hydration(_) => "hydration",
+            Self::Health(_) => "health",
+            Self::Satisfaction(_) => "satisfaction",
+            Self::Stress(_) => "stress",
+        }
+    }
+
+    /// Whether the stat belongs to the morning routine (sleep and energy) rather than the
+    /// evening reflection.
+    #[must_use]
+    pub fn is_morning(&self) -> bool {
+        matches!(self, Self::Sleep(_) | Self::Energy(_))
+    }
+
+    /// Recorded value of the stat, if it has been filled in.
+    #[must_use]
+    pub fn value(&self) -> Option<u32> {
+        match self {
+            Self::Sleep(value)
+            | Self::Energy(value)
+            | Self::Flow(value)
+            | Self::Hydration(value)
+            | Self::Health(value)
+            | Self::Satisfaction(value)
+            | Self::Stress(value) => *value,
+        }
+    }
+
+    /// Replace the recorded value of the stat.
+    pub fn set_value(&mut self, value: Option<u32>) {
+        match self {
+            Self::Sleep(_) => *self = Self::Sleep(value),
+            Self::Energy(_) => *self = Self::Energy(value),
+            Self::Flow(_) => *self = Self::Flow(value),
+            Self::Hydration(_) => *self = Self::Hydration(value),
+            Self::Health(_) => *self = Self::Health(value),
+            Self::Satisfaction(_) => *self = Self::Satisfaction(value),
+            Self::Stress(_) => *self = Self::Stress(value),
+        }
+    }
+
+    /// Gid of the Asana custom field backing the stat.
+    #[must_use]
+    pub fn field_gid(&self) -> &'static str {
+        match self {
+            Self::Sleep(_) => "1204172638538713",
+            Self::Energy(_) => "1204172638540767",
+            Self::Flow(_) => "1204172638540769",
+            Self::Hydration(_) => "1204172638540771",
+            Self::Health(_) => "1204172638540773",
+            Self::Satisfaction(_) => "1204172638540775",
+            Self::Stress(_) => "1204172638540777",
+        }
+    }
+}
+
+impl TryFrom<FocusTaskCustomField> for FocusDayStat {
+    type Error = anyhow::Error;
+
+    fn try_from(custom_field: FocusTaskCustomField) -> Result<Self, Self::Error> {
+        Ok(match custom_field.gid.as_str() {
+            "1204172638538713" => Self::Sleep(custom_field.number_value),
+            "1204172638540767" => Self::Energy(custom_field.number_value),
+            "1204172638540769" => Self::Flow(custom_field.number_value),
+            "1204172638540771" => Self::Hydration(custom_field.number_value),
+            "1204172638540773" => Self::Health(custom_field.number_value),
+            "1204172638540775" => Self::Satisfaction(custom_field.number_value),
+            "1204172638540777" => Self::Stress(custom_field.number_value),
+            gid => anyhow::bail!("unknown focus day stat gid: {}", gid),
+        })
+    }
+}
+
+impl Display for FocusDayStat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{name}={value}",
+            name = self.name(),
+            value = self.value().map_or("-".to_string(), |v| v.to_string())
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section(name: &str) -> Section {
+        Section {
+            gid: "1".to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    fn week(from: &str, to: &str) -> FocusWeek {
+        FocusWeek {
+            section: section(&format!("Daily Focuses ({from} to {to})")),
+            from: from.parse().unwrap(),
+            to: to.parse().unwrap(),
+        }
+    }
+
+    fn day(date: &str) -> FocusDay {
+        FocusDay {
+            task: FocusTask {
+                gid: "1".to_string(),
+                name: format!("Daily Focus for Monday ({date})"),
+                notes: String::new(),
+                modified_at: None,
+                custom_fields: None,
+            },
+            date: date.parse().unwrap(),
+            stats: FocusDayStats::default(),
+            diary: String::new(),
+            prompts: vec![],
+            subtasks: None,
+        }
+    }
+
+    fn at(hour: u32) -> chrono::DateTime<chrono::Local> {
+        use chrono::TimeZone as _;
+        chrono::Local
+            .with_ymd_and_hms(2024, 1, 15, hour, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn the_phase_honors_the_clock_past_dates_and_force_eod() {
+        // (day date, hour, force_eod) -> phase
+        let cases = [
+            (("2024-01-15", 9, false), FocusPhase::Morning),
+            (("2024-01-15", 19, false), FocusPhase::Morning),
+            (("2024-01-15", 20, false), FocusPhase::Evening),
+            (("2024-01-15", 23, false), FocusPhase::Evening),
+            (("2024-01-10", 9, false), FocusPhase::Evening),
+            (("2024-01-15", 9, true), FocusPhase::Evening),
+        ];
+        for ((date, hour, force_eod), phase) in cases {
+            assert_eq!(
+                day(date).phase(at(hour), force_eod),
+                phase,
+                "date={date} hour={hour} force_eod={force_eod}"
+            );
+        }
+    }
+
+    #[test]
+    fn pending_stats_follow_the_phase() {
+        let mut day = day("2024-01-15");
+        day.stats.sleep.set_value(Some(7));
+
+        let names = |stats: Vec<&FocusDayStat>| -> Vec<&str> {
+            stats.into_iter().map(FocusDayStat::name).collect()
+        };
+        assert_eq!(names(day.pending_stats(at(9), false)), ["energy"]);
+        assert_eq!(
+            names(day.pending_stats(at(21), false)),
+            ["energy", "flow", "hydration", "health", "satisfaction", "stress"]
+        );
+        assert_eq!(
+            names(day.pending_stats(at(9), true)),
+            names(day.pending_stats(at(21), false))
+        );
+    }
+
+    #[test]
+    fn draft_stats_prefill_only_the_entered_stats() {
+        let draft = FocusDraft {
+            date: "2024-01-15".parse().unwrap(),
+            stats: vec![("1204172638538713".to_string(), 4)],
+            diary: None,
+            pending_subtasks: vec!["water the plants".to_string()],
+        };
+
+        let mut stats = FocusDayStats::default();
+        draft.apply_to(&mut stats);
+
+        assert_eq!(stats.sleep.value(), Some(4));
+        assert_eq!(stats.energy.value(), None);
+    }
+
+    #[test]
+    fn the_line_renderer_pins_full_partial_and_empty_days() {
+        console::set_colors_enabled(false);
+        let mut full = day("2024-01-15");
+        full.stats.sleep.set_value(Some(7));
+        full.stats.energy.set_value(Some(6));
+        full.stats.flow.set_value(Some(5));
+        full.stats.hydration.set_value(Some(4));
+        full.stats.health.set_value(Some(8));
+        full.stats.satisfaction.set_value(Some(7));
+        full.stats.stress.set_value(Some(3));
+        full.diary = "shipped the refactor and closed out the sprint".to_string();
+
+        assert_eq!(
+            full.to_line_string(120),
+            "Mon 2024-01-15  s7 e6 f5 h4 H8 S7 st3  \"shipped the refactor and closed out \
+             the sprint\""
+        );
+        // A tight width truncates the diary on a char boundary with an ellipsis...
+        assert_eq!(
+            full.to_line_string(50),
+            "Mon 2024-01-15  s7 e6 f5 h4 H8 S7 st3  \"shipped …\""
+        );
+        // ...and one with no room for a snippet drops the diary, never the fixed parts.
+        assert_eq!(
+            full.to_line_string(40),
+            "Mon 2024-01-15  s7 e6 f5 h4 H8 S7 st3"
+        );
+
+        let mut partial = day("2024-01-15");
+        partial.stats.sleep.set_value(Some(7));
+        assert_eq!(
+            partial.to_line_string(80),
+            "Mon 2024-01-15  s7 e- f- h- H- S- st-"
+        );
+
+        let empty = day("2024-01-15");
+        assert_eq!(
+            empty.to_line_string(80),
+            "Mon 2024-01-15  s- e- f- h- H- S- st-"
+        );
+        assert!(!empty.to_line_string(80).contains('\n'));
+    }
+
+    #[test]
+    fn stat_initials_stay_unique_as_names_collide() {
+        assert_eq!(
+            stat_initials(&["sleep", "energy", "flow", "hydration", "health", "satisfaction", "stress"]),
+            ["s", "e", "f", "h", "H", "S", "st"]
+        );
+    }
+
+    #[test]
+    fn prompt_blocks_round_trip_through_the_notes() {
+        let answers = vec![
+            PromptAnswer {
+                key: "gratitude".to_string(),
+                answer: "the quiet morning".to_string(),
+            },
+            PromptAnswer {
+                key: "priority".to_string(),
+                answer: "ship the release\nthen rest".to_string(),
+            },
+        ];
+        let notes = join_prompt_block("a good day", &answers);
+        assert_eq!(split_prompt_block(&notes), ("a good day".to_string(), answers.clone()));
+
+        // No answers means the notes are exactly the diary, and the other way around.
+        assert_eq!(join_prompt_block("just a diary", &[]), "just a diary");
+        assert_eq!(
+            split_prompt_block("just a diary"),
+            ("just a diary".to_string(), vec![])
+        );
+
+        // A day that is all answers and no diary round-trips too.
+        let (diary, parsed) = split_prompt_block(&join_prompt_block("", &answers));
+        assert!(diary.is_empty());
+        assert_eq!(parsed, answers);
+    }
+
+    #[test]
+    fn hand_edited_prompt_blocks_never_corrupt_the_diary() {
+        // A half-deleted block (no closing marker) stays part of the diary rather than
+        // silently vanishing on the next sync.
+        let (diary, answers) = split_prompt_block("entry\n\n<!-- todo:prompts -->\n[key]\nkept");
+        assert!(diary.contains("entry"));
+        assert!(diary.contains("kept"));
+        assert!(answers.is_empty());
+
+        // Stray text before the first key and blank answers are dropped, and diary text on
+        // both sides of the block is stitched back together.
+        let notes = "entry\n\n<!-- todo:prompts -->\nstray\n[empty]\n\n[kept]\nanswer\n\
+                     <!-- /todo:prompts -->\n\ntrailing thought";
+        let (diary, answers) = split_prompt_block(notes);
+        assert_eq!(diary, "entry\n\ntrailing thought");
+        assert_eq!(
+            answers,
+            vec![PromptAnswer {
+                key: "kept".to_string(),
+                answer: "answer".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn answered_prompts_render_under_a_prompts_heading() {
+        console::set_colors_enabled(false);
+        let mut day = day("2024-01-15");
+        assert!(!day.to_full_string(None).contains("Prompts"));
+
+        day.prompts.push(PromptAnswer {
+            key: "gratitude".to_string(),
+            answer: "tea\nand quiet".to_string(),
+        });
+        let rendered = day.to_full_string(None);
+        assert!(rendered.contains("💬 Prompts"));
+        assert!(rendered.contains("gratitude: tea\n      and quiet"));
+    }
+
+    #[test]
+    fn the_compact_overview_omits_unfilled_stats() {
+        let mut day = day("2024-01-15");
+        assert!(day.to_full_string(None).contains("Statistics"));
+        // With nothing filled in, the whole statistics section disappears.
+        assert!(!day.to_compact_string(None).contains("Statistics"));
+
+        day.stats.sleep.set_value(Some(7));
+        let compact = day.to_compact_string(None);
+        assert!(compact.contains("sleep"));
+        assert!(!compact.contains("energy"));
+        assert!(day.to_full_string(None).contains("energy"));
+    }
+
+    #[test]
+    fn sync_diff_reports_changed_stats_and_diary_growth() {
+        let old_stats = FocusDayStats::default();
+        let mut new_stats = old_stats.clone();
+        new_stats.sleep.set_value(Some(4));
+
+        let diff = FocusSyncDiff::compute(&old_stats, &new_stats, "short", "a longer entry");
+
+        assert_eq!(diff.stat_changes, ["sleep: - → 4"]);
+        assert_eq!(diff.diary_change.as_deref(), Some("+9 chars"));
+        assert!(!diff.diary_cleared);
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn sync_diff_flags_a_cleared_diary() {
+        let stats = FocusDayStats::default();
+        let diff = FocusSyncDiff::compute(&stats, &stats, "something precious", "");
+
+        assert!(diff.stat_changes.is_empty());
+        assert_eq!(diff.diary_change.as_deref(), Some("cleared!"));
+        assert!(diff.diary_cleared);
+    }
+
+    #[test]
+    fn sync_diff_is_empty_when_nothing_changed() {
+        let stats = FocusDayStats::default();
+        let diff = FocusSyncDiff::compute(&stats, &stats, "same", "same");
+
+        assert!(diff.is_empty());
+        assert!(diff.diary_change.is_none());
+    }
+
+    #[test]
+    fn archive_cutoff_keeps_the_most_recent_weeks() {
+        let today: NaiveDate = "2024-03-04".parse().unwrap();
+        // Ended exactly 8 weeks ago: kept.
+        assert!(!week("2024-01-02", "2024-01-08").is_past_archive_cutoff(today, 8));
+        // Ended a day earlier: archived.
+        assert!(week("2024-01-01", "2024-01-07").is_past_archive_cutoff(today, 8));
+        // `--keep-weeks 0` archives every finished week.
+        assert!(week("2024-02-26", "2024-03-03").is_past_archive_cutoff(today, 0));
+    }
+
+    #[test]
+    fn archived_sections_no_longer_parse_as_focus_weeks() {
+        let week = week("2024-01-01", "2024-01-07");
+        assert_eq!(
+            week.archived_name(),
+            "[archived] Daily Focuses (2024-01-01 to 2024-01-07)"
+        );
+        assert!(FocusWeek::try_from(section(&week.archived_name())).is_err());
+        assert!(FocusWeek::try_from(section(&week.section.name)).is_ok());
+        assert!(!week.archived_name().starts_with("Daily Focuses"));
+    }
+
+    // One fully populated sample per request type, so every `fields()` list is checked against
+    // the struct's serde field names and a typo fails here instead of returning nulls at runtime.
+
+    #[test]
+    fn section_opt_fields_match_the_struct() {
+        crate::asana::assert_opt_fields_match(&section("Daily Focuses (2024-01-01 to 2024-01-07)"));
+    }
+
+    #[test]
+    fn focus_task_opt_fields_match_the_struct() {
+        crate::asana::assert_opt_fields_match(&FocusTask {
+            gid: "1".to_string(),
+            name: "Daily Focus for Monday (2024-01-15)".to_string(),
+            notes: "diary".to_string(),
+            modified_at: Some("2024-01-15T21:00:00Z".parse().unwrap()),
+            custom_fields: Some(vec![FocusTaskCustomField {
+                gid: "f1".to_string(),
+                number_value: Some(5),
+            }]),
+        });
+    }
+
+    #[test]
+    fn weekly_goals_parse_lines_with_optional_completion_markers() {
+        let goals = parse_weekly_goals("[x] ship the release\n\n  [ ] write the retro\nread a paper\n");
+        assert_eq!(
+            goals,
+            vec![
+                WeeklyGoal {
+                    text: "ship the release".to_string(),
+                    done: true,
+                },
+                WeeklyGoal {
+                    text: "write the retro".to_string(),
+                    done: false,
+                },
+                WeeklyGoal {
+                    text: "read a paper".to_string(),
+                    done: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn weekly_goals_round_trip_through_the_notes_format() {
+        let goals = parse_weekly_goals("[X] one\ntwo\n[ ] three");
+        let notes = render_weekly_goals(&goals);
+        assert_eq!(notes, "[x] one\n[ ] two\n[ ] three\n");
+        assert_eq!(parse_weekly_goals(&notes), goals);
+    }
+
+    fn entered(day: &str, hour: u32) -> chrono::DateTime<chrono::Local> {
+        use chrono::TimeZone as _;
+        let date: NaiveDate = day.parse().unwrap();
+        chrono::Local
+            .with_ymd_and_hms(date.year(), date.month(), date.day(), hour, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn recording_dedupes_re_entries_and_keeps_corrections() {
+        let date: NaiveDate = "2024-01-15".parse().unwrap();
+        let mut entries = Vec::new();
+        record_stat_entry(&mut entries, date, "sleep", 7, entered("2024-01-15", 7));
+        // A resumed draft replays the same value; the original timestamp survives.
+        record_stat_entry(&mut entries, date, "sleep", 7, entered("2024-01-15", 9));
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].entered_at, entered("2024-01-15", 7));
+
+        // A correction gets its own entry, so the audit log keeps the original.
+        record_stat_entry(&mut entries, date, "sleep", 5, entered("2024-01-16", 8));
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].value, 5);
+    }
+
+    #[test]
+    fn audited_rendering_marks_backfills_but_not_same_day_entries() {
+        let mut focus_day = day("2024-01-15");
+        focus_day.stats.sleep.set_value(Some(7));
+        focus_day.stats.energy.set_value(Some(6));
+        let mut entries = Vec::new();
+        record_stat_entry(
+            &mut entries,
+            focus_day.date,
+            "sleep",
+            7,
+            entered("2024-01-15", 7),
+        );
+        record_stat_entry(
+            &mut entries,
+            focus_day.date,
+            "energy",
+            6,
+            entered("2024-01-17", 9),
+        );
+
+        let rendered = focus_day.to_audited_string(None, &entries);
+        assert!(rendered.contains("(entered 2d late)"));
+        assert!(!rendered.contains("(entered 0d late)"));
+        // Only the backfilled stat carries a note.
+        assert_eq!(rendered.matches("late)").count(), 1);
+    }
+
+    #[test]
+    fn the_audit_log_filters_to_the_range_and_flags_lateness() {
+        let mut entries = Vec::new();
+        record_stat_entry(
+            &mut entries,
+            "2024-01-15".parse().unwrap(),
+            "sleep",
+            7,
+            entered("2024-01-18", 9),
+        );
+        record_stat_entry(
+            &mut entries,
+            "2024-01-10".parse().unwrap(),
+            "energy",
+            4,
+            entered("2024-01-10", 8),
+        );
+
+        let audit = render_stat_audit(
+            &entries,
+            "2024-01-14".parse().unwrap(),
+            "2024-01-15".parse().unwrap(),
+        );
+        assert!(audit.contains("2024-01-15"));
+        assert!(audit.contains("(3d late)"));
+        assert!(!audit.contains("energy"));
+    }
+
+    #[test]
+    fn focus_task_snapshot_opt_fields_match_the_struct() {
+        crate::asana::assert_opt_fields_match(&FocusTaskSnapshot {
+            gid: "1".to_string(),
+            notes: "diary".to_string(),
+            modified_at: Some("2024-01-15T21:00:00Z".parse().unwrap()),
+        });
+    }
+
+    #[test]
+    fn focus_task_subtask_opt_fields_match_the_struct() {
+        crate::asana::assert_opt_fields_match(&FocusTaskSubtask {
+            gid: "1".to_string(),
+            name: "subtask".to_string(),
+            completed: false,
+            due_on: Some("2024-01-15".parse().unwrap()),
+        });
+    }
+}
@@ -0,0 +1,6 @@
+//! Serialization of cached tasks into other task managers' formats.
+//!
+//! Each format gets its own struct set here instead of reusing [`crate::task::UserTask`]'s
+//! serde, which is tuned to the Asana API and cache formats and would leak their field names.
+
+pub mod taskwarrior;
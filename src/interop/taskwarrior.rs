@@ -0,0 +1,155 @@
+//! Taskwarrior's JSON import format, suitable for piping into `task import`.
+
+use chrono::{NaiveDate, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::context::GroupedTasks;
+use crate::task::UserTask;
+
+/// Which due bucket of tasks to export.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum TaskwarriorFilter {
+    /// Only overdue tasks.
+    Overdue,
+    /// Only tasks due today.
+    Today,
+    /// Only tasks due within a week.
+    Week,
+}
+
+/// Single task in taskwarrior's import format.
+#[derive(Debug, Serialize)]
+struct TaskwarriorTask {
+    uuid: String,
+    description: String,
+    status: &'static str,
+    entry: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    due: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+}
+
+impl From<&UserTask> for TaskwarriorTask {
+    fn from(task: &UserTask) -> Self {
+        Self {
+            // Deriving the UUID from the permalink keeps re-imports updating the same
+            // taskwarrior task instead of duplicating it.
+            uuid: Uuid::new_v5(
+                &Uuid::NAMESPACE_URL,
+                crate::render::task_permalink(&task.gid).as_bytes(),
+            )
+            .to_string(),
+            description: task.name.clone(),
+            status: "pending",
+            entry: task
+                .created_at
+                .with_timezone(&Utc)
+                .format("%Y%m%dT%H%M%SZ")
+                .to_string(),
+            due: task
+                .due_on
+                .map(|due| format!("{}T000000Z", due.format("%Y%m%d"))),
+            tags: task.projects.iter().map(|project| tag(&project.name)).collect(),
+        }
+    }
+}
+
+/// Taskwarrior tags cannot contain whitespace, so project names collapse onto underscores.
+fn tag(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<&str>>().join("_")
+}
+
+/// Render tasks as a taskwarrior-compatible JSON array, optionally restricted to one of the
+/// [`GroupedTasks`] due buckets.
+///
+/// # Errors
+///
+/// This function will return an error if the tasks could not be serialized.
+pub fn render(
+    tasks: &[UserTask],
+    filter: Option<TaskwarriorFilter>,
+    today: NaiveDate,
+) -> anyhow::Result<String> {
+    let grouped = GroupedTasks::group(tasks, today);
+    let selected: Vec<&UserTask> = match filter {
+        None => tasks.iter().collect(),
+        Some(TaskwarriorFilter::Overdue) => grouped.overdue.clone(),
+        Some(TaskwarriorFilter::Today) => grouped.due_today.clone(),
+        Some(TaskwarriorFilter::Week) => grouped.due_week.clone(),
+    };
+    let rows: Vec<TaskwarriorTask> = selected.into_iter().map(TaskwarriorTask::from).collect();
+    Ok(serde_json::to_string_pretty(&rows)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Local, TimeZone};
+
+    use crate::task::ProjectRef;
+
+    use super::*;
+
+    fn task(gid: &str, due_on: Option<&str>) -> UserTask {
+        UserTask {
+            gid: gid.to_string(),
+            created_at: Local.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap(),
+            due_on: due_on.map(|d| d.parse().unwrap()),
+            name: format!("task {gid}"),
+            projects: Vec::new(),
+            custom_fields: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn uuids_are_deterministic_per_gid() {
+        let tasks = vec![task("1", None)];
+        let today = "2024-01-15".parse().unwrap();
+        let first = render(&tasks, None, today).unwrap();
+        let second = render(&tasks, None, today).unwrap();
+        assert_eq!(first, second);
+        assert_ne!(
+            render(&[task("2", None)], None, today).unwrap(),
+            render(&[task("1", None)], None, today)
+                .unwrap()
+                .replace("task 1", "task 2")
+        );
+    }
+
+    #[test]
+    fn filters_select_one_due_bucket() {
+        let tasks = vec![
+            task("1", Some("2024-01-10")),
+            task("2", Some("2024-01-15")),
+            task("3", Some("2024-01-18")),
+            task("4", None),
+        ];
+        let today = "2024-01-15".parse().unwrap();
+        for (filter, name) in [
+            (TaskwarriorFilter::Overdue, "task 1"),
+            (TaskwarriorFilter::Today, "task 2"),
+            (TaskwarriorFilter::Week, "task 3"),
+        ] {
+            let json = render(&tasks, Some(filter), today).unwrap();
+            let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed.as_array().unwrap().len(), 1);
+            assert_eq!(parsed[0]["description"], name);
+        }
+        let all: serde_json::Value =
+            serde_json::from_str(&render(&tasks, None, today).unwrap()).unwrap();
+        assert_eq!(all.as_array().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn tags_collapse_project_name_whitespace() {
+        let mut tagged = task("1", None);
+        tagged.projects = vec![ProjectRef {
+            gid: "p1".to_string(),
+            name: "Side Projects".to_string(),
+        }];
+        let json = render(&[tagged], None, "2024-01-15".parse().unwrap()).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed[0]["tags"][0], "Side_Projects");
+    }
+}
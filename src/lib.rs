@@ -2,4 +2,23 @@
 #![warn(missing_docs)]
 #![warn(clippy::pedantic)]
 
+#[cfg(feature = "cli")]
+pub mod app;
 pub mod asana;
+pub mod cache;
+#[cfg(feature = "cli")]
+pub mod cli;
+#[cfg(feature = "cli")]
+pub mod commands;
+pub mod config;
+pub mod context;
+pub mod daily_note;
+#[cfg(feature = "cli")]
+pub mod errors;
+pub mod focus;
+#[cfg(feature = "cli")]
+pub mod interop;
+#[cfg(feature = "cli")]
+pub mod render;
+pub mod task;
+pub mod utils;
@@ -3,587 +3,375 @@
 use std::{
     collections::HashMap,
     env,
-    fmt::Display,
     fs,
+    io::IsTerminal as _,
     path::{Path, PathBuf},
 };
 
 use anyhow::Context;
-use chrono::{DateTime, Datelike, Days, Local, NaiveDate, Timelike, Weekday};
-use clap::{Parser, Subcommand};
+use chrono::{Datelike, Local, NaiveDate, Timelike, Weekday};
+use clap::Parser;
 use console::{style, Term};
-use dialoguer::{theme::ColorfulTheme, Input};
+use dialoguer::{theme::ColorfulTheme, Confirm, Input, Select};
 use futures::future::join_all;
 use human_panic::setup_panic;
-use regex::Regex;
-use reqwest::{Method, Url};
+use reqwest::Method;
 use serde::{Deserialize, Serialize};
+use tracing::Instrument as _;
 
 use todo::asana::{
-    ask_for_pat, execute_authorization_flow, Client, Credentials, DataRequest, DataWrapper,
+    ask_for_pat, execute_authorization_flow, Client, Credentials, MutationRequest,
+};
+use todo::app::{client_from_config, CreateTaskRequest, UpdateTaskRequest};
+use todo::cache;
+use todo::cli::{
+    Args, Command, ConfigCommand, ExportCommand, FocusCommand, InstallCommand, ReportCommand,
+};
+use todo::commands::agenda::FocusMarker;
+use todo::commands::count::CountFormat;
+use todo::commands::focus::{
+    ensure_focus_day, ensure_weekly_goals_task, fetch_focus_week_days, find_focus_day,
+    find_weekly_goals_task,
+};
+use todo::commands::gate;
+use todo::commands::install::UninstallTarget;
+use todo::commands::list::{GroupBy, LinkMode, ListFormat};
+use todo::commands::log::LogFormat;
+use todo::commands::notify;
+use todo::commands::pause;
+use todo::commands::status::{Status, StatusFormat, StatusSymbols};
+use todo::commands::update::{UpdateFormat, UpdateReport};
+use todo::context::{task_or_tasks, AppContext, GroupedTasks, OutputMode, StatusLine};
+use todo::focus::{
+    FocusDay, FocusDayStat, FocusDayStats, FocusDraft, FocusPhase, FocusSyncDiff, FocusTask,
+    FocusTaskSnapshot, FocusTaskSubtask, FocusWeek, PromptAnswer, Section,
+    ASANA_FOCUS_PROJECT_GID, START_HOUR_FOR_EOD,
+};
+use todo::task::{
+    CompletedTask, Project, User, UserTask, UserTaskList, Workspace, WorkspaceUser,
+    ASANA_WORKSPACE_GID,
 };
-
-const ASANA_WORKSPACE_GID: &str = "1199118829113557";
-const ASANA_FOCUS_PROJECT_GID: &str = "1200179899177794";
-
-const FOCUS_WEEK_PATTERN: &str =
-    r"^Daily Focuses \((?<from>\d{4}-\d{2}-\d{2}) to (?<to>\d{4}-\d{2}-\d{2})\)$";
-const FOCUS_DAY_PATTERN: &str = r"^Daily Focus for \w+ \((?<date>\d{4}-\d{2}-\d{2})\)$";
-
-/// The hour of the day at which the end of day is considered to be starting.
-const START_HOUR_FOR_EOD: u32 = 20;
-
-/// Todo is a simple Asana helper script that pulls data from Asana and shows it in CLI settings
-#[derive(Debug, Parser)]
-#[command(author, version, about, long_about = None)]
-struct Args {
-    /// Path to the cache file
-    #[arg(long, default_value = "~/.cache/todo/cache.json")]
-    cache_path: PathBuf,
-
-    /// Path to the configuration file
-    #[arg(long, default_value = "~/.config/todo/config.toml")]
-    config_path: PathBuf,
-
-    /// If set, uses the discouraged PAT flow (instead of OAuth)
-    #[arg(long)]
-    use_pat: bool,
-
-    /// If set, uses the cache instead of pulling from Asana
-    #[arg(long)]
-    use_cache: bool,
-
-    #[command(subcommand)]
-    command: Command,
-}
-
-#[derive(Debug, Subcommand)]
-enum Command {
-    /// Print out a summary of current todo tasks
-    Summary,
-
-    /// Print out a list of todo tasks ordered by due date
-    List,
-
-    /// Manage the Focus project
-    Focus {
-        /// The date to focus on
-        #[arg(long)]
-        date: Option<NaiveDate>,
-
-        /// If set, forces the end of day to be considered to be starting
-        #[arg(long, default_value = "false")]
-        force_eod: bool,
-
-        #[command(subcommand)]
-        command: Option<FocusCommand>,
-    },
-
-    /// Pull and cache information about todo task and focus, without printing anything
-    Update,
-}
-
-#[derive(Debug, Subcommand)]
-enum FocusCommand {
-    /// Run the focus routine
-    Run,
-    /// Print out an overview of the focus day
-    Overview,
-}
-
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
-struct Cache {
-    creds: Option<Credentials>,
-    user_task_list: Option<UserTaskList>,
-    tasks: Option<Vec<UserTask>>,
-    last_updated: Option<DateTime<Local>>,
-}
-
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
-struct Config {}
-
-#[derive(Clone, Debug, Deserialize, Serialize)]
-struct UserTask {
-    gid: String,
-    #[serde(with = "todo::asana::serde_formats::datetime")]
-    created_at: DateTime<Local>,
-    #[serde(with = "todo::asana::serde_formats::optional_date")]
-    due_on: Option<NaiveDate>,
-    name: String,
-}
-
-impl<'a> DataRequest<'a> for UserTask {
-    type RequestData = String;
-    type ResponseData = Vec<Self>;
-
-    fn segments(request_data: &'a Self::RequestData) -> Vec<String> {
-        vec![
-            "user_task_lists".to_string(),
-            request_data.clone(),
-            "tasks".to_string(),
-        ]
-    }
-
-    fn fields() -> &'a [&'a str] {
-        &["this.gid", "this.created_at", "this.due_on", "this.name"]
-    }
-
-    fn params() -> Vec<(&'a str, String)> {
-        vec![("completed_since", "now".to_string())]
-    }
-}
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
-struct UserTaskList {
-    gid: String,
+struct UpdateFocusTaskCustomFieldsRequest {
+    notes: String,
+    custom_fields: HashMap<String, u32>,
 }
 
-impl<'a> DataRequest<'a> for UserTaskList {
+impl<'a> MutationRequest<'a> for UpdateFocusTaskCustomFieldsRequest {
     type RequestData = String;
-    type ResponseData = Self;
-
-    fn segments(user_gid: &'a Self::RequestData) -> Vec<String> {
-        vec![
-            "users".to_string(),
-            user_gid.clone(),
-            "user_task_list".to_string(),
-        ]
-    }
+    type ResponseData = serde_json::Value;
 
-    fn fields() -> &'a [&'a str] {
-        &["this.gid"]
+    fn segments(task_gid: &'a Self::RequestData) -> Vec<String> {
+        vec!["tasks".to_string(), task_gid.clone()]
     }
 
-    fn params() -> Vec<(&'a str, String)> {
-        vec![("workspace", ASANA_WORKSPACE_GID.to_string())]
+    fn method() -> Method {
+        Method::PUT
     }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
-struct Section {
-    gid: String,
+struct UpdateSectionRequest {
     name: String,
 }
 
-impl<'a> DataRequest<'a> for Section {
+impl<'a> MutationRequest<'a> for UpdateSectionRequest {
     type RequestData = String;
-    type ResponseData = Vec<Self>;
+    type ResponseData = serde_json::Value;
 
-    fn segments(request_data: &'a Self::RequestData) -> Vec<String> {
-        vec![
-            "projects".to_string(),
-            request_data.clone(),
-            "sections".to_string(),
-        ]
+    fn segments(section_gid: &'a Self::RequestData) -> Vec<String> {
+        vec!["sections".to_string(), section_gid.clone()]
     }
 
-    fn fields() -> &'a [&'a str] {
-        &["this.gid", "this.name"]
+    fn method() -> Method {
+        Method::PUT
     }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
-struct CreateSectionRequest {
-    name: String,
-    insert_before: String,
-}
-
-#[derive(Clone, Debug, Deserialize, Serialize)]
-struct FocusTask {
-    gid: String,
-    name: String,
+struct UpdateTaskNotesRequest {
     notes: String,
-    custom_fields: Option<Vec<FocusTaskCustomField>>,
 }
 
-impl<'a> DataRequest<'a> for FocusTask {
+impl<'a> MutationRequest<'a> for UpdateTaskNotesRequest {
     type RequestData = String;
-    type ResponseData = Vec<Self>;
+    type ResponseData = serde_json::Value;
 
-    fn segments(request_data: &'a Self::RequestData) -> Vec<String> {
-        vec![
-            "sections".to_string(),
-            request_data.clone(),
-            "tasks".to_string(),
-        ]
+    fn segments(task_gid: &'a Self::RequestData) -> Vec<String> {
+        vec!["tasks".to_string(), task_gid.clone()]
     }
 
-    fn fields() -> &'a [&'a str] {
-        &[
-            "this.gid",
-            "this.name",
-            "this.notes",
-            "this.custom_fields",
-            "this.custom_fields.gid",
-            "this.custom_fields.number_value",
-        ]
+    fn method() -> Method {
+        Method::PUT
     }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
-struct FocusTaskCustomField {
-    gid: String,
-    number_value: Option<u32>,
-}
-
-#[derive(Clone, Debug, Deserialize, Serialize)]
-struct UpdateFocusTaskCustomFieldsRequest {
-    notes: String,
-    custom_fields: HashMap<String, u32>,
-}
-
-#[derive(Clone, Debug, Deserialize, Serialize)]
-struct CreateSectionTaskRequest {
-    name: String,
-    projects: Vec<String>,
-    memberships: Vec<CreateSectionTaskRequestMembership>,
-}
-
-#[derive(Clone, Debug, Deserialize, Serialize)]
-struct CreateSectionTaskRequestMembership {
-    project: String,
-    section: String,
-}
-
-#[derive(Clone, Debug, Deserialize, Serialize)]
-struct AddTaskToSectionRequest {
-    task: String,
-    insert_after: String,
-}
-
-#[derive(Clone, Debug, Deserialize, Serialize)]
-struct FocusTaskSubtask {
-    gid: String,
+struct CreateSubtaskRequest {
     name: String,
-    completed: bool,
+    assignee: String,
+    #[serde(with = "todo::asana::serde_formats::optional_date")]
+    due_on: Option<NaiveDate>,
 }
 
-impl DataRequest<'_> for FocusTaskSubtask {
+impl<'a> MutationRequest<'a> for CreateSubtaskRequest {
     type RequestData = String;
-    type ResponseData = Vec<Self>;
+    type ResponseData = serde_json::Value;
 
-    fn segments(request_data: &Self::RequestData) -> Vec<String> {
+    fn segments(parent_gid: &'a Self::RequestData) -> Vec<String> {
         vec![
             "tasks".to_string(),
-            request_data.clone(),
+            parent_gid.clone(),
             "subtasks".to_string(),
         ]
     }
 
-    fn fields() -> &'static [&'static str] {
-        &["this.gid", "this.name", "this.completed"]
+    fn method() -> Method {
+        Method::POST
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-struct CreateSubtaskRequest {
-    name: String,
-    assignee: String,
-    #[serde(with = "todo::asana::serde_formats::optional_date")]
-    due_on: Option<NaiveDate>,
-}
-
-#[derive(Clone, Debug, Deserialize, Serialize)]
-struct FocusWeek {
-    section: Section,
-    from: NaiveDate,
-    to: NaiveDate,
-}
-
-impl TryFrom<Section> for FocusWeek {
-    type Error = anyhow::Error;
-
-    fn try_from(section: Section) -> Result<Self, Self::Error> {
-        let captures = Regex::new(FOCUS_WEEK_PATTERN)
-            .context("unable to parse focus section pattern")?
-            .captures(&section.name)
-            .context(section.name.clone())?;
-        Ok(Self {
-            section: section.clone(),
-            from: NaiveDate::parse_from_str(&captures["from"], "%Y-%m-%d")
-                .context(section.name.clone())?,
-            to: NaiveDate::parse_from_str(&captures["to"], "%Y-%m-%d")
-                .context(section.name.clone())?,
-        })
-    }
-}
 
-impl Display for FocusWeek {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "Focus Week ({from} to {to})",
-            from = self.from.format("%Y-%m-%d"),
-            to = self.to.format("%Y-%m-%d")
-        )
+/// Write an integration block into a configuration file (idempotently replacing any previous
+/// block), backing the file up first; with `dry_run`, print the change instead.
+fn write_integration_block(path: &Path, block: &str, dry_run: bool) -> anyhow::Result<()> {
+    let contents = if path.exists() {
+        fs::read_to_string(path)
+            .with_context(|| format!("could not read {}", path.display()))?
+    } else {
+        String::new()
+    };
+    let updated = todo::commands::install::upsert_integration_block(&contents, block);
+    if updated == contents {
+        println!("{} is already up to date", path.display());
+        return Ok(());
     }
-}
-
-#[derive(Clone, Debug, Deserialize, Serialize)]
-struct FocusDay {
-    task: FocusTask,
-    date: NaiveDate,
-    stats: FocusDayStats,
-    diary: String,
-    subtasks: Option<Vec<FocusTaskSubtask>>,
-}
 
-impl FocusDay {
-    fn to_full_string(&self) -> String {
-        let mut string = String::new();
-
-        string.push_str(&format!(
-            "🧠 {} {}",
-            style(format!(
-                "Focus Day: {}",
-                style(self.date.weekday().to_string()).blue()
-            ))
-            .bold(),
-            style(format!("({})", self.date.format("%Y-%m-%d"))).dim(),
-        ));
-        string.push_str(&format!(
-            "\n\n{}",
-            if self.diary.is_empty() {
-                style("no diary entry — yet.").dim()
-            } else {
-                style(self.diary.as_str())
-            },
-        ));
-        string.push_str(&format!("\n\n{}\n", style("❤️ Statistics").bold().cyan()));
-
-        for stat in self.stats.stats() {
-            let line = format!(
-                "{name}: {value}",
-                name = style(stat.name().to_string()).bold(),
-                value = style(stat.value().map_or("-".to_string(), |v| v.to_string()))
-            );
-            string.push_str(&format!(
-                "   {}\n",
-                if stat.value().is_some() {
-                    style(line)
-                } else {
-                    style(line).dim()
-                }
-            ));
+    if dry_run {
+        println!("--- {}", path.display());
+        println!("+++ {}", path.display());
+        if let Some(existing) = todo::commands::install::find_integration_block(&contents) {
+            for line in existing.lines() {
+                println!("-{line}");
+            }
+        }
+        for line in block.lines() {
+            println!("+{line}");
         }
-        string
+        return Ok(());
     }
 
-    async fn load_subtasks(&mut self, client: &mut Client) -> anyhow::Result<&[FocusTaskSubtask]> {
-        let subtasks = client.get::<FocusTaskSubtask>(&self.task.gid).await?;
-        self.subtasks = Some(subtasks);
-        Ok(self.subtasks.as_ref().unwrap())
+    if path.exists() {
+        let backup = path.with_extension(format!(
+            "bak.{timestamp}",
+            timestamp = Local::now().format("%Y%m%d%H%M%S")
+        ));
+        fs::copy(path, &backup)
+            .with_context(|| format!("could not back up {}", path.display()))?;
+        println!("Backed up {} to {}", path.display(), backup.display());
+    } else if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("could not create path to {}", path.display()))?;
     }
+    fs::write(path, updated).with_context(|| format!("could not write {}", path.display()))?;
+    println!("Wrote {}", path.display());
+    Ok(())
 }
 
-impl TryFrom<FocusTask> for FocusDay {
-    type Error = anyhow::Error;
-
-    fn try_from(task: FocusTask) -> Result<Self, Self::Error> {
-        let captures = Regex::new(FOCUS_DAY_PATTERN)
-            .context("unable to parse focus section pattern")?
-            .captures(&task.name)
-            .context(task.name.clone())?;
-        Ok(Self {
-            task: task.clone(),
-            date: NaiveDate::parse_from_str(&captures["date"], "%Y-%m-%d")
-                .context(task.name.clone())?,
-            stats: task
-                .custom_fields
-                .context("could not find custom fields")?
-                .try_into()?,
-            diary: task.notes,
-            subtasks: None,
-        })
+/// Remove the integration block from the file at `path`, reporting whether anything was (or
+/// would be) removed. Missing files and files without a block are left untouched.
+fn remove_integration_block_from(path: &Path, dry_run: bool) -> anyhow::Result<bool> {
+    if !path.exists() {
+        return Ok(false);
     }
-}
-
-impl Display for FocusDay {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "Focus Day ({date}) (stats: {stats})",
-            date = self.date.format("%Y-%m-%d"),
-            stats = self.stats
-        )
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("could not read {}", path.display()))?;
+    if todo::commands::install::find_integration_block(&contents).is_none() {
+        return Ok(false);
     }
+    if dry_run {
+        println!(
+            "Would remove the todo integration block from {}",
+            path.display()
+        );
+        return Ok(true);
+    }
+    fs::write(
+        path,
+        todo::commands::install::remove_integration_block(&contents),
+    )
+    .with_context(|| format!("could not write {}", path.display()))?;
+    println!(
+        "Removed the todo integration block from {}",
+        path.display()
+    );
+    Ok(true)
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
-struct FocusDayStats {
-    sleep: FocusDayStat,
-    energy: FocusDayStat,
-    flow: FocusDayStat,
-    hydration: FocusDayStat,
-    health: FocusDayStat,
-    satisfaction: FocusDayStat,
-    stress: FocusDayStat,
-}
-
-impl FocusDayStats {
-    fn stats(&self) -> Vec<&FocusDayStat> {
-        vec![
-            &self.sleep,
-            &self.energy,
-            &self.flow,
-            &self.hydration,
-            &self.health,
-            &self.satisfaction,
-            &self.stress,
-        ]
+/// Remove the file at `path` if it exists, reporting whether anything was (or would be) removed.
+fn remove_artifact(path: &Path, dry_run: bool) -> anyhow::Result<bool> {
+    if !path.exists() {
+        return Ok(false);
     }
-
-    fn set_stat(&mut self, stat: FocusDayStat) {
-        match stat {
-            FocusDayStat::Sleep(_) => self.sleep = stat,
-            FocusDayStat::Energy(_) => self.energy = stat,
-            FocusDayStat::Flow(_) => self.flow = stat,
-            FocusDayStat::Hydration(_) => self.hydration = stat,
-            FocusDayStat::Health(_) => self.health = stat,
-            FocusDayStat::Satisfaction(_) => self.satisfaction = stat,
-            FocusDayStat::Stress(_) => self.stress = stat,
-        }
+    if dry_run {
+        println!("Would remove {}", path.display());
+        return Ok(true);
     }
+    fs::remove_file(path).with_context(|| format!("could not remove {}", path.display()))?;
+    println!("Removed {}", path.display());
+    Ok(true)
 }
 
-impl Default for FocusDayStats {
-    fn default() -> Self {
-        Self {
-            sleep: FocusDayStat::Sleep(None),
-            energy: FocusDayStat::Energy(None),
-            flow: FocusDayStat::Flow(None),
-            hydration: FocusDayStat::Hydration(None),
-            health: FocusDayStat::Health(None),
-            satisfaction: FocusDayStat::Satisfaction(None),
-            stress: FocusDayStat::Stress(None),
-        }
+/// Point at `--offline` when an error looks like a dead network rather than a bad request.
+fn suggest_offline(error: &anyhow::Error) {
+    if todo::asana::is_network_error(error) {
+        eprintln!("the network looks unreachable; re-run with --offline to read from the cache");
     }
 }
 
-impl TryFrom<Vec<FocusTaskCustomField>> for FocusDayStats {
-    type Error = anyhow::Error;
-
-    fn try_from(custom_fields: Vec<FocusTaskCustomField>) -> Result<Self, Self::Error> {
-        let mut stats = Self::default();
-        for custom_field in custom_fields {
-            let stat = FocusDayStat::try_from(custom_field)?;
-            match stat {
-                FocusDayStat::Sleep(_) => stats.sleep = stat,
-                FocusDayStat::Energy(_) => stats.energy = stat,
-                FocusDayStat::Flow(_) => stats.flow = stat,
-                FocusDayStat::Hydration(_) => stats.hydration = stat,
-                FocusDayStat::Health(_) => stats.health = stat,
-                FocusDayStat::Satisfaction(_) => stats.satisfaction = stat,
-                FocusDayStat::Stress(_) => stats.stress = stat,
-            }
-        }
-        Ok(stats)
+/// The message a non-interactive command bails with when credentials are missing: the usual
+/// pointer at `todo update`, unless another process already holds the auth lock and re-running
+/// would only collide with its flow.
+fn auth_required_message(cache_path: &Path) -> &'static str {
+    if cache::is_auth_in_progress(cache_path) {
+        "auth required; another process is already authenticating"
+    } else {
+        "no credentials in cache; run `todo update` to authenticate"
     }
 }
 
-impl Display for FocusDayStats {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{sleep}, {energy}, {flow}, {hydration}, {health}, {satisfaction}, {stress}",
-            sleep = self.sleep,
-            energy = self.energy,
-            flow = self.flow,
-            hydration = self.hydration,
-            health = self.health,
-            satisfaction = self.satisfaction,
-            stress = self.stress,
-        )
+/// Bail out of a fetch the client refused because it would need interactive authorization.
+///
+/// The status-bar formats print an `auth!` token on stdout so the bar shows a hint instead of
+/// going blank — dimmed for the short format, plain for starship, which treats ANSI codes as
+/// garbage. Everything else gets the standard message on stderr. Either way the process exits
+/// with 3, the same code as the other authorization bails.
+fn exit_auth_required(command: &Command, cache_path: &Path) -> ! {
+    match command {
+        Command::Status {
+            format: StatusFormat::Short,
+            ..
+        } => println!("{}", style("auth!").dim()),
+        Command::Status {
+            format: StatusFormat::Starship,
+            ..
+        } => println!("auth!"),
+        _ => eprintln!("{}", auth_required_message(cache_path)),
     }
+    std::process::exit(3);
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
-enum FocusDayStat {
-    Sleep(Option<u32>),
-    Energy(Option<u32>),
-    Flow(Option<u32>),
-    Hydration(Option<u32>),
-    Health(Option<u32>),
-    Satisfaction(Option<u32>),
-    Stress(Option<u32>),
-}
-
-impl FocusDayStat {
-    fn name(&self) -> &'static str {
-        match self {
-            Self::Sleep(_) => "sleep",
-            Self::Energy(_) => "energy",
-            Self::Flow(_) => "flow",
-            Self::Hydration(_) => "hydration",
-            Self::Health(_) => "health",
-            Self::Satisfaction(_) => "satisfaction",
-            Self::Stress(_) => "stress",
-        }
+/// Await a network fallback under the command's shared wall-clock budget, if one is set.
+///
+/// Returns `None` when the budget runs out first; callers degrade to cached data instead of
+/// erroring, so a stalled connection cannot freeze a prompt-critical command.
+async fn within_budget<T>(
+    deadline: Option<tokio::time::Instant>,
+    future: impl std::future::Future<Output = T>,
+) -> Option<T> {
+    match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline, future).await.ok(),
+        None => Some(future.await),
     }
+}
 
-    fn value(&self) -> Option<u32> {
-        match self {
-            Self::Sleep(value)
-            | Self::Energy(value)
-            | Self::Flow(value)
-            | Self::Hydration(value)
-            | Self::Health(value)
-            | Self::Satisfaction(value)
-            | Self::Stress(value) => *value,
-        }
+/// Upload `files` onto the task with the given gid, printing each attachment's gid and
+/// permalink as it lands, or `would have` lines under `dry_run`.
+///
+/// Every file is checked to exist up front so a typo in one path fails the whole batch before
+/// anything is uploaded. `mime` overrides extension sniffing for all of the files.
+async fn upload_attachments(
+    client: &Client,
+    task_gid: &str,
+    files: &[PathBuf],
+    mime: Option<&str>,
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    for file in files {
+        anyhow::ensure!(
+            file.is_file(),
+            "{file} is not a file that can be attached",
+            file = file.display()
+        );
     }
-
-    fn set_value(&mut self, value: Option<u32>) {
-        match self {
-            Self::Sleep(_) => *self = Self::Sleep(value),
-            Self::Energy(_) => *self = Self::Energy(value),
-            Self::Flow(_) => *self = Self::Flow(value),
-            Self::Hydration(_) => *self = Self::Hydration(value),
-            Self::Health(_) => *self = Self::Health(value),
-            Self::Satisfaction(_) => *self = Self::Satisfaction(value),
-            Self::Stress(_) => *self = Self::Stress(value),
+    if dry_run {
+        for file in files {
+            println!("would have attached {file}", file = file.display());
         }
+        return Ok(());
     }
 
-    fn field_gid(&self) -> &'static str {
-        match self {
-            Self::Sleep(_) => "1204172638538713",
-            Self::Energy(_) => "1204172638540767",
-            Self::Flow(_) => "1204172638540769",
-            Self::Hydration(_) => "1204172638540771",
-            Self::Health(_) => "1204172638540773",
-            Self::Satisfaction(_) => "1204172638540775",
-            Self::Stress(_) => "1204172638540777",
-        }
+    // Uploads run concurrently so attaching several files does not pay one round trip each.
+    let uploads: Vec<_> = files
+        .iter()
+        .map(|file| {
+            println!(
+                "{}",
+                style(format!("Uploading {file}...", file = file.display())).dim()
+            );
+            let client = client.clone();
+            let task_gid = task_gid.to_string();
+            let content_type = mime.map_or_else(
+                || todo::commands::attach::sniff_mime(file).to_string(),
+                ToString::to_string,
+            );
+            let file = file.clone();
+            tokio::spawn(
+                async move {
+                    let attachment = client.upload(&task_gid, &file, &content_type).await?;
+                    Ok::<_, anyhow::Error>((file, attachment))
+                }
+                .in_current_span(),
+            )
+        })
+        .collect();
+    for result in join_all(uploads).await {
+        let (file, attachment) = result??;
+        println!(
+            "Attached {file}: gid {gid}{permalink}",
+            file = file.display(),
+            gid = attachment.gid,
+            permalink = attachment
+                .permalink_url
+                .map_or_else(String::new, |url| format!(", {url}")),
+        );
     }
+    Ok(())
 }
 
-impl TryFrom<FocusTaskCustomField> for FocusDayStat {
-    type Error = anyhow::Error;
-
-    fn try_from(custom_field: FocusTaskCustomField) -> Result<Self, Self::Error> {
-        Ok(match custom_field.gid.as_str() {
-            "1204172638538713" => Self::Sleep(custom_field.number_value),
-            "1204172638540767" => Self::Energy(custom_field.number_value),
-            "1204172638540769" => Self::Flow(custom_field.number_value),
-            "1204172638540771" => Self::Hydration(custom_field.number_value),
-            "1204172638540773" => Self::Health(custom_field.number_value),
-            "1204172638540775" => Self::Satisfaction(custom_field.number_value),
-            "1204172638540777" => Self::Stress(custom_field.number_value),
-            gid => anyhow::bail!("unknown focus day stat gid: {}", gid),
-        })
-    }
+/// Whether the freshly fetched focus day has different stats or diary text than the cached one,
+/// so updates only rewrite the daily note when something actually changed.
+fn focus_day_changed(cached: Option<&FocusDay>, fresh: &FocusDay) -> bool {
+    cached.is_none_or(|cached| {
+        cached.date != fresh.date || cached.stats != fresh.stats || cached.diary != fresh.diary
+    })
 }
 
-impl Display for FocusDayStat {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{name}={value}",
-            name = self.name(),
-            value = self.value().map_or("-".to_string(), |v| v.to_string())
-        )
+/// Mirror the focus day's diary and stats into the configured markdown daily note, returning the
+/// path written, or `None` when the integration is not configured or dry-run skipped the write.
+fn sync_daily_note(
+    config: &todo::config::Config,
+    day: &FocusDay,
+    dry_run: bool,
+) -> anyhow::Result<Option<PathBuf>> {
+    let Some(directory) = &config.integrations.daily_note.directory else {
+        return Ok(None);
+    };
+    let directory = expand_homedir(Path::new(directory))?;
+    if dry_run {
+        println!(
+            "would have updated the daily note in {}",
+            directory.display()
+        );
+        return Ok(None);
     }
+    Ok(Some(todo::daily_note::sync(
+        &directory,
+        &config.integrations.daily_note.filename,
+        &config.integrations.daily_note.heading,
+        day,
+    )?))
 }
 
 fn expand_homedir(path: &Path) -> anyhow::Result<PathBuf> {
@@ -593,552 +381,1797 @@ fn expand_homedir(path: &Path) -> anyhow::Result<PathBuf> {
         .into())
 }
 
-fn load_cache(path: &Path) -> anyhow::Result<Cache> {
-    log::debug!("Checking if cache file exists at {}...", path.display());
-    if !path.exists() {
-        log::warn!(
-            "Could not find cache at {}, so creating and using an empty cache...",
-            path.display()
-        );
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent).context("could not create path to cache file")?;
-        }
-        save_cache(path, &Cache::default())?;
-    }
-
-    log::debug!("Loading cache from {}...", path.display());
-    let cache =
-        serde_json::from_str(&fs::read_to_string(path).context("could not read cache file")?);
-    match cache {
-        Ok(cache) => {
-            log::trace!("Loaded cache: {cache:#?}");
-            Ok(cache)
+/// Set up the tracing subscriber: `RUST_LOG`-style filtering, human-readable output on stderr,
+/// or JSON lines appended to `--log-file` for daemon-ish invocations like `update --watch`.
+fn init_tracing(args: &Args) -> anyhow::Result<()> {
+    // -v/-vv raise this crate's log level without requiring RUST_LOG, which still works and
+    // still controls every other crate.
+    let mut filter = tracing_subscriber::EnvFilter::from_default_env();
+    match args.verbose {
+        0 => {}
+        1 => {
+            filter = filter.add_directive("todo=debug".parse()?);
         }
-        Err(err) => {
-            log::warn!(
-                "Could not deserialize cache file at {}, wiping it and trying again...",
-                path.display()
-            );
-            log::debug!("Cache deserialization error: {err}");
-            save_cache(path, &Cache::default())?;
-            load_cache(path)
-        }
-    }
-}
-
-fn save_cache(path: &Path, cache: &Cache) -> anyhow::Result<()> {
-    log::debug!("Saving cache to {}...", path.display());
-    fs::write(
-        path,
-        serde_json::to_string_pretty(cache).context("could not serialize cache")?,
-    )
-    .context("could not write to cache file")?;
-    log::trace!("Saved cache: {cache:#?}");
-
-    Ok(())
-}
-
-fn load_config(path: &Path) -> anyhow::Result<Config> {
-    log::debug!(
-        "Checking if configuration file exists at {}...",
-        path.display()
-    );
-    if !path.exists() {
-        log::warn!(
-            "Could not find configuration at {}, so creating and using an empty configuration...",
-            path.display()
-        );
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent).context("could not create path to configuration file")?;
+        _ => {
+            filter = filter.add_directive("todo=trace".parse()?);
         }
-        fs::OpenOptions::new()
-            .write(true)
-            .create_new(true)
-            .open(path)
-            .context("could not create configuration file")?;
     }
 
-    log::debug!("Loading configuration from {}...", path.display());
-    let config: Config =
-        toml::from_str(&fs::read_to_string(path).context("could not read configuration file")?)
-            .context("could not deserialize configuration file")?;
-    log::trace!("Loaded configuration: {config:#?}");
-
-    Ok(config)
-}
-
-#[allow(clippy::too_many_lines)]
-async fn get_focus_day(day: NaiveDate, client: &mut Client) -> anyhow::Result<FocusDay> {
-    log::info!("Getting focus sections...");
-    let sections = client
-        .get::<Section>(&ASANA_FOCUS_PROJECT_GID.to_string())
-        .await?;
-    log::debug!("Got {} sections", sections.len());
-    log::trace!("Sections: {sections:#?}", sections = sections);
-
-    log::info!("Constructing focus weeks...");
-    let focus_weeks = sections
-        .into_iter()
-        .filter(|s| s.name.starts_with("Daily Focuses"))
-        .filter_map(|s| match s.try_into() {
-            Ok(s) => Some(s),
-            Err(err) => {
-                log::warn!("Could not parse focus section name: {}", err);
-                None
-            }
-        })
-        .collect::<Vec<FocusWeek>>();
-    log::debug!("Constructed {} focus weeks", focus_weeks.len());
-    log::trace!("Focus weeks: {focus_weeks:#?}", focus_weeks = focus_weeks);
-
-    log::info!("Finding current focus week...");
-    let current_week =
-        if let Some(current_week) = focus_weeks.iter().find(|w| w.from <= day && w.to >= day) {
-            log::debug!(
-                "Found current focus week: {current_week}",
-                current_week = current_week
-            );
-            current_week.clone()
-        } else {
-            log::warn!("Could not find current focus week, so creating it...");
-            let week = day.week(Weekday::Mon);
-            let current_week: FocusWeek = client
-                .mutate_request(
-                    Method::POST,
-                    &format!(
-                        "https://app.asana.com/api/1.0/projects/{ASANA_FOCUS_PROJECT_GID}/sections"
-                    )
-                    .parse()
-                    .context("issue parsing focus week creation request url")?,
-                    DataWrapper {
-                        data: CreateSectionRequest {
-                            name: format!(
-                                "Daily Focuses ({from} to {to})",
-                                from = week.first_day().format("%Y-%m-%d"),
-                                to = week.last_day().format("%Y-%m-%d")
-                            ),
-                            insert_before: focus_weeks
-                                .first()
-                                .context("unable to get any focus weeks")?
-                                .section
-                                .gid
-                                .clone(),
-                        },
-                    },
-                )
-                .await
-                .context("issue creating focus week")?
-                .json::<DataWrapper<Section>>()
-                .await
-                .context("unable to parse focus week creation response")?
-                .data
-                .try_into()?;
-            log::debug!(
-                "Created current focus week: {current_week}",
-                current_week = current_week
-            );
-            current_week
-        };
-    log::debug!(
-        "Got current focus week: {current_week}",
-        current_week = current_week
-    );
-
-    log::info!("Getting tasks in current focus week...");
-    let tasks = client.get::<FocusTask>(&current_week.section.gid).await?;
-    log::debug!("Got {} tasks", tasks.len());
-
-    log::info!("Constructing focus days...");
-    let focus_days = tasks
-        .into_iter()
-        .filter(|t| t.name.starts_with("Daily Focus for"))
-        .filter_map(|t| match t.try_into() {
-            Ok(t) => Some(t),
-            Err(err) => {
-                log::warn!("Could not parse focus task name: {}", err);
-                None
-            }
-        })
-        .collect::<Vec<FocusDay>>();
-    log::debug!("Constructed {} focus days", focus_days.len());
-    log::trace!("Focus days: {focus_days:#?}", focus_days = focus_days);
-
-    log::info!("Finding current focus day...");
-    let current_day = if let Some(current_day) = focus_days.iter().find(|d| d.date == day) {
-        log::debug!(
-            "Found current focus day: {current_day}",
-            current_day = current_day
-        );
-        current_day.clone()
+    if let Some(path) = &args.log_file {
+        let path = expand_homedir(path)?;
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("could not open log file at {}", path.display()))?;
+        tracing_subscriber::fmt()
+            .json()
+            .with_env_filter(filter)
+            .with_writer(file)
+            .init();
     } else {
-        log::warn!("Could not find current focus day, so creating it...");
-        let current_day: FocusDay = client
-            .mutate_request(
-                Method::POST,
-                &"https://app.asana.com/api/1.0/tasks"
-                    .to_string()
-                    .parse()
-                    .context("issue parsing focus day creation request url")?,
-                DataWrapper {
-                    data: CreateSectionTaskRequest {
-                        name: format!(
-                            "Daily Focus for {day} ({date})",
-                            day = day.weekday(),
-                            date = day.format("%Y-%m-%d")
-                        ),
-                        projects: vec![ASANA_FOCUS_PROJECT_GID.to_string()],
-                        memberships: vec![CreateSectionTaskRequestMembership {
-                            project: ASANA_FOCUS_PROJECT_GID.to_string(),
-                            section: current_week.section.gid.clone(),
-                        }],
-                    },
-                },
-            )
-            .await
-            .context("issue creating focus day")?
-            .json::<DataWrapper<FocusTask>>()
-            .await
-            .context("unable to parse focus day creation response")?
-            .data
-            .try_into()?;
-        log::debug!(
-            "Created current focus day: {current_day}",
-            current_day = current_day
-        );
-
-        if let Some(previous_closest_day) = focus_days
-            .iter()
-            .filter(|d| d.date < day)
-            .max_by_key(|d| d.date)
-        {
-            log::debug!("Ordering the created focus day correctly...");
-            client
-                .mutate_request(
-                    Method::POST,
-                    &format!(
-                        "https://app.asana.com/api/1.0/sections/{section_gid}/addTask",
-                        section_gid = current_week.section.gid
-                    )
-                    .parse()
-                    .context("issue parsing focus day ordering request url")?,
-                    DataWrapper {
-                        data: AddTaskToSectionRequest {
-                            task: current_day.task.gid.clone(),
-                            insert_after: previous_closest_day.task.gid.clone(),
-                        },
-                    },
-                )
-                .await
-                .context("issue ordering focus day")?;
-        }
-
-        current_day
-    };
-    log::debug!(
-        "Got current focus day: {current_day}",
-        current_day = current_day
-    );
-
-    Ok(current_day)
+        tracing_subscriber::fmt()
+            .with_env_filter(filter)
+            .with_writer(std::io::stderr)
+            .init();
+    }
+    Ok(())
 }
 
-fn task_or_tasks(num: usize) -> String {
-    if num == 1 {
-        "1 task".to_string()
-    } else {
-        format!("{num} tasks")
+/// The stable name a run's command span is labeled with, matching the CLI subcommand.
+fn command_name(command: &Command) -> &'static str {
+    match command {
+        Command::Init => "init",
+        Command::Summary => "summary",
+        Command::List { .. } => "list",
+        Command::Agenda { .. } => "agenda",
+        Command::Log { .. } => "log",
+        Command::Add { .. } => "add",
+        Command::Attach { .. } => "attach",
+        Command::Triage => "triage",
+        Command::Count { .. } => "count",
+        Command::Status { .. } => "status",
+        Command::Gate => "gate",
+        Command::Notify { .. } => "notify",
+        Command::Pause { .. } => "pause",
+        Command::Resume => "resume",
+        Command::Focus { .. } => "focus",
+        Command::Config { .. } => "config",
+        Command::Install { .. } => "install",
+        Command::Uninstall { .. } => "uninstall",
+        Command::Report { .. } => "report",
+        Command::Export { .. } => "export",
+        Command::Update { .. } => "update",
+        Command::CompleteTasks { .. } => "__complete-tasks",
     }
 }
 
-#[allow(clippy::too_many_lines)]
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     setup_panic!();
-    env_logger::init();
-
-    let term = Term::stdout();
 
-    log::debug!("Parsing command line arguments...");
     let args = Args::parse();
-    log::trace!("Parsed command line arguments: {args:#?}");
-
-    let cache_path = expand_homedir(&args.cache_path)?;
-    let config_path = expand_homedir(&args.config_path)?;
+    init_tracing(&args)?;
+
+    // Every event in the run nests under one span per invocation so interleaved async work can
+    // be correlated. The name is recorded as soon as the command is known — immediately, unless
+    // a bare `todo` has to resolve the configured default command first.
+    let command_span = tracing::info_span!("command", name = tracing::field::Empty);
+    if let Some(command) = &args.command {
+        command_span.record("name", command_name(command));
+    }
 
-    let mut cache = load_cache(&cache_path)?;
-    let _config = load_config(&config_path)?;
+    // Errors surface as a short classified message rather than a raw anyhow chain, which stays
+    // behind -v.
+    let verbose = args.verbose;
+    if let Err(error) = Box::pin(run(args).instrument(command_span)).await {
+        eprintln!("{}", todo::errors::render(&error, verbose > 0));
+        std::process::exit(1);
+    }
+    Ok(())
+}
 
-    if args.use_cache {
-        log::debug!("Using cache, ensuring that we've updated recently...");
-        if let Some(last_updated) = cache.last_updated {
-            log::debug!(
-                "Cache last updated at {last_updated}, checking if we should update...",
-                last_updated = last_updated
-            );
-            if Local::now() - last_updated < chrono::Duration::minutes(3) {
-                log::debug!("Cache is recent enough, we're good.");
-            } else {
-                log::warn!("Cache is not recent enough, letting the user know...");
-                term.write_line(
-                    &style("Warning: cache has not been updated in more than 3 minutes, is the update command in the background? See the README.md")
-                        .red()
-                        .to_string(),
+#[allow(clippy::too_many_lines)]
+async fn run(args: Args) -> anyhow::Result<()> {
+    let term = Term::stdout();
+    tracing::trace!("Parsed command line arguments: {args:#?}");
+
+    // Install never touches Asana, so it runs before any cache or credential handling.
+    if let Some(Command::Install { command }) = &args.command {
+        match command {
+            InstallCommand::Show => {
+                // The state comes from probing actual artifacts, not configuration; only the
+                // update-interval threshold and cache timestamp need the config and cache files.
+                let config = todo::config::load(
+                    &expand_homedir(&args.config_path)?,
+                    args.strict_config,
                 )?;
+                let cache = cache::load(&expand_homedir(&args.cache_path)?)?;
+                let is_enabled = |unit: &str| {
+                    std::process::Command::new("systemctl")
+                        .args(["--user", "is-enabled", "--quiet", unit])
+                        .output()
+                        .ok()
+                        .map(|output| output.status.success())
+                };
+                let statuses = [
+                    todo::commands::install::probe_xbar(&expand_homedir(Path::new(
+                        todo::commands::install::XBAR_PLUGIN_DIR,
+                    ))?),
+                    todo::commands::install::probe_shell_block(
+                        "zsh",
+                        &expand_homedir(Path::new("~/.zshrc"))?,
+                        "todo install zsh --write",
+                    ),
+                    todo::commands::install::probe_shell_block(
+                        "tmux",
+                        &expand_homedir(Path::new("~/.tmux.conf"))?,
+                        "todo install tmux --write",
+                    ),
+                    todo::commands::install::probe_systemd(
+                        &expand_homedir(Path::new(todo::commands::install::SYSTEMD_UNIT_DIR))?,
+                        &is_enabled,
+                    ),
+                    todo::commands::install::probe_update_schedule(
+                        cache.last_updated,
+                        Local::now(),
+                        config.behavior.update_interval_minutes,
+                    ),
+                ];
+                for status in statuses {
+                    println!("{name:<8} {state}", name = status.name, state = status.state);
+                    if let Some(hint) = status.hint {
+                        println!("         {hint}");
+                    }
+                }
             }
-        } else {
-            log::warn!("Cache has never been updated, letting the user know...");
-            term.write_line(
-                &style(
-                    "Warning: cache has never been updated, is caching working? See the README.md",
-                )
-                .red()
-                .to_string(),
-            )?;
-        }
-    }
-
-    let creds = if args.use_pat {
-        if let Some(Credentials::PersonalAccessToken(pat)) = &cache.creds {
-            Credentials::PersonalAccessToken(pat.clone())
-        } else {
-            let creds = ask_for_pat()?;
-            cache.creds = Some(creds.clone());
-            save_cache(&cache_path, &cache)?;
-            creds
-        }
+            InstallCommand::Starship => {
+                print!("{}", todo::commands::install::render_starship());
+            }
+            InstallCommand::Notifications => {
+                print!("{}", todo::commands::install::render_notifications());
+            }
+            InstallCommand::Zsh {
+                write,
+                dry_run,
+                path,
+            } => {
+                let block =
+                    todo::commands::install::integration_block(&todo::commands::install::render_zsh());
+                if *write {
+                    write_integration_block(&expand_homedir(path)?, &block, *dry_run)?;
+                } else {
+                    print!("{block}");
+                }
+            }
+            InstallCommand::Tmux {
+                write,
+                dry_run,
+                path,
+                dracula,
+            } => {
+                let block = todo::commands::install::integration_block(
+                    &todo::commands::install::render_tmux(*dracula),
+                );
+                if *write {
+                    write_integration_block(&expand_homedir(path)?, &block, *dry_run)?;
+                } else {
+                    print!("{block}");
+                }
+                if *dracula {
+                    let script_path = expand_homedir(Path::new(
+                        todo::commands::install::DRACULA_SCRIPT_PATH,
+                    ))?;
+                    if *write && !*dry_run {
+                        if let Some(parent) = script_path.parent() {
+                            fs::create_dir_all(parent)
+                                .context("could not create path to dracula script")?;
+                        }
+                        fs::write(
+                            &script_path,
+                            todo::commands::install::render_dracula_script(),
+                        )
+                        .context("could not write dracula script")?;
+                        let mut permissions =
+                            fs::metadata(&script_path)?.permissions();
+                        std::os::unix::fs::PermissionsExt::set_mode(&mut permissions, 0o755);
+                        fs::set_permissions(&script_path, permissions)?;
+                        println!("Wrote {}", script_path.display());
+                    } else {
+                        println!(
+                            "Also {} the dracula script at {}",
+                            if *write { "would write" } else { "install" },
+                            script_path.display()
+                        );
+                    }
+                }
+            }
+            InstallCommand::Raycast { dir } => {
+                let dir = expand_homedir(dir)?;
+                let binary = env::current_exe().context("could not find the todo executable")?;
+                fs::create_dir_all(&dir)
+                    .with_context(|| format!("could not create {}", dir.display()))?;
+                for (name, contents) in todo::commands::install::render_raycast_scripts(&binary) {
+                    let path = dir.join(&name);
+                    fs::write(&path, contents)
+                        .with_context(|| format!("could not write {}", path.display()))?;
+                    let mut permissions = fs::metadata(&path)?.permissions();
+                    std::os::unix::fs::PermissionsExt::set_mode(&mut permissions, 0o755);
+                    fs::set_permissions(&path, permissions)?;
+                    println!("Wrote {}", path.display());
+                }
+            }
+            InstallCommand::Systemd {
+                enable,
+                uninstall,
+                service,
+            } => {
+                let dir = expand_homedir(Path::new(todo::commands::install::SYSTEMD_UNIT_DIR))?;
+
+                if *uninstall {
+                    let removable: Vec<&str> = todo::commands::install::SYSTEMD_UNITS
+                        .iter()
+                        .copied()
+                        .chain([todo::commands::install::SYSTEMD_WATCH_UNIT])
+                        .collect();
+                    for unit in &removable {
+                        let path = dir.join(unit);
+                        if path.exists() {
+                            fs::remove_file(&path).with_context(|| {
+                                format!("could not remove {}", path.display())
+                            })?;
+                            println!("Removed {}", path.display());
+                        }
+                    }
+                    println!("Clean up with:");
+                    for unit in removable.iter().filter(|unit| {
+                        std::path::Path::new(unit)
+                            .extension()
+                            .is_some_and(|extension| extension == "timer")
+                            || **unit == todo::commands::install::SYSTEMD_WATCH_UNIT
+                    }) {
+                        println!("  systemctl --user disable --now {unit}");
+                    }
+                    println!("  systemctl --user daemon-reload");
+                    return Ok(());
+                }
+
+                // The update interval comes from the configuration, which install commands
+                // normally never touch; a broken config should not block uninstalling.
+                let config = todo::config::load(
+                    &expand_homedir(&args.config_path)?,
+                    args.strict_config,
+                )?;
+                let binary = env::current_exe().context("could not find the todo executable")?;
+
+                // With --service the update timer pair is replaced by the long-lived watch
+                // service; the notify timers stay either way.
+                let mut units: Vec<(String, String)> = todo::commands::install::render_systemd_units(
+                    &binary,
+                    config.behavior.update_interval_minutes,
+                );
+                if *service {
+                    units.retain(|(name, _)| !name.starts_with("todo-update"));
+                    units.insert(
+                        0,
+                        todo::commands::install::render_systemd_watch_unit(
+                            &binary,
+                            config.behavior.update_interval_minutes,
+                        ),
+                    );
+                }
+                let enable_units: Vec<&str> = units
+                    .iter()
+                    .map(|(name, _)| name.as_str())
+                    .filter(|name| {
+                        std::path::Path::new(name)
+                            .extension()
+                            .is_some_and(|extension| extension == "timer")
+                            || *name == todo::commands::install::SYSTEMD_WATCH_UNIT
+                    })
+                    .collect();
+
+                fs::create_dir_all(&dir)
+                    .with_context(|| format!("could not create {}", dir.display()))?;
+                for (name, contents) in &units {
+                    let path = dir.join(name);
+                    fs::write(&path, contents)
+                        .with_context(|| format!("could not write {}", path.display()))?;
+                    println!("Wrote {}", path.display());
+                }
+
+                if *enable {
+                    let mut commands = vec![vec!["--user", "daemon-reload"]];
+                    for unit in &enable_units {
+                        commands.push(vec!["--user", "enable", "--now", unit]);
+                    }
+                    for arguments in commands {
+                        let command_status = std::process::Command::new("systemctl")
+                            .args(&arguments)
+                            .status()
+                            .context("could not run systemctl")?;
+                        anyhow::ensure!(
+                            command_status.success(),
+                            "systemctl {} failed",
+                            arguments.join(" ")
+                        );
+                    }
+                } else {
+                    println!("Enable with:");
+                    println!("  systemctl --user daemon-reload");
+                    for unit in &enable_units {
+                        println!("  systemctl --user enable --now {unit}");
+                    }
+                }
+            }
+            InstallCommand::Man { write, out } => {
+                let pages = todo::commands::install::render_man_pages()?;
+                if *write || out.is_some() {
+                    let dir = out
+                        .clone()
+                        .unwrap_or_else(|| PathBuf::from(todo::commands::install::MAN_DIR));
+                    fs::create_dir_all(&dir).with_context(|| {
+                        format!("could not create man page directory {}", dir.display())
+                    })?;
+                    for (name, contents) in &pages {
+                        let path = dir.join(name);
+                        fs::write(&path, contents).with_context(|| {
+                            format!("could not write man page {}", path.display())
+                        })?;
+                        println!("Wrote {}", path.display());
+                    }
+                } else {
+                    let mut stdout = std::io::stdout();
+                    for (_, contents) in &pages {
+                        std::io::Write::write_all(&mut stdout, contents)?;
+                    }
+                }
+            }
+            InstallCommand::Completions { shell, write } => {
+                let script = todo::commands::install::render_completions(*shell);
+                if *write {
+                    let path = expand_homedir(Path::new(
+                        todo::commands::install::completions_path(*shell),
+                    ))?;
+                    if let Some(parent) = path.parent() {
+                        fs::create_dir_all(parent)
+                            .context("could not create path to completions file")?;
+                    }
+                    fs::write(&path, script).context("could not write completions file")?;
+                    println!("Wrote completions to {}", path.display());
+                } else {
+                    print!("{script}");
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    // Uninstall stays offline too, and deliberately skips the configuration and cache so broken
+    // state can never block cleanup. Every artifact is optional: removing what is not there is a
+    // no-op, not an error.
+    if let Some(Command::Uninstall { target, dry_run }) = &args.command {
+        let all = matches!(target, UninstallTarget::All);
+        let mut removed_any = false;
+
+        if all || matches!(target, UninstallTarget::Xbar) {
+            let dir = expand_homedir(Path::new(todo::commands::install::XBAR_PLUGIN_DIR))?;
+            // The filename carries the refresh interval, so the plugins are located by glob.
+            for plugin in todo::commands::install::xbar_plugin_files(&dir) {
+                removed_any |= remove_artifact(&plugin, *dry_run)?;
+            }
+        }
+
+        if all || matches!(target, UninstallTarget::Zsh) {
+            removed_any |=
+                remove_integration_block_from(&expand_homedir(Path::new("~/.zshrc"))?, *dry_run)?;
+        }
+
+        if all || matches!(target, UninstallTarget::Tmux) {
+            removed_any |= remove_integration_block_from(
+                &expand_homedir(Path::new("~/.tmux.conf"))?,
+                *dry_run,
+            )?;
+            removed_any |= remove_artifact(
+                &expand_homedir(Path::new(todo::commands::install::DRACULA_SCRIPT_PATH))?,
+                *dry_run,
+            )?;
+        }
+
+        if all || matches!(target, UninstallTarget::Systemd) {
+            let dir = expand_homedir(Path::new(todo::commands::install::SYSTEMD_UNIT_DIR))?;
+            let mut removed_units = false;
+            for unit in todo::commands::install::SYSTEMD_UNITS
+                .iter()
+                .copied()
+                .chain([todo::commands::install::SYSTEMD_WATCH_UNIT])
+            {
+                removed_units |= remove_artifact(&dir.join(unit), *dry_run)?;
+            }
+            if removed_units && !*dry_run {
+                println!("Clean up with:");
+                for timer in todo::commands::install::SYSTEMD_UNITS.iter().filter(|unit| {
+                    std::path::Path::new(unit)
+                        .extension()
+                        .is_some_and(|extension| extension == "timer")
+                }) {
+                    println!("  systemctl --user disable --now {timer}");
+                }
+                println!("  systemctl --user daemon-reload");
+            }
+            removed_any |= removed_units;
+        }
+
+        if !removed_any {
+            println!("Nothing to remove.");
+        }
+        return Ok(());
+    }
+
+    let cache_path = expand_homedir(&args.cache_path)?;
+    let config_path = expand_homedir(&args.config_path)?;
+
+    // Styling is suppressed globally so every command renders plain text consistently.
+    let color = !args.no_color && env::var_os("NO_COLOR").is_none();
+    if !color {
+        console::set_colors_enabled(false);
+        console::set_colors_enabled_stderr(false);
+    }
+
+    let config = todo::config::load(&config_path, args.strict_config)?;
+    let theme = todo::render::Theme::resolve(&config.colors);
+    let mut ctx = AppContext {
+        config,
+        cache: cache::load(&cache_path)?,
+        color,
+        theme,
+        output: OutputMode::new(args.quiet, term.features().is_attended()),
+        dry_run: args.dry_run,
+        timings: todo::asana::RequestTimings::default(),
+        now: Local::now(),
+        writer: Box::new(todo::context::TermOutput::new(term.clone())),
+    };
+
+    // A bare `todo` runs the configured default command (summary unless overridden), which can
+    // only be resolved once the configuration is loaded.
+    let command = args.command.unwrap_or_else(|| {
+        tracing::debug!("No subcommand given, falling back to the configured default command...");
+        match ctx.config.behavior.default_command {
+            todo::config::DefaultCommand::Summary => Command::Summary,
+            todo::config::DefaultCommand::List => Command::List {
+                all: false,
+                format: ListFormat::default(),
+                group_by: GroupBy::default(),
+                absolute: false,
+                links: false,
+            },
+            todo::config::DefaultCommand::Status => Command::Status {
+                format: StatusFormat::default(),
+                max_wait: None,
+            },
+            todo::config::DefaultCommand::Focus => Command::Focus {
+                date: None,
+                force_eod: false,
+                force: false,
+                force_sync: false,
+                command: None,
+            },
+        }
+    });
+    tracing::Span::current().record("name", command_name(&command));
+
+    // A date the `focus.days` schedule excludes short-circuits the focus routine before the
+    // terminal check below, so scheduled automation gets a quiet notice instead of an error.
+    if let Command::Focus {
+        date,
+        force: false,
+        command: Some(FocusCommand::Run) | None,
+        ..
+    } = &command
+    {
+        let date = date.unwrap_or_else(|| ctx.today());
+        if !ctx.config.focus.is_scheduled(date) {
+            println!(
+                "{}",
+                style(format!(
+                    "No focus scheduled for {date}; pass --force to run it anyway."
+                ))
+                .dim()
+            );
+            return Ok(());
+        }
+    }
+
+    // Interactive commands prompt from their first step, so a non-terminal invocation (a git
+    // hook, the xbar plugin) fails up front — before any credential or network work, and long
+    // before any mutation could fire.
+    if !std::io::stdin().is_terminal() {
+        match &command {
+            Command::Triage => anyhow::bail!(
+                "triage is interactive and needs a terminal; use `todo snooze` for scripted \
+                 rescheduling"
+            ),
+            Command::Init => anyhow::bail!(
+                "init is interactive and needs a terminal; use `todo config set` to change \
+                 settings non-interactively"
+            ),
+            Command::Focus {
+                command: Some(FocusCommand::Run) | None,
+                ..
+            } => anyhow::bail!(
+                "the focus routine is interactive and needs a terminal; use `todo focus \
+                 overview` to print the day without prompting"
+            ),
+            _ => {}
+        }
+    }
+
+    // The config command only touches the configuration file, so it never goes near the cache,
+    // credentials, or the network.
+    if let Command::Config { command } = &command {
+        match command {
+            ConfigCommand::List => {
+                let file: toml::Value =
+                    toml::from_str(&fs::read_to_string(&config_path)?)
+                        .context("could not parse configuration file")?;
+                for entry in todo::config::entries(&ctx.config, &file)? {
+                    println!(
+                        "{key} = {value}  ({source})",
+                        key = entry.key,
+                        value = entry.value,
+                        source = entry.source
+                    );
+                }
+            }
+            ConfigCommand::Get { key } => {
+                println!("{}", todo::config::get(&ctx.config, key)?);
+            }
+            ConfigCommand::Set { key, value } => {
+                let config = todo::config::set(&ctx.config, key, value)?;
+                todo::config::save(&config_path, &config)?;
+                println!("{key} = {}", todo::config::get(&config, key)?);
+            }
+            ConfigCommand::Edit => {
+                let editor = env::var("EDITOR").context("$EDITOR is not set")?;
+                let original = fs::read_to_string(&config_path)
+                    .context("could not read configuration file")?;
+                let editor_status = std::process::Command::new(&editor)
+                    .arg(&config_path)
+                    .status()
+                    .with_context(|| format!("could not run editor `{editor}`"))?;
+                anyhow::ensure!(editor_status.success(), "editor exited with an error");
+
+                // Re-validate what the editor wrote; an unparseable config would otherwise be
+                // silently replaced with defaults on the next run.
+                if let Err(err) = todo::config::load(&config_path, args.strict_config) {
+                    fs::write(&config_path, original)
+                        .context("could not restore configuration file")?;
+                    return Err(err.context("edited configuration does not parse; restored the previous contents"));
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    // The completion helper reads only from the cache: it must never touch the network, prompt
+    // for authorization, or warn, since shells call it mid-keystroke. A missing cache means no
+    // suggestions, not an error.
+    if let Command::CompleteTasks { with_gids } = &command {
+        for task in ctx.cache.tasks.as_deref().unwrap_or_default() {
+            if *with_gids {
+                println!("{}\t{}", task.gid, task.name);
+            } else {
+                println!("{}", task.name);
+            }
+        }
+        return Ok(());
+    }
+
+    // The configured workspace and focus project win over the built-in defaults.
+    let workspace_gid = ctx
+        .config
+        .asana
+        .workspace_gid
+        .clone()
+        .unwrap_or_else(|| ASANA_WORKSPACE_GID.to_string());
+    let focus_project_gid = ctx
+        .config
+        .asana
+        .focus_project_gid
+        .clone()
+        .unwrap_or_else(|| ASANA_FOCUS_PROJECT_GID.to_string());
+
+    // Offline implies reading from the cache; anything not cached errors instead of hanging
+    // through connect timeouts.
+    let use_cache = args.use_cache || args.offline;
+
+    // Every cached date was computed against the zone the last update ran under; after travel
+    // across timezones "due today" and the cached focus day no longer line up with the local
+    // calendar. Dropping `last_updated` makes every freshness check downstream treat the cache
+    // as stale, so the next update recomputes everything under the new zone.
+    let current_offset = ctx.now.offset().to_string();
+    let timezone_changed = ctx.cache.timezone_change(&current_offset).is_some();
+    if let Some(stored) = ctx.cache.timezone_change(&current_offset).map(str::to_string) {
+        tracing::warn!(
+            "Cache was last updated under UTC offset {stored} but the machine is now at \
+             {current_offset}, treating the cache as stale..."
+        );
+        if ctx.output.show_warnings() {
+            Term::stderr().write_line(
+                &style(format!(
+                    "Warning: the timezone changed from {stored} to {current_offset} since the \
+                     last update; treating the cache as stale"
+                ))
+                .red()
+                .to_string(),
+            )?;
+        }
+        ctx.cache.last_updated = None;
+        ctx.cache.last_updated_offset = None;
+    }
+
+    if use_cache {
+        tracing::debug!("Using cache, ensuring that we've updated recently...");
+        // Warnings go to stderr so scriptable consumers (prompts, status bars) never see them in
+        // their output, and --quiet drops them entirely.
+        let stderr = Term::stderr();
+        if let Some(last_updated) = ctx.cache.last_updated {
+            tracing::debug!(
+                "Cache last updated at {last_updated}, checking if we should update...",
+                last_updated = last_updated
+            );
+            if ctx.now - last_updated < chrono::Duration::minutes(3) {
+                tracing::debug!("Cache is recent enough, we're good.");
+            } else {
+                tracing::warn!("Cache is not recent enough, letting the user know...");
+                if ctx.output.show_warnings() {
+                    stderr.write_line(
+                        &style("Warning: cache has not been updated in more than 3 minutes, is the update command in the background? See the README.md")
+                            .red()
+                            .to_string(),
+                    )?;
+                }
+            }
+        } else if !timezone_changed {
+            // After a timezone change the notice above already explains the missing
+            // freshness timestamp; piling the "is caching working?" warning on top of it
+            // would point the user at the wrong problem.
+            tracing::warn!("Cache has never been updated, letting the user know...");
+            if ctx.output.show_warnings() {
+                stderr.write_line(
+                    &style(
+                        "Warning: cache has never been updated, is caching working? See the README.md",
+                    )
+                    .red()
+                    .to_string(),
+                )?;
+            }
+        }
+    }
+
+    // Scriptable commands must never block on an interactive authorization flow; they bail out
+    // with a distinct exit code instead so callers can tell "no creds" apart from real failures.
+    // Offline disables it wholesale since the flow itself needs the network.
+    let interactive_auth = !args.offline
+        && !matches!(
+            command,
+            Command::Count { .. }
+                | Command::Status { .. }
+                | Command::Gate
+                | Command::Notify { .. }
+                | Command::Pause { .. }
+                | Command::Resume
+        );
+
+    let creds = if args.use_pat {
+        if let Some(Credentials::PersonalAccessToken(pat)) = &ctx.cache.creds {
+            Credentials::PersonalAccessToken(pat.clone())
+        } else if interactive_auth {
+            let Some(_auth_lock) = cache::try_auth_lock(&cache_path)? else {
+                eprintln!("another process is already authenticating; retry once it finishes");
+                std::process::exit(3);
+            };
+            let creds = ask_for_pat()?;
+            ctx.cache.creds = Some(creds.clone());
+            cache::save(&cache_path, &ctx.cache)?;
+            creds
+        } else {
+            tracing::warn!("No credentials in cache and authorization is not allowed, bailing...");
+            eprintln!("{}", auth_required_message(&cache_path));
+            std::process::exit(3);
+        }
     } else if let Some(Credentials::OAuth2 {
         access_token,
         refresh_token,
-    }) = &cache.creds
+    }) = &ctx.cache.creds
     {
         Credentials::OAuth2 {
             access_token: access_token.clone(),
             refresh_token: refresh_token.clone(),
         }
-    } else {
+    } else if interactive_auth {
+        let Some(_auth_lock) = cache::try_auth_lock(&cache_path)? else {
+            eprintln!("another process is already authenticating; retry once it finishes");
+            std::process::exit(3);
+        };
         let creds = execute_authorization_flow().await?;
-        cache.creds = Some(creds.clone());
-        save_cache(&cache_path, &cache)?;
+        ctx.cache.creds = Some(creds.clone());
+        cache::save(&cache_path, &ctx.cache)?;
         creds
+    } else {
+        tracing::warn!("No credentials in cache and authorization is not allowed, bailing...");
+        eprintln!("{}", auth_required_message(&cache_path));
+        std::process::exit(3);
     };
 
-    let mut client = Client::new(creds)?;
-
-    log::info!("Getting user task list..");
-    let user_task_list =
-        if let (Some(user_task_list), true) = (cache.user_task_list.clone(), args.use_cache) {
-            log::debug!("Using cached user task list...");
-            user_task_list
-        } else {
-            let user_task_list = client.get::<UserTaskList>(&"me".to_string()).await?;
-            log::debug!("Saving new user task list to cache...");
-            cache.user_task_list = Some(user_task_list.clone());
-            save_cache(&cache_path, &cache)?;
-            user_task_list
-        };
-    log::debug!("Got user task list: {user_task_list:#?}");
+    let mut client = client_from_config(&ctx.config, creds)?;
+    client.set_dry_run(ctx.dry_run);
+    client.set_offline(args.offline);
+    // Token refreshes can fall back to the full interactive flow, which must obey the same
+    // interactivity gate and auth lock as the credential resolution above.
+    client.set_interactive_auth(interactive_auth);
+    client.set_cache_path(cache_path.clone());
+    ctx.timings = client.timings();
+
+    // The status and count commands sit on prompt render paths, so every network fallback
+    // below shares one wall-clock budget; past the deadline they degrade to cached data with a
+    // stale marker instead of stalling the prompt for the full request timeouts.
+    let network_deadline = match &command {
+        Command::Status { max_wait, .. } | Command::Count { max_wait, .. } => {
+            Some(tokio::time::Instant::now() + ctx.config.status.max_wait(*max_wait))
+        }
+        _ => None,
+    };
+    let mut network_budget_spent = false;
+
+    tracing::info!("Getting user task list..");
+    // The gid is durable — it only changes if the workspace does — so a cached one is trusted
+    // indefinitely; a stale gid surfaces as a 404 on the tasks fetch below and re-resolves there.
+    let mut user_task_list = if let Some(user_task_list) = ctx.cache.user_task_list.clone() {
+        tracing::debug!("Using cached user task list...");
+        user_task_list
+    } else {
+        let user_gid = ctx
+            .cache
+            .user
+            .as_ref()
+            .map_or_else(|| "me".to_string(), |user| user.gid.clone());
+        let request = (user_gid, workspace_gid.clone());
+        match within_budget(network_deadline, client.get::<UserTaskList>(&request)).await {
+            Some(Ok(user_task_list)) => {
+                tracing::debug!("Saving new user task list to cache...");
+                ctx.cache.user_task_list = Some(user_task_list.clone());
+                cache::save(&cache_path, &ctx.cache)?;
+                user_task_list
+            }
+            Some(Err(error)) if todo::asana::is_auth_required(&error) => {
+                exit_auth_required(&command, &cache_path)
+            }
+            Some(Err(error)) => {
+                suggest_offline(&error);
+                return Err(error);
+            }
+            // The placeholder never reaches the cache or the network: a spent budget also
+            // short-circuits the tasks fetch below, and the prompt commands read nothing
+            // else from the list.
+            None => {
+                tracing::warn!("Network budget expired resolving the user task list...");
+                network_budget_spent = true;
+                UserTaskList { gid: String::new() }
+            }
+        }
+    };
+    tracing::debug!("Got user task list: {user_task_list:#?}");
 
-    log::info!("Getting tasks...");
-    let tasks = if let (Some(tasks), true) = (cache.tasks.clone(), args.use_cache) {
-        log::debug!("Using cached tasks...");
+    tracing::info!("Getting tasks...");
+    let tasks = if let (Some(tasks), true) = (ctx.cache.tasks.clone(), use_cache) {
+        tracing::debug!("Using cached tasks...");
         tasks
+    } else if network_budget_spent {
+        tracing::warn!("Network budget already spent, serving whatever tasks are cached...");
+        ctx.cache.tasks.clone().unwrap_or_default()
     } else {
-        log::debug!("Getting tasks from Asana...");
-        let tasks = client
-            .get::<UserTask>(&user_task_list.gid.to_string())
-            .await?;
-
-        log::debug!("Saving new tasks to cache...");
-        cache.tasks = Some(tasks.clone());
-        save_cache(&cache_path, &cache)?;
-        tasks
+        tracing::debug!("Getting tasks from Asana...");
+        let fetch = todo::commands::update::fetch_tasks(
+            &mut client,
+            user_task_list.clone(),
+            &workspace_gid,
+        );
+        match within_budget(network_deadline, fetch).await {
+            Some(Ok((tasks, resolved))) => {
+                if resolved.gid != user_task_list.gid {
+                    ctx.cache.user_task_list = Some(resolved.clone());
+                    user_task_list = resolved;
+                }
+
+                tracing::debug!("Saving new tasks to cache...");
+                ctx.cache.tasks = Some(tasks.clone());
+                cache::save(&cache_path, &ctx.cache)?;
+                tasks
+            }
+            Some(Err(error)) if todo::asana::is_auth_required(&error) => {
+                exit_auth_required(&command, &cache_path)
+            }
+            Some(Err(error)) => {
+                suggest_offline(&error);
+                return Err(error);
+            }
+            None => {
+                tracing::warn!("Network budget expired fetching tasks, serving the cache...");
+                network_budget_spent = true;
+                ctx.cache.tasks.clone().unwrap_or_default()
+            }
+        }
     };
-    log::debug!("Got {} tasks", tasks.len());
-    log::trace!("Tasks: {tasks:#?}");
+    tracing::debug!("Got {} tasks", tasks.len());
+    tracing::trace!("Tasks: {tasks:#?}");
 
-    let now = Local::now();
-    let today = now.date_naive();
+    let now = ctx.now;
+    let today = ctx.today();
 
-    log::info!("Grouping tasks...");
-    let overdue_tasks = {
-        let mut tasks: Vec<_> = tasks
-            .iter()
-            .filter(|t| t.due_on.is_some_and(|d| d < today))
-            .collect();
-        tasks.sort_by_key(|t| t.due_on.unwrap());
-        tasks
-    };
-    let due_today_tasks = {
-        let mut tasks: Vec<_> = tasks
-            .iter()
-            .filter(|t| t.due_on.is_some_and(|d| d == today))
-            .collect();
-        tasks.sort_by_key(|t| t.due_on.unwrap());
+    tracing::info!("Grouping tasks...");
+    let mut grouped_tasks = GroupedTasks::group(&tasks, today);
+    if let Some(priority_field_gid) = &ctx.config.list.priority_field_gid {
+        grouped_tasks.sort_by_priority(priority_field_gid);
+    }
+    tracing::debug!(
+        "Grouped tasks: {overdue_tasks} overdue, {due_today_tasks} due today, {due_week_tasks} due this week, {no_due_date_tasks} undated",
+        overdue_tasks = grouped_tasks.overdue.len(),
+        due_today_tasks = grouped_tasks.due_today.len(),
+        due_week_tasks = grouped_tasks.due_week.len(),
+        no_due_date_tasks = grouped_tasks.no_due_date.len()
+    );
+    let eod = now.hour() >= START_HOUR_FOR_EOD;
+    let paused = pause::is_paused(ctx.cache.paused.as_ref(), today);
+    // Entries carry their completion timestamps, so filtering on today's local date keeps a
+    // cache fetched yesterday evening from counting towards this morning.
+    let done_today = ctx.cache.completed_today.as_ref().map_or(0, |tasks| {
         tasks
-    };
-    let due_week_tasks = {
-        let mut tasks: Vec<_> = tasks
             .iter()
-            .filter(|t| {
-                t.due_on.is_some_and(|d| {
-                    d > today && d <= today.checked_add_days(Days::new(7)).unwrap()
-                })
-            })
-            .collect();
-        tasks.sort_by_key(|t| t.due_on.unwrap());
-        tasks
-    };
-    log::debug!(
-        "Grouped tasks: {overdue_tasks} overdue, {due_today_tasks} due today, {due_week_tasks} due this week",
-        overdue_tasks = overdue_tasks.len(),
-        due_today_tasks = due_today_tasks.len(),
-        due_week_tasks = due_week_tasks.len()
+            .filter(|t| t.completed_at.is_some_and(|at| at.date_naive() == today))
+            .count()
+    });
+    let mut status = Status::new(
+        &grouped_tasks,
+        ctx.cache.focus_day.as_ref().filter(|d| d.date == today),
+        eod,
+        today,
+        ctx.config.focus.is_scheduled(today),
+        paused,
+        done_today,
     );
+    // Counts served from the cache after the budget expired carry the marker into every format.
+    status.stale = network_budget_spent;
+
+    let outcome = match command {
+        Command::Init => {
+            tracing::info!("Running interactive setup...");
+            let theme = ColorfulTheme::default();
+
+            let workspaces = client.get::<Workspace>(&()).await?;
+            anyhow::ensure!(
+                !workspaces.is_empty(),
+                "no workspaces are visible to this account"
+            );
+            let workspace_names: Vec<_> = workspaces.iter().map(|w| w.name.as_str()).collect();
+            let workspace = &workspaces[Select::with_theme(&theme)
+                .with_prompt("Which workspace should tasks be pulled from?")
+                .items(&workspace_names)
+                .default(
+                    workspaces
+                        .iter()
+                        .position(|w| w.gid == workspace_gid)
+                        .unwrap_or(0),
+                )
+                .interact()?];
+
+            let projects = client.get::<Project>(&workspace.gid).await?;
+            let mut project_names: Vec<_> = projects.iter().map(|p| p.name.as_str()).collect();
+            project_names.push("(none)");
+            let selection = Select::with_theme(&theme)
+                .with_prompt("Which project holds your daily focuses?")
+                .items(&project_names)
+                .default(
+                    projects
+                        .iter()
+                        .position(|p| p.gid == focus_project_gid)
+                        .unwrap_or(project_names.len() - 1),
+                )
+                .interact()?;
+            let focus_project = projects.get(selection).map(|p| p.gid.clone());
+            if focus_project.is_none() {
+                term.write_line(
+                    &style(
+                        "No focus project selected. Create a \"Daily Focuses\" project in Asana \
+                         and re-run `todo init` to enable focus tracking.",
+                    )
+                    .dim()
+                    .to_string(),
+                )?;
+            }
+
+            let flavors = ["xbar", "SwiftBar"];
+            let flavor = Select::with_theme(&theme)
+                .with_prompt("Which menu bar app do you use, if any?")
+                .items(&flavors)
+                .default(match ctx.config.menubar.flavor {
+                    todo::config::MenubarFlavor::Xbar => 0,
+                    todo::config::MenubarFlavor::Swiftbar => 1,
+                })
+                .interact()?;
+            let blocking = Confirm::with_theme(&theme)
+                .with_prompt("Block new shells while a focus routine is pending (`todo gate`)?")
+                .default(ctx.config.terminal.blocking)
+                .interact()?;
+
+            ctx.config.asana.workspace_gid = Some(workspace.gid.clone());
+            ctx.config.asana.focus_project_gid = focus_project;
+            ctx.config.menubar.flavor = if flavor == 1 {
+                todo::config::MenubarFlavor::Swiftbar
+            } else {
+                todo::config::MenubarFlavor::Xbar
+            };
+            ctx.config.terminal.blocking = blocking;
+            todo::config::save(&config_path, &ctx.config)?;
+            term.write_line(&format!(
+                "Wrote configuration to {}",
+                config_path.display()
+            ))?;
+
+            // Warm the cache against the chosen workspace, mirroring the update command. The
+            // profile is a nicety (greeting, gid lookups), so a failed fetch is only logged.
+            match client.get::<User>(&()).await {
+                Ok(user) => ctx.cache.user = Some(user),
+                Err(error) => tracing::warn!("Could not fetch the user profile: {error:#}"),
+            }
+            let user_gid = ctx
+                .cache
+                .user
+                .as_ref()
+                .map_or_else(|| "me".to_string(), |user| user.gid.clone());
+            let request = (user_gid, workspace.gid.clone());
+            let user_task_list = client.get::<UserTaskList>(&request).await?;
+            ctx.cache.tasks = Some(client.get::<UserTask>(&user_task_list.gid).await?);
+            ctx.cache.user_task_list = Some(user_task_list);
+            if let Some(focus_project_gid) = &ctx.config.asana.focus_project_gid {
+                ctx.cache.focus_day = Some(ensure_focus_day(today, &mut client, focus_project_gid).await?);
+            }
+            ctx.cache.mark_updated(ctx.now);
+            cache::save(&cache_path, &ctx.cache)?;
+            term.write_line("All set. Run `todo summary` to see your tasks, and `todo install completions <shell>` for tab completion.")?;
+            None
+        }
+
+        Command::Summary => {
+            tracing::info!("Producing a summary of tasks...");
+            let focus_tracked = ctx.config.focus.is_scheduled(today) && !paused;
+            let mut focus_day = ctx.cache.focus_day.clone().filter(|d| d.date == today);
+            let mut focus_unknown = false;
+            if focus_tracked && focus_day.is_none() {
+                // The cache has nothing for today. The live fallback is strictly read-only —
+                // creating the day task is reserved for `update` and the focus flows — and is
+                // skipped entirely when disabled, offline, or asked to stay instant.
+                if ctx.config.summary.fetch_focus_fallback && !args.offline && !use_cache {
+                    match find_focus_day(today, &mut client, &focus_project_gid).await {
+                        Ok(day) => focus_day = day,
+                        Err(error) => {
+                            tracing::warn!("Could not fetch the focus day: {error:#}");
+                            focus_unknown = true;
+                        }
+                    }
+                } else {
+                    focus_unknown = true;
+                }
+            }
+            // Mirror the gating in Status::new: stats only nag while focus is scheduled and
+            // unpaused, and the evening ones only once the reflection window opens. A missing
+            // day counts as everything pending, but only when its absence is known rather
+            // than an artifact of a stale cache.
+            let pending_stats = if focus_tracked && !focus_unknown {
+                focus_day
+                    .as_ref()
+                    .map_or(&FocusDayStats::default(), |d| &d.stats)
+                    .pending_names(eod)
+            } else {
+                Vec::new()
+            };
+            todo::commands::summary::run(
+                &mut ctx,
+                &grouped_tasks,
+                status.focus_subtasks_overdue + status.focus_subtasks_pending,
+                status.done_today,
+                &pending_stats,
+                focus_unknown,
+                args.offline,
+            )?;
+            Some(status.outcome())
+        }
+
+        Command::List {
+            all,
+            format,
+            group_by,
+            absolute,
+            links,
+        } => {
+            tracing::info!("Producing a list of tasks...");
+            let priority_field_gid = ctx.config.list.priority_field_gid.clone();
+            let theme = ctx.theme.clone();
+            let date_format = ctx.config.display.date_format.clone();
+            let options = todo::commands::list::ListOptions {
+                all,
+                relative_to: if absolute || !ctx.config.list.relative_dates {
+                    None
+                } else {
+                    Some(today)
+                },
+                links: if links && term.features().is_attended() {
+                    if todo::render::supports_hyperlinks(&term) {
+                        LinkMode::Hyperlink
+                    } else {
+                        LinkMode::Plain
+                    }
+                } else {
+                    LinkMode::None
+                },
+                priority_field: priority_field_gid.as_deref(),
+                theme: Some(&theme),
+                date_format: date_format.as_deref(),
+            };
+            todo::commands::list::run(&mut ctx, &grouped_tasks, format, group_by, options)?;
+            Some(status.outcome())
+        }
+
+        Command::Agenda { weeks, format } => {
+            tracing::info!("Producing an agenda of tasks...");
+            let focus_days: Vec<(chrono::NaiveDate, FocusMarker)> = ctx
+                .cache
+                .focus_day
+                .as_ref()
+                .map(|day| {
+                    let marker = if day.is_morning_done() && day.is_evening_done() {
+                        FocusMarker::Done
+                    } else {
+                        FocusMarker::Pending
+                    };
+                    (day.date, marker)
+                })
+                .into_iter()
+                .collect();
+            let agenda = todo::commands::agenda::render(
+                &tasks,
+                &focus_days,
+                today,
+                weeks,
+                format,
+                ctx.config.display.date_format.as_deref(),
+            );
+            println!("{}", agenda.trim_end());
+            Some(status.outcome())
+        }
+
+        Command::Log {
+            days,
+            format,
+            project,
+        } => {
+            tracing::info!("Producing a completion log...");
+            anyhow::ensure!(days > 0, "--days expects at least 1");
+            let since = today - chrono::Days::new(days - 1);
+
+            let from_cache = args.use_cache || args.offline;
+            let mut completed = if from_cache {
+                ctx.cache.completed_today.clone().unwrap_or_default()
+            } else {
+                client
+                    .get::<CompletedTask>(&(user_task_list.gid.clone(), since))
+                    .await
+                    .inspect_err(suggest_offline)?
+            };
+            completed.retain(|t| t.completed_at.is_some_and(|at| at.date_naive() >= since));
+            if let Some(project) = &project {
+                completed.retain(|t| {
+                    t.projects
+                        .iter()
+                        .any(|p| p.name.eq_ignore_ascii_case(project))
+                });
+            }
+
+            let log_days = todo::commands::log::group_by_day(&completed);
+            let date_format = ctx.config.display.date_format.as_deref();
+            let time_format = ctx.config.display.time_format.as_deref();
+            let output = match format {
+                LogFormat::Plain => {
+                    todo::commands::log::render_plain(&log_days, date_format, time_format)
+                }
+                LogFormat::Json => todo::commands::log::render_json(&log_days)?,
+                LogFormat::Markdown => {
+                    todo::commands::log::render_markdown(&log_days, date_format, time_format)
+                }
+            };
+            ctx.writer.line(&output)?;
+
+            // The cache only holds the completed-today window, so make the narrower range
+            // obvious; the note stays out of the machine-readable formats.
+            if from_cache && format == LogFormat::Plain {
+                let age = ctx
+                    .cache
+                    .last_updated
+                    .map_or_else(String::new, |last_updated| {
+                        format!(
+                            ", {} minutes old",
+                            (ctx.now - last_updated).num_minutes()
+                        )
+                    });
+                ctx.writer.line(
+                    &style(format!("(from cache: covers today only{age})"))
+                        .dim()
+                        .to_string(),
+                )?;
+            }
+            None
+        }
+
+        Command::Add {
+            name,
+            due,
+            start,
+            at,
+            assignee,
+            followers,
+            ask_assignee,
+            force,
+            allow_past,
+            attachments,
+        } => {
+            tracing::info!("Creating a task...");
+            // A due date that resolved into the past is usually a mis-anchored weekday, so it
+            // needs confirmation (or --allow-past) instead of silently creating overdue work.
+            let due_on = due
+                .as_deref()
+                .map(|spec| {
+                    todo::utils::resolve_due_date(
+                        spec,
+                        today,
+                        allow_past,
+                        term.features().is_attended(),
+                    )
+                })
+                .transpose()?;
+            let start_on = start
+                .as_deref()
+                .map(|spec| {
+                    todo::utils::parse_flexible_date(spec, today)
+                        .with_context(|| format!("could not parse `{spec}` as a date"))
+                })
+                .transpose()?;
+            if let (Some(start_on), Some(due_on)) = (start_on, due_on) {
+                anyhow::ensure!(
+                    start_on <= due_on,
+                    "the start date ({start_on}) is after the due date ({due_on})"
+                );
+            }
+            // `--at` requires `--due` at the CLI level, so the pairing always holds here.
+            let due_at = at
+                .as_deref()
+                .zip(due_on)
+                .map(|(spec, due_on)| {
+                    let time = chrono::NaiveTime::parse_from_str(spec, "%H:%M")
+                        .with_context(|| format!("could not parse `{spec}` as an HH:MM time"))?;
+                    todo::utils::local_time_to_utc(due_on, time)
+                })
+                .transpose()?;
+
+            // The same task often gets captured twice in a week; a likely duplicate among the
+            // cached incomplete tasks is worth a question before another copy is created.
+            let mut proceed = true;
+            if !force {
+                if let Some(existing) = todo::commands::add::find_duplicate(&name, &tasks) {
+                    let existing_due = existing
+                        .due_on
+                        .map_or_else(|| "no due date".to_string(), |due| due.to_string());
+                    if std::io::stdin().is_terminal() {
+                        proceed = Confirm::with_theme(&ColorfulTheme::default())
+                            .with_prompt(format!(
+                                "\"{existing}\" ({existing_due}) looks like the same task; add \
+                                 anyway?",
+                                existing = existing.name
+                            ))
+                            .default(false)
+                            .interact()?;
+                    } else {
+                        eprintln!(
+                            "{}",
+                            style(format!(
+                                "Warning: \"{existing}\" ({existing_due}) looks like the same \
+                                 task; pass --force to skip this check",
+                                existing = existing.name
+                            ))
+                            .yellow()
+                        );
+                    }
+                }
+            }
+
+            if proceed {
+                // Workspace users are only needed (and fetched) when something has to be
+                // resolved by email or name; `me` and bare gids go straight through.
+                let needs_users = ask_assignee || assignee != "me" || !followers.is_empty();
+                let users = if needs_users {
+                    if let (Some(users), true) = (ctx.cache.workspace_users.clone(), use_cache) {
+                        users
+                    } else {
+                        let users = client
+                            .get::<WorkspaceUser>(&workspace_gid)
+                            .await
+                            .inspect_err(suggest_offline)?;
+                        ctx.cache.workspace_users = Some(users.clone());
+                        cache::save(&cache_path, &ctx.cache)?;
+                        users
+                    }
+                } else {
+                    Vec::new()
+                };
+
+                let assignee = if ask_assignee {
+                    anyhow::ensure!(
+                        std::io::stdin().is_terminal(),
+                        "--ask-assignee is interactive and needs a terminal; use --assignee \
+                         instead"
+                    );
+                    let user_names: Vec<String> = users
+                        .iter()
+                        .map(|user| match &user.email {
+                            Some(email) => format!("{} <{email}>", user.name),
+                            None => user.name.clone(),
+                        })
+                        .collect();
+                    let index = Select::with_theme(&ColorfulTheme::default())
+                        .with_prompt("Who should this task be assigned to?")
+                        .items(&user_names)
+                        .default(0)
+                        .interact()?;
+                    users[index].gid.clone()
+                } else {
+                    todo::commands::add::resolve_user(&assignee, &users)?
+                };
+                // A literal `me` works against the API, but the cached profile's gid keeps the
+                // request explicit about who it targets.
+                let assignee = match (&ctx.cache.user, assignee.as_str()) {
+                    (Some(user), "me") => user.gid.clone(),
+                    _ => assignee,
+                };
+                let followers = followers
+                    .iter()
+                    .map(|follower| todo::commands::add::resolve_user(follower, &users))
+                    .collect::<anyhow::Result<Vec<String>>>()?;
+
+                if ctx.dry_run {
+                    println!(
+                        "would have created \"{name}\"{due}",
+                        due = due_on.map_or_else(String::new, |due| format!(" due {due}"))
+                    );
+                    for file in &attachments {
+                        println!("would have attached {file}", file = file.display());
+                    }
+                } else {
+                    let assigned_to_me = ctx
+                        .cache
+                        .user
+                        .as_ref()
+                        .map_or(assignee == "me", |user| user.gid == assignee);
+                    let created = client
+                        .mutate(
+                            &(),
+                            CreateTaskRequest {
+                                name: name.clone(),
+                                workspace: workspace_gid.clone(),
+                                assignee,
+                                due_on: if due_at.is_some() { None } else { due_on },
+                                due_at,
+                                start_on,
+                                followers,
+                            },
+                        )
+                        .await
+                        .context("issue creating task")?;
+                    println!(
+                        "Added \"{name}\"{due}.",
+                        due = due_on.map_or_else(String::new, |due| format!(" due {due}"))
+                    );
+
+                    if !attachments.is_empty() {
+                        upload_attachments(&client, &created.gid, &attachments, None, false)
+                            .await?;
+                    }
+
+                    // Mirror the new task into the cache when it lands in the viewed task list,
+                    // so cached views stay accurate until the next update.
+                    if assigned_to_me {
+                        if let Some(tasks) = &mut ctx.cache.tasks {
+                            tasks.push(created);
+                            cache::save(&cache_path, &ctx.cache)?;
+                        }
+                    }
+                }
+            } else {
+                println!("Left it alone.");
+            }
+            None
+        }
+
+        Command::Attach { gid, files, mime } => {
+            upload_attachments(&client, &gid, &files, mime.as_deref(), ctx.dry_run).await?;
+            None
+        }
+
+        Command::Report { command } => {
+            let ReportCommand::Week { date, out } = command;
+            tracing::info!("Generating a weekly report...");
+            let week_start = date.unwrap_or(today).week(Weekday::Mon).first_day();
+
+            let focus_days =
+                fetch_focus_week_days(week_start, &mut client, &focus_project_gid).await?;
+            let completed = client
+                .get::<CompletedTask>(&(user_task_list.gid.clone(), week_start))
+                .await
+                .inspect_err(suggest_offline)?;
+
+            let report = todo::commands::report::render_week(
+                &todo::commands::report::WeekReportData {
+                    week_start,
+                    focus_days,
+                    completed,
+                    open_tasks: tasks.clone(),
+                },
+            );
+            match out {
+                Some(out) => {
+                    let out = expand_homedir(&out)?;
+                    fs::write(&out, &report).context("could not write report file")?;
+                    println!("Wrote the weekly report to {}.", out.display());
+                }
+                None => print!("{report}"),
+            }
+            None
+        }
+
+        Command::Export { command } => {
+            match command {
+                ExportCommand::Ical { out, days, todos } => {
+                    tracing::info!("Exporting tasks as an iCalendar feed...");
+                    let ical = todo::commands::export::render_ical(&tasks, today, days, todos);
+                    match out {
+                        Some(out) => {
+                            let out = expand_homedir(&out)?;
+                            fs::write(&out, &ical).context("could not write iCalendar file")?;
+                            println!(
+                                "Wrote {count} to {path}.",
+                                count = todo::commands::export::event_or_events(
+                                    todo::commands::export::event_count(&ical)
+                                ),
+                                path = out.display()
+                            );
+                        }
+                        None => print!("{ical}"),
+                    }
+                }
+                ExportCommand::Taskwarrior { filter } => {
+                    tracing::info!("Exporting tasks as taskwarrior JSON...");
+                    println!(
+                        "{}",
+                        todo::interop::taskwarrior::render(&tasks, filter, today)?
+                    );
+                }
+            }
+            None
+        }
+
+        Command::Triage => {
+            tracing::info!("Triaging overdue tasks...");
+            let mut summary = todo::commands::triage::TriageSummary::default();
+            let mut mutation_tasks: Vec<tokio::task::JoinHandle<anyhow::Result<()>>> = Vec::new();
+            let mut actions: Vec<(String, todo::commands::triage::TriageAction)> = Vec::new();
+
+            if grouped_tasks.overdue.is_empty() {
+                println!("{}", style("Nothing overdue to triage!").green().bold());
+            }
+            'triage: for task in &grouped_tasks.overdue {
+                let Some(due) = task.due_on else { continue };
+                term.write_line(&format!(
+                    "{name}  {due}",
+                    name = style(&task.name).bold(),
+                    due = style(format!(
+                        "due {due} ({age})",
+                        age = todo::utils::format_relative_date(due, today)
+                    ))
+                    .red()
+                ))?;
+                term.write_line(
+                    &style("[c]omplete [t]oday [r]eschedule [s]kip [o]pen [q]uit")
+                        .dim()
+                        .to_string(),
+                )?;
+
+                let action = loop {
+                    match term.read_key()? {
+                        console::Key::Char('c') => {
+                            break todo::commands::triage::TriageAction::Completed
+                        }
+                        console::Key::Char('t') => {
+                            break todo::commands::triage::TriageAction::Rescheduled(today)
+                        }
+                        console::Key::Char('r') => {
+                            let date = loop {
+                                let text = Input::<String>::with_theme(&ColorfulTheme::default())
+                                    .with_prompt("reschedule to")
+                                    .interact_text()?;
+                                // A declined past date falls through to another prompt, the
+                                // same as a spec that did not parse.
+                                match todo::utils::resolve_due_date(&text, today, false, true) {
+                                    Ok(date) => break date,
+                                    Err(error) => term.write_line(
+                                        &style(format!("{error:#}")).yellow().to_string(),
+                                    )?,
+                                }
+                            };
+                            break todo::commands::triage::TriageAction::Rescheduled(date);
+                        }
+                        console::Key::Char('s') => {
+                            break todo::commands::triage::TriageAction::Skipped
+                        }
+                        console::Key::Char('o') => {
+                            open::that_detached(todo::render::task_permalink(&task.gid))
+                                .context("could not open the task in the browser")?;
+                        }
+                        console::Key::Char('q') => break 'triage,
+                        _ => {}
+                    }
+                };
+
+                summary.record(action);
+                let body = match action {
+                    todo::commands::triage::TriageAction::Completed => UpdateTaskRequest {
+                        completed: Some(true),
+                        due_on: None,
+                    },
+                    todo::commands::triage::TriageAction::Rescheduled(date) => UpdateTaskRequest {
+                        completed: None,
+                        due_on: Some(date),
+                    },
+                    todo::commands::triage::TriageAction::Skipped => continue,
+                };
+                actions.push((task.gid.clone(), action));
+
+                if ctx.dry_run {
+                    match action {
+                        todo::commands::triage::TriageAction::Completed => {
+                            println!("would have completed \"{name}\"", name = task.name);
+                        }
+                        todo::commands::triage::TriageAction::Rescheduled(date) => {
+                            println!(
+                                "would have rescheduled \"{name}\" to {date}",
+                                name = task.name
+                            );
+                        }
+                        todo::commands::triage::TriageAction::Skipped => {}
+                    }
+                    continue;
+                }
+
+                // Mutations run concurrently so the sweep never waits on the API between tasks.
+                let mutation_task = tokio::spawn({
+                    let client = client.clone();
+                    let task_gid = task.gid.clone();
+
+                    async move {
+                        tracing::info!("Updating task...");
+                        client.mutate(&task_gid, body).await?;
+                        tracing::debug!("Updated task");
+                        Ok::<(), anyhow::Error>(())
+                    }
+                    .in_current_span()
+                });
+                mutation_tasks.push(mutation_task);
+            }
+
+            if mutation_tasks.iter().any(|t| !t.is_finished()) {
+                let status = StatusLine::show(
+                    ctx.writer.as_mut(),
+                    ctx.output,
+                    &style("Waiting for updates to sync...").dim().to_string(),
+                );
+                for res in join_all(mutation_tasks).await {
+                    res??;
+                }
+                drop(status);
+            }
+
+            // Mirror the confirmed actions into the cache so the next cached view is accurate.
+            if !ctx.dry_run {
+                if let Some(tasks) = &mut ctx.cache.tasks {
+                    for (gid, action) in &actions {
+                        match action {
+                            todo::commands::triage::TriageAction::Completed => {
+                                tasks.retain(|t| &t.gid != gid);
+                            }
+                            todo::commands::triage::TriageAction::Rescheduled(date) => {
+                                if let Some(task) = tasks.iter_mut().find(|t| &t.gid == gid) {
+                                    task.due_on = Some(*date);
+                                }
+                            }
+                            todo::commands::triage::TriageAction::Skipped => {}
+                        }
+                    }
+                }
+                cache::save(&cache_path, &ctx.cache)?;
+            }
+
+            println!("{}", summary.render());
+            None
+        }
 
-    match args.command {
-        Command::Summary => {
-            log::info!("Producing a summary of tasks...");
-            let mut string = String::new();
-            string.push_str(&match (overdue_tasks.len(), due_today_tasks.len()) {
-                (0, 0) => style("Nice! Everything done for now!")
-                    .green()
-                    .bold()
-                    .to_string(),
-                (o, 0) => style(format!("You have {} overdue.", task_or_tasks(o)))
-                    .red()
-                    .bold()
-                    .to_string(),
-                (0, t) => style(format!("You have {} due today.", task_or_tasks(t)))
-                    .yellow()
-                    .bold()
+        Command::Count { format, .. } => {
+            tracing::info!("Producing task counts...");
+            let focus_day = ctx.cache.focus_day.as_ref().filter(|d| d.date == today);
+            let mut counts = todo::commands::count::Counts::new(&grouped_tasks, focus_day);
+            counts.stale = network_budget_spent;
+            match format {
+                CountFormat::Shell => {
+                    println!("{}", todo::commands::count::render_shell(&counts));
+                }
+                CountFormat::Json => {
+                    println!("{}", todo::commands::count::render_json(&counts)?);
+                }
+            }
+            None
+        }
+
+        Command::Status { format, .. } => {
+            tracing::info!("Producing a status line...");
+            let symbols = StatusSymbols::resolve(&ctx.config.status);
+            let line = match format {
+                // The short string itself stays free of ANSI codes since the starship and
+                // waybar formats reuse it, so the paused and due-week tokens are dimmed here
+                // instead.
+                StatusFormat::Short => {
+                    let mut line = status
+                        .to_short_string(&symbols)
+                        .replace("paused", &style("paused").dim().to_string())
+                        .replace("stale", &style("stale").dim().to_string());
+                    if status.due_week > 0 {
+                        let token = format!("~{}", status.due_week);
+                        line = line.replace(&token, &style(&token).dim().to_string());
+                    }
+                    line
+                }
+                StatusFormat::Json => {
+                    let report = todo::commands::status::StatusReport::new(
+                        status,
+                        ctx.cache.focus_day.as_ref().filter(|d| d.date == today),
+                        ctx.cache.last_updated,
+                        now,
+                    );
+                    todo::commands::status::render_json(&report)?
+                }
+                StatusFormat::Waybar => todo::commands::status::render_waybar(&status, &symbols)?,
+                StatusFormat::Xbar => status
+                    .to_xbar_string(
+                        &grouped_tasks,
+                        &symbols,
+                        &ctx.config.menubar,
+                        ctx.config.status.ascii_only,
+                        &todo::commands::status::XbarExtras {
+                            focus_gid: ctx
+                                .cache
+                                .focus_day
+                                .as_ref()
+                                .filter(|d| d.date == today)
+                                .map(|d| d.task.gid.as_str()),
+                            last_updated: ctx.cache.last_updated,
+                            now,
+                        },
+                    )
+                    .trim_end()
                     .to_string(),
-                (o, t) => style(format!(
-                    "You have {} overdue or due today",
-                    task_or_tasks(o + t)
-                ))
-                .red()
-                .bold()
+                StatusFormat::Starship => {
+                    todo::commands::status::render_starship(&status, &symbols)
+                }
+                StatusFormat::Prometheus => todo::commands::status::render_prometheus(
+                    &status,
+                    ctx.cache.last_updated.map(|t| (now - t).num_seconds()),
+                )
+                .trim_end()
                 .to_string(),
-            });
+            };
+            ctx.writer.line(&line)?;
+            Some(status.outcome())
+        }
 
-            string.push_str(&match due_week_tasks.len() {
-                0 => String::new(),
-                w => style(format!(
-                    " You have another {} due within a week.",
-                    task_or_tasks(w)
-                ))
-                .blue()
-                .to_string(),
-            });
+        Command::Gate => {
+            tracing::info!("Running the terminal gate...");
+            let phase = gate::pending_phase(&status);
+            if let Some(phase) = phase.filter(|_| ctx.config.terminal.blocking) {
+                let acknowledged =
+                    gate::is_acknowledged(ctx.cache.gate_acknowledged.as_ref(), today, phase);
+                if !acknowledged && std::io::stdin().is_terminal() {
+                    println!(
+                        "Your {} focus routine is still pending.",
+                        style(phase).bold()
+                    );
+                    println!(
+                        "Press {} to run it now, or any other key to continue.",
+                        style("f").bold()
+                    );
+                    let key = term.read_key()?;
 
-            term.write_line(&format!(
-                "{string} {}",
-                style(format!(
-                    "(https://app.asana.com/0/{user_task_list_gid}/list)",
-                    user_task_list_gid = user_task_list.gid
-                ))
-                .dim()
-            ))?;
+                    // Record the acknowledgement either way: running the routine marks it done,
+                    // and dismissing it should keep later shells quiet for the rest of the day.
+                    ctx.cache.gate_acknowledged = Some(gate::GateAcknowledgement {
+                        date: today,
+                        phase,
+                    });
+                    cache::save(&cache_path, &ctx.cache)?;
+
+                    if matches!(key, console::Key::Char('f' | 'F')) {
+                        let exe =
+                            env::current_exe().context("could not find the todo executable")?;
+                        std::process::Command::new(exe)
+                            .arg("focus")
+                            .status()
+                            .context("could not run the focus routine")?;
+                    }
+                }
+            }
+            None
         }
 
-        Command::List => {
-            log::info!("Producing a list of tasks...");
-            let mut string = String::new();
-
-            if !overdue_tasks.is_empty() {
-                string.push_str(&format!(
-                    "{} {}\n",
-                    style(task_or_tasks(overdue_tasks.len())).red().bold(),
-                    style("overdue:").bold()
-                ));
-                for task in overdue_tasks {
-                    string.push_str(&format!(
-                        "- ({}) {}\n",
-                        style(task.due_on.unwrap().to_string()).red(),
-                        task.name
-                    ));
+        Command::Notify { phase, dry_run } => {
+            tracing::info!("Sending a focus reminder notification...");
+            if let Some(phase) = notify::resolve_phase(phase, &status) {
+                let (summary, body) = notify::message(phase);
+                if dry_run {
+                    println!("would notify: {summary} — {body}");
+                } else {
+                    notify::send(phase)?;
+                }
+            } else if dry_run {
+                if paused {
+                    println!("would not notify: paused");
+                } else {
+                    println!("would not notify: no focus routine is pending");
                 }
-                string.push('\n');
             }
+            None
+        }
 
-            if !due_today_tasks.is_empty() {
-                string.push_str(&format!(
-                    "{} {}\n",
-                    style(task_or_tasks(due_today_tasks.len())).yellow(),
-                    style("due today:").bold()
-                ));
-                for task in due_today_tasks {
-                    string.push_str(&format!("- {}\n", task.name));
+        Command::Pause { until, days } => {
+            tracing::info!("Managing the pause state...");
+            match (until, days) {
+                (None, None) => {
+                    if let Some(window) = ctx.cache.paused.filter(|_| paused) {
+                        println!(
+                            "Paused until {until}; reminders and focus tracking resume {resumes}.",
+                            until = window.until,
+                            resumes = window.resumes_on(),
+                        );
+                    } else {
+                        println!("Not paused.");
+                    }
                 }
-                string.push('\n');
-            }
+                (until, days) => {
+                    let until = if let Some(input) = until {
+                        todo::utils::parse_flexible_date(&input, today).with_context(|| {
+                            format!("could not parse `{input}` as a date")
+                        })?
+                    } else {
+                        let days = days.unwrap_or_default();
+                        anyhow::ensure!(days > 0, "--days expects at least 1");
+                        today + chrono::Days::new(days - 1)
+                    };
+                    anyhow::ensure!(
+                        until >= today,
+                        "cannot pause until {until}, which is in the past"
+                    );
 
-            if !due_week_tasks.is_empty() {
-                string.push_str(&format!(
-                    "{} {}\n",
-                    style(task_or_tasks(due_week_tasks.len())).blue(),
-                    style("due within a week:").bold()
-                ));
-                for task in due_week_tasks {
-                    string.push_str(&format!(
-                        "- ({}) {}\n",
-                        style(task.due_on.unwrap().to_string()).blue(),
-                        task.name
-                    ));
+                    let window = pause::PauseWindow { until };
+                    println!(
+                        "Paused until {until}; reminders and focus tracking resume {resumes}.",
+                        resumes = window.resumes_on(),
+                    );
+                    ctx.cache.paused = Some(window);
+                    cache::save(&cache_path, &ctx.cache)?;
                 }
             }
+            None
+        }
 
-            if string.is_empty() {
-                string.push_str(
-                    &style("Nice! Everything done for now!")
-                        .green()
-                        .bold()
-                        .to_string(),
-                );
+        Command::Resume => {
+            tracing::info!("Clearing the pause state...");
+            if paused {
+                println!("Resumed; reminders and focus tracking are active again.");
             } else {
-                println!("{}", string.trim());
+                println!("Not paused.");
             }
+            // An expired window gets cleaned out too, even though it no longer had any effect.
+            if ctx.cache.paused.take().is_some() {
+                cache::save(&cache_path, &ctx.cache)?;
+            }
+            None
         }
 
         Command::Focus {
             date,
             force_eod,
+            force: _,
+            force_sync,
             command,
         } => {
-            log::info!("Managing focus...");
+            tracing::info!("Managing focus...");
 
             let date = if let Some(date) = date {
-                log::info!("Using date from command line: {}", date);
+                tracing::info!("Using date from command line: {}", date);
                 date
             } else {
-                log::info!("Using today's date: {}", today);
+                tracing::info!("Using today's date: {}", today);
                 today
             };
 
-            term.write_str(&style("Loading focus day...").dim().to_string())?;
-            let mut focus_day = get_focus_day(date, &mut client).await?;
-            term.clear_line()?;
-
             match command {
                 Some(FocusCommand::Run) | None => {
-                    log::info!("Running focus...");
+                    tracing::info!("Running focus...");
 
-                    log::debug!("Calculating unfilled stats...");
-                    let unfilled_stats_at_this_time: Vec<&FocusDayStat> = focus_day
-                        .stats
-                        .stats()
-                        .into_iter()
-                        .filter(|s| match s {
-                            FocusDayStat::Sleep(_) | FocusDayStat::Energy(_) => s.value().is_none(),
-                            _ => {
-                                s.value().is_none()
-                                    && (force_eod
-                                        || date < today
-                                        || now.hour() >= START_HOUR_FOR_EOD)
+                    let status = StatusLine::show(
+                        ctx.writer.as_mut(),
+                        ctx.output,
+                        &style("Loading focus day...").dim().to_string(),
+                    );
+                    let mut focus_day =
+                        ensure_focus_day(date, &mut client, &focus_project_gid).await?;
+                    drop(status);
+
+                    // Monday mornings open on the week's goals, so the week starts with them
+                    // in view; other days skip the extra round trip.
+                    if date.weekday() == Weekday::Mon
+                        && focus_day.phase(now, force_eod) == FocusPhase::Morning
+                    {
+                        if let Some(goals_task) =
+                            find_weekly_goals_task(date, &mut client, &focus_project_gid).await?
+                        {
+                            let goals = todo::focus::parse_weekly_goals(&goals_task.notes);
+                            if !goals.is_empty() {
+                                println!("{}\n", todo::focus::format_weekly_goals(&goals));
                             }
-                        })
-                        .collect::<Vec<_>>();
-                    log::trace!(
+                        }
+                    }
+
+                    // A draft left over from an interrupted run on the same date can be resumed:
+                    // its entered stats and diary prefill the prompts, and its unconfirmed
+                    // subtask creations get retried.
+                    let mut draft = FocusDraft {
+                        date,
+                        ..FocusDraft::default()
+                    };
+                    if let Some(existing) = ctx.cache.focus_draft.clone() {
+                        if existing.date == date
+                            && Confirm::with_theme(&ColorfulTheme::default())
+                                .with_prompt(
+                                    "Found a draft from an interrupted focus run. Resume it?",
+                                )
+                                .default(true)
+                                .interact()?
+                        {
+                            draft = existing;
+                        } else {
+                            ctx.cache.focus_draft = None;
+                            cache::save(&cache_path, &ctx.cache)?;
+                        }
+                    }
+
+                    tracing::debug!("Calculating unfilled stats...");
+                    let unfilled_stats_at_this_time: Vec<&FocusDayStat> =
+                        focus_day.pending_stats(now, force_eod);
+                    tracing::trace!(
                         "Calculated unfilled stats: {unfilled_stats_at_this_time:#?}",
                         unfilled_stats_at_this_time = unfilled_stats_at_this_time
                     );
@@ -1147,52 +2180,250 @@ async fn main() -> anyhow::Result<()> {
                     if unfilled_stats_at_this_time.is_empty() {
                         println!("{}\n", style("All caught up on stats!").bold().green());
                     } else {
-                        log::info!("Updating focus day stats...");
+                        tracing::info!("Updating focus day stats...");
                         println!("{}", style("Time to fill out some stats!").bold().cyan());
                         for stat in unfilled_stats_at_this_time {
                             let mut new_stat = stat.clone();
-                            let value = Input::<u32>::with_theme(&ColorfulTheme::default())
-                                .with_prompt(format!("{} {}", stat.name(), style("(0-9)").dim()))
-                                .validate_with(|i: &u32| {
-                                    if *i > 9 {
-                                        Err("value must be between 0 and 9".to_string())
-                                    } else {
-                                        Ok(())
-                                    }
-                                })
-                                .interact_text()?;
+                            let drafted = draft
+                                .stats
+                                .iter()
+                                .find(|(gid, _)| gid == stat.field_gid())
+                                .map(|(_, value)| *value);
+                            let value = if let Some(value) = drafted {
+                                println!(
+                                    "{}",
+                                    style(format!("{}: {value} (from draft)", stat.name())).dim()
+                                );
+                                value
+                            } else {
+                                let value = Input::<u32>::with_theme(&ColorfulTheme::default())
+                                    .with_prompt(format!(
+                                        "{} {}",
+                                        stat.name(),
+                                        style("(0-9)").dim()
+                                    ))
+                                    .validate_with(|i: &u32| {
+                                        if *i > 9 {
+                                            Err("value must be between 0 and 9".to_string())
+                                        } else {
+                                            Ok(())
+                                        }
+                                    })
+                                    .interact_text()?;
+                                // Each entered value is persisted immediately, so an
+                                // interruption after this prompt cannot lose it.
+                                draft.stats.push((stat.field_gid().to_string(), value));
+                                ctx.cache.focus_draft = Some(draft.clone());
+                                value
+                            };
+                            // Asana number fields cannot carry an entry timestamp, so when the
+                            // value was logged is recorded locally alongside the draft. A
+                            // resumed draft replays into a dedupe, keeping the original time.
+                            todo::focus::record_stat_entry(
+                                ctx.cache.stat_entries.get_or_insert_with(Vec::new),
+                                date,
+                                stat.name(),
+                                value,
+                                now,
+                            );
+                            cache::save(&cache_path, &ctx.cache)?;
                             new_stat.set_value(Some(value));
                             new_stats.set_stat(new_stat);
                         }
                         println!();
-                        log::debug!(
+                        tracing::debug!(
                             "Updated focus day stats: {new_stats:#?}",
                             new_stats = new_stats
                         );
                     }
 
-                    log::info!("Updating focus day diary...");
+                    let mut new_prompts = focus_day.prompts.clone();
+                    let pending_prompts = ctx
+                        .config
+                        .focus
+                        .prompts
+                        .iter()
+                        .filter(|p| {
+                            p.phase == FocusPhase::Morning
+                                || focus_day.phase(now, force_eod) == FocusPhase::Evening
+                        })
+                        .filter(|p| !new_prompts.iter().any(|a| a.key == p.key))
+                        .collect::<Vec<_>>();
+                    if !pending_prompts.is_empty() {
+                        tracing::info!("Asking the custom prompts...");
+                        println!("{}", style("A few more questions!").bold().blue());
+                        for prompt in pending_prompts {
+                            let answer = Input::<String>::with_theme(&ColorfulTheme::default())
+                                .with_prompt(prompt.question.clone())
+                                .allow_empty(true)
+                                .interact_text()?;
+                            // An empty answer skips the prompt; it comes back next run.
+                            if !answer.trim().is_empty() {
+                                new_prompts.push(PromptAnswer {
+                                    key: prompt.key.clone(),
+                                    answer: answer.trim().to_string(),
+                                });
+                            }
+                        }
+                        println!();
+                    }
+
+                    tracing::info!("Updating focus day diary...");
                     println!("{}", style("Have anything to say?").bold().magenta());
-                    let new_diary_entry = Input::<String>::with_theme(&ColorfulTheme::default())
+                    let mut new_diary_entry = Input::<String>::with_theme(&ColorfulTheme::default())
                         .with_prompt("diary")
-                        .with_initial_text(focus_day.diary.clone())
+                        .with_initial_text(
+                            draft
+                                .diary
+                                .clone()
+                                .unwrap_or_else(|| focus_day.diary.clone()),
+                        )
                         .allow_empty(true)
                         .interact_text()?;
-                    log::debug!(
+                    draft.diary = Some(new_diary_entry.clone());
+                    ctx.cache.focus_draft = Some(draft.clone());
+                    cache::save(&cache_path, &ctx.cache)?;
+                    tracing::debug!(
                         "Updated focus day diary: {new_diary_entry}",
                         new_diary_entry = new_diary_entry
                     );
                     println!();
 
+                    // Loading the subtasks up front doubles as a liveness check on the day
+                    // task: a 404 means it was deleted in the Asana UI, in which case the
+                    // cached day is invalidated and `ensure_focus_day` recreates or relocates it,
+                    // carrying the entered stats and diary over to the new task.
+                    tracing::info!("Loading subtasks for the focus day...");
+                    let status = StatusLine::show(
+                        ctx.writer.as_mut(),
+                        ctx.output,
+                        &style("Loading subtasks...").dim().to_string(),
+                    );
+                    if let Err(error) = focus_day.load_subtasks(&mut client).await {
+                        if !todo::asana::is_not_found(&error) {
+                            return Err(error);
+                        }
+                        tracing::warn!(
+                            "Focus day task {gid} no longer exists, recreating the focus day...",
+                            gid = focus_day.task.gid
+                        );
+                        ctx.cache.focus_day = None;
+                        cache::save(&cache_path, &ctx.cache)?;
+                        focus_day = ensure_focus_day(date, &mut client, &focus_project_gid).await?;
+                        focus_day.load_subtasks(&mut client).await?;
+                    }
+                    drop(status);
+                    tracing::debug!(
+                        "Loaded {} subtasks",
+                        focus_day.subtasks.as_ref().map_or(0, Vec::len)
+                    );
+
+                    // The sync overwrites the whole notes field, so a diary edited remotely —
+                    // on a phone during the day, say, especially with `--use-cache` — would be
+                    // silently lost. Re-fetch the task and reconcile before anything is sent;
+                    // `--force-sync` skips the round trip and overwrites unconditionally.
+                    if !force_sync && !client.dry_run() && !client.offline() {
+                        let snapshot = match client
+                            .get::<FocusTaskSnapshot>(&focus_day.task.gid)
+                            .await
+                        {
+                            Ok(snapshot) => Some(snapshot),
+                            // A deleted task is handled by the not-found replay below.
+                            Err(error) if todo::asana::is_not_found(&error) => None,
+                            Err(error) => return Err(error),
+                        };
+                        if let Some(snapshot) =
+                            snapshot.filter(|s| s.conflicts_with(&focus_day.task))
+                        {
+                            let (remote_diary, remote_prompts) =
+                                todo::focus::split_prompt_block(&snapshot.notes);
+                            println!(
+                                "{}",
+                                style("The focus task was edited in Asana since this run loaded it:")
+                                    .bold()
+                                    .yellow()
+                            );
+                            println!("  remote: {}", style(&remote_diary).dim());
+                            println!("  local:  {}", style(&new_diary_entry).dim());
+                            let choice = Select::with_theme(&ColorfulTheme::default())
+                                .with_prompt("Which diary should the sync keep?")
+                                .items(&[
+                                    "the remote version",
+                                    "this run's version",
+                                    "both, appending this run's text",
+                                ])
+                                .default(0)
+                                .interact()?;
+                            match choice {
+                                0 => {
+                                    new_diary_entry = remote_diary;
+                                    new_prompts = remote_prompts;
+                                }
+                                2 => {
+                                    if new_diary_entry.trim().is_empty()
+                                        || new_diary_entry == remote_diary
+                                    {
+                                        new_diary_entry = remote_diary;
+                                    } else if !remote_diary.trim().is_empty() {
+                                        new_diary_entry =
+                                            format!("{remote_diary}\n\n{new_diary_entry}");
+                                    }
+                                    // Remote answers win for keys answered in both places.
+                                    for answer in remote_prompts {
+                                        match new_prompts
+                                            .iter_mut()
+                                            .find(|a| a.key == answer.key)
+                                        {
+                                            Some(existing) => *existing = answer,
+                                            None => new_prompts.push(answer),
+                                        }
+                                    }
+                                }
+                                _ => {}
+                            }
+                            println!();
+                        }
+                    }
+
+                    // The sync can wipe data — a cleared prefilled diary especially — so show
+                    // what would change and confirm before anything is sent: always for a
+                    // cleared diary, and for every change under `focus.confirm_sync`.
+                    let diff = FocusSyncDiff::compute(
+                        &focus_day.stats,
+                        &new_stats,
+                        &focus_day.diary,
+                        &new_diary_entry,
+                    );
+                    if !diff.is_empty() && (ctx.config.focus.confirm_sync || diff.diary_cleared) {
+                        println!("{}", style("About to sync these focus changes:").bold());
+                        for change in &diff.stat_changes {
+                            println!("- {change}");
+                        }
+                        if let Some(change) = &diff.diary_change {
+                            println!("- diary: {change}");
+                        }
+                        if !Confirm::with_theme(&ColorfulTheme::default())
+                            .with_prompt("Sync these changes?")
+                            .default(!diff.diary_cleared)
+                            .interact()?
+                        {
+                            println!("{}", style("Keeping the focus day as it was.").dim());
+                            new_stats = focus_day.stats.clone();
+                            new_diary_entry = focus_day.diary.clone();
+                            new_prompts = focus_day.prompts.clone();
+                        }
+                        println!();
+                    }
+
+                    // Kept out of the sync task so the update can be replayed against a new
+                    // task gid if the day task vanishes mid-sync.
+                    let sync_stats = new_stats.clone();
+                    let sync_diary = new_diary_entry.clone();
+                    let sync_prompts = new_prompts.clone();
                     let sync_task = tokio::spawn({
                         let client = client.clone();
                         let focus_day = focus_day.clone();
-                        let url: Url = format!(
-                            "https://app.asana.com/api/1.0/tasks/{task_gid}",
-                            task_gid = focus_day.task.gid
-                        )
-                        .parse()
-                        .context("issue parsing focus day update request url")?;
+                        let new_prompts = new_prompts.clone();
                         let custom_fields = new_stats
                             .stats()
                             .into_iter()
@@ -1206,51 +2437,119 @@ async fn main() -> anyhow::Result<()> {
                             .collect();
 
                         async move {
-                            log::info!(
+                            tracing::info!(
                                 "Deciding if there are any changes to focus data to sync..."
                             );
-                            if new_stats == focus_day.stats && new_diary_entry == focus_day.diary {
-                                log::info!("No changes to focus data to sync");
+                            if new_stats == focus_day.stats
+                                && new_diary_entry == focus_day.diary
+                                && new_prompts == focus_day.prompts
+                            {
+                                tracing::info!("No changes to focus data to sync");
                                 return Ok::<bool, anyhow::Error>(false);
                             }
+                            if client.dry_run() {
+                                println!(
+                                    "would have updated the focus day's stats and diary"
+                                );
+                                return Ok(false);
+                            }
 
-                            log::info!("Sending new focus data...");
+                            tracing::info!("Sending new focus data...");
                             client
-                                .mutate_request(
-                                    Method::PUT,
-                                    &url,
-                                    DataWrapper {
-                                        data: UpdateFocusTaskCustomFieldsRequest {
-                                            notes: new_diary_entry,
-                                            custom_fields,
-                                        },
+                                .mutate(
+                                    &focus_day.task.gid,
+                                    UpdateFocusTaskCustomFieldsRequest {
+                                        notes: todo::focus::join_prompt_block(
+                                            &new_diary_entry,
+                                            &new_prompts,
+                                        ),
+                                        custom_fields,
                                     },
                                 )
                                 .await?;
-                            log::debug!("Sent new focus data");
+                            tracing::debug!("Sent new focus data");
                             Ok(true)
                         }
+                        .in_current_span()
                     });
 
-                    log::info!("Loading subtasks for the focus day...");
-                    term.write_str(&style("Loading subtasks...").dim().to_string())?;
-                    focus_day.load_subtasks(&mut client).await?;
-                    term.clear_line()?;
-                    log::debug!(
-                        "Loaded {} subtasks",
-                        focus_day.subtasks.as_ref().map_or(0, Vec::len)
-                    );
-
                     let mut subtasks = focus_day.subtasks.clone().unwrap_or_default();
 
-                    log::info!("Asking for tasks to add to focus day...");
+                    tracing::info!("Asking for tasks to add to focus day...");
                     println!("{}", style("Any tasks to do today?").bold().red());
-                    let mut subtask_tasks: Vec<tokio::task::JoinHandle<anyhow::Result<()>>> =
-                        Vec::new();
+                    let mut subtask_tasks: Vec<(
+                        String,
+                        tokio::task::JoinHandle<anyhow::Result<()>>,
+                    )> = Vec::new();
                     let task_gid = focus_day.task.gid.clone();
+                    // A literal `me` works against the API, but the cached profile's gid keeps
+                    // the request explicit about who it targets.
+                    let subtask_assignee = ctx
+                        .cache
+                        .user
+                        .as_ref()
+                        .map_or_else(|| "me".to_string(), |user| user.gid.clone());
+
+                    let spawn_subtask_creation = |client: &Client,
+                                                  task_gid: &str,
+                                                  subtask_name: String|
+                     -> tokio::task::JoinHandle<anyhow::Result<()>> {
+                        let client = client.clone();
+                        let assignee = subtask_assignee.clone();
+                        let task_gid = task_gid.to_string();
+
+                        tokio::spawn(
+                            async move {
+                                if client.dry_run() {
+                                    println!("would have created subtask \"{subtask_name}\"");
+                                    return Ok(());
+                                }
+                                tracing::info!("Creating subtask...");
+                                client
+                                    .mutate(
+                                        &task_gid,
+                                        CreateSubtaskRequest {
+                                            name: subtask_name,
+                                            assignee,
+                                            due_on: Some(today),
+                                        },
+                                    )
+                                    .await?;
+                                tracing::debug!("Created subtask");
+                                Ok::<(), anyhow::Error>(())
+                            }
+                            .in_current_span(),
+                        )
+                    };
+
+                    // Retry the subtask creations a previous interrupted run never confirmed.
+                    for subtask_name in draft.pending_subtasks.clone() {
+                        println!(
+                            "{}",
+                            style(format!("retrying draft task \"{subtask_name}\"")).dim()
+                        );
+                        subtasks.push(FocusTaskSubtask {
+                            gid: "new".to_string(),
+                            name: subtask_name.clone(),
+                            completed: false,
+                            due_on: Some(today),
+                        });
+                        subtask_tasks.push((
+                            subtask_name.clone(),
+                            spawn_subtask_creation(&client, &task_gid, subtask_name),
+                        ));
+                    }
+
                     loop {
-                        for subtask in &subtasks {
-                            println!("- {}", subtask.name);
+                        // Size the view each pass so a mid-session resize doesn't break the
+                        // clearing math; one row stays reserved for the prompt below the list.
+                        let mut list_view = todo::render::ListView::from_term(&term);
+                        let items: Vec<String> = subtasks
+                            .iter()
+                            .map(|subtask| format!("- {}", subtask.name))
+                            .collect();
+                        for line in list_view.render(&items, 1) {
+                            println!("{line}");
                         }
 
                         let subtask_name = Input::<String>::with_theme(&ColorfulTheme::default())
@@ -1265,74 +2564,682 @@ async fn main() -> anyhow::Result<()> {
                             gid: "new".to_string(),
                             name: subtask_name.clone(),
                             completed: false,
+                            due_on: Some(today),
                         });
 
-                        let subtask_task = tokio::spawn({
+                        draft.pending_subtasks.push(subtask_name.clone());
+                        ctx.cache.focus_draft = Some(draft.clone());
+                        cache::save(&cache_path, &ctx.cache)?;
+
+                        // Clear exactly what was written: the capped list plus the answered
+                        // prompt at its wrapped height.
+                        let prompt_rows =
+                            list_view.rows_for(&format!("✔ new task · {subtask_name}"));
+                        term.clear_last_lines(list_view.rendered_rows() + prompt_rows)?;
+
+                        subtask_tasks.push((
+                            subtask_name.clone(),
+                            spawn_subtask_creation(&client, &task_gid, subtask_name),
+                        ));
+                    }
+
+                    let status = if sync_task.is_finished() {
+                        StatusLine::none()
+                    } else {
+                        StatusLine::show(
+                            ctx.writer.as_mut(),
+                            ctx.output,
+                            &style("Waiting for focus data to sync...").dim().to_string(),
+                        )
+                    };
+                    let sync_result = sync_task.await?;
+                    drop(status);
+                    if let Err(error) = sync_result {
+                        if !todo::asana::is_not_found(&error) {
+                            return Err(error);
+                        }
+                        // The day task vanished between loading and syncing; recreate the day
+                        // and replay the update against the new gid.
+                        tracing::warn!(
+                            "Focus day task disappeared mid-sync, recreating it and replaying \
+                             the update..."
+                        );
+                        ctx.cache.focus_day = None;
+                        cache::save(&cache_path, &ctx.cache)?;
+                        focus_day = ensure_focus_day(date, &mut client, &focus_project_gid).await?;
+                        client
+                            .mutate(
+                                &focus_day.task.gid,
+                                UpdateFocusTaskCustomFieldsRequest {
+                                    notes: todo::focus::join_prompt_block(
+                                        &sync_diary,
+                                        &sync_prompts,
+                                    ),
+                                    custom_fields: sync_stats
+                                        .stats()
+                                        .into_iter()
+                                        .filter_map(|s| {
+                                            s.value().map(|v| (s.field_gid().to_string(), v))
+                                        })
+                                        .collect(),
+                                },
+                            )
+                            .await?;
+                    }
+
+                    let status = if subtask_tasks.iter().all(|(_, t)| t.is_finished()) {
+                        StatusLine::none()
+                    } else {
+                        StatusLine::show(
+                            ctx.writer.as_mut(),
+                            ctx.output,
+                            &style("Waiting for subtasks to sync...").dim().to_string(),
+                        )
+                    };
+                    for (subtask_name, handle) in subtask_tasks {
+                        if let Err(error) = handle.await? {
+                            if !todo::asana::is_not_found(&error) {
+                                return Err(error);
+                            }
+                            // Same recovery as the sync: refresh the day (recreating it if
+                            // needed) and replay this creation against the current task gid.
+                            tracing::warn!(
+                                "Subtask creation hit a deleted focus task, replaying \
+                                 \"{subtask_name}\" against the current day task..."
+                            );
+                            ctx.cache.focus_day = None;
+                            cache::save(&cache_path, &ctx.cache)?;
+                            focus_day =
+                                ensure_focus_day(date, &mut client, &focus_project_gid).await?;
+                            spawn_subtask_creation(&client, &focus_day.task.gid, subtask_name)
+                                .await??;
+                        }
+                    }
+                    drop(status);
+
+                    // Every sync has been confirmed, so the draft has served its purpose.
+                    if ctx.cache.focus_draft.is_some() {
+                        ctx.cache.focus_draft = None;
+                        cache::save(&cache_path, &ctx.cache)?;
+                    }
+
+                    if let Some(path) = sync_daily_note(&ctx.config, &focus_day, ctx.dry_run)? {
+                        println!(
+                            "{}",
+                            style(format!("Synced the focus diary to {}.", path.display()))
+                                .dim()
+                        );
+                    }
+                }
+                Some(FocusCommand::Overview {
+                    from,
+                    to,
+                    last,
+                    week,
+                    verbose,
+                    audit,
+                }) => {
+                    let entries = ctx.cache.stat_entries.clone().unwrap_or_default();
+                    if from.is_none() && last.is_none() && !week {
+                        // An overview is a read: a date nobody has run focus on yet gets
+                        // reported as missing, not silently created the way the run itself
+                        // would.
+                        let Some(focus_day) =
+                            find_focus_day(date, &mut client, &focus_project_gid).await?
+                        else {
+                            anyhow::bail!("no focus day exists for {date}");
+                        };
+                        let rendered = if verbose {
+                            focus_day.to_audited_string(
+                                ctx.config.display.date_format.as_deref(),
+                                &entries,
+                            )
+                        } else {
+                            focus_day.to_full_string(ctx.config.display.date_format.as_deref())
+                        };
+                        ctx.writer.line(rendered.trim_end())?;
+                        if audit {
+                            ctx.writer.line("")?;
+                            ctx.writer.line(
+                                todo::focus::render_stat_audit(&entries, date, date).trim_end(),
+                            )?;
+                        }
+                    } else {
+                        let (range_from, range_to) = if week {
+                            let current = date.week(Weekday::Mon);
+                            (current.first_day(), current.last_day())
+                        } else {
+                            todo::utils::parse_date_range(
+                                from.as_deref(),
+                                to.as_deref(),
+                                last.as_deref(),
+                                today,
+                            )?
+                        };
+                        if week {
+                            let goals = find_weekly_goals_task(
+                                date,
+                                &mut client,
+                                &focus_project_gid,
+                            )
+                            .await?
+                            .map(|t| todo::focus::parse_weekly_goals(&t.notes))
+                            .unwrap_or_default();
+                            ctx.writer
+                                .line(&todo::focus::format_weekly_goals(&goals))?;
+                            ctx.writer
+                                .line(&style("────────────────────").dim().to_string())?;
+                        }
+
+                        // The cached day covers today without a round trip; every other date
+                        // costs one week fetch, shared by all the dates that week covers.
+                        let mut days_by_date: HashMap<NaiveDate, FocusDay> = ctx
+                            .cache
+                            .focus_day
+                            .clone()
+                            .filter(|d| d.date >= range_from && d.date <= range_to)
+                            .map(|d| (d.date, d))
+                            .into_iter()
+                            .collect();
+                        let mut week_starts = Vec::new();
+                        let mut cursor = range_from;
+                        while cursor <= range_to {
+                            let week_start = cursor.week(Weekday::Mon).first_day();
+                            if !days_by_date.contains_key(&cursor)
+                                && week_starts.last() != Some(&week_start)
+                            {
+                                week_starts.push(week_start);
+                            }
+                            cursor = cursor + chrono::Days::new(1);
+                        }
+
+                        if week_starts.len() > 4
+                            && !Confirm::with_theme(&ColorfulTheme::default())
+                                .with_prompt(format!(
+                                    "This range needs {count} weeks fetched from Asana; \
+                                     continue?",
+                                    count = week_starts.len()
+                                ))
+                                .default(true)
+                                .interact()?
+                        {
+                            anyhow::bail!("aborted");
+                        }
+                        for week_start in week_starts {
+                            for day in
+                                fetch_focus_week_days(week_start, &mut client, &focus_project_gid)
+                                    .await?
+                            {
+                                days_by_date.entry(day.date).or_insert(day);
+                            }
+                        }
+
+                        // Most recent first, so the freshest entry is right at the prompt.
+                        let mut cursor = range_to;
+                        loop {
+                            match days_by_date.get(&cursor) {
+                                Some(day) => {
+                                    let rendered = if verbose {
+                                        day.to_compact_audited_string(
+                                            ctx.config.display.date_format.as_deref(),
+                                            &entries,
+                                        )
+                                    } else {
+                                        day.to_compact_string(
+                                            ctx.config.display.date_format.as_deref(),
+                                        )
+                                    };
+                                    ctx.writer.line(rendered.trim_end())?;
+                                }
+                                None => ctx.writer.line(
+                                    &style(format!("{cursor}: no entry")).dim().to_string(),
+                                )?,
+                            }
+                            if cursor == range_from {
+                                break;
+                            }
+                            cursor = cursor - chrono::Days::new(1);
+                            ctx.writer
+                                .line(&style("────────────────────").dim().to_string())?;
+                        }
+                        if audit {
+                            ctx.writer.line("")?;
+                            ctx.writer.line(
+                                todo::focus::render_stat_audit(&entries, range_from, range_to)
+                                    .trim_end(),
+                            )?;
+                        }
+                    }
+                }
+                Some(FocusCommand::Goals { set }) => {
+                    if set {
+                        let task =
+                            ensure_weekly_goals_task(date, &mut client, &focus_project_gid)
+                                .await?;
+                        let editor = env::var("EDITOR").context("$EDITOR is not set")?;
+                        let path = env::temp_dir()
+                            .join(format!("todo-weekly-goals-{}.md", std::process::id()));
+                        fs::write(&path, &task.notes)
+                            .context("could not write goals to a temporary file")?;
+                        let editor_status = std::process::Command::new(&editor)
+                            .arg(&path)
+                            .status()
+                            .with_context(|| format!("could not run editor `{editor}`"))?;
+                        anyhow::ensure!(editor_status.success(), "editor exited with an error");
+                        let edited = fs::read_to_string(&path)
+                            .context("could not read the edited goals")?;
+
+                        if edited == task.notes {
+                            println!("Goals unchanged.");
+                        } else if ctx.dry_run {
+                            println!("would have updated this week's goals");
+                        } else {
+                            // Round-tripping through the parser normalizes hand-typed markers
+                            // before the notes land in Asana.
+                            let notes = todo::focus::render_weekly_goals(
+                                &todo::focus::parse_weekly_goals(&edited),
+                            );
+                            client
+                                .mutate(
+                                    &task.gid,
+                                    UpdateTaskNotesRequest {
+                                        notes: notes.clone(),
+                                    },
+                                )
+                                .await
+                                .context("issue updating weekly goals")?;
+                            ctx.writer.line(&todo::focus::format_weekly_goals(
+                                &todo::focus::parse_weekly_goals(&notes),
+                            ))?;
+                        }
+                    } else {
+                        let Some(task) =
+                            find_weekly_goals_task(date, &mut client, &focus_project_gid)
+                                .await?
+                        else {
+                            anyhow::bail!(
+                                "no weekly goals set for this week; create them with \
+                                 `todo focus goals --set`"
+                            );
+                        };
+                        ctx.writer.line(&todo::focus::format_weekly_goals(
+                            &todo::focus::parse_weekly_goals(&task.notes),
+                        ))?;
+                    }
+                }
+                Some(FocusCommand::Archive { keep_weeks }) => {
+                    tracing::info!("Archiving focus weeks older than {keep_weeks} weeks...");
+                    let sections = client
+                        .get::<Section>(&focus_project_gid.clone())
+                        .await?;
+                    let archivable = sections
+                        .into_iter()
+                        .filter(|s| s.name.starts_with("Daily Focuses"))
+                        .filter_map(|s| TryInto::<FocusWeek>::try_into(s).ok())
+                        .filter(|w| w.is_past_archive_cutoff(today, keep_weeks))
+                        .collect::<Vec<_>>();
+                    tracing::debug!("Found {} archivable weeks", archivable.len());
+                    if archivable.is_empty() {
+                        println!(
+                            "Nothing to archive: every focus week ended within the last {keep_weeks} weeks."
+                        );
+                    }
+
+                    let mut mutation_tasks: Vec<tokio::task::JoinHandle<anyhow::Result<()>>> =
+                        Vec::new();
+                    for week in &archivable {
+                        let day_tasks = client
+                            .get::<FocusTask>(&week.section.gid)
+                            .await?
+                            .into_iter()
+                            .filter(|t| t.name.starts_with("Daily Focus for"))
+                            .collect::<Vec<_>>();
+                        if ctx.dry_run {
+                            println!(
+                                "would archive {week}: complete {count} and rename the section to \"{name}\"",
+                                count = task_or_tasks(day_tasks.len()),
+                                name = week.archived_name(),
+                            );
+                            continue;
+                        }
+                        println!(
+                            "Archiving {week} ({count})...",
+                            count = task_or_tasks(day_tasks.len())
+                        );
+
+                        for day_task in day_tasks {
                             let client = client.clone();
-                            let task_gid = task_gid.clone();
-                            let url: Url =
-                                format!("https://app.asana.com/api/1.0/tasks/{task_gid}/subtasks")
-                                    .parse()
-                                    .context("issue parsing subtask creation request url")?;
+                            mutation_tasks.push(tokio::spawn(
+                                async move {
+                                    client
+                                        .mutate(
+                                            &day_task.gid,
+                                            UpdateTaskRequest {
+                                                completed: Some(true),
+                                                due_on: None,
+                                            },
+                                        )
+                                        .await?;
+                                    Ok(())
+                                }
+                                .in_current_span(),
+                            ));
+                        }
 
+                        let client = client.clone();
+                        let section_gid = week.section.gid.clone();
+                        let name = week.archived_name();
+                        mutation_tasks.push(tokio::spawn(
                             async move {
-                                log::info!("Creating subtask...");
                                 client
-                                    .mutate_request(
-                                        Method::POST,
-                                        &url,
-                                        DataWrapper {
-                                            data: CreateSubtaskRequest {
-                                                name: subtask_name,
-                                                assignee: "me".to_string(),
-                                                due_on: Some(today),
-                                            },
-                                        },
-                                    )
+                                    .mutate(&section_gid, UpdateSectionRequest { name })
                                     .await?;
-                                log::debug!("Created subtask");
-                                Ok::<(), anyhow::Error>(())
+                                Ok(())
                             }
-                        });
-                        subtask_tasks.push(subtask_task);
+                            .in_current_span(),
+                        ));
+                    }
 
-                        term.clear_last_lines(subtasks.len())?;
+                    if !mutation_tasks.is_empty() {
+                        let status = StatusLine::show(
+                            ctx.writer.as_mut(),
+                            ctx.output,
+                            &style("Waiting for the archive to sync...").dim().to_string(),
+                        );
+                        for res in join_all(mutation_tasks).await {
+                            res??;
+                        }
+                        drop(status);
+                        println!(
+                            "Archived {count} focus {noun}.",
+                            count = archivable.len(),
+                            noun = if archivable.len() == 1 { "week" } else { "weeks" },
+                        );
                     }
+                }
+            }
+            None
+        }
 
-                    if !sync_task.is_finished() {
-                        term.write_str(
-                            &style("Waiting for focus data to sync...").dim().to_string(),
-                        )?;
-                        sync_task.await??;
-                        term.clear_line()?;
+        Command::Update {
+            watch,
+            interval,
+            format,
+            notify: notify_changes,
+        } => {
+            // The profile rarely changes, so only the first update after init (or a cleared
+            // cache) fetches it; nothing critical hangs off it, so a failure is only logged.
+            if ctx.cache.user.is_none() && !args.offline {
+                match client.get::<User>(&()).await {
+                    Ok(user) => ctx.cache.user = Some(user),
+                    Err(error) => tracing::warn!("Could not fetch the user profile: {error:#}"),
+                }
+            }
+            if watch {
+                tracing::info!("Watching for updates every ~{interval} seconds...");
+                let base = std::time::Duration::from_secs(interval);
+                let mut terminate =
+                    tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+                        .context("could not install the SIGTERM handler")?;
+                let mut consecutive_failures: u32 = 0;
+
+                loop {
+                    // The lock and cache write both finish before the next shutdown check, so
+                    // a signal never interrupts an in-flight write.
+                    if let Some(_lock) = cache::try_update_lock(&cache_path)? {
+                        let cycle = async {
+                            // The task list and the focus day are independent, so refresh them
+                            // concurrently; the cycle takes as long as the slower half.
+                            let mut tasks_client = client.clone();
+                            let mut focus_client = client.clone();
+                            // The loop outlives the frozen `ctx.now`, so each cycle reads
+                            // the real clock to notice day rollovers.
+                            let day = Local::now().date_naive();
+                            let focus_tracked = ctx.config.focus.is_scheduled(day)
+                                && !pause::is_paused(ctx.cache.paused.as_ref(), day);
+                            let ((tasks, completed, list), focus_day) =
+                                todo::commands::update::join_fetches(
+                                    async {
+                                        let (tasks, list) = todo::commands::update::fetch_tasks(
+                                            &mut tasks_client,
+                                            user_task_list.clone(),
+                                            &workspace_gid,
+                                        )
+                                        .await?;
+                                        let mut completed = tasks_client
+                                            .get::<CompletedTask>(&(list.gid.clone(), day))
+                                            .await?;
+                                        // The window also returns still-open tasks.
+                                        completed.retain(|t| t.completed_at.is_some());
+                                        Ok((tasks, completed, list))
+                                    },
+                                    async {
+                                        // On a day the schedule excludes (or a pause covers),
+                                        // only refresh a day task that already exists; creating
+                                        // one would re-arm the prompts being suppressed.
+                                    let mut focus_day = if focus_tracked {
+                                        Some(
+                                            ensure_focus_day(
+                                                day,
+                                                &mut focus_client,
+                                                &focus_project_gid,
+                                            )
+                                            .await?,
+                                        )
+                                    } else {
+                                        find_focus_day(day, &mut focus_client, &focus_project_gid)
+                                            .await?
+                                    };
+                                    // Cache the subtasks too, so prompt integrations can count
+                                    // them without a network round trip.
+                                    if let Some(focus_day) = focus_day.as_mut() {
+                                        focus_day.load_subtasks(&mut focus_client).await?;
+                                    }
+                                    Ok(focus_day)
+                                },
+                            )
+                            .await?;
+                            let task_count = tasks.len();
+                            let report = todo::commands::update::UpdateReport {
+                                tasks: todo::task::diff_tasks(
+                                    ctx.cache.tasks.as_deref().unwrap_or_default(),
+                                    &tasks,
+                                ),
+                                focus_changed: focus_day.as_ref().is_some_and(|fresh| {
+                                    focus_day_changed(ctx.cache.focus_day.as_ref(), fresh)
+                                }),
+                            };
+                            // Watch mode always notifies; a pause window keeps it quiet like
+                            // every other reminder, and the limiter rides the cache so the
+                            // stamps survive a daemon restart.
+                            if !pause::is_paused(ctx.cache.paused.as_ref(), day) {
+                                let changes = todo::task::notable_changes(
+                                    ctx.cache.tasks.as_deref().unwrap_or_default(),
+                                    &tasks,
+                                    day,
+                                );
+                                let mut limiter = ctx.cache.notified.unwrap_or_default();
+                                notify::send_update_changes(
+                                    &changes,
+                                    &mut limiter,
+                                    ctx.config.notify.update_min_gap(),
+                                    Local::now(),
+                                );
+                                ctx.cache.notified = Some(limiter);
+                            }
+                            ctx.cache.user_task_list = Some(list.clone());
+                            ctx.cache.tasks = Some(tasks);
+                            ctx.cache.completed_today = Some(completed);
+                            if let Some(focus_day) = focus_day {
+                                if focus_day_changed(ctx.cache.focus_day.as_ref(), &focus_day) {
+                                    sync_daily_note(&ctx.config, &focus_day, ctx.dry_run)?;
+                                }
+                                ctx.cache.focus_day = Some(focus_day);
+                            }
+                            ctx.cache.mark_updated(Local::now());
+                            cache::save(&cache_path, &ctx.cache)?;
+                            Ok::<(usize, UserTaskList, UpdateReport), anyhow::Error>((
+                                task_count, list, report,
+                            ))
+                        };
+                        match cycle.await {
+                            Ok((task_count, list, report)) => {
+                                user_task_list = list;
+                                consecutive_failures = 0;
+                                if !args.quiet {
+                                    match format {
+                                        UpdateFormat::Short => println!(
+                                            "{timestamp} refreshed {task_count} tasks; {summary}",
+                                            timestamp =
+                                                Local::now().format("%Y-%m-%dT%H:%M:%S"),
+                                            summary = report.render(),
+                                        ),
+                                        UpdateFormat::Json => {
+                                            println!("{}", serde_json::to_string(&report)?);
+                                        }
+                                    }
+                                }
+                            }
+                            Err(err) => {
+                                consecutive_failures += 1;
+                                eprintln!(
+                                    "{timestamp} update failed ({consecutive_failures} in a row): {err:#}",
+                                    timestamp = Local::now().format("%Y-%m-%dT%H:%M:%S")
+                                );
+                            }
+                        }
+                    } else {
+                        tracing::warn!("Another update holds the lock, skipping this cycle...");
                     }
-                    if subtask_tasks.iter().any(|t| !t.is_finished()) {
-                        term.write_str(
-                            &style("Waiting for subtasks to sync...").dim().to_string(),
-                        )?;
-                        for res in join_all(subtask_tasks).await {
-                            res??;
+
+                    let delay = todo::commands::update::jittered_interval(
+                        todo::commands::update::backoff_interval(base, consecutive_failures),
+                        u64::from(Local::now().nanosecond()),
+                    );
+                    tracing::debug!("Sleeping for {delay:?} until the next cycle...");
+                    tokio::select! {
+                        () = tokio::time::sleep(delay) => {}
+                        _ = tokio::signal::ctrl_c() => {
+                            tracing::info!("Received SIGINT, shutting down...");
+                            break;
+                        }
+                        _ = terminate.recv() => {
+                            tracing::info!("Received SIGTERM, shutting down...");
+                            break;
                         }
-                        term.clear_line()?;
                     }
                 }
-                Some(FocusCommand::Overview) => {
-                    print!(
-                        "{}",
-                        get_focus_day(date, &mut client).await?.to_full_string()
+            } else if let Some(_update_lock) = cache::try_update_lock(&cache_path)? {
+                tracing::info!("Updating cache...");
+                let mut tasks_client = client.clone();
+                let mut focus_client = client.clone();
+                let focus_tracked = ctx.config.focus.is_scheduled(today) && !paused;
+                let ((tasks, completed, list), focus_day) = todo::commands::update::join_fetches(
+                    async {
+                        let (tasks, list) = todo::commands::update::fetch_tasks(
+                            &mut tasks_client,
+                            user_task_list.clone(),
+                            &workspace_gid,
+                        )
+                        .await?;
+                        let mut completed = tasks_client
+                            .get::<CompletedTask>(&(list.gid.clone(), today))
+                            .await?;
+                        completed.retain(|t| t.completed_at.is_some());
+                        Ok((tasks, completed, list))
+                    },
+                    async {
+                        let mut focus_day = if focus_tracked {
+                            Some(ensure_focus_day(today, &mut focus_client, &focus_project_gid).await?)
+                        } else {
+                            find_focus_day(today, &mut focus_client, &focus_project_gid).await?
+                        };
+                        if let Some(focus_day) = focus_day.as_mut() {
+                            focus_day.load_subtasks(&mut focus_client).await?;
+                        }
+                        Ok(focus_day)
+                    },
+                )
+                .await?;
+                let report = todo::commands::update::UpdateReport {
+                    tasks: todo::task::diff_tasks(
+                        ctx.cache.tasks.as_deref().unwrap_or_default(),
+                        &tasks,
+                    ),
+                    focus_changed: focus_day
+                        .as_ref()
+                        .is_some_and(|fresh| focus_day_changed(ctx.cache.focus_day.as_ref(), fresh)),
+                };
+                if notify_changes && !paused {
+                    let changes = todo::task::notable_changes(
+                        ctx.cache.tasks.as_deref().unwrap_or_default(),
+                        &tasks,
+                        today,
                     );
+                    let mut limiter = ctx.cache.notified.unwrap_or_default();
+                    notify::send_update_changes(
+                        &changes,
+                        &mut limiter,
+                        ctx.config.notify.update_min_gap(),
+                        ctx.now,
+                    );
+                    ctx.cache.notified = Some(limiter);
+                }
+                ctx.cache.user_task_list = Some(list);
+                ctx.cache.tasks = Some(tasks);
+                ctx.cache.completed_today = Some(completed);
+                if let Some(focus_day) = focus_day {
+                    if focus_day_changed(ctx.cache.focus_day.as_ref(), &focus_day) {
+                        sync_daily_note(&ctx.config, &focus_day, ctx.dry_run)?;
+                    }
+                    ctx.cache.focus_day = Some(focus_day);
+                }
+                ctx.cache.mark_updated(ctx.now);
+                cache::save(&cache_path, &ctx.cache)?;
+                if !args.quiet {
+                    match format {
+                        UpdateFormat::Short => println!("{}", report.render()),
+                        UpdateFormat::Json => println!("{}", serde_json::to_string(&report)?),
+                    }
                 }
+            } else {
+                // A scheduled run and a manual one sometimes overlap; the loser reports and
+                // exits cleanly so launchd/cron timers do not alarm on the collision.
+                println!("another update is running");
             }
+            None
         }
 
-        Command::Update => {
-            log::info!("Updating cache...");
-            let tasks = client
-                .get::<UserTask>(&user_task_list.gid.to_string())
-                .await?;
-            cache.tasks = Some(tasks.clone());
-            cache.last_updated = Some(Local::now());
-            save_cache(&cache_path, &cache)?;
+        // Handled before any cache or credential work above.
+        Command::Config { .. }
+        | Command::Install { .. }
+        | Command::Uninstall { .. }
+        | Command::CompleteTasks { .. } => {
+            unreachable!()
+        }
+    };
+
+    // A mid-command token refresh rotates the credentials inside the client; any cache save a
+    // command made above still carried the pre-refresh ones, so fold the rotated credentials
+    // back in and persist them before exiting.
+    if ctx.cache.creds.as_ref() != Some(client.credentials()) {
+        ctx.cache.creds = Some(client.credentials().clone());
+        ctx.cache.creds_refreshed_at = Some(Local::now());
+        cache::save(&cache_path, &ctx.cache)?;
+    }
+
+    if args.timings {
+        let timings = ctx.timings.lock().map(|t| t.clone()).unwrap_or_default();
+        if timings.is_empty() {
+            eprintln!("No API requests were made.");
+        } else {
+            eprint!("{}", todo::asana::render_timings(&timings));
+        }
+    }
+
+    if args.exit_code || ctx.config.behavior.exit_codes {
+        if let Some(outcome) = outcome {
+            std::process::exit(outcome.exit_code());
         }
     }
 
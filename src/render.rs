@@ -0,0 +1,289 @@
+//! Terminal rendering helpers shared across commands.
+
+use console::{Color, Style, Term};
+
+use crate::config::ColorsConfig;
+
+/// Resolved styles for the semantic roles used across human-readable output.
+///
+/// Built once from the `[colors]` configuration table at startup; renderers pick a role from
+/// here instead of hardcoding `.red()` or `.yellow()`, so users who cannot distinguish the
+/// default scheme can remap it.
+#[derive(Clone, Debug)]
+pub struct Theme {
+    /// Style for overdue counts, dates, and messages; red by default.
+    pub overdue: Style,
+    /// Style for due-today counts, dates, and messages; yellow by default.
+    pub due_today: Style,
+    /// Style for due-within-a-week counts and dates; blue by default.
+    pub due_week: Style,
+    /// Style for all-clear and progress messages; green by default.
+    pub ok: Style,
+    /// Style for pending focus work; magenta by default.
+    pub pending: Style,
+    /// Style for de-emphasized text like permalinks and undated counts; the terminal's dim
+    /// attribute by default.
+    pub dim: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            overdue: Style::new().red(),
+            due_today: Style::new().yellow(),
+            due_week: Style::new().blue(),
+            ok: Style::new().green(),
+            pending: Style::new().magenta(),
+            dim: Style::new().dim(),
+        }
+    }
+}
+
+impl Theme {
+    /// Resolve the configured colors into concrete styles, keeping the default for any role
+    /// that is unset. Unparseable values also keep the default; configuration loading has
+    /// already rejected them, so this stays infallible.
+    #[must_use]
+    pub fn resolve(config: &ColorsConfig) -> Self {
+        let defaults = Self::default();
+        let style = |value: &Option<String>, default: Style| {
+            value
+                .as_deref()
+                .and_then(|name| parse_color(name).ok())
+                .map_or(default, |color| Style::new().fg(color))
+        };
+        Self {
+            overdue: style(&config.overdue, defaults.overdue),
+            due_today: style(&config.due_today, defaults.due_today),
+            due_week: style(&config.due_week, defaults.due_week),
+            ok: style(&config.ok, defaults.ok),
+            pending: style(&config.pending, defaults.pending),
+            dim: style(&config.dim, defaults.dim),
+        }
+    }
+}
+
+/// A rolling list rendered above an interactive prompt, sized to the terminal.
+///
+/// Printing more lines than the terminal is tall (or lines wider than it is wide) breaks
+/// `Term::clear_last_lines`: the top of the list scrolls away, the row math drifts, and the
+/// clearing eats unrelated scrollback. This view truncates the list to fit, counts every line
+/// at its wrapped height, and remembers exactly how many terminal rows the last render
+/// occupied so the caller clears only those.
+#[derive(Clone, Copy, Debug)]
+pub struct ListView {
+    height: usize,
+    width: usize,
+    rendered_rows: usize,
+}
+
+impl ListView {
+    /// A view with explicit dimensions, for tests and for environments that misreport theirs.
+    #[must_use]
+    pub fn new(height: usize, width: usize) -> Self {
+        Self {
+            height: height.max(1),
+            width: width.max(1),
+            rendered_rows: 0,
+        }
+    }
+
+    /// A view sized to the given terminal.
+    #[must_use]
+    pub fn from_term(term: &Term) -> Self {
+        let (height, width) = term.size();
+        Self::new(height as usize, width as usize)
+    }
+
+    /// The lines to print for `items`, keeping `reserved` rows free below the list.
+    ///
+    /// When the items do not fit, the tail is replaced with an `… and K more` row; the number
+    /// of terminal rows the returned lines occupy is remembered for [`Self::rendered_rows`].
+    pub fn render(&mut self, items: &[String], reserved: usize) -> Vec<String> {
+        let budget = self.height.saturating_sub(reserved).max(1);
+        let total_rows: usize = items.iter().map(|item| self.rows_for(item)).sum();
+
+        let mut lines = Vec::new();
+        let mut rows = 0;
+        if total_rows <= budget {
+            lines.extend(items.iter().cloned());
+            rows = total_rows;
+        } else {
+            // Reserve room for the summary row at its widest possible wrapping.
+            let summary_rows = self.rows_for(&format!("… and {} more", items.len()));
+            for item in items {
+                let item_rows = self.rows_for(item);
+                if rows + item_rows + summary_rows > budget {
+                    break;
+                }
+                lines.push(item.clone());
+                rows += item_rows;
+            }
+            let summary = format!("… and {} more", items.len() - lines.len());
+            rows += self.rows_for(&summary);
+            lines.push(summary);
+        }
+        self.rendered_rows = rows;
+        lines
+    }
+
+    /// How many terminal rows the last [`Self::render`] occupied.
+    #[must_use]
+    pub fn rendered_rows(&self) -> usize {
+        self.rendered_rows
+    }
+
+    /// Terminal rows a single line occupies at this width once wrapped.
+    #[must_use]
+    pub fn rows_for(&self, line: &str) -> usize {
+        console::measure_text_width(line).max(1).div_ceil(self.width)
+    }
+}
+
+/// Parse a configured color: one of the eight basic color names (case-insensitive) or a
+/// 256-color index.
+///
+/// # Errors
+///
+/// This function will return an error listing the accepted values if the input is neither.
+pub fn parse_color(name: &str) -> anyhow::Result<Color> {
+    if let Ok(index) = name.parse::<u8>() {
+        return Ok(Color::Color256(index));
+    }
+    match name.to_ascii_lowercase().as_str() {
+        "black" => Ok(Color::Black),
+        "red" => Ok(Color::Red),
+        "green" => Ok(Color::Green),
+        "yellow" => Ok(Color::Yellow),
+        "blue" => Ok(Color::Blue),
+        "magenta" => Ok(Color::Magenta),
+        "cyan" => Ok(Color::Cyan),
+        "white" => Ok(Color::White),
+        _ => anyhow::bail!(
+            "`{name}` is not a color; expected black, red, green, yellow, blue, magenta, \
+             cyan, white, or a 256-color index (0-255)"
+        ),
+    }
+}
+
+/// Return the Asana permalink for a task gid.
+#[must_use]
+pub fn task_permalink(gid: &str) -> String {
+    format!("https://app.asana.com/0/0/{gid}/f")
+}
+
+/// Wrap `text` in an OSC 8 hyperlink escape sequence pointing at `url`.
+///
+/// Terminals that support OSC 8 (iTerm2, `WezTerm`, kitty, recent VTE) render the text as a
+/// clickable link; detection is up to the caller via [`supports_hyperlinks`].
+#[must_use]
+pub fn hyperlink(text: &str, url: &str) -> String {
+    format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\")
+}
+
+/// Whether the given terminal supports OSC 8 hyperlink escape sequences.
+///
+/// There is no terminfo capability for hyperlinks, so this is a best-effort check: the terminal
+/// must be attended, and the environment must look like one of the emulators known to support
+/// OSC 8.
+#[must_use]
+pub fn supports_hyperlinks(term: &Term) -> bool {
+    if !term.features().is_attended() {
+        return false;
+    }
+    if std::env::var("VTE_VERSION").is_ok_and(|v| v.parse::<u32>().is_ok_and(|v| v >= 5000)) {
+        return true;
+    }
+    std::env::var("TERM_PROGRAM").is_ok_and(|p| {
+        matches!(p.as_str(), "iTerm.app" | "WezTerm" | "Hyper" | "vscode" | "ghostty")
+    }) || std::env::var("TERM").is_ok_and(|t| t.starts_with("xterm-kitty"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors_parse_by_name_and_index_but_not_typos() {
+        assert_eq!(parse_color("red").unwrap(), Color::Red);
+        assert_eq!(parse_color("Cyan").unwrap(), Color::Cyan);
+        assert_eq!(parse_color("208").unwrap(), Color::Color256(208));
+        let error = parse_color("redd").unwrap_err().to_string();
+        assert!(error.contains("256-color index"), "{error}");
+    }
+
+    #[test]
+    fn the_theme_keeps_defaults_for_unset_roles() {
+        let theme = Theme::resolve(&ColorsConfig {
+            overdue: Some("208".to_string()),
+            ..ColorsConfig::default()
+        });
+        assert_eq!(
+            theme.overdue,
+            Style::new().fg(Color::Color256(208))
+        );
+        assert_eq!(theme.due_today, Style::new().yellow());
+        assert_eq!(theme.dim, Style::new().dim());
+    }
+
+    fn items(count: usize) -> Vec<String> {
+        (1..=count).map(|n| format!("- task {n}")).collect()
+    }
+
+    #[test]
+    fn a_roomy_terminal_renders_every_line() {
+        let mut view = ListView::new(24, 80);
+        let lines = view.render(&items(5), 1);
+        assert_eq!(lines, items(5));
+        assert_eq!(view.rendered_rows(), 5);
+    }
+
+    #[test]
+    fn a_short_terminal_truncates_with_a_more_row() {
+        // 6 rows minus 1 reserved leaves 5; 4 items fit once the summary row takes one.
+        let mut view = ListView::new(6, 80);
+        let lines = view.render(&items(10), 1);
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[3], "- task 4");
+        assert_eq!(lines[4], "… and 6 more");
+        assert_eq!(view.rendered_rows(), 5);
+    }
+
+    #[test]
+    fn a_narrow_terminal_counts_wrapped_lines() {
+        // "- task 1" is 8 columns, so each item wraps onto two 5-column rows.
+        let mut view = ListView::new(24, 5);
+        view.render(&items(3), 1);
+        assert_eq!(view.rendered_rows(), 6);
+
+        // With 7 rows of budget, two wrapped items fit alongside the thrice-wrapped summary.
+        let mut view = ListView::new(8, 5);
+        let lines = view.render(&items(10), 1);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[2], "… and 8 more");
+        assert_eq!(view.rendered_rows(), 7);
+    }
+
+    #[test]
+    fn degenerate_dimensions_still_render_something() {
+        let mut view = ListView::new(0, 0);
+        let lines = view.render(&items(3), 1);
+        assert_eq!(lines, vec!["… and 3 more".to_string()]);
+        // One column wide: the summary's 12 columns occupy 12 rows.
+        assert_eq!(view.rendered_rows(), 12);
+        assert_eq!(view.rows_for(""), 1);
+    }
+
+    #[test]
+    fn permalink_points_at_the_task() {
+        assert_eq!(task_permalink("123"), "https://app.asana.com/0/0/123/f");
+    }
+
+    #[test]
+    fn hyperlink_wraps_text_in_osc8_sequences() {
+        assert_eq!(
+            hyperlink("task", "https://example.com"),
+            "\x1b]8;;https://example.com\x1b\\task\x1b]8;;\x1b\\"
+        );
+    }
+}
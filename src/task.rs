@@ -0,0 +1,865 @@
+//! Types and requests for tasks, projects, and workspaces in Asana.
+
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::asana::DataRequest;
+
+/// Gid of the Asana workspace that tasks are pulled from.
+pub const ASANA_WORKSPACE_GID: &str = "1199118829113557";
+
+/// Reference to a project that a task belongs to.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ProjectRef {
+    /// Globally unique identifier of the project in Asana.
+    pub gid: String,
+    /// Human-readable name of the project.
+    pub name: String,
+}
+
+/// Reference to one option of an enum custom field.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct EnumOptionRef {
+    /// Globally unique identifier of the option in Asana.
+    pub gid: String,
+    /// Human-readable name of the option.
+    pub name: String,
+}
+
+/// Custom field value on a task.
+///
+/// Number, text, and enum fields coexist on real tasks, so everything beyond the gid is
+/// optional: enum fields carry their chosen option in `enum_value`, while `display_value` covers
+/// every type as rendered text (and is null when the field is unset).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TaskCustomField {
+    /// Globally unique identifier of the custom field in Asana.
+    pub gid: String,
+    /// The field's value rendered as text, whatever its type, if it is set.
+    #[serde(default)]
+    pub display_value: Option<String>,
+    /// The chosen option, only set on enum fields with a value.
+    #[serde(default)]
+    pub enum_value: Option<EnumOptionRef>,
+}
+
+/// Single incomplete task assigned to the user.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct UserTask {
+    /// Globally unique identifier of the task in Asana.
+    pub gid: String,
+    /// When the task was created.
+    #[serde(with = "crate::asana::serde_formats::datetime")]
+    pub created_at: DateTime<Local>,
+    /// When the task is due, if it has a due date at all.
+    #[serde(with = "crate::asana::serde_formats::optional_date")]
+    pub due_on: Option<NaiveDate>,
+    /// Human-readable name of the task.
+    pub name: String,
+    /// Projects the task belongs to, possibly empty.
+    ///
+    /// Defaults to empty when deserializing so cache files written before this field existed
+    /// still load.
+    #[serde(default)]
+    pub projects: Vec<ProjectRef>,
+    /// Custom fields set on the task, possibly empty.
+    ///
+    /// Defaults to empty when deserializing so cache files written before this field existed
+    /// still load.
+    #[serde(default)]
+    pub custom_fields: Vec<TaskCustomField>,
+}
+
+impl UserTask {
+    /// The label of the given custom field on this task: the enum option's name when it is an
+    /// enum field, the display value otherwise, and `None` when the field is missing or unset.
+    #[must_use]
+    pub fn custom_field_label(&self, field_gid: &str) -> Option<&str> {
+        let field = self.custom_fields.iter().find(|f| f.gid == field_gid)?;
+        field
+            .enum_value
+            .as_ref()
+            .map(|option| option.name.as_str())
+            .or(field.display_value.as_deref())
+    }
+}
+
+impl<'a> DataRequest<'a> for UserTask {
+    type RequestData = String;
+    type ResponseData = Vec<Self>;
+
+    fn segments(request_data: &'a Self::RequestData) -> Vec<String> {
+        vec![
+            "user_task_lists".to_string(),
+            request_data.clone(),
+            "tasks".to_string(),
+        ]
+    }
+
+    fn fields() -> &'a [&'a str] {
+        &[
+            "gid",
+            "created_at",
+            "due_on",
+            "name",
+            "projects.gid",
+            "projects.name",
+            "custom_fields.gid",
+            "custom_fields.display_value",
+            "custom_fields.enum_value.gid",
+            "custom_fields.enum_value.name",
+        ]
+    }
+
+    fn params(_: &'a Self::RequestData) -> Vec<(&'a str, String)> {
+        // Asana defaults to pages of 50; request the maximum page size so fewer tasks fall off
+        // the end until pagination is followed.
+        vec![
+            ("completed_since", "now".to_string()),
+            ("limit", "100".to_string()),
+        ]
+    }
+}
+
+/// Summary of how a freshly fetched task list differs from a cached one, keyed by gid.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct TaskDiff {
+    /// Tasks whose gid was not in the old list.
+    pub added: usize,
+    /// Tasks that disappeared from the list: completed in Asana, reassigned, or deleted.
+    pub removed: usize,
+    /// Tasks present in both lists under a different name.
+    pub renamed: usize,
+    /// Tasks present in both lists with a different due date.
+    pub due_changed: usize,
+}
+
+impl TaskDiff {
+    /// Whether the two lists held the same tasks with the same names and due dates.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Compare two task lists by gid, counting additions, removals, renames, and due-date changes.
+///
+/// A task both renamed and rescheduled counts once in each bucket, and ordering differences
+/// count as nothing at all.
+#[must_use]
+pub fn diff_tasks(old: &[UserTask], new: &[UserTask]) -> TaskDiff {
+    let old_by_gid: std::collections::HashMap<&str, &UserTask> =
+        old.iter().map(|task| (task.gid.as_str(), task)).collect();
+    let new_gids: std::collections::HashSet<&str> =
+        new.iter().map(|task| task.gid.as_str()).collect();
+
+    let mut diff = TaskDiff {
+        removed: old
+            .iter()
+            .filter(|task| !new_gids.contains(task.gid.as_str()))
+            .count(),
+        ..TaskDiff::default()
+    };
+    for task in new {
+        match old_by_gid.get(task.gid.as_str()) {
+            None => diff.added += 1,
+            Some(previous) => {
+                if previous.name != task.name {
+                    diff.renamed += 1;
+                }
+                if previous.due_on != task.due_on {
+                    diff.due_changed += 1;
+                }
+            }
+        }
+    }
+    diff
+}
+
+/// Task-list changes worth interrupting the user about, by name rather than count.
+///
+/// Where [`TaskDiff`] summarizes everything for the update's one-line report, this keeps only
+/// the changes that warrant a desktop notification.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NotableChanges {
+    /// Names of tasks whose gid was not in the old list.
+    pub new_tasks: Vec<String>,
+    /// Names of already-known tasks whose due date crossed into the past since the old list.
+    pub newly_overdue: Vec<String>,
+}
+
+impl NotableChanges {
+    /// Whether nothing notification-worthy changed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.new_tasks.is_empty() && self.newly_overdue.is_empty()
+    }
+}
+
+/// Collect the changes between two task lists that warrant a desktop notification.
+///
+/// A task counts as newly overdue only when the old list knew it and did not already consider
+/// it overdue; a brand-new task that arrives past due is reported as new, not twice.
+#[must_use]
+pub fn notable_changes(old: &[UserTask], new: &[UserTask], today: NaiveDate) -> NotableChanges {
+    let old_by_gid: std::collections::HashMap<&str, &UserTask> =
+        old.iter().map(|task| (task.gid.as_str(), task)).collect();
+
+    let mut changes = NotableChanges::default();
+    for task in new {
+        let overdue = task.due_on.is_some_and(|due| due < today);
+        match old_by_gid.get(task.gid.as_str()) {
+            None => changes.new_tasks.push(task.name.clone()),
+            Some(previous) => {
+                let was_overdue = previous.due_on.is_some_and(|due| due < today);
+                if overdue && !was_overdue {
+                    changes.newly_overdue.push(task.name.clone());
+                }
+            }
+        }
+    }
+    changes
+}
+
+/// Reference to the user a task is assigned to.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AssigneeRef {
+    /// Globally unique identifier of the user in Asana.
+    pub gid: String,
+    /// Human-readable name of the user.
+    pub name: String,
+}
+
+/// Full detail of a single task, fetched on demand by commands that show one task.
+///
+/// [`UserTask`] stays deliberately small since it is fetched for every task in the list; this
+/// type carries everything else (notes, permalink, assignee, membership, subtask count) at the
+/// cost of one request per task.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TaskDetail {
+    /// Globally unique identifier of the task in Asana.
+    pub gid: String,
+    /// Human-readable name of the task.
+    pub name: String,
+    /// Free-form notes on the task, possibly empty.
+    #[serde(default)]
+    pub notes: String,
+    /// Stable link to the task in the Asana app.
+    pub permalink_url: String,
+    /// Whether the task has been completed.
+    pub completed: bool,
+    /// When the task was completed, unset while it is still open.
+    #[serde(default, with = "crate::asana::serde_formats::optional_datetime")]
+    pub completed_at: Option<DateTime<Local>>,
+    /// When the task is due, if it has a due date at all.
+    #[serde(default, with = "crate::asana::serde_formats::optional_date")]
+    pub due_on: Option<NaiveDate>,
+    /// Exact due time, only set when the task is due at a specific time rather than a whole day.
+    #[serde(default, with = "crate::asana::serde_formats::optional_datetime")]
+    pub due_at: Option<DateTime<Local>>,
+    /// When the task was created.
+    #[serde(with = "crate::asana::serde_formats::datetime")]
+    pub created_at: DateTime<Local>,
+    /// Who the task is assigned to, if anyone.
+    #[serde(default)]
+    pub assignee: Option<AssigneeRef>,
+    /// Projects the task belongs to, possibly empty.
+    #[serde(default)]
+    pub projects: Vec<ProjectRef>,
+    /// Number of subtasks directly under the task.
+    #[serde(default)]
+    pub num_subtasks: u32,
+}
+
+impl<'a> DataRequest<'a> for TaskDetail {
+    /// The gid of the task to fetch.
+    type RequestData = String;
+    type ResponseData = Self;
+
+    fn segments(task_gid: &'a Self::RequestData) -> Vec<String> {
+        vec!["tasks".to_string(), task_gid.clone()]
+    }
+
+    fn fields() -> &'a [&'a str] {
+        &[
+            "gid",
+            "name",
+            "notes",
+            "permalink_url",
+            "completed",
+            "completed_at",
+            "due_on",
+            "due_at",
+            "created_at",
+            "assignee.gid",
+            "assignee.name",
+            "projects.gid",
+            "projects.name",
+            "num_subtasks",
+        ]
+    }
+}
+
+/// Task assigned to the user that may have been completed, fetched for reports.
+///
+/// The user task list endpoint with a `completed_since` window returns completed tasks alongside
+/// still-incomplete ones, so callers filter on `completed_at`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CompletedTask {
+    /// Globally unique identifier of the task in Asana.
+    pub gid: String,
+    /// Human-readable name of the task.
+    pub name: String,
+    /// When the task was completed, unset while it is still open.
+    #[serde(default, with = "crate::asana::serde_formats::optional_datetime")]
+    pub completed_at: Option<DateTime<Local>>,
+    /// Projects the task belongs to, possibly empty.
+    ///
+    /// Defaults to empty when deserializing so cache files written before this field existed
+    /// still load.
+    #[serde(default)]
+    pub projects: Vec<ProjectRef>,
+}
+
+impl<'a> DataRequest<'a> for CompletedTask {
+    /// The user task list gid and the start of the window completed tasks are pulled from.
+    type RequestData = (String, NaiveDate);
+    type ResponseData = Vec<Self>;
+
+    fn segments((user_task_list_gid, _): &'a Self::RequestData) -> Vec<String> {
+        vec![
+            "user_task_lists".to_string(),
+            user_task_list_gid.clone(),
+            "tasks".to_string(),
+        ]
+    }
+
+    fn fields() -> &'a [&'a str] {
+        &["gid", "name", "completed_at", "projects.gid", "projects.name"]
+    }
+
+    fn params((_, since): &'a Self::RequestData) -> Vec<(&'a str, String)> {
+        // The window starts at local midnight, not UTC: a task completed late in the evening
+        // must count towards the local day it was finished on.
+        let midnight = since
+            .and_hms_opt(0, 0, 0)
+            .and_then(|time| time.and_local_timezone(Local).earliest())
+            .map_or_else(
+                || format!("{}T00:00:00Z", since.format("%Y-%m-%d")),
+                |time| time.to_rfc3339(),
+            );
+        vec![("completed_since", midnight)]
+    }
+}
+
+/// User task list that holds every task assigned to a user.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct UserTaskList {
+    /// Globally unique identifier of the user task list in Asana.
+    pub gid: String,
+}
+
+impl<'a> DataRequest<'a> for UserTaskList {
+    /// The user gid (or `"me"`) and the gid of the workspace to look the task list up in.
+    type RequestData = (String, String);
+    type ResponseData = Self;
+
+    fn segments((user_gid, _): &'a Self::RequestData) -> Vec<String> {
+        vec![
+            "users".to_string(),
+            user_gid.clone(),
+            "user_task_list".to_string(),
+        ]
+    }
+
+    fn fields() -> &'a [&'a str] {
+        &["gid"]
+    }
+
+    fn params((_, workspace_gid): &'a Self::RequestData) -> Vec<(&'a str, String)> {
+        vec![("workspace", workspace_gid.clone())]
+    }
+}
+
+/// Workspace visible to the authenticated user.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Workspace {
+    /// Globally unique identifier of the workspace in Asana.
+    pub gid: String,
+    /// Human-readable name of the workspace.
+    pub name: String,
+}
+
+impl<'a> DataRequest<'a> for Workspace {
+    type RequestData = ();
+    type ResponseData = Vec<Self>;
+
+    fn segments((): &'a Self::RequestData) -> Vec<String> {
+        vec!["workspaces".to_string()]
+    }
+
+    fn fields() -> &'a [&'a str] {
+        &["gid", "name"]
+    }
+}
+
+/// Profile of the authenticated user, fetched once and cached for greetings and gid lookups.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct User {
+    /// Globally unique identifier of the user in Asana.
+    pub gid: String,
+    /// Full display name of the user.
+    pub name: String,
+    /// Email address of the user, when the workspace exposes one.
+    #[serde(default)]
+    pub email: Option<String>,
+    /// Profile photo renditions, when the user has set one.
+    #[serde(default)]
+    pub photo: Option<UserPhoto>,
+}
+
+/// Photo renditions attached to a user profile.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct UserPhoto {
+    /// URL of the 128x128 pixel rendition.
+    #[serde(default)]
+    pub image_128x128: Option<String>,
+}
+
+impl<'a> DataRequest<'a> for User {
+    type RequestData = ();
+    type ResponseData = Self;
+
+    fn segments((): &'a Self::RequestData) -> Vec<String> {
+        vec!["users".to_string(), "me".to_string()]
+    }
+
+    fn fields() -> &'a [&'a str] {
+        &["gid", "name", "email", "photo.image_128x128"]
+    }
+}
+
+/// User visible in a workspace, for resolving assignees and followers by email or name.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WorkspaceUser {
+    /// Globally unique identifier of the user in Asana.
+    pub gid: String,
+    /// Human-readable name of the user.
+    pub name: String,
+    /// Email address of the user, when their workspace exposes one.
+    pub email: Option<String>,
+}
+
+impl<'a> DataRequest<'a> for WorkspaceUser {
+    /// The gid of the workspace to list users from.
+    type RequestData = String;
+    type ResponseData = Vec<Self>;
+
+    fn segments(workspace_gid: &'a Self::RequestData) -> Vec<String> {
+        vec![
+            "workspaces".to_string(),
+            workspace_gid.clone(),
+            "users".to_string(),
+        ]
+    }
+
+    fn fields() -> &'a [&'a str] {
+        &["gid", "name", "email"]
+    }
+}
+
+/// Project in a workspace.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Project {
+    /// Globally unique identifier of the project in Asana.
+    pub gid: String,
+    /// Human-readable name of the project.
+    pub name: String,
+}
+
+impl<'a> DataRequest<'a> for Project {
+    /// The gid of the workspace to list projects from.
+    type RequestData = String;
+    type ResponseData = Vec<Self>;
+
+    fn segments(workspace_gid: &'a Self::RequestData) -> Vec<String> {
+        vec![
+            "workspaces".to_string(),
+            workspace_gid.clone(),
+            "projects".to_string(),
+        ]
+    }
+
+    fn fields() -> &'a [&'a str] {
+        &["gid", "name"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_task_deserializes_from_cache_without_projects() {
+        // Cache files written before the projects field existed must still load.
+        let task: UserTask = serde_json::from_str(
+            r#"{
+                "gid": "123",
+                "created_at": "2024-01-01T12:00:00.000Z",
+                "due_on": "2024-01-15",
+                "name": "old task"
+            }"#,
+        )
+        .unwrap();
+        assert!(task.projects.is_empty());
+    }
+
+    #[test]
+    fn custom_fields_tolerate_mixed_types() {
+        // Number, text, and enum fields coexist on real tasks; only enum fields carry an option.
+        let task: UserTask = serde_json::from_str(
+            r#"{
+                "gid": "123",
+                "created_at": "2024-01-01T12:00:00.000Z",
+                "due_on": null,
+                "name": "task",
+                "custom_fields": [
+                    {"gid": "n1", "display_value": "4"},
+                    {"gid": "t1", "display_value": null},
+                    {"gid": "p1", "display_value": "P1", "enum_value": {"gid": "o1", "name": "P1"}}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(task.custom_field_label("p1"), Some("P1"));
+        assert_eq!(task.custom_field_label("n1"), Some("4"));
+        assert_eq!(task.custom_field_label("t1"), None);
+        assert_eq!(task.custom_field_label("missing"), None);
+    }
+
+    #[test]
+    fn task_detail_deserializes_a_full_payload() {
+        // Shaped like a real `GET /tasks/{gid}` response with the fields we request.
+        let detail: TaskDetail = serde_json::from_str(
+            r#"{
+                "gid": "1205",
+                "name": "write the report",
+                "notes": "outline first,\nthen fill in.",
+                "permalink_url": "https://app.asana.com/0/1200/1205",
+                "completed": true,
+                "completed_at": "2024-01-16T18:30:00.000Z",
+                "due_on": "2024-01-16",
+                "due_at": "2024-01-16T17:00:00.000Z",
+                "created_at": "2024-01-01T12:00:00.000Z",
+                "assignee": {"gid": "42", "name": "Ziyad"},
+                "projects": [{"gid": "1200", "name": "Work"}],
+                "num_subtasks": 3
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(detail.name, "write the report");
+        assert!(detail.completed);
+        assert!(detail.completed_at.is_some());
+        assert_eq!(detail.due_at.unwrap().naive_utc().to_string(), "2024-01-16 17:00:00");
+        assert_eq!(detail.assignee.unwrap().name, "Ziyad");
+        assert_eq!(detail.projects[0].name, "Work");
+        assert_eq!(detail.num_subtasks, 3);
+    }
+
+    #[test]
+    fn task_detail_tolerates_null_nested_fields() {
+        let detail: TaskDetail = serde_json::from_str(
+            r#"{
+                "gid": "1205",
+                "name": "bare task",
+                "notes": "",
+                "permalink_url": "https://app.asana.com/0/1200/1205",
+                "completed": false,
+                "completed_at": null,
+                "due_on": null,
+                "due_at": null,
+                "created_at": "2024-01-01T12:00:00.000Z",
+                "assignee": null,
+                "projects": []
+            }"#,
+        )
+        .unwrap();
+        assert!(detail.completed_at.is_none());
+        assert!(detail.due_on.is_none());
+        assert!(detail.due_at.is_none());
+        assert!(detail.assignee.is_none());
+        assert!(detail.projects.is_empty());
+        assert_eq!(detail.num_subtasks, 0);
+    }
+
+    #[test]
+    fn user_task_deserializes_projects() {
+        let task: UserTask = serde_json::from_str(
+            r#"{
+                "gid": "123",
+                "created_at": "2024-01-01T12:00:00.000Z",
+                "due_on": null,
+                "name": "task",
+                "projects": [{"gid": "1", "name": "Home"}]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(task.projects.len(), 1);
+        assert_eq!(task.projects[0].name, "Home");
+    }
+
+    #[test]
+    fn user_deserializes_a_full_profile() {
+        // Shaped like a real `GET /users/me` response with the fields we request.
+        let user: User = serde_json::from_str(
+            r#"{
+                "gid": "42",
+                "name": "Ziyad Edher",
+                "email": "ziyad@example.com",
+                "photo": {"image_128x128": "https://example.com/photo.png"}
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(user.gid, "42");
+        assert_eq!(user.name, "Ziyad Edher");
+        assert_eq!(user.email.as_deref(), Some("ziyad@example.com"));
+        assert_eq!(
+            user.photo.unwrap().image_128x128.as_deref(),
+            Some("https://example.com/photo.png")
+        );
+    }
+
+    #[test]
+    fn user_tolerates_missing_email_and_photo() {
+        // Some workspaces hide emails, and a photo is optional; neither may block loading.
+        let user: User = serde_json::from_str(
+            r#"{
+                "gid": "42",
+                "name": "Ziyad Edher",
+                "email": null,
+                "photo": null
+            }"#,
+        )
+        .unwrap();
+        assert!(user.email.is_none());
+        assert!(user.photo.is_none());
+    }
+
+    #[test]
+    fn completed_since_window_starts_at_local_midnight() {
+        let since = "2024-01-15".parse::<NaiveDate>().unwrap();
+        let request = ("utl1".to_string(), since);
+        let params = CompletedTask::params(&request);
+        assert_eq!(params.len(), 1);
+        assert_eq!(params[0].0, "completed_since");
+
+        // The rendered timestamp carries the local offset, so parsing it back lands exactly on
+        // local midnight of the requested day regardless of the machine's timezone.
+        let parsed = DateTime::parse_from_rfc3339(&params[0].1)
+            .unwrap()
+            .with_timezone(&Local);
+        assert_eq!(parsed.date_naive(), since);
+        assert_eq!(parsed.time(), chrono::NaiveTime::MIN);
+    }
+
+    fn diff_task(gid: &str, name: &str, due_on: Option<&str>) -> UserTask {
+        UserTask {
+            gid: gid.to_string(),
+            created_at: "2024-01-01T00:00:00Z".parse().unwrap(),
+            due_on: due_on.map(|d| d.parse().unwrap()),
+            name: name.to_string(),
+            projects: Vec::new(),
+            custom_fields: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn diff_tasks_counts_each_kind_of_change() {
+        let old = [
+            diff_task("1", "write the report", Some("2024-01-10")),
+            diff_task("2", "book flights", Some("2024-01-12")),
+            diff_task("3", "water the plants", None),
+        ];
+        let new = [
+            diff_task("1", "finish the report", Some("2024-01-10")),
+            diff_task("2", "book flights", Some("2024-01-19")),
+            diff_task("4", "pay the invoice", None),
+        ];
+        assert_eq!(
+            diff_tasks(&old, &new),
+            TaskDiff {
+                added: 1,
+                removed: 1,
+                renamed: 1,
+                due_changed: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn diff_tasks_ignores_ordering_and_reports_no_changes() {
+        let old = [
+            diff_task("1", "write the report", Some("2024-01-10")),
+            diff_task("2", "book flights", None),
+        ];
+        let new = [
+            diff_task("2", "book flights", None),
+            diff_task("1", "write the report", Some("2024-01-10")),
+        ];
+        assert!(diff_tasks(&old, &new).is_empty());
+        assert!(diff_tasks(&[], &[]).is_empty());
+    }
+
+    #[test]
+    fn a_rename_and_reschedule_on_one_task_counts_in_both_buckets() {
+        let old = [diff_task("1", "write the report", Some("2024-01-10"))];
+        let new = [diff_task("1", "finish the report", None)];
+        let diff = diff_tasks(&old, &new);
+        assert_eq!(diff.renamed, 1);
+        assert_eq!(diff.due_changed, 1);
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn notable_changes_name_new_and_newly_overdue_tasks() {
+        let today: NaiveDate = "2024-01-15".parse().unwrap();
+        let old = [
+            diff_task("1", "write the report", Some("2024-01-15")),
+            diff_task("2", "book flights", None),
+            diff_task("3", "file expenses", Some("2024-01-01")),
+        ];
+        let new = [
+            // Slipped from due today into overdue.
+            diff_task("1", "write the report", Some("2024-01-14")),
+            // Gained a past due date it never had.
+            diff_task("2", "book flights", Some("2024-01-10")),
+            // Already overdue before, so not worth another interruption.
+            diff_task("3", "file expenses", Some("2024-01-01")),
+            // Arrives past due, but is reported as new rather than twice.
+            diff_task("4", "renew the passport", Some("2024-01-12")),
+        ];
+        let changes = notable_changes(&old, &new, today);
+        assert_eq!(changes.new_tasks, ["renew the passport"]);
+        assert_eq!(changes.newly_overdue, ["write the report", "book flights"]);
+        assert!(!changes.is_empty());
+    }
+
+    #[test]
+    fn an_unchanged_list_has_no_notable_changes() {
+        let today: NaiveDate = "2024-01-15".parse().unwrap();
+        let old = [
+            diff_task("1", "write the report", Some("2024-01-01")),
+            diff_task("2", "book flights", Some("2024-01-20")),
+        ];
+        let changes = notable_changes(&old, &old, today);
+        assert!(changes.is_empty());
+        // A task merely disappearing is a completion, not a reason to notify.
+        assert!(notable_changes(&old, &old[..1], today).is_empty());
+    }
+
+    // One fully populated sample per request type, so every `fields()` list is checked against
+    // the struct's serde field names and a typo fails here instead of returning nulls at runtime.
+
+    #[test]
+    fn user_task_opt_fields_match_the_struct() {
+        crate::asana::assert_opt_fields_match(&UserTask {
+            gid: "1".to_string(),
+            created_at: "2024-01-01T12:00:00Z".parse().unwrap(),
+            due_on: Some("2024-01-15".parse().unwrap()),
+            name: "task".to_string(),
+            projects: vec![ProjectRef {
+                gid: "p1".to_string(),
+                name: "Work".to_string(),
+            }],
+            custom_fields: vec![TaskCustomField {
+                gid: "f1".to_string(),
+                display_value: Some("P1".to_string()),
+                enum_value: Some(EnumOptionRef {
+                    gid: "o1".to_string(),
+                    name: "P1".to_string(),
+                }),
+            }],
+        });
+    }
+
+    #[test]
+    fn task_detail_opt_fields_match_the_struct() {
+        crate::asana::assert_opt_fields_match(&TaskDetail {
+            gid: "1".to_string(),
+            name: "task".to_string(),
+            notes: "notes".to_string(),
+            permalink_url: "https://app.asana.com/0/1200/1".to_string(),
+            completed: false,
+            completed_at: Some("2024-01-16T18:30:00Z".parse().unwrap()),
+            due_on: Some("2024-01-16".parse().unwrap()),
+            due_at: Some("2024-01-16T17:00:00Z".parse().unwrap()),
+            created_at: "2024-01-01T12:00:00Z".parse().unwrap(),
+            assignee: Some(AssigneeRef {
+                gid: "42".to_string(),
+                name: "Ziyad".to_string(),
+            }),
+            projects: vec![ProjectRef {
+                gid: "p1".to_string(),
+                name: "Work".to_string(),
+            }],
+            num_subtasks: 3,
+        });
+    }
+
+    #[test]
+    fn completed_task_opt_fields_match_the_struct() {
+        crate::asana::assert_opt_fields_match(&CompletedTask {
+            gid: "1".to_string(),
+            name: "task".to_string(),
+            completed_at: Some("2024-01-15T18:30:00Z".parse().unwrap()),
+            projects: vec![ProjectRef {
+                gid: "p1".to_string(),
+                name: "Work".to_string(),
+            }],
+        });
+    }
+
+    #[test]
+    fn user_task_list_opt_fields_match_the_struct() {
+        crate::asana::assert_opt_fields_match(&UserTaskList {
+            gid: "utl1".to_string(),
+        });
+    }
+
+    #[test]
+    fn workspace_opt_fields_match_the_struct() {
+        crate::asana::assert_opt_fields_match(&Workspace {
+            gid: "w1".to_string(),
+            name: "Workspace".to_string(),
+        });
+    }
+
+    #[test]
+    fn user_opt_fields_match_the_struct() {
+        crate::asana::assert_opt_fields_match(&User {
+            gid: "42".to_string(),
+            name: "Ziyad Edher".to_string(),
+            email: Some("ziyad@example.com".to_string()),
+            photo: Some(UserPhoto {
+                image_128x128: Some("https://example.com/photo.png".to_string()),
+            }),
+        });
+    }
+
+    #[test]
+    fn workspace_user_opt_fields_match_the_struct() {
+        crate::asana::assert_opt_fields_match(&WorkspaceUser {
+            gid: "42".to_string(),
+            name: "Ziyad Edher".to_string(),
+            email: Some("ziyad@example.com".to_string()),
+        });
+    }
+
+    #[test]
+    fn project_opt_fields_match_the_struct() {
+        crate::asana::assert_opt_fields_match(&Project {
+            gid: "p1".to_string(),
+            name: "Work".to_string(),
+        });
+    }
+}
@@ -0,0 +1,523 @@
+//! Small shared helpers that don't belong to any one command.
+
+use anyhow::Context;
+use chrono::{DateTime, Datelike, Local, LocalResult, NaiveDate, NaiveTime, TimeZone, Utc};
+
+/// Format a date with a strftime-style format string, typically `display.date_format` with the
+/// caller's current format as the fallback.
+///
+/// An invalid format string falls back to the ISO date; configuration loading already rejects
+/// those, so this is only a backstop.
+#[must_use]
+pub fn format_date(date: NaiveDate, format: &str) -> String {
+    use std::fmt::Write as _;
+
+    let mut string = String::new();
+    match write!(string, "{}", date.format(format)) {
+        Ok(()) => string,
+        Err(_) => date.format("%Y-%m-%d").to_string(),
+    }
+}
+
+/// Format a timestamp with a strftime-style format string, typically `display.time_format` with
+/// the caller's current format as the fallback.
+///
+/// An invalid format string falls back to `%H:%M`; configuration loading already rejects those,
+/// so this is only a backstop.
+#[must_use]
+pub fn format_datetime(at: DateTime<Local>, format: &str) -> String {
+    use std::fmt::Write as _;
+
+    let mut string = String::new();
+    match write!(string, "{}", at.format(format)) {
+        Ok(()) => string,
+        Err(_) => at.format("%H:%M").to_string(),
+    }
+}
+
+/// Format a due date relative to `today`, e.g. "yesterday", "tomorrow", or "in 3 days (Thu)".
+///
+/// Dates more than a week away in either direction fall back to the plain date, including the
+/// year when it differs from today's.
+#[must_use]
+pub fn format_relative_date(due: NaiveDate, today: NaiveDate) -> String {
+    let days = (due - today).num_days();
+    match days {
+        -1 => "yesterday".to_string(),
+        0 => "today".to_string(),
+        1 => "tomorrow".to_string(),
+        2..=7 => format!("in {days} days ({weekday})", weekday = due.weekday()),
+        -7..=-2 => format!(
+            "{days} days ago ({weekday})",
+            days = -days,
+            weekday = due.weekday()
+        ),
+        _ if due.year() == today.year() => due.format("%b %-d").to_string(),
+        _ => due.format("%b %-d, %Y").to_string(),
+    }
+}
+
+/// Parse a human-friendly date relative to `today`: `today`, `tomorrow`, `+N`/`+Nd`/`+Nw`/`+Nm`
+/// offsets (days, weeks, or months out), `N days` phrasing, `eod`/`eow`/`eom` for the end of the
+/// day, week, or month, a weekday name (always the next occurrence, e.g. `fri` or `friday`), or
+/// a full `YYYY-MM-DD` date.
+///
+/// Weeks start on Monday, as everywhere else in the tool, so `eow` is the upcoming Sunday (or
+/// today, when today is Sunday). `+Nm` clamps to the end of the target month when the day does
+/// not exist there, e.g. January 31st plus a month is the last day of February.
+#[must_use]
+pub fn parse_flexible_date(input: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let input = input.trim().to_lowercase();
+    match input.as_str() {
+        "today" | "eod" => return Some(today),
+        "tomorrow" => return Some(today + chrono::Days::new(1)),
+        "eow" => {
+            let days_left = 6 - u64::from(today.weekday().num_days_from_monday());
+            return Some(today + chrono::Days::new(days_left));
+        }
+        "eom" => return end_of_month(today),
+        _ => {}
+    }
+    if let Some(rest) = input.strip_prefix('+') {
+        if let Some(months) = rest.strip_suffix('m') {
+            return today.checked_add_months(chrono::Months::new(months.parse().ok()?));
+        }
+        let (count, per_unit) = match rest.strip_suffix(['d', 'w']) {
+            Some(count) if rest.ends_with('w') => (count, 7),
+            Some(count) => (count, 1),
+            None => (rest, 1),
+        };
+        return Some(today + chrono::Days::new(count.parse::<u64>().ok()? * per_unit));
+    }
+    if let Some(count) = input
+        .strip_suffix(" days")
+        .or_else(|| input.strip_suffix(" day"))
+    {
+        return Some(today + chrono::Days::new(count.trim().parse().ok()?));
+    }
+    if let Ok(weekday) = input.parse::<chrono::Weekday>() {
+        let days_ahead =
+            (7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday() - 1) % 7
+                + 1;
+        return Some(today + chrono::Days::new(u64::from(days_ahead)));
+    }
+    NaiveDate::parse_from_str(&input, "%Y-%m-%d").ok()
+}
+
+/// Resolve a due-date spec through [`parse_flexible_date`], guarding against dates that land
+/// in the past.
+///
+/// A spec that resolves before `today` is usually an accident — a weekday anchored to the
+/// wrong week — so unless `allow_past` is set, interactive runs show what was parsed and ask
+/// for confirmation, and non-interactive runs fail with the same explanation.
+///
+/// # Errors
+///
+/// This function will return an error if the spec does not parse, or if it resolves to a past
+/// date that was neither waved through with `allow_past` nor confirmed at the prompt.
+#[cfg(feature = "cli")]
+pub fn resolve_due_date(
+    input: &str,
+    today: NaiveDate,
+    allow_past: bool,
+    interactive: bool,
+) -> anyhow::Result<NaiveDate> {
+    let date = parse_flexible_date(input, today).with_context(|| {
+        format!(
+            "could not parse `{input}` as a date; try today, tomorrow, +N, a weekday, or \
+             YYYY-MM-DD"
+        )
+    })?;
+    if allow_past || date >= today {
+        return Ok(date);
+    }
+    anyhow::ensure!(
+        interactive,
+        "`{input}` → {date}, which is in the past; pass --allow-past to use it anyway"
+    );
+    let confirmed = dialoguer::Confirm::new()
+        .with_prompt(format!(
+            "`{input}` → {date}, which is in the past — use it anyway?"
+        ))
+        .default(false)
+        .interact()?;
+    anyhow::ensure!(confirmed, "not using the past due date {date}");
+    Ok(date)
+}
+
+/// The last day of `today`'s month, February 29th included when the year cooperates.
+fn end_of_month(today: NaiveDate) -> Option<NaiveDate> {
+    today
+        .with_day(1)?
+        .checked_add_months(chrono::Months::new(1))?
+        .pred_opt()
+}
+
+/// Build an inclusive date range from `--from`/`--to` endpoint specs (each anything
+/// [`parse_flexible_date`] accepts) or a `--last` trailing window like `7d`, `2w`, or a plain
+/// number of days, ending at `today` when open-ended.
+///
+/// # Errors
+///
+/// This function will return an error if an endpoint or window does not parse, no endpoint was
+/// given at all, or the range runs backwards.
+pub fn parse_date_range(
+    from: Option<&str>,
+    to: Option<&str>,
+    last: Option<&str>,
+    today: NaiveDate,
+) -> anyhow::Result<(NaiveDate, NaiveDate)> {
+    if let Some(window) = last {
+        let window = window.trim().to_lowercase();
+        let (count, per_unit) = match window.strip_suffix(['d', 'w']) {
+            Some(count) if window.ends_with('w') => (count, 7),
+            Some(count) => (count, 1),
+            None => (window.as_str(), 1),
+        };
+        let days = count
+            .parse::<u64>()
+            .ok()
+            .map(|count| count * per_unit)
+            .filter(|days| *days > 0)
+            .with_context(|| format!("could not parse window \"{window}\": use e.g. 7d or 2w"))?;
+        return Ok((today - chrono::Days::new(days - 1), today));
+    }
+
+    let from = from.context("a date range needs --from or --last")?;
+    let from = parse_flexible_date(from, today)
+        .with_context(|| format!("could not parse date \"{from}\""))?;
+    let to = match to {
+        Some(to) => parse_flexible_date(to, today)
+            .with_context(|| format!("could not parse date \"{to}\""))?,
+        None => today,
+    };
+    anyhow::ensure!(
+        from <= to,
+        "the range runs backwards: {from} is after {to}"
+    );
+    Ok((from, to))
+}
+
+/// Convert a local date and wall-clock time to UTC, resolving the DST edge cases.
+///
+/// A time that occurs twice (clocks rolled back) resolves to its earlier occurrence; a time
+/// that never occurs (clocks jumped forward) is an error rather than a silent shift.
+///
+/// # Errors
+///
+/// This function will return an error if the local timezone skips the given time.
+pub fn local_time_to_utc(date: NaiveDate, time: NaiveTime) -> anyhow::Result<DateTime<Utc>> {
+    resolve_local(Local.from_local_datetime(&date.and_time(time))).ok_or_else(|| {
+        anyhow::anyhow!("{date} {time} does not exist locally; DST skips over it")
+    })
+}
+
+/// Collapse a possibly-ambiguous local datetime to UTC: the earlier occurrence when clocks
+/// rolled back, nothing when the time was skipped.
+fn resolve_local<Tz: TimeZone>(result: LocalResult<DateTime<Tz>>) -> Option<DateTime<Utc>> {
+    match result {
+        LocalResult::Single(at) | LocalResult::Ambiguous(at, _) => Some(at.with_timezone(&Utc)),
+        LocalResult::None => None,
+    }
+}
+
+/// Similarity between two task names as a ratio in `0.0..=1.0`, where 1.0 is an exact match.
+///
+/// Names are compared case-insensitively with whitespace runs collapsed, so "book  Dentist "
+/// and "Book dentist" are identical; the ratio is the edit distance normalized by the longer
+/// name. Used to flag likely duplicates when capturing tasks.
+#[must_use]
+pub fn name_similarity(a: &str, b: &str) -> f64 {
+    let normalize = |s: &str| {
+        s.split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase()
+    };
+    let a = normalize(a);
+    let b = normalize(b);
+    let longest = a.chars().count().max(b.chars().count());
+    if longest == 0 {
+        return 1.0;
+    }
+    // Name lengths are far below f64's integer precision, so the casts are exact.
+    #[allow(clippy::cast_precision_loss)]
+    {
+        1.0 - edit_distance(&a, &b) as f64 / longest as f64
+    }
+}
+
+/// Levenshtein edit distance between two strings, used for did-you-mean suggestions.
+#[must_use]
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let mut distances: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.iter().enumerate() {
+        let mut previous = distances[0];
+        distances[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous + usize::from(a_char != b_char);
+            previous = distances[j + 1];
+            distances[j + 1] = substitution
+                .min(previous + 1)
+                .min(distances[j] + 1);
+        }
+    }
+    distances[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn edit_distance_counts_single_character_changes() {
+        assert_eq!(edit_distance("status", "status"), 0);
+        assert_eq!(edit_distance("maxwidth", "max_width"), 1);
+        assert_eq!(edit_distance("behavor", "behavior"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn ambiguous_local_times_resolve_to_the_earlier_occurrence() {
+        // A fall-back transition à la America/New_York: 01:30 happens once at UTC-4 and again
+        // at UTC-5. The earlier (UTC-4) occurrence wins.
+        let half_past_one = date("2024-11-03").and_hms_opt(1, 30, 0).unwrap();
+        let edt = chrono::FixedOffset::west_opt(4 * 3600).unwrap();
+        let est = chrono::FixedOffset::west_opt(5 * 3600).unwrap();
+        let resolved = resolve_local(LocalResult::Ambiguous(
+            edt.from_local_datetime(&half_past_one).unwrap(),
+            est.from_local_datetime(&half_past_one).unwrap(),
+        ))
+        .unwrap();
+        assert_eq!(resolved.to_rfc3339(), "2024-11-03T05:30:00+00:00");
+
+        // A spring-forward gap has no valid reading at all.
+        assert_eq!(resolve_local::<chrono::FixedOffset>(LocalResult::None), None);
+    }
+
+    #[test]
+    fn local_times_convert_to_utc_and_back() {
+        // Whatever the machine's timezone, converting back must land on the same wall-clock
+        // time. The DST edge cases are exercised through resolve_local directly since they
+        // need a real timezone transition.
+        let wall_clock = date("2024-01-15")
+            .and_time(NaiveTime::from_hms_opt(17, 0, 0).unwrap());
+        let at = local_time_to_utc(wall_clock.date(), wall_clock.time()).unwrap();
+        assert_eq!(at.with_timezone(&Local).naive_local(), wall_clock);
+    }
+
+    #[test]
+    fn name_similarity_flags_near_matches_but_not_different_tasks() {
+        assert!((name_similarity("Book dentist", "book  Dentist ") - 1.0).abs() < f64::EPSILON);
+        assert!(name_similarity("Book dentist", "Book the dentist") > 0.7);
+        assert!(name_similarity("Book dentist appointment", "Book dentist appt") > 0.7);
+        assert!(name_similarity("Book dentist", "Water the plants") < 0.4);
+        assert!(name_similarity("Pay rent", "File taxes") < 0.4);
+        assert!((name_similarity("", "") - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn flexible_dates_parse_keywords_offsets_weekdays_and_iso() {
+        // 2024-01-15 is a Monday.
+        let today = date("2024-01-15");
+        assert_eq!(parse_flexible_date("today", today), Some(today));
+        assert_eq!(
+            parse_flexible_date("Tomorrow", today),
+            Some(date("2024-01-16"))
+        );
+        assert_eq!(parse_flexible_date("+3", today), Some(date("2024-01-18")));
+        assert_eq!(parse_flexible_date("fri", today), Some(date("2024-01-19")));
+        // A weekday names the next occurrence, never today.
+        assert_eq!(
+            parse_flexible_date("monday", today),
+            Some(date("2024-01-22"))
+        );
+        assert_eq!(
+            parse_flexible_date("2024-02-01", today),
+            Some(date("2024-02-01"))
+        );
+        assert_eq!(parse_flexible_date("not a date", today), None);
+    }
+
+    #[test]
+    fn flexible_dates_parse_shorthand_offsets_and_period_ends() {
+        // One row per form: (input, today, expected date).
+        let table = [
+            // Suffixed offsets, with and without whitespace or case quirks.
+            ("+3d", "2024-01-15", Some("2024-01-18")),
+            ("+2w", "2024-01-15", Some("2024-01-29")),
+            ("+1m", "2024-01-15", Some("2024-02-15")),
+            // A month offset clamps to the end of the shorter target month, leap day included.
+            ("+1m", "2024-01-31", Some("2024-02-29")),
+            ("+1m", "2023-01-31", Some("2023-02-28")),
+            ("+12m", "2024-02-29", Some("2025-02-28")),
+            // Spelled-out day counts.
+            ("3 days", "2024-01-15", Some("2024-01-18")),
+            ("1 day", "2024-01-15", Some("2024-01-16")),
+            ("0 days", "2024-01-15", Some("2024-01-15")),
+            // End of day is just today.
+            ("eod", "2024-01-15", Some("2024-01-15")),
+            // Weeks start on Monday, so they end on Sunday — which may be today.
+            ("eow", "2024-01-15", Some("2024-01-21")),
+            ("eow", "2024-01-20", Some("2024-01-21")),
+            ("eow", "2024-01-21", Some("2024-01-21")),
+            // Month ends, including a leap February and a 31st.
+            ("eom", "2024-01-15", Some("2024-01-31")),
+            ("eom", "2024-01-31", Some("2024-01-31")),
+            ("eom", "2024-02-01", Some("2024-02-29")),
+            ("eom", "2023-02-01", Some("2023-02-28")),
+            ("eom", "2024-12-31", Some("2024-12-31")),
+            // A weekday on its eve and on the day itself both resolve forward.
+            ("friday", "2024-01-20", Some("2024-01-26")),
+            ("fri", "2024-01-19", Some("2024-01-26")),
+            // Malformed offsets fall through to nothing rather than guessing.
+            ("+d", "2024-01-15", None),
+            ("+3x", "2024-01-15", None),
+            ("days", "2024-01-15", None),
+        ];
+        for (input, today, expected) in table {
+            assert_eq!(
+                parse_flexible_date(input, date(today)),
+                expected.map(date),
+                "{input} relative to {today}"
+            );
+        }
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn due_dates_in_the_past_need_allowing_when_non_interactive() {
+        let today = date("2024-01-15");
+        // The boundary: today and anything later pass straight through.
+        assert_eq!(
+            resolve_due_date("today", today, false, false).unwrap(),
+            today
+        );
+        assert_eq!(
+            resolve_due_date("+1", today, false, false).unwrap(),
+            date("2024-01-16")
+        );
+        // A past date fails fast without a terminal, naming the escape hatch...
+        let error = resolve_due_date("2024-01-14", today, false, false).unwrap_err();
+        assert!(format!("{error:#}").contains("--allow-past"), "{error:#}");
+        assert!(format!("{error:#}").contains("2024-01-14"), "{error:#}");
+        // ...and --allow-past waves it through.
+        assert_eq!(
+            resolve_due_date("2024-01-14", today, true, false).unwrap(),
+            date("2024-01-14")
+        );
+        // Nonsense keeps the parse hint regardless of the flags.
+        let error = resolve_due_date("not a date", today, true, false).unwrap_err();
+        assert!(format!("{error:#}").contains("YYYY-MM-DD"), "{error:#}");
+    }
+
+    #[test]
+    fn date_ranges_accept_endpoints_and_trailing_windows() {
+        let today = date("2024-01-15");
+        assert_eq!(
+            parse_date_range(Some("2024-01-01"), Some("2024-01-10"), None, today).unwrap(),
+            (date("2024-01-01"), date("2024-01-10"))
+        );
+        // An open end runs through today, and endpoints take the flexible forms.
+        assert_eq!(
+            parse_date_range(Some("2024-01-10"), None, None, today).unwrap(),
+            (date("2024-01-10"), today)
+        );
+        assert_eq!(
+            parse_date_range(None, None, Some("7d"), today).unwrap(),
+            (date("2024-01-09"), today)
+        );
+        assert_eq!(
+            parse_date_range(None, None, Some("2w"), today).unwrap(),
+            (date("2024-01-02"), today)
+        );
+        // A bare number counts days, and `1d` is just today.
+        assert_eq!(
+            parse_date_range(None, None, Some("3"), today).unwrap(),
+            (date("2024-01-13"), today)
+        );
+        assert_eq!(
+            parse_date_range(None, None, Some("1d"), today).unwrap(),
+            (today, today)
+        );
+    }
+
+    #[test]
+    fn bad_date_ranges_error_rather_than_guess() {
+        let today = date("2024-01-15");
+        let backwards = parse_date_range(Some("2024-01-10"), Some("2024-01-01"), None, today);
+        assert!(backwards.unwrap_err().to_string().contains("backwards"));
+        let unparseable = parse_date_range(Some("not a date"), None, None, today);
+        assert!(unparseable.unwrap_err().to_string().contains("not a date"));
+        let empty_window = parse_date_range(None, None, Some("0d"), today);
+        assert!(empty_window.unwrap_err().to_string().contains("7d"));
+        let no_endpoints = parse_date_range(None, None, None, today);
+        assert!(no_endpoints.unwrap_err().to_string().contains("--from"));
+    }
+
+    #[test]
+    fn format_helpers_apply_strftime_strings_with_an_iso_backstop() {
+        let day = date("2024-01-15");
+        assert_eq!(format_date(day, "%Y-%m-%d"), "2024-01-15");
+        assert_eq!(format_date(day, "%d/%m/%Y"), "15/01/2024");
+        // An invalid specifier falls back instead of panicking mid-render.
+        assert_eq!(format_date(day, "%Q"), "2024-01-15");
+
+        let at = chrono::TimeZone::with_ymd_and_hms(&Local, 2024, 1, 15, 9, 5, 0).unwrap();
+        assert_eq!(format_datetime(at, "%H:%M"), "09:05");
+        assert_eq!(format_datetime(at, "%-I:%M %p"), "9:05 AM");
+        assert_eq!(format_datetime(at, "%Q"), "09:05");
+    }
+
+    #[test]
+    fn relative_dates_around_today() {
+        let today = date("2024-01-15");
+        assert_eq!(format_relative_date(date("2024-01-14"), today), "yesterday");
+        assert_eq!(format_relative_date(date("2024-01-15"), today), "today");
+        assert_eq!(format_relative_date(date("2024-01-16"), today), "tomorrow");
+    }
+
+    #[test]
+    fn relative_dates_within_a_week_show_the_weekday() {
+        let today = date("2024-01-15");
+        assert_eq!(
+            format_relative_date(date("2024-01-18"), today),
+            "in 3 days (Thu)"
+        );
+        assert_eq!(
+            format_relative_date(date("2024-01-22"), today),
+            "in 7 days (Mon)"
+        );
+        assert_eq!(
+            format_relative_date(date("2024-01-12"), today),
+            "3 days ago (Fri)"
+        );
+    }
+
+    #[test]
+    fn relative_dates_beyond_a_week_show_the_date() {
+        let today = date("2024-01-15");
+        assert_eq!(format_relative_date(date("2024-03-04"), today), "Mar 4");
+        assert_eq!(format_relative_date(date("2024-01-01"), today), "Jan 1");
+    }
+
+    #[test]
+    fn relative_dates_in_another_year_include_the_year() {
+        let today = date("2024-01-15");
+        assert_eq!(
+            format_relative_date(date("2023-12-01"), today),
+            "Dec 1, 2023"
+        );
+        assert_eq!(
+            format_relative_date(date("2025-02-01"), today),
+            "Feb 1, 2025"
+        );
+    }
+}
@@ -0,0 +1,169 @@
+//! Integration tests pinning the context clock and running the summary/status glue at
+//! different times of day against one fixture cache, asserting the time-dependent differences.
+
+use chrono::{DateTime, Local, TimeZone, Timelike};
+use todo::cache::Cache;
+use todo::commands::status::Status;
+use todo::config::Config;
+use todo::context::{AppContext, BufferOutput, GroupedTasks};
+use todo::focus::{FocusDay, FocusDayStats, FocusTask, START_HOUR_FOR_EOD};
+use todo::task::{CompletedTask, UserTask};
+
+fn task(gid: &str, due_on: &str) -> UserTask {
+    UserTask {
+        gid: gid.to_string(),
+        created_at: "2024-01-01T00:00:00Z".parse().unwrap(),
+        due_on: Some(due_on.parse().unwrap()),
+        name: format!("task {gid}"),
+        projects: Vec::new(),
+        custom_fields: Vec::new(),
+    }
+}
+
+/// A local time on the given January 2024 day, so the tests behave the same in every zone.
+fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+    Local
+        .with_ymd_and_hms(2024, 1, day, hour, minute, 0)
+        .unwrap()
+}
+
+/// A cache as `todo update` would have left it on the morning of Monday 2024-01-15: one task
+/// due that day, one due later in the week, one completion, and a focus day with only the
+/// sleep stat filled in.
+fn fixture_cache() -> Cache {
+    let mut stats = FocusDayStats::default();
+    stats.sleep.set_value(Some(7));
+    Cache {
+        tasks: Some(vec![task("1", "2024-01-15"), task("2", "2024-01-18")]),
+        completed_today: Some(vec![CompletedTask {
+            gid: "3".to_string(),
+            name: "task 3".to_string(),
+            completed_at: Some(at(15, 6, 0)),
+            projects: Vec::new(),
+        }]),
+        focus_day: Some(FocusDay {
+            task: FocusTask {
+                gid: "4".to_string(),
+                name: "Daily Focus for Monday (2024-01-15)".to_string(),
+                notes: String::new(),
+                modified_at: None,
+                custom_fields: None,
+            },
+            date: "2024-01-15".parse().unwrap(),
+            stats,
+            diary: String::new(),
+            prompts: Vec::new(),
+            subtasks: None,
+        }),
+        last_updated: Some(at(15, 6, 30)),
+        ..Cache::default()
+    }
+}
+
+/// Mirror the binary's summary glue at a pinned `now`: group the cached tasks, derive the EOD
+/// flag and today's counts from the context clock, and run the summary through a buffer.
+fn run_at(now: DateTime<Local>) -> (Status, Vec<String>) {
+    console::set_colors_enabled(false);
+    let buffer = BufferOutput::default();
+    let mut ctx = AppContext::new_with_now(
+        Config::default(),
+        fixture_cache(),
+        Box::new(buffer.clone()),
+        now,
+    );
+    let today = ctx.today();
+    let tasks = ctx.cache.tasks.clone().unwrap_or_default();
+    let grouped = GroupedTasks::group(&tasks, today);
+    let eod = now.hour() >= START_HOUR_FOR_EOD;
+    let done_today = ctx.cache.completed_today.as_ref().map_or(0, |tasks| {
+        tasks
+            .iter()
+            .filter(|t| t.completed_at.is_some_and(|a| a.date_naive() == today))
+            .count()
+    });
+    let focus_day = ctx.cache.focus_day.clone().filter(|d| d.date == today);
+    let status = Status::new(&grouped, focus_day.as_ref(), eod, today, true, false, done_today);
+    // With the live fallback unavailable, a cache without today's focus day means the focus
+    // status is unknown rather than fully pending.
+    let focus_unknown = focus_day.is_none();
+    let pending_stats = focus_day
+        .as_ref()
+        .map_or_else(Vec::new, |d| d.stats.pending_names(eod));
+    todo::commands::summary::run(
+        &mut ctx,
+        &grouped,
+        status.focus_subtasks_overdue + status.focus_subtasks_pending,
+        status.done_today,
+        &pending_stats,
+        focus_unknown,
+        true,
+    )
+    .unwrap();
+    (status, buffer.lines())
+}
+
+#[test]
+fn status_flags_follow_the_clock() {
+    let (morning, _) = run_at(at(15, 7, 0));
+    assert!(morning.morning_pending);
+    assert!(!morning.evening_pending);
+    assert_eq!(morning.due_today, 1);
+    assert_eq!(morning.overdue, 0);
+    assert_eq!(morning.done_today, 1);
+
+    // The reflection window has not opened yet, so the afternoon looks like the morning.
+    let (afternoon, _) = run_at(at(15, 14, 0));
+    assert!(!afternoon.evening_pending);
+
+    let (evening, _) = run_at(at(15, 20, 30));
+    assert!(evening.evening_pending);
+
+    // Past midnight the cached focus day no longer covers "today": the morning routine is
+    // pending again, yesterday's completion stops counting, and the task due on the 15th
+    // has rolled into overdue.
+    let (past_midnight, _) = run_at(at(16, 0, 30));
+    assert!(past_midnight.morning_pending);
+    assert!(!past_midnight.evening_pending);
+    assert_eq!(past_midnight.overdue, 1);
+    assert_eq!(past_midnight.due_today, 0);
+    assert_eq!(past_midnight.done_today, 0);
+}
+
+#[test]
+fn summary_output_differs_across_the_same_fixture() {
+    let (_, morning) = run_at(at(15, 7, 0));
+    assert!(morning[0].contains("due today"), "{morning:?}");
+    assert!(morning[0].contains("(missing: energy)"), "{morning:?}");
+    assert!(
+        morning.iter().any(|l| l.contains("cache is 30 minutes old")),
+        "{morning:?}"
+    );
+
+    // Only the cache age moves during the morning window.
+    let (_, afternoon) = run_at(at(15, 14, 0));
+    assert!(afternoon[0].contains("(missing: energy)"), "{afternoon:?}");
+    assert!(
+        afternoon
+            .iter()
+            .any(|l| l.contains("cache is 450 minutes old")),
+        "{afternoon:?}"
+    );
+
+    // Once the reflection window opens, the evening stats join the missing list.
+    let (_, evening) = run_at(at(15, 20, 30));
+    assert!(
+        evening[0].contains("(missing: energy, flow, hydration +3 more)"),
+        "{evening:?}"
+    );
+
+    // Past midnight the focus day is stale, so the summary flags it instead of nagging.
+    let (_, past_midnight) = run_at(at(16, 0, 30));
+    assert!(past_midnight[0].contains("overdue"), "{past_midnight:?}");
+    assert!(!past_midnight[0].contains("missing"), "{past_midnight:?}");
+    assert!(
+        past_midnight
+            .iter()
+            .any(|l| l.contains("focus status unknown (cache stale)")),
+        "{past_midnight:?}"
+    );
+}
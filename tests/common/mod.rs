@@ -0,0 +1,108 @@
+//! Shared fixture-cache helpers for integration tests that run the binary.
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::{env, fs};
+
+use chrono::{Days, Local};
+
+use todo::asana::Credentials;
+use todo::cache::Cache;
+use todo::focus::{FocusDay, FocusDayStats, FocusTask};
+use todo::task::{UserTask, UserTaskList};
+
+/// Build a task due in `due_in_days` days (negative for overdue), or undated.
+pub fn task(gid: &str, due_in_days: Option<i64>) -> UserTask {
+    let today = Local::now().date_naive();
+    UserTask {
+        gid: gid.to_string(),
+        created_at: Local::now(),
+        due_on: due_in_days.map(|days| {
+            if days < 0 {
+                today - Days::new(days.unsigned_abs())
+            } else {
+                today + Days::new(days.unsigned_abs())
+            }
+        }),
+        name: format!("task {gid}"),
+        projects: Vec::new(),
+        custom_fields: Vec::new(),
+    }
+}
+
+/// Build a focus day for today, with all stats either filled or unfilled.
+pub fn focus_day(filled: bool) -> FocusDay {
+    let mut stats = FocusDayStats::default();
+    if filled {
+        stats.sleep.set_value(Some(5));
+        stats.energy.set_value(Some(5));
+        stats.flow.set_value(Some(5));
+        stats.hydration.set_value(Some(5));
+        stats.health.set_value(Some(5));
+        stats.satisfaction.set_value(Some(5));
+        stats.stress.set_value(Some(5));
+    }
+    FocusDay {
+        task: FocusTask {
+            gid: "1".to_string(),
+            name: "Daily Focus".to_string(),
+            notes: String::new(),
+            modified_at: None,
+            custom_fields: None,
+        },
+        date: Local::now().date_naive(),
+        stats,
+        diary: String::new(),
+        prompts: vec![],
+        subtasks: None,
+    }
+}
+
+/// Write a fixture cache with fake credentials, the given tasks, and a focus day, returning its
+/// path. `name` keeps concurrently running tests from clobbering each other.
+pub fn fixture(name: &str, tasks: Vec<UserTask>, focus_filled: bool) -> PathBuf {
+    let dir = env::temp_dir()
+        .join("todo-integration-tests")
+        .join(format!("{name}-{pid}", pid = std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let cache = Cache {
+        creds: Some(Credentials::OAuth2 {
+            access_token: "test-access-token".to_string(),
+            refresh_token: Some("test-refresh-token".to_string()),
+        }),
+        creds_refreshed_at: None,
+        user: None,
+        user_task_list: Some(UserTaskList {
+            gid: "42".to_string(),
+        }),
+        tasks: Some(tasks),
+        completed_today: None,
+        workspace_users: None,
+        focus_day: Some(focus_day(focus_filled)),
+        focus_draft: None,
+        stat_entries: None,
+        gate_acknowledged: None,
+        paused: None,
+        notified: None,
+        last_updated: Some(Local::now()),
+        last_updated_offset: Some(Local::now().offset().to_string()),
+    };
+    let cache_path = dir.join("cache.json");
+    todo::cache::save(&cache_path, &cache).unwrap();
+    cache_path
+}
+
+/// Run the binary against the fixture cache (cache-only) with the extra arguments.
+pub fn run(cache_path: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_todo"))
+        .arg("--cache-path")
+        .arg(cache_path)
+        .arg("--config-path")
+        .arg(cache_path.with_file_name("config.toml"))
+        .arg("--use-cache")
+        .args(args)
+        .output()
+        .unwrap()
+}
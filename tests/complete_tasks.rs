@@ -0,0 +1,50 @@
+//! Integration tests for the hidden task-name completion helper.
+
+use std::process::Command;
+
+mod common;
+use common::{fixture, run, task};
+
+#[test]
+fn helper_prints_cached_task_names_one_per_line() {
+    let cache_path = fixture(
+        "complete-tasks",
+        vec![task("1", Some(-3)), task("2", None)],
+        false,
+    );
+    let output = run(&cache_path, &["__complete-tasks"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout, "task 1\ntask 2\n");
+    assert!(!stdout.contains('\x1b'), "found ANSI escapes: {stdout:?}");
+}
+
+#[test]
+fn helper_prints_gids_when_asked() {
+    let cache_path = fixture("complete-tasks-gids", vec![task("1", Some(0))], false);
+    let output = run(&cache_path, &["__complete-tasks", "--with-gids"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1\ttask 1\n");
+}
+
+#[test]
+fn helper_degrades_to_nothing_without_a_cache_or_use_cache_flag() {
+    let cache_path = std::env::temp_dir()
+        .join("todo-integration-tests")
+        .join(format!("complete-tasks-empty-{}", std::process::id()))
+        .join("cache.json");
+
+    // No --use-cache here: the helper must still never touch the network or prompt for
+    // authorization, and an absent cache yields no suggestions rather than an error.
+    let output = Command::new(env!("CARGO_BIN_EXE_todo"))
+        .arg("--cache-path")
+        .arg(&cache_path)
+        .arg("--config-path")
+        .arg(cache_path.with_file_name("config.toml"))
+        .arg("__complete-tasks")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(output.stderr.is_empty());
+}
@@ -0,0 +1,45 @@
+//! Integration tests for running `todo` with no subcommand.
+
+use std::fs;
+
+mod common;
+use common::{fixture, run, task};
+
+#[test]
+fn bare_invocation_runs_summary_by_default() {
+    let cache_path = fixture("default-command", vec![task("1", Some(-3))], true);
+    let output = run(&cache_path, &[]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("overdue"), "expected a summary: {stdout:?}");
+}
+
+#[test]
+fn bare_invocation_respects_the_configured_default() {
+    let cache_path = fixture("default-command-status", vec![task("1", Some(-3))], true);
+    fs::write(
+        cache_path.with_file_name("config.toml"),
+        "[behavior]\ndefault_command = \"status\"\n",
+    )
+    .unwrap();
+    let output = run(&cache_path, &[]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "!1\n");
+}
+
+#[test]
+fn invalid_configured_default_is_a_helpful_error() {
+    let cache_path = fixture("default-command-invalid", vec![task("1", Some(-3))], true);
+    fs::write(
+        cache_path.with_file_name("config.toml"),
+        "[behavior]\ndefault_command = \"frobnicate\"\n",
+    )
+    .unwrap();
+    let output = run(&cache_path, &[]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("unknown variant") && stderr.contains("summary"),
+        "expected the valid commands in the error: {stderr:?}"
+    );
+}
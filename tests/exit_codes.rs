@@ -0,0 +1,52 @@
+//! Integration tests asserting process exit codes against fixture caches.
+
+use std::fs;
+use std::path::Path;
+
+mod common;
+use common::{fixture, run, task};
+
+fn exit_code(cache_path: &Path, args: &[&str]) -> i32 {
+    run(cache_path, args).status.code().unwrap()
+}
+
+#[test]
+fn exit_code_is_zero_by_default_even_with_overdue_tasks() {
+    let cache_path = fixture("default-zero", vec![task("1", Some(-3))], false);
+    assert_eq!(exit_code(&cache_path, &["summary"]), 0);
+    assert_eq!(exit_code(&cache_path, &["list"]), 0);
+    assert_eq!(exit_code(&cache_path, &["status"]), 0);
+}
+
+#[test]
+fn exit_code_is_zero_when_nothing_is_pending() {
+    let cache_path = fixture("all-clear", vec![task("1", Some(3))], true);
+    assert_eq!(exit_code(&cache_path, &["--exit-code", "status"]), 0);
+}
+
+#[test]
+fn exit_code_encodes_overdue_tasks() {
+    let cache_path = fixture("overdue", vec![task("1", Some(-3))], true);
+    assert_eq!(exit_code(&cache_path, &["--exit-code", "summary"]), 2);
+    assert_eq!(exit_code(&cache_path, &["--exit-code", "list"]), 2);
+}
+
+#[test]
+fn exit_code_encodes_a_pending_focus_routine() {
+    let cache_path = fixture("focus-pending", Vec::new(), false);
+    assert_eq!(exit_code(&cache_path, &["--exit-code", "status"]), 4);
+}
+
+#[test]
+fn exit_code_is_bitwise_orable() {
+    let cache_path = fixture("both", vec![task("1", Some(-1))], false);
+    assert_eq!(exit_code(&cache_path, &["--exit-code", "status"]), 6);
+}
+
+#[test]
+fn exit_codes_can_be_enabled_through_config() {
+    let cache_path = fixture("config", vec![task("1", Some(-1))], true);
+    let config_path = cache_path.with_file_name("config.toml");
+    fs::write(&config_path, "[behavior]\nexit_codes = true\n").unwrap();
+    assert_eq!(exit_code(&cache_path, &["summary"]), 2);
+}
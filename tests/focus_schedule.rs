@@ -0,0 +1,46 @@
+//! The `focus.days` schedule must short-circuit the focus routine on excluded dates — before
+//! the terminal check, so the notice also works from scripts — unless `--force` is passed.
+
+mod common;
+
+use std::fs;
+
+use common::{fixture, run};
+
+/// Write a weekday-only schedule next to the fixture cache, where `run` looks for the
+/// configuration file.
+fn write_weekday_schedule(cache_path: &std::path::Path) {
+    fs::write(
+        cache_path.with_file_name("config.toml"),
+        "[focus]\ndays = [\"mon\", \"tue\", \"wed\", \"thu\", \"fri\"]\n",
+    )
+    .unwrap();
+}
+
+#[test]
+fn an_excluded_date_prints_a_notice_instead_of_running() {
+    let cache_path = fixture("focus-schedule-excluded", Vec::new(), false);
+    write_weekday_schedule(&cache_path);
+
+    // 2024-01-06 is a Saturday, which the weekday-only schedule excludes.
+    let output = run(&cache_path, &["focus", "--date", "2024-01-06"]);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("No focus scheduled for 2024-01-06"));
+    assert!(stdout.contains("--force"));
+}
+
+#[test]
+fn force_overrides_the_schedule() {
+    let cache_path = fixture("focus-schedule-forced", Vec::new(), false);
+    write_weekday_schedule(&cache_path);
+
+    // With --force the routine proceeds past the schedule check and hits the terminal check
+    // instead, since the test harness runs with stdin closed.
+    let output = run(&cache_path, &["focus", "--date", "2024-01-06", "--force"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("focus routine is interactive and needs a terminal"));
+}
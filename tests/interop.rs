@@ -0,0 +1,53 @@
+//! Golden-file tests pinning the interop export formats, so accidental serialization changes
+//! show up as a readable diff against the checked-in file.
+
+use chrono::{Local, TimeZone, Utc};
+use todo::interop::taskwarrior::{render, TaskwarriorFilter};
+use todo::task::{ProjectRef, UserTask};
+
+fn fixture_tasks() -> Vec<UserTask> {
+    let created_at = Utc
+        .with_ymd_and_hms(2024, 1, 1, 12, 0, 0)
+        .unwrap()
+        .with_timezone(&Local);
+    vec![
+        UserTask {
+            gid: "1".to_string(),
+            created_at,
+            due_on: Some("2024-01-10".parse().unwrap()),
+            name: "call mom".to_string(),
+            projects: vec![ProjectRef {
+                gid: "p1".to_string(),
+                name: "Side Projects".to_string(),
+            }],
+            custom_fields: Vec::new(),
+        },
+        UserTask {
+            gid: "2".to_string(),
+            created_at,
+            due_on: None,
+            name: "buy milk".to_string(),
+            projects: Vec::new(),
+            custom_fields: Vec::new(),
+        },
+    ]
+}
+
+#[test]
+fn taskwarrior_export_matches_the_golden_file() {
+    let json = render(&fixture_tasks(), None, "2024-01-15".parse().unwrap()).unwrap();
+    assert_eq!(json.trim_end(), include_str!("golden/taskwarrior.json").trim_end());
+}
+
+#[test]
+fn taskwarrior_filter_drops_the_undated_task() {
+    let json = render(
+        &fixture_tasks(),
+        Some(TaskwarriorFilter::Overdue),
+        "2024-01-15".parse().unwrap(),
+    )
+    .unwrap();
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.as_array().unwrap().len(), 1);
+    assert_eq!(parsed[0]["description"], "call mom");
+}
@@ -0,0 +1,102 @@
+//! Integration tests for `--max-wait`, which caps how long the prompt-critical commands may
+//! spend on the network before degrading to cached data with a stale marker.
+
+mod common;
+
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::{Command, Output};
+use std::time::{Duration, Instant};
+
+use common::{fixture, task};
+
+/// Start a proxy that accepts connections and then never answers, so any request routed
+/// through it stalls until the client gives up. Returns its URL.
+fn stalling_proxy() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        // Holding the sockets open keeps the requests pending instead of erroring out fast
+        // with a refused connection, which would bypass the budget entirely.
+        let mut held = Vec::new();
+        while let Ok((socket, _)) = listener.accept() {
+            held.push(socket);
+        }
+    });
+    format!("http://{address}")
+}
+
+/// Run the binary against the fixture cache without `--use-cache`, so the commands fall
+/// through to the (stalled) network.
+fn run_online(cache_path: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_todo"))
+        .arg("--cache-path")
+        .arg(cache_path)
+        .arg("--config-path")
+        .arg(cache_path.with_file_name("config.toml"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn status_returns_within_the_budget_with_cached_data() {
+    let cache_path = fixture("max-wait-status", vec![task("1", Some(-1))], true);
+    std::fs::write(
+        cache_path.with_file_name("config.toml"),
+        format!("[asana]\nproxy_url = \"{}\"\n", stalling_proxy()),
+    )
+    .unwrap();
+
+    let started = Instant::now();
+    let output = run_online(&cache_path, &["status", "--max-wait", "250"]);
+    let elapsed = started.elapsed();
+
+    // Well under the 15 seconds the connect and request timeouts would otherwise allow.
+    assert!(elapsed < Duration::from_secs(5), "took {elapsed:?}");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("!1"), "expected cached counts: {stdout:?}");
+    assert!(stdout.contains("stale"), "expected a stale marker: {stdout:?}");
+}
+
+#[test]
+fn count_shell_output_carries_the_stale_flag() {
+    let cache_path = fixture("max-wait-count", vec![task("1", Some(-1))], true);
+    std::fs::write(
+        cache_path.with_file_name("config.toml"),
+        format!("[asana]\nproxy_url = \"{}\"\n", stalling_proxy()),
+    )
+    .unwrap();
+
+    let started = Instant::now();
+    let output = run_online(&cache_path, &["count", "--max-wait", "250"]);
+    let elapsed = started.elapsed();
+
+    assert!(elapsed < Duration::from_secs(5), "took {elapsed:?}");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("OVERDUE=1"), "expected cached counts: {stdout:?}");
+    assert!(stdout.contains("STALE=1"), "expected the stale flag: {stdout:?}");
+}
+
+#[test]
+fn configured_budget_applies_without_the_flag() {
+    let cache_path = fixture("max-wait-config", vec![task("1", Some(-1))], true);
+    std::fs::write(
+        cache_path.with_file_name("config.toml"),
+        format!(
+            "[asana]\nproxy_url = \"{}\"\n\n[status]\nmax_wait_ms = 250\n",
+            stalling_proxy()
+        ),
+    )
+    .unwrap();
+
+    let started = Instant::now();
+    let output = run_online(&cache_path, &["status"]);
+    let elapsed = started.elapsed();
+
+    assert!(elapsed < Duration::from_secs(5), "took {elapsed:?}");
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout).unwrap().contains("stale"));
+}
@@ -0,0 +1,707 @@
+//! Integration tests exercising the request/response layer against a mock Asana server, using
+//! recorded fixture responses.
+
+use todo::asana::{ApiError, Client, Credentials, DataWrapper};
+use todo::cache::Cache;
+use todo::focus::{FocusTask, FocusTaskSnapshot, FocusTaskSubtask, FocusWeek, Section};
+use todo::task::{UserTask, UserTaskList};
+use wiremock::matchers::{body_partial_json, header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const USER_TASK_LIST: &str = include_str!("fixtures/user_task_list.json");
+const TASKS: &str = include_str!("fixtures/tasks.json");
+const SECTIONS: &str = include_str!("fixtures/sections.json");
+const FOCUS_TASKS: &str = include_str!("fixtures/focus_tasks.json");
+const ERROR_ENVELOPE: &str = include_str!("fixtures/error_envelope.json");
+
+fn client_for(server: &MockServer) -> Client {
+    Client::new_with_base_url(
+        Credentials::PersonalAccessToken("test-token".to_string()),
+        format!("{}/api/1.0/", server.uri()).parse().unwrap(),
+    )
+    .unwrap()
+}
+
+fn json_response(body: &str) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_raw(body, "application/json")
+}
+
+#[tokio::test]
+async fn update_flow_populates_the_cache() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/1.0/users/me/user_task_list"))
+        .and(query_param("workspace", "workspace1"))
+        .respond_with(json_response(USER_TASK_LIST))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/1.0/user_task_lists/1204986416015644/tasks"))
+        .and(query_param("completed_since", "now"))
+        .respond_with(json_response(TASKS))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = client_for(&server);
+    let user_task_list = client
+        .get::<UserTaskList>(&("me".to_string(), "workspace1".to_string()))
+        .await
+        .unwrap();
+    let tasks = client.get::<UserTask>(&user_task_list.gid).await.unwrap();
+
+    // The fetched data round-trips through the cache the way the update command stores it.
+    let dir = std::env::temp_dir()
+        .join("todo-integration-tests")
+        .join(format!("mock-api-{pid}", pid = std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let cache_path = dir.join("cache.json");
+    let cache = Cache {
+        user_task_list: Some(user_task_list),
+        tasks: Some(tasks),
+        ..Cache::default()
+    };
+    todo::cache::save(&cache_path, &cache).unwrap();
+
+    let reloaded = todo::cache::load(&cache_path).unwrap();
+    assert_eq!(reloaded.user_task_list.unwrap().gid, "1204986416015644");
+    let tasks = reloaded.tasks.unwrap();
+    assert_eq!(tasks.len(), 2);
+    assert_eq!(tasks[0].name, "write the report");
+    assert_eq!(tasks[0].due_on, Some("2024-01-10".parse().unwrap()));
+    assert_eq!(tasks[0].projects[0].name, "Work");
+    assert_eq!(tasks[0].custom_field_label("1205000000000200"), Some("P1"));
+}
+
+#[tokio::test]
+async fn faulty_custom_fields_still_parse() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/1.0/user_task_lists/utl1/tasks"))
+        .respond_with(json_response(TASKS))
+        .mount(&server)
+        .await;
+
+    let mut client = client_for(&server);
+    let tasks = client.get::<UserTask>(&"utl1".to_string()).await.unwrap();
+
+    // The second task carries a field with null display and enum values, and a number field
+    // with keys that were never requested; neither may break parsing or labeling.
+    let faulty = &tasks[1];
+    assert_eq!(faulty.custom_fields.len(), 2);
+    assert_eq!(faulty.custom_field_label("1205000000000200"), None);
+    assert_eq!(faulty.custom_field_label("1205000000000300"), Some("12"));
+}
+
+#[tokio::test]
+async fn focus_tasks_tolerate_missing_custom_fields() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/1.0/sections/section1/tasks"))
+        .respond_with(json_response(FOCUS_TASKS))
+        .mount(&server)
+        .await;
+
+    let mut client = client_for(&server);
+    let tasks = client.get::<FocusTask>(&"section1".to_string()).await.unwrap();
+
+    assert_eq!(tasks.len(), 2);
+    let fields = tasks[0].custom_fields.as_ref().unwrap();
+    assert_eq!(fields[0].number_value, Some(4));
+    assert_eq!(fields[1].number_value, None);
+    assert!(tasks[1].custom_fields.is_none());
+}
+
+#[tokio::test]
+async fn missing_focus_week_is_created_with_the_expected_body() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/1.0/projects/project1/sections"))
+        .respond_with(json_response(SECTIONS))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/1.0/projects/project1/sections"))
+        .and(body_partial_json(serde_json::json!({
+            "data": { "name": "Daily Focuses (2024-01-15 to 2024-01-21)" }
+        })))
+        .respond_with(ResponseTemplate::new(201).set_body_raw(
+            r#"{"data": {"gid": "1205000000000600", "name": "Daily Focuses (2024-01-15 to 2024-01-21)"}}"#,
+            "application/json",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = client_for(&server);
+
+    // Neither the archived week nor the unrelated section parses as a focus week, which is
+    // exactly the state that forces the focus command to create one.
+    let sections = client.get::<Section>(&"project1".to_string()).await.unwrap();
+    assert!(sections
+        .into_iter()
+        .filter_map(|s| TryInto::<FocusWeek>::try_into(s).ok())
+        .next()
+        .is_none());
+
+    let url = format!("{}/api/1.0/projects/project1/sections", server.uri());
+    let response = client
+        .mutate_request(
+            reqwest::Method::POST,
+            &url.parse().unwrap(),
+            serde_json::json!({
+                "data": { "name": "Daily Focuses (2024-01-15 to 2024-01-21)" }
+            }),
+        )
+        .await
+        .unwrap();
+    let created: DataWrapper<Section> = response.json().await.unwrap();
+    assert_eq!(created.data.gid, "1205000000000600");
+}
+
+#[tokio::test]
+async fn builder_timeout_cuts_off_a_slow_endpoint() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/1.0/user_task_lists/utl1/tasks"))
+        .respond_with(json_response(TASKS).set_delay(std::time::Duration::from_secs(5)))
+        .mount(&server)
+        .await;
+
+    let mut client = Client::builder(Credentials::PersonalAccessToken("test-token".to_string()))
+        .base_url(format!("{}/api/1.0/", server.uri()).parse().unwrap())
+        .timeout(std::time::Duration::from_millis(100))
+        .build()
+        .unwrap();
+
+    let error = client
+        .get::<UserTask>(&"utl1".to_string())
+        .await
+        .unwrap_err();
+    assert!(todo::asana::is_network_error(&error));
+}
+
+#[tokio::test]
+async fn default_user_agent_names_the_crate_and_version() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/1.0/user_task_lists/utl1/tasks"))
+        .and(header(
+            "user-agent",
+            concat!("todo/", env!("CARGO_PKG_VERSION")),
+        ))
+        .respond_with(json_response(TASKS))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = client_for(&server);
+    client.get::<UserTask>(&"utl1".to_string()).await.unwrap();
+}
+
+#[tokio::test]
+async fn unauthorized_responses_surface_the_refresh_failure() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/1.0/user_task_lists/utl1/tasks"))
+        .respond_with(ResponseTemplate::new(401).set_body_raw(ERROR_ENVELOPE, "application/json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // A personal access token cannot be refreshed, so the 401-refresh-retry path stops at the
+    // refresh step instead of retrying the request.
+    let mut client = client_for(&server);
+    let error = client
+        .get::<UserTask>(&"utl1".to_string())
+        .await
+        .unwrap_err();
+    assert!(format!("{error:#}").contains("unable to refresh access token"));
+}
+
+#[tokio::test]
+async fn a_noninteractive_client_never_authorizes_on_an_expired_token() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/1.0/user_task_lists/utl1/tasks"))
+        .respond_with(ResponseTemplate::new(401).set_body_raw(ERROR_ENVELOPE, "application/json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // An expired OAuth token with no refresh token would normally fall back to the full
+    // interactive flow; with interactivity forbidden, the request comes back promptly with the
+    // typed refusal instead of reading stdin or opening a browser (either of which would hang
+    // this test, since there is no terminal or display to answer).
+    let mut client = Client::new_with_base_url(
+        Credentials::OAuth2 {
+            access_token: "expired-access-token".to_string(),
+            refresh_token: None,
+        },
+        format!("{}/api/1.0/", server.uri()).parse().unwrap(),
+    )
+    .unwrap();
+    client.set_interactive_auth(false);
+    let error = client
+        .get::<UserTask>(&"utl1".to_string())
+        .await
+        .unwrap_err();
+    assert!(todo::asana::is_auth_required(&error), "{error:#}");
+    assert!(
+        format!("{error:#}").contains("authorization required; run `todo update`"),
+        "{error:#}"
+    );
+}
+
+#[tokio::test]
+async fn bad_requests_name_the_requested_opt_fields() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/1.0/user_task_lists/utl1/tasks"))
+        .respond_with(ResponseTemplate::new(400).set_body_raw(
+            r#"{"errors": [{"message": "opt_fields: Not a recognized field on task: this.nmae"}]}"#,
+            "application/json",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // A 400 is usually a bad `opt_fields` entry, so the error carries both Asana's message
+    // (which names the offending field) and the full list this request asked for.
+    let mut client = client_for(&server);
+    let error = client
+        .get::<UserTask>(&"utl1".to_string())
+        .await
+        .unwrap_err();
+    let rendered = format!("{error:#}");
+    assert!(rendered.contains("Not a recognized field on task: this.nmae"), "{rendered}");
+    assert!(rendered.contains("the requested opt_fields were: this.gid,"), "{rendered}");
+}
+
+#[tokio::test]
+async fn a_stale_user_task_list_gid_reresolves_and_retries() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/1.0/user_task_lists/stale/tasks"))
+        .respond_with(ResponseTemplate::new(404).set_body_raw(ERROR_ENVELOPE, "application/json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/1.0/users/me/user_task_list"))
+        .and(query_param("workspace", "workspace1"))
+        .respond_with(json_response(USER_TASK_LIST))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/1.0/user_task_lists/1204986416015644/tasks"))
+        .respond_with(json_response(TASKS))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // The cached gid is trusted without a TTL, so a workspace change surfaces as a 404 here;
+    // the fetch re-resolves the list and retries in one call.
+    let mut client = client_for(&server);
+    let stale = UserTaskList {
+        gid: "stale".to_string(),
+    };
+    let (tasks, resolved) =
+        todo::commands::update::fetch_tasks(&mut client, stale, "workspace1")
+            .await
+            .unwrap();
+    assert_eq!(resolved.gid, "1204986416015644");
+    assert_eq!(tasks.len(), 2);
+}
+
+#[tokio::test]
+async fn a_good_user_task_list_gid_is_not_reresolved() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/1.0/user_task_lists/utl1/tasks"))
+        .respond_with(json_response(TASKS))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/1.0/users/me/user_task_list"))
+        .respond_with(json_response(USER_TASK_LIST))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let mut client = client_for(&server);
+    let list = UserTaskList {
+        gid: "utl1".to_string(),
+    };
+    let (tasks, resolved) = todo::commands::update::fetch_tasks(&mut client, list, "workspace1")
+        .await
+        .unwrap();
+    assert_eq!(resolved.gid, "utl1");
+    assert_eq!(tasks.len(), 2);
+}
+
+#[tokio::test]
+async fn find_focus_day_never_creates_anything() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/1.0/projects/project1/sections"))
+        .respond_with(json_response(SECTIONS))
+        .mount(&server)
+        .await;
+
+    // No week in the fixture covers the date; a read must come back empty-handed instead of
+    // creating one. No POST mock is mounted, so any creation attempt would fail the call.
+    let mut client = client_for(&server);
+    let day = todo::commands::focus::find_focus_day(
+        "2024-01-15".parse().unwrap(),
+        &mut client,
+        "project1",
+    )
+    .await
+    .unwrap();
+    assert!(day.is_none());
+}
+
+#[tokio::test]
+async fn ensure_focus_day_creates_the_missing_week_and_day() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/1.0/projects/project1/sections"))
+        .respond_with(json_response(
+            r#"{"data": [{"gid": "1205000000000600", "name": "Daily Focuses (2024-01-08 to 2024-01-14)"}]}"#,
+        ))
+        .mount(&server)
+        .await;
+    // The new week lands before the newest existing one, covering the requested date.
+    Mock::given(method("POST"))
+        .and(path("/api/1.0/projects/project1/sections"))
+        .and(body_partial_json(serde_json::json!({
+            "data": {
+                "name": "Daily Focuses (2024-01-15 to 2024-01-21)",
+                "insert_before": "1205000000000600"
+            }
+        })))
+        .respond_with(ResponseTemplate::new(201).set_body_raw(
+            r#"{"data": {"gid": "1205000000000601", "name": "Daily Focuses (2024-01-15 to 2024-01-21)"}}"#,
+            "application/json",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/1.0/tasks"))
+        .and(body_partial_json(serde_json::json!({
+            "data": {
+                "name": "Daily Focus for Mon (2024-01-15)",
+                "memberships": [{"project": "project1", "section": "1205000000000601"}]
+            }
+        })))
+        .respond_with(ResponseTemplate::new(201).set_body_raw(
+            r#"{"data": {"gid": "1205000000000700", "name": "Daily Focus for Mon (2024-01-15)", "notes": "", "custom_fields": []}}"#,
+            "application/json",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = client_for(&server);
+    let day = todo::commands::focus::ensure_focus_day(
+        "2024-01-15".parse().unwrap(),
+        &mut client,
+        "project1",
+    )
+    .await
+    .unwrap();
+    // A freshly created week cannot contain tasks, so there is no task fetch and — with no
+    // sibling days — no ordering call either.
+    assert_eq!(day.date, "2024-01-15".parse::<chrono::NaiveDate>().unwrap());
+    assert_eq!(day.task.gid, "1205000000000700");
+}
+
+#[tokio::test]
+async fn ensure_focus_day_orders_a_created_day_after_the_previous_one() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/1.0/projects/project1/sections"))
+        .respond_with(json_response(
+            r#"{"data": [{"gid": "1205000000000600", "name": "Daily Focuses (2024-01-08 to 2024-01-14)"}]}"#,
+        ))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/1.0/sections/1205000000000600/tasks"))
+        .respond_with(json_response(FOCUS_TASKS))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/1.0/tasks"))
+        .and(body_partial_json(serde_json::json!({
+            "data": {"name": "Daily Focus for Wed (2024-01-10)"}
+        })))
+        .respond_with(ResponseTemplate::new(201).set_body_raw(
+            r#"{"data": {"gid": "1205000000000701", "name": "Daily Focus for Wed (2024-01-10)", "notes": "", "custom_fields": []}}"#,
+            "application/json",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+    // The fixture's Tuesday has no custom fields and so does not parse as a focus day, which
+    // leaves Monday as the closest earlier day the new Wednesday must slot in after.
+    Mock::given(method("POST"))
+        .and(path("/api/1.0/sections/1205000000000600/addTask"))
+        .and(body_partial_json(serde_json::json!({
+            "data": {"task": "1205000000000701", "insert_after": "1205000000000500"}
+        })))
+        .respond_with(json_response(r#"{"data": {}}"#))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = client_for(&server);
+    let day = todo::commands::focus::ensure_focus_day(
+        "2024-01-10".parse().unwrap(),
+        &mut client,
+        "project1",
+    )
+    .await
+    .unwrap();
+    assert_eq!(day.task.gid, "1205000000000701");
+}
+
+#[tokio::test]
+async fn remote_diary_edits_are_flagged_but_stats_only_edits_are_not() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/1.0/sections/section1/tasks"))
+        .respond_with(json_response(FOCUS_TASKS))
+        .mount(&server)
+        .await;
+    // The remote task was edited after this run loaded it: a newer `modified_at` and
+    // different notes together flag a conflict.
+    Mock::given(method("GET"))
+        .and(path("/api/1.0/tasks/1205000000000500"))
+        .respond_with(json_response(
+            r###"{"data": {"gid": "1205000000000500", "notes": "## Diary\nedited on a phone", "modified_at": "2024-01-08T21:30:00.000Z"}}"###,
+        ))
+        .mount(&server)
+        .await;
+
+    let mut client = client_for(&server);
+    let loaded = client
+        .get::<FocusTask>(&"section1".to_string())
+        .await
+        .unwrap()
+        .remove(0);
+    let snapshot = client
+        .get::<FocusTaskSnapshot>(&loaded.gid)
+        .await
+        .unwrap();
+    assert!(snapshot.conflicts_with(&loaded));
+
+    // A stats update moves `modified_at` without touching the notes; that must not count
+    // as a conflict, or every sync after a stats-only edit would prompt for no reason.
+    let stats_only = FocusTaskSnapshot {
+        notes: loaded.notes.clone(),
+        ..snapshot
+    };
+    assert!(!stats_only.conflicts_with(&loaded));
+}
+
+#[tokio::test]
+async fn deleted_focus_task_is_detected_and_the_recreated_gid_succeeds() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/1.0/tasks/1205000000000500/subtasks"))
+        .respond_with(ResponseTemplate::new(404).set_body_raw(ERROR_ENVELOPE, "application/json"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/1.0/tasks/1205000000000600/subtasks"))
+        .respond_with(json_response(r#"{"data": []}"#))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // A 404 on the cached gid classifies as not-found, which is what triggers the focus run to
+    // invalidate the cached day and recreate it; the replay against the new gid then succeeds.
+    let mut client = client_for(&server);
+    let error = client
+        .get::<FocusTaskSubtask>(&"1205000000000500".to_string())
+        .await
+        .unwrap_err();
+    assert!(todo::asana::is_not_found(&error));
+    assert!(!todo::asana::is_not_found(&anyhow::anyhow!("unrelated")));
+
+    let replayed = client
+        .get::<FocusTaskSubtask>(&"1205000000000600".to_string())
+        .await
+        .unwrap();
+    assert!(replayed.is_empty());
+}
+
+/// Minimal keep-alive HTTP server that counts accepted connections and records request headers;
+/// wiremock does not expose a connection counter, so connection reuse needs a hand-rolled mock.
+async fn connection_counting_server() -> (
+    std::net::SocketAddr,
+    std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    std::sync::Arc<std::sync::Mutex<String>>,
+) {
+    use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let connections = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let headers = std::sync::Arc::new(std::sync::Mutex::new(String::new()));
+
+    let (connections_handle, headers_handle) =
+        (std::sync::Arc::clone(&connections), std::sync::Arc::clone(&headers));
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            connections_handle.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let headers = std::sync::Arc::clone(&headers_handle);
+            tokio::spawn(async move {
+                let mut buffer = Vec::new();
+                let mut chunk = [0_u8; 1024];
+                loop {
+                    let read = socket.read(&mut chunk).await.unwrap_or(0);
+                    if read == 0 {
+                        break;
+                    }
+                    buffer.extend_from_slice(&chunk[..read]);
+                    // GET requests have no body, so a blank line ends the request.
+                    if buffer.windows(4).any(|window| window == b"\r\n\r\n") {
+                        headers
+                            .lock()
+                            .unwrap()
+                            .push_str(&String::from_utf8_lossy(&buffer).to_lowercase());
+                        buffer.clear();
+                        let body = r#"{"data": []}"#;
+                        let response = format!(
+                            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n\
+                             content-length: {length}\r\n\r\n{body}",
+                            length = body.len()
+                        );
+                        socket.write_all(response.as_bytes()).await.unwrap();
+                    }
+                }
+            });
+        }
+    });
+    (address, connections, headers)
+}
+
+#[tokio::test]
+async fn sequential_gets_reuse_one_connection_and_advertise_compression() {
+    let (address, connections, headers) = connection_counting_server().await;
+    let mut client = Client::new_with_base_url(
+        Credentials::PersonalAccessToken("test-token".to_string()),
+        format!("http://{address}/api/1.0/").parse().unwrap(),
+    )
+    .unwrap();
+
+    client.get::<UserTask>(&"utl1".to_string()).await.unwrap();
+    client.get::<UserTask>(&"utl2".to_string()).await.unwrap();
+
+    assert_eq!(connections.load(std::sync::atomic::Ordering::SeqCst), 1);
+    assert!(headers.lock().unwrap().contains("accept-encoding: gzip"));
+}
+
+#[tokio::test]
+async fn api_errors_carry_the_status_and_asana_message() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/1.0/user_task_lists/utl1/tasks"))
+        .respond_with(ResponseTemplate::new(404).set_body_raw(ERROR_ENVELOPE, "application/json"))
+        .mount(&server)
+        .await;
+
+    let mut client = client_for(&server);
+    let error = client
+        .get::<UserTask>(&"utl1".to_string())
+        .await
+        .unwrap_err();
+
+    let api_error = error.downcast_ref::<ApiError>().unwrap();
+    assert_eq!(api_error.status, reqwest::StatusCode::NOT_FOUND);
+    assert_eq!(
+        api_error.message.as_deref(),
+        Some("project: Not a recognized ID: 1205000000000999")
+    );
+}
+
+#[tokio::test]
+async fn uploads_send_the_file_as_a_multipart_form() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/1.0/tasks/77/attachments"))
+        .respond_with(ResponseTemplate::new(201).set_body_raw(
+            r#"{"data": {"gid": "1205000000000700", "permalink_url": "https://app.asana.com/app/asana/-/get_asset?asset_id=700"}}"#,
+            "application/json",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let dir = std::env::temp_dir()
+        .join("todo-integration-tests")
+        .join(format!("mock-upload-{pid}", pid = std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("crash.log");
+    std::fs::write(&file, "thread 'main' panicked").unwrap();
+
+    let client = client_for(&server);
+    let attachment = client.upload("77", &file, "text/plain").await.unwrap();
+    assert_eq!(attachment.gid, "1205000000000700");
+    assert!(attachment.permalink_url.unwrap().contains("asset_id=700"));
+
+    // The request must be a multipart form carrying the file's bytes, name, and content type
+    // under the `file` field Asana expects.
+    let requests = server.received_requests().await.unwrap();
+    let content_type = requests[0]
+        .headers
+        .get("content-type")
+        .unwrap()
+        .to_str()
+        .unwrap();
+    let boundary = content_type
+        .strip_prefix("multipart/form-data; boundary=")
+        .unwrap();
+    let body = String::from_utf8_lossy(&requests[0].body).to_lowercase();
+    assert!(body.contains(&format!("--{boundary}")), "{body}");
+    assert!(body.contains("name=\"file\""), "{body}");
+    assert!(body.contains("filename=\"crash.log\""), "{body}");
+    assert!(body.contains("content-type: text/plain"), "{body}");
+    assert!(body.contains("thread 'main' panicked"), "{body}");
+}
+
+#[tokio::test]
+async fn oversized_attachments_are_rejected_before_uploading() {
+    let server = MockServer::start().await;
+
+    let dir = std::env::temp_dir()
+        .join("todo-integration-tests")
+        .join(format!("mock-upload-limit-{pid}", pid = std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("huge.bin");
+    // A sparse 101 MB file: the limit check only reads metadata, so nothing is materialized.
+    std::fs::File::create(&file)
+        .unwrap()
+        .set_len(101 * 1024 * 1024)
+        .unwrap();
+
+    let client = client_for(&server);
+    let error = client
+        .upload("77", &file, "application/octet-stream")
+        .await
+        .unwrap_err();
+
+    assert!(
+        error.to_string().contains("100 MB attachment limit"),
+        "{error}"
+    );
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
@@ -0,0 +1,46 @@
+//! Integration tests asserting that no-color output is free of ANSI escape sequences.
+
+mod common;
+use common::{fixture, run, task};
+
+fn stdout_without_color(name: &str, args: &[&str]) -> String {
+    let cache_path = fixture(
+        name,
+        vec![task("1", Some(-3)), task("2", Some(0)), task("3", Some(4))],
+        false,
+    );
+    let output = run(&cache_path, args);
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn no_color_list_has_no_escape_sequences() {
+    let stdout = stdout_without_color("no-color-list", &["--no-color", "list", "--all"]);
+    assert!(!stdout.contains('\x1b'), "found ANSI escapes: {stdout:?}");
+    assert!(stdout.contains("task 1"));
+}
+
+#[test]
+fn no_color_summary_has_no_escape_sequences() {
+    let stdout = stdout_without_color("no-color-summary", &["--no-color", "summary"]);
+    assert!(!stdout.contains('\x1b'), "found ANSI escapes: {stdout:?}");
+    assert!(stdout.contains("overdue or due today"));
+}
+
+#[test]
+fn no_color_env_variable_is_honored() {
+    let cache_path = fixture("no-color-env", vec![task("1", Some(-3))], false);
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_todo"))
+        .arg("--cache-path")
+        .arg(&cache_path)
+        .arg("--config-path")
+        .arg(cache_path.with_file_name("config.toml"))
+        .arg("--use-cache")
+        .arg("summary")
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(!String::from_utf8(output.stdout).unwrap().contains('\x1b'));
+}
@@ -0,0 +1,39 @@
+//! Interactive commands must fail fast, before any API call, when stdin is not a terminal —
+//! `run` spawns the binary with stdin closed, exactly like a git hook or the xbar plugin would.
+
+mod common;
+
+use common::{fixture, run, task};
+
+#[test]
+fn triage_fails_fast_without_a_terminal() {
+    let cache_path = fixture("non-tty-triage", vec![task("1", Some(-1))], true);
+    let output = run(&cache_path, &["triage"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("triage is interactive and needs a terminal"));
+    assert!(stderr.contains("todo snooze"));
+}
+
+#[test]
+fn init_fails_fast_without_a_terminal() {
+    let cache_path = fixture("non-tty-init", Vec::new(), true);
+    let output = run(&cache_path, &["init"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("init is interactive and needs a terminal"));
+    assert!(stderr.contains("todo config set"));
+}
+
+#[test]
+fn focus_run_fails_fast_without_a_terminal() {
+    let cache_path = fixture("non-tty-focus", Vec::new(), false);
+    let output = run(&cache_path, &["focus"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("focus routine is interactive and needs a terminal"));
+    assert!(stderr.contains("todo focus overview"));
+}
@@ -0,0 +1,34 @@
+//! Integration tests for `--offline`, which must never touch the network.
+
+mod common;
+use common::{fixture, run, task};
+
+#[test]
+fn offline_summary_reads_the_cache_and_reports_its_age() {
+    let cache_path = fixture("offline-summary", vec![task("1", Some(-3))], true);
+    let output = run(&cache_path, &["--offline", "summary"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("overdue"), "expected a summary: {stdout:?}");
+    assert!(
+        stdout.contains("offline: cache is"),
+        "expected a cache-age annotation: {stdout:?}"
+    );
+}
+
+#[test]
+fn offline_without_credentials_exits_instead_of_authorizing() {
+    let dir = std::env::temp_dir()
+        .join("todo-integration-tests")
+        .join(format!("offline-no-creds-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let cache_path = dir.join("cache.json");
+
+    let output = run(&cache_path, &["--offline", "summary"]);
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("no credentials in cache"),
+        "expected the no-credentials message: {stderr:?}"
+    );
+}
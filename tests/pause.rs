@@ -0,0 +1,55 @@
+//! Pausing stores a window in the cache that every later invocation sees, so these tests drive
+//! the binary through a pause/status/resume round trip against a fixture cache.
+
+mod common;
+
+use common::{fixture, run, task};
+
+#[test]
+fn pausing_quiets_the_status_until_resumed() {
+    let cache_path = fixture("pause-round-trip", vec![task("1", Some(-1))], false);
+
+    let output = run(&cache_path, &["pause", "--days", "2"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Paused until"));
+
+    // The routines stay quiet, but the overdue count and the paused token still show.
+    let output = run(&cache_path, &["--no-color", "status"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.trim(), "!1 paused");
+
+    // A bare `pause` reports the current state without changing it.
+    let output = run(&cache_path, &["pause"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Paused until"));
+
+    let output = run(&cache_path, &["resume"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Resumed"));
+
+    let output = run(&cache_path, &["--no-color", "status"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.contains("paused"));
+}
+
+#[test]
+fn bare_pause_reports_not_paused_and_bad_dates_error() {
+    let cache_path = fixture("pause-state", Vec::new(), true);
+
+    let output = run(&cache_path, &["pause"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Not paused."));
+
+    let output = run(&cache_path, &["pause", "--until", "not a date"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("could not parse `not a date` as a date"));
+
+    let output = run(&cache_path, &["pause", "--days", "0"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("--days expects at least 1"));
+}